@@ -0,0 +1,138 @@
+//! Times parsing and rendering a generated 5,000-type schema, measured apart
+//! from the phases gumwood reports with --report
+//!
+//! Run with `cargo bench`; set GUMWOOD_BENCH_FIXTURE to a path to keep the
+//! generated schema there.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gumwood::Options;
+use serde_json::Value;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+use structopt::StructOpt;
+
+const TYPE_COUNT: usize = 5000;
+
+fn bench_large_schema(c: &mut Criterion) {
+    let dir = env::temp_dir().join(format!("gumwood-bench-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let schema = env::var_os("GUMWOOD_BENCH_FIXTURE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dir.join("large.graphql"));
+    fs::write(&schema, generate_schema(TYPE_COUNT)).unwrap();
+
+    let mut group = c.benchmark_group(format!("{} types", TYPE_COUNT));
+    group.sample_size(10);
+    // Parsing is the load phase: reading the schema into gumwood's model
+    group.bench_function("parse", |b| {
+        b.iter_custom(|iters| time_phase(&schema, &dir, "load", iters))
+    });
+    group.bench_function("render", |b| {
+        b.iter_custom(|iters| time_phase(&schema, &dir, "render", iters))
+    });
+    group.finish();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Runs gumwood the specified number of times, returning how long the phase
+// took in all
+fn time_phase(schema: &Path, dir: &Path, phase: &str, iters: u64) -> Duration {
+    let out_dir = dir.join("out");
+    let report = dir.join("report.json");
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let args = Options::from_iter_safe(&[
+            "gumwood".as_ref(),
+            "--schema".as_ref(),
+            schema.as_os_str(),
+            "--out-dir".as_ref(),
+            out_dir.as_os_str(),
+            "--report".as_ref(),
+            report.as_os_str(),
+        ])
+        .unwrap();
+        gumwood::run(args).unwrap();
+
+        let report: Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+        let millis = report["phases"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|timing| timing["name"] == phase)
+            .and_then(|timing| timing["milliseconds"].as_f64())
+            .unwrap_or_else(|| panic!("no {} phase in the report", phase));
+        total += Duration::from_secs_f64(millis / 1000.0);
+    }
+    total
+}
+
+/// Returns SDL for a schema with the specified number of types: three fifths
+/// objects, one fifth inputs, and one fifth enums, with cross-references,
+/// arguments, deprecations, and a query and mutation for each input
+fn generate_schema(count: usize) -> String {
+    let mut sdl = String::new();
+    sdl.push_str("\"Something with an ID\"\ninterface Node {\n  id: ID!\n}\n\n");
+
+    let mut queries = Vec::new();
+    let mut mutations = Vec::new();
+    for i in 0..count {
+        match i % 5 {
+            0..=2 => {
+                sdl.push_str(&format!(
+                    "\"Object number {i}\"\ntype Object{i} implements Node {{\n  id: ID!\n  \
+                     \"The name of object {i}\"\n  name: String\n  parent: {parent}\n  \
+                     children(first: Int = 10, after: String): [{child}!]!\n  \
+                     status: Enum{status}\n  \
+                     legacy: String @deprecated(reason: \"Use name\")\n}}\n\n",
+                    i = i,
+                    parent = object(i + 7, count),
+                    child = object(i * 13 + 1, count),
+                    status = i - i % 5 + 4,
+                ));
+            }
+            3 => {
+                sdl.push_str(&format!(
+                    "input Input{i} {{\n  name: String!\n  status: Enum{status} = ACTIVE\n  \
+                     parentId: ID\n}}\n\n",
+                    i = i,
+                    status = i + 1,
+                ));
+                queries.push(format!(
+                    "  \"Finds object {o}\"\n  find{i}(id: ID!): {o}\n",
+                    i = i,
+                    o = object(i, count)
+                ));
+                mutations.push(format!(
+                    "  create{i}(input: Input{i}!): {o}\n",
+                    i = i,
+                    o = object(i, count)
+                ));
+            }
+            _ => {
+                sdl.push_str(&format!(
+                    "enum Enum{} {{\n  ACTIVE\n  INACTIVE\n  \
+                     ARCHIVED @deprecated(reason: \"Use INACTIVE\")\n}}\n\n",
+                    i
+                ));
+            }
+        }
+    }
+
+    sdl.push_str(&format!("type Query {{\n{}}}\n\n", queries.concat()));
+    sdl.push_str(&format!("type Mutation {{\n{}}}\n", mutations.concat()));
+    sdl
+}
+
+// Returns the name of an object type near n, wrapping around the schema
+fn object(n: usize, count: usize) -> String {
+    let i = n % count;
+    format!("Object{}", i - i % 5 + (i % 5) % 3)
+}
+
+criterion_group!(benches, bench_large_schema);
+criterion_main!(benches);
@@ -0,0 +1,216 @@
+use super::budget::Budget;
+use flate2::{write::GzEncoder, Compression};
+use std::{
+    error::Error,
+    fs,
+    io::{Cursor, Write},
+    path::{Component, Path, PathBuf},
+};
+use tar::{Builder, Header};
+use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipWriter};
+
+// Every entry's modification time, 1980-01-01, the earliest a zip can
+// hold, so the same docs always make the same archive
+const MTIME: u64 = 315_532_800;
+
+/// Writes the files into a zip, tar, or gzipped tar archive, picked by the
+/// archive's extension: `.zip`, `.tar`, or `.tar.gz` and `.tgz`
+///
+/// # Arguments
+///
+/// * `files` - the paths, relative to the archive's root, and contents of
+///   the files
+/// * `archive` - the archive to write
+/// * `budget` - the output budget to count the archive against
+pub fn write(
+    files: &[(PathBuf, String)],
+    archive: &Path,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    let name = archive
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let bytes = if name.ends_with(".zip") {
+        to_zip(files)?
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&to_tar(files)?)?;
+        encoder.finish()?
+    } else if name.ends_with(".tar") {
+        to_tar(files)?
+    } else {
+        return Err(format!(
+            "can't tell what kind of archive {} is; end it in .zip, .tar, .tar.gz, or .tgz",
+            archive.display()
+        )
+        .into());
+    };
+    budget.spend(bytes.len())?;
+    fs::write(archive, bytes)
+        .map_err(|err| format!("can't write archive {}: {}", archive.display(), err).into())
+}
+
+// Returns the path with forward slashes, as both formats store it
+fn to_entry_name(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => return Err(format!("can't archive {}", path.display()).into()),
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+fn to_tar(files: &[(PathBuf, String)]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut tar = Builder::new(Vec::new());
+    for (path, text) in files {
+        let name = to_entry_name(path)?;
+        let mut header = Header::new_ustar();
+        // ustar splits a name too long for the name field across the prefix
+        // field, at a slash
+        header
+            .set_path(&name)
+            .map_err(|_| format!("{} is too long to archive", name))?;
+        header.set_size(text.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(MTIME);
+        header.set_cksum();
+        tar.append(&header, text.as_bytes())?;
+    }
+    Ok(tar.into_inner()?)
+}
+
+fn to_zip(files: &[(PathBuf, String)]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, text) in files {
+        zip.start_file(to_entry_name(path)?, options)?;
+        zip.write_all(text.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tar::Archive;
+    use zip::ZipArchive;
+
+    fn files() -> Vec<(PathBuf, String)> {
+        vec![
+            (PathBuf::from("docs/queries.md"), "# Queries\n".to_string()),
+            (
+                PathBuf::from("mkdocs.yml"),
+                "site_name: League\n".to_string(),
+            ),
+        ]
+    }
+
+    // Reads each entry's name and contents back with a real tar reader
+    fn read_tar(tar: &[u8]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+        let mut entries = Vec::new();
+        for entry in Archive::new(tar).entries()? {
+            let mut entry = entry?;
+            assert_eq!(0o644, entry.header().mode()?);
+            assert_eq!(MTIME, entry.header().mtime()?);
+            let mut text = String::new();
+            entry.read_to_string(&mut text)?;
+            entries.push((entry.path()?.to_string_lossy().to_string(), text));
+        }
+        Ok(entries)
+    }
+
+    // Reads each entry's name and contents back with a real zip reader
+    fn read_zip(zip: Vec<u8>) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+        let mut archive = ZipArchive::new(Cursor::new(zip))?;
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            entries.push((file.name()?.to_string(), text));
+        }
+        Ok(entries)
+    }
+
+    fn expected() -> Vec<(String, String)> {
+        vec![
+            ("docs/queries.md".to_string(), "# Queries\n".to_string()),
+            ("mkdocs.yml".to_string(), "site_name: League\n".to_string()),
+        ]
+    }
+
+    #[test]
+    fn to_tar_should_write_readable_ustar_entries() -> Result<(), Box<dyn Error>> {
+        let tar = to_tar(&files())?;
+        assert_eq!(b"ustar\0", &tar[257..263]);
+        assert_eq!(expected(), read_tar(&tar)?);
+        Ok(())
+    }
+
+    #[test]
+    fn to_tar_should_split_long_names_across_prefix() -> Result<(), Box<dyn Error>> {
+        let name = format!(
+            "{}/{}/{}.md",
+            "a".repeat(60),
+            "b".repeat(60),
+            "c".repeat(30)
+        );
+        let tar = to_tar(&[(PathBuf::from(&name), "# Long\n".to_string())])?;
+        // No GNU long name entry; the name is split between the header fields
+        assert_eq!(format!("{}.md\0", "c".repeat(30)).as_bytes(), &tar[..34]);
+        assert_eq!(
+            format!("{}/{}\0", "a".repeat(60), "b".repeat(60)).as_bytes(),
+            &tar[345..467]
+        );
+        assert_eq!(vec![(name, "# Long\n".to_string())], read_tar(&tar)?);
+        Ok(())
+    }
+
+    #[test]
+    fn to_tar_should_fail_when_name_cant_be_split() {
+        assert!(to_tar(&[(PathBuf::from("c".repeat(101)), String::new())]).is_err());
+    }
+
+    #[test]
+    fn to_zip_should_write_readable_deflated_entries() -> Result<(), Box<dyn Error>> {
+        let zip = to_zip(&files())?;
+        let mut archive = ZipArchive::new(Cursor::new(zip.clone()))?;
+        assert_eq!(
+            CompressionMethod::Deflated,
+            archive.by_index(0)?.compression()
+        );
+        assert_eq!(expected(), read_zip(zip)?);
+        Ok(())
+    }
+
+    #[test]
+    fn to_zip_should_hold_more_entries_than_fit_in_16_bits() -> Result<(), Box<dyn Error>> {
+        let count = usize::from(u16::MAX) + 10;
+        let files: Vec<(PathBuf, String)> = (0..count)
+            .map(|i| (PathBuf::from(format!("{}.md", i)), String::new()))
+            .collect();
+        let archive = ZipArchive::new(Cursor::new(to_zip(&files)?))?;
+        assert_eq!(count, archive.len());
+        assert!(archive.index_for_name("65544.md").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn to_zip_should_be_the_same_each_time() -> Result<(), Box<dyn Error>> {
+        assert_eq!(to_zip(&files())?, to_zip(&files())?);
+        Ok(())
+    }
+
+    #[test]
+    fn write_should_fail_on_unknown_extension() {
+        assert!(write(&files(), Path::new("docs.rar"), &Budget::default()).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+use super::format::get_nav_pages;
+use super::links::Links;
+use super::markdown::{parse_blocks, parse_inline, Block, Inline};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A page as gumwood builds it before writing it as markdown: the blocks
+/// before its first heading, then a section for each heading
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Page {
+    pub name: String,
+    /// The page's file, relative to the other pages
+    pub file: String,
+    pub blocks: Vec<Node>,
+    pub sections: Vec<Section>,
+}
+
+/// A heading, the blocks under it, and the sections under it with deeper
+/// headings
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Section {
+    pub level: usize,
+    /// The heading's named anchor or heading ID, if it has one
+    pub anchor: Option<String>,
+    pub title: Vec<Span>,
+    pub blocks: Vec<Node>,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Node {
+    /// A heading inside a quote, which doesn't start a section
+    Heading {
+        level: usize,
+        content: Vec<Span>,
+    },
+    Paragraph {
+        content: Vec<Span>,
+    },
+    Notice {
+        content: Vec<Span>,
+    },
+    List {
+        items: Vec<Vec<Span>>,
+    },
+    /// A table, with its first row as the header
+    Table {
+        header: Vec<Vec<Span>>,
+        rows: Vec<Vec<Vec<Span>>>,
+    },
+    Quote {
+        blocks: Vec<Node>,
+    },
+    Code {
+        language: String,
+        code: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Span {
+    Text {
+        text: String,
+    },
+    Code {
+        code: String,
+    },
+    Strong {
+        content: Vec<Span>,
+    },
+    Link {
+        content: Vec<Span>,
+        destination: String,
+    },
+    Anchor {
+        name: String,
+    },
+}
+
+/// Returns the pages in navigation order as a JSON array of their document
+/// models, for tooling that renders or diffs the docs without parsing
+/// markdown
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name
+/// * `links` - how pages are named
+pub fn to_json(contents: &HashMap<String, String>, links: &Links) -> String {
+    let pages: Vec<Page> = get_nav_pages(contents)
+        .into_iter()
+        .map(|name| to_page(name, &contents[name], links))
+        .collect();
+    let mut json = serde_json::to_string_pretty(&pages).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+/// Returns a page's document model
+///
+/// # Arguments
+///
+/// * `name` - the page's name
+/// * `markdown` - the page
+/// * `links` - how pages are named
+pub fn to_page(name: &str, markdown: &str, links: &Links) -> Page {
+    let mut page = Page {
+        name: name.to_string(),
+        file: links.page_file(name),
+        blocks: Vec::new(),
+        sections: Vec::new(),
+    };
+    // The sections whose headings are open, outermost first
+    let mut open: Vec<Section> = Vec::new();
+    for block in parse_blocks(markdown) {
+        match block {
+            Block::Heading(level, text) => {
+                close_sections(&mut open, &mut page, level);
+                let mut anchor = None;
+                let title = parse_inline(&text)
+                    .into_iter()
+                    .filter(|inline| match inline {
+                        Inline::Anchor(name) => {
+                            anchor = Some(name.clone());
+                            false
+                        }
+                        _ => true,
+                    })
+                    .map(to_span)
+                    .collect::<Vec<Span>>();
+                open.push(Section {
+                    level,
+                    anchor,
+                    title: trim_spans(title),
+                    blocks: Vec::new(),
+                    sections: Vec::new(),
+                });
+            }
+            block => match open.last_mut() {
+                Some(section) => section.blocks.push(to_node(block)),
+                None => page.blocks.push(to_node(block)),
+            },
+        }
+    }
+    close_sections(&mut open, &mut page, 0);
+    page
+}
+
+// Moves the open sections at the level or deeper into their parents, or into
+// the page if they have none
+fn close_sections(open: &mut Vec<Section>, page: &mut Page, level: usize) {
+    while open.last().is_some_and(|section| section.level >= level) {
+        let section = open.pop().unwrap();
+        match open.last_mut() {
+            Some(parent) => parent.sections.push(section),
+            None => page.sections.push(section),
+        }
+    }
+}
+
+// Drops the space left between a heading's anchor and its text
+fn trim_spans(mut spans: Vec<Span>) -> Vec<Span> {
+    if let Some(Span::Text { text }) = spans.first_mut() {
+        *text = text.trim_start().to_string();
+    }
+    spans.retain(|span| !matches!(span, Span::Text { text } if text.is_empty()));
+    spans
+}
+
+fn to_node(block: Block) -> Node {
+    match block {
+        Block::Heading(level, text) => Node::Heading {
+            level,
+            content: to_spans(&text),
+        },
+        Block::Paragraph(text) => Node::Paragraph {
+            content: to_spans(&text),
+        },
+        Block::Notice(text) => Node::Notice {
+            content: to_spans(&text),
+        },
+        Block::List(items) => Node::List {
+            items: items.iter().map(|item| to_spans(item)).collect(),
+        },
+        Block::Table(rows) => {
+            let mut rows = rows
+                .iter()
+                .map(|row| row.iter().map(|cell| to_spans(cell)).collect());
+            Node::Table {
+                header: rows.next().unwrap_or_default(),
+                rows: rows.collect(),
+            }
+        }
+        Block::Quote(blocks) => Node::Quote {
+            blocks: blocks.into_iter().map(to_node).collect(),
+        },
+        Block::Code { language, code } => Node::Code { language, code },
+    }
+}
+
+fn to_spans(text: &str) -> Vec<Span> {
+    parse_inline(text).into_iter().map(to_span).collect()
+}
+
+fn to_span(inline: Inline) -> Span {
+    match inline {
+        Inline::Text(text) => Span::Text { text },
+        Inline::Code(code) => Span::Code { code },
+        Inline::Strong(inlines) => Span::Strong {
+            content: inlines.into_iter().map(to_span).collect(),
+        },
+        Inline::Link { text, destination } => Span::Link {
+            content: text.into_iter().map(to_span).collect(),
+            destination,
+        },
+        Inline::Anchor(name) => Span::Anchor { name },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_page_should_nest_sections_under_headings() {
+        let page = to_page(
+            "objects",
+            "# Objects\n\n## <a name=\"player\"></a>Player\n\n> A player\n\n| Field | Type |\n| --- | --- |\n| name | [`String`](scalars.md#string) |\n\n### Example\n\n```graphql\n# not a heading\n```\n\n## Team {#team}\n\n",
+            &Links::default(),
+        );
+        assert_eq!(
+            Page {
+                name: "objects".to_string(),
+                file: "objects.md".to_string(),
+                blocks: Vec::new(),
+                sections: vec![Section {
+                    level: 1,
+                    anchor: None,
+                    title: vec![Span::Text {
+                        text: "Objects".to_string()
+                    }],
+                    blocks: Vec::new(),
+                    sections: vec![
+                        Section {
+                            level: 2,
+                            anchor: Some("player".to_string()),
+                            title: vec![Span::Text {
+                                text: "Player".to_string()
+                            }],
+                            blocks: vec![
+                                Node::Quote {
+                                    blocks: vec![Node::Paragraph {
+                                        content: vec![Span::Text {
+                                            text: "A player".to_string()
+                                        }]
+                                    }]
+                                },
+                                Node::Table {
+                                    header: vec![
+                                        vec![Span::Text {
+                                            text: "Field".to_string()
+                                        }],
+                                        vec![Span::Text {
+                                            text: "Type".to_string()
+                                        }]
+                                    ],
+                                    rows: vec![vec![
+                                        vec![Span::Text {
+                                            text: "name".to_string()
+                                        }],
+                                        vec![Span::Link {
+                                            content: vec![Span::Code {
+                                                code: "String".to_string()
+                                            }],
+                                            destination: "scalars.md#string".to_string()
+                                        }]
+                                    ]]
+                                }
+                            ],
+                            sections: vec![Section {
+                                level: 3,
+                                anchor: None,
+                                title: vec![Span::Text {
+                                    text: "Example".to_string()
+                                }],
+                                blocks: vec![Node::Code {
+                                    language: "graphql".to_string(),
+                                    code: "# not a heading".to_string()
+                                }],
+                                sections: Vec::new(),
+                            }]
+                        },
+                        Section {
+                            level: 2,
+                            anchor: Some("team".to_string()),
+                            title: vec![Span::Text {
+                                text: "Team".to_string()
+                            }],
+                            blocks: Vec::new(),
+                            sections: Vec::new(),
+                        }
+                    ]
+                }]
+            },
+            page
+        );
+    }
+
+    #[test]
+    fn to_json_should_tag_nodes_by_type() {
+        let mut contents = HashMap::new();
+        contents.insert("scalars".to_string(), "_Deprecated_\n".to_string());
+        contents.insert("objects".to_string(), String::new());
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(&contents, &Links::default())).unwrap();
+        assert_eq!(
+            serde_json::json!([{
+                "name": "scalars",
+                "file": "scalars.md",
+                "blocks": [{
+                    "type": "notice",
+                    "content": [{"type": "text", "text": "Deprecated"}]
+                }],
+                "sections": []
+            }]),
+            json
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use std::{cell::Cell, error::Error};
+
+/// A running count of bytes against --max-output-size, so gumwood stops as
+/// soon as the output passes the limit instead of after it's all made
+#[derive(Debug, Default)]
+pub struct Budget {
+    limit: Option<u64>,
+    spent: Cell<u64>,
+}
+
+impl Budget {
+    /// Returns a budget of the specified number of bytes, or an unlimited
+    /// one if there's no limit
+    pub fn new(limit: Option<u64>) -> Budget {
+        Budget {
+            limit,
+            spent: Cell::new(0),
+        }
+    }
+
+    /// Counts the bytes against the budget, failing once they pass it
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - how many bytes were rendered or are about to be written
+    pub fn spend(&self, bytes: usize) -> Result<(), Box<dyn Error>> {
+        let spent = self.spent.get().saturating_add(bytes as u64);
+        self.spent.set(spent);
+        match self.limit {
+            Some(limit) if spent > limit => Err(format!(
+                "the docs would be more than --max-output-size {} bytes; check the source, or raise the limit",
+                limit
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spend_should_fail_once_past_limit() {
+        let budget = Budget::new(Some(10));
+        assert!(budget.spend(6).is_ok());
+        assert!(budget.spend(4).is_ok());
+        assert!(budget.spend(1).is_err());
+    }
+
+    #[test]
+    fn spend_should_never_fail_without_limit() {
+        let budget = Budget::default();
+        assert!(budget.spend(usize::MAX).is_ok());
+        assert!(budget.spend(usize::MAX).is_ok());
+    }
+}
@@ -0,0 +1,163 @@
+use super::schema::Type;
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+/// The file in --cache-dir that holds the cache
+pub const CACHE_FILE: &str = "render-cache.json";
+
+/// Rendered types from earlier runs, keyed by a hash of each type's
+/// introspection data and the settings it was rendered with, so a run only
+/// renders the types that changed
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    enabled: bool,
+    settings: String,
+    previous: HashMap<String, String>,
+    current: HashMap<String, String>,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl RenderCache {
+    /// Loads the cache from the directory, starting empty if there isn't
+    /// one yet or it can't be read, since it can always be rebuilt
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - the cache directory
+    /// * `settings` - everything besides the type that affects how it renders
+    pub fn load(dir: &Path, settings: &str) -> RenderCache {
+        let previous = fs::read_to_string(dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        RenderCache {
+            enabled: true,
+            settings: format!("{} {}", env!("CARGO_PKG_VERSION"), settings),
+            previous,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the type's markdown from the cache, or renders it
+    ///
+    /// # Arguments
+    ///
+    /// * `typ` - the type
+    /// * `render` - renders the type when it isn't cached
+    pub fn render<F: FnOnce() -> String>(&mut self, typ: &Type, render: F) -> String {
+        if !self.enabled {
+            return render();
+        }
+        let mut bytes = self.settings.clone().into_bytes();
+        bytes.extend(serde_json::to_vec(typ).unwrap_or_default());
+        let key = format!("{:016x}", fnv1a_64(&bytes));
+
+        let markdown = match self.previous.get(&key) {
+            Some(markdown) => {
+                self.hits += 1;
+                markdown.clone()
+            }
+            None => {
+                self.misses += 1;
+                render()
+            }
+        };
+        self.current.insert(key, markdown.clone());
+        markdown
+    }
+
+    /// Writes the types rendered this run to the directory, dropping any
+    /// that weren't used
+    pub fn save(&self, dir: &Path) -> Result<(), Box<dyn Error>> {
+        if !self.enabled {
+            return Ok(());
+        }
+        fs::create_dir_all(dir)?;
+        let file = dir.join(CACHE_FILE);
+        fs::write(&file, serde_json::to_string(&self.current)?)
+            .map_err(|err| format!("can't write cache {}: {}", file.display(), err).into())
+    }
+}
+
+// 64 bits, so thousands of types don't collide the way they could in 32
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn player(description: &str) -> Type {
+        Type {
+            name: Some("Player".to_string()),
+            kind: Some("OBJECT".to_string()),
+            description: Some(description.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn render_should_reuse_unchanged_types() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-cache-test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut cache = RenderCache::load(&dir, "icons");
+        assert_eq!("first", cache.render(&player("A"), || "first".to_string()));
+        cache.save(&dir)?;
+
+        let mut cache = RenderCache::load(&dir, "icons");
+        assert_eq!("first", cache.render(&player("A"), || "second".to_string()));
+        assert_eq!("third", cache.render(&player("B"), || "third".to_string()));
+        assert_eq!((1, 1), (cache.hits, cache.misses));
+        Ok(())
+    }
+
+    #[test]
+    fn save_should_drop_unused_types() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-cache-prune-test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut cache = RenderCache::load(&dir, "icons");
+        cache.render(&player("A"), || "first".to_string());
+        cache.save(&dir)?;
+
+        let mut cache = RenderCache::load(&dir, "icons");
+        cache.render(&player("B"), || "second".to_string());
+        cache.save(&dir)?;
+
+        let mut cache = RenderCache::load(&dir, "icons");
+        assert_eq!("third", cache.render(&player("A"), || "third".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn render_should_miss_when_settings_change() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-cache-settings-test");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut cache = RenderCache::load(&dir, "icons");
+        cache.render(&player("A"), || "plain".to_string());
+        cache.save(&dir)?;
+
+        let mut cache = RenderCache::load(&dir, "emoji");
+        assert_eq!("emoji", cache.render(&player("A"), || "emoji".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn render_should_always_render_when_disabled() {
+        let mut cache = RenderCache::default();
+        assert_eq!("a", cache.render(&player("A"), || "a".to_string()));
+        assert_eq!("b", cache.render(&player("A"), || "b".to_string()));
+    }
+
+    #[test]
+    fn fnv1a_64_should_hash_known_value() {
+        assert_eq!(0xcbf2_9ce4_8422_2325, fnv1a_64(b""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a_64(b"a"));
+    }
+}
@@ -0,0 +1,194 @@
+use super::budget::Budget;
+use super::schema::{Field, Input, Schema};
+use serde::Serialize;
+use std::{error::Error, fs, path::Path};
+
+/// Every operation the API exposes, in a shape API portals and gateways
+/// can ingest without knowing GraphQL
+#[derive(Debug, Serialize)]
+pub struct Catalog {
+    pub description: Option<String>,
+    pub operations: Vec<Operation>,
+}
+
+/// A query, mutation, or subscription
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    pub operation_type: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<Argument>,
+    pub return_type: String,
+    pub deprecated: bool,
+    pub deprecation_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cost: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Argument {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub arg_type: String,
+    pub default_value: Option<String>,
+}
+
+impl Catalog {
+    /// Builds the catalog from the schema's query, mutation, and
+    /// subscription types, sorted by operation type and name
+    pub fn from_schema(schema: &Schema) -> Catalog {
+        let mut operations = Vec::new();
+        for (operation_type, type_name) in &[
+            ("query", schema.get_query_name()),
+            ("mutation", schema.get_mutation_name()),
+            ("subscription", schema.get_subscription_name()),
+        ] {
+            let fields = type_name
+                .as_ref()
+                .and_then(|name| schema.get_type(name))
+                .and_then(|typ| typ.fields.as_ref());
+            let mut ops: Vec<Operation> = fields
+                .iter()
+                .copied()
+                .flatten()
+                .map(|field| to_operation(operation_type, field))
+                .collect();
+            ops.sort_by(|a, b| a.name.cmp(&b.name));
+            operations.extend(ops);
+        }
+
+        Catalog {
+            description: schema.description.clone(),
+            operations,
+        }
+    }
+
+    /// Writes the catalog as pretty-printed JSON, counting it against the
+    /// output budget
+    pub fn write(&self, file: &Path, budget: &Budget) -> Result<(), Box<dyn Error>> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        budget.spend(json.len())?;
+        fs::write(file, json)
+            .map_err(|err| format!("can't write catalog {}: {}", file.display(), err).into())
+    }
+}
+
+fn to_operation(operation_type: &str, field: &Field) -> Operation {
+    Operation {
+        operation_type: operation_type.to_string(),
+        name: field.name.clone().unwrap_or_default(),
+        description: field.description.clone(),
+        arguments: field.args.iter().flatten().map(to_argument).collect(),
+        return_type: field
+            .field_type
+            .as_ref()
+            .map(|typ| typ.get_decorated_name())
+            .unwrap_or_default(),
+        deprecated: field.is_deprecated == Some(true),
+        deprecation_reason: field.deprecation_reason.clone(),
+        estimated_cost: field.cost,
+    }
+}
+
+fn to_argument(input: &Input) -> Argument {
+    Argument {
+        name: input.name.clone().unwrap_or_default(),
+        description: input.description.clone(),
+        arg_type: input
+            .input_type
+            .as_ref()
+            .map(|typ| typ.get_decorated_name())
+            .unwrap_or_default(),
+        default_value: input.default_value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn from_schema_should_list_operations() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+                "Finds a player"
+                player(id: ID!, active: Boolean = true): Player
+                old: Int @deprecated(reason: "Use player")
+            }
+            type Mutation { addPlayer(name: String!): Player! @cost(weight: "10") }
+            type Player { id: ID! }
+            "#,
+        )?;
+        let catalog = Catalog::from_schema(&schema);
+        assert_eq!(
+            json!({
+                "description": null,
+                "operations": [
+                    {
+                        "operationType": "query",
+                        "name": "old",
+                        "description": null,
+                        "arguments": [],
+                        "returnType": "Int",
+                        "deprecated": true,
+                        "deprecationReason": "Use player"
+                    },
+                    {
+                        "operationType": "query",
+                        "name": "player",
+                        "description": "Finds a player",
+                        "arguments": [
+                            {
+                                "name": "id",
+                                "description": null,
+                                "type": "ID!",
+                                "defaultValue": null
+                            },
+                            {
+                                "name": "active",
+                                "description": null,
+                                "type": "Boolean",
+                                "defaultValue": "true"
+                            }
+                        ],
+                        "returnType": "Player",
+                        "deprecated": false,
+                        "deprecationReason": null
+                    },
+                    {
+                        "operationType": "mutation",
+                        "name": "addPlayer",
+                        "description": null,
+                        "arguments": [
+                            {
+                                "name": "name",
+                                "description": null,
+                                "type": "String!",
+                                "defaultValue": null
+                            }
+                        ],
+                        "returnType": "Player!",
+                        "deprecated": false,
+                        "deprecationReason": null,
+                        "estimatedCost": 10
+                    }
+                ]
+            }),
+            serde_json::to_value(&catalog)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_schema_should_be_empty_without_operations() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl("type Player { id: ID! }")?;
+        assert!(Catalog::from_schema(&schema).operations.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::{error::Error, io::ErrorKind, process::Command};
+
+// The commands that print the clipboard, in the order to try them
+#[cfg(target_os = "macos")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+
+#[cfg(windows)]
+const PASTE_COMMANDS: &[&[&str]] = &[&[
+    "powershell",
+    "-NoProfile",
+    "-Command",
+    "[Console]::OutputEncoding = [Text.Encoding]::UTF8; Get-Clipboard -Raw",
+]];
+
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Returns the text on the system clipboard, read with the platform's paste
+/// command: pbpaste on macOS, PowerShell on Windows, and wl-paste, xclip, or
+/// xsel elsewhere
+pub fn read() -> Result<String, Box<dyn Error>> {
+    read_from(PASTE_COMMANDS)
+}
+
+fn read_from(commands: &[&[&str]]) -> Result<String, Box<dyn Error>> {
+    let mut failures = Vec::new();
+    for command in commands {
+        let output = match Command::new(command[0]).args(&command[1..]).output() {
+            Ok(output) => output,
+            // Not installed, so try the next one
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(format!("can't run {}: {}", command[0], err).into()),
+        };
+        if !output.status.success() {
+            // wl-paste fails outside Wayland, and xclip and xsel outside X
+            failures.push(format!(
+                "{}: {}",
+                command[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+            continue;
+        }
+        let text = String::from_utf8(output.stdout)
+            .map_err(|_| "the clipboard doesn't contain text".to_string())?;
+        if text.trim().is_empty() {
+            return Err("the clipboard is empty".into());
+        }
+        return Ok(text);
+    }
+    let programs: Vec<&str> = commands.iter().map(|command| command[0]).collect();
+    if failures.is_empty() {
+        Err(format!(
+            "can't read the clipboard; install {}",
+            programs.join(" or ")
+        )
+        .into())
+    } else {
+        Err(format!("can't read the clipboard ({})", failures.join("; ")).into())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_from_should_skip_missing_commands() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            "type Query { a: Int }",
+            read_from(&[
+                &["gumwood-missing-paste"],
+                &["printf", "type Query { a: Int }"]
+            ])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_from_should_fail_when_nothing_works() {
+        assert!(read_from(&[&["gumwood-missing-paste"]])
+            .unwrap_err()
+            .to_string()
+            .contains("install gumwood-missing-paste"));
+        assert!(read_from(&[&["false"]])
+            .unwrap_err()
+            .to_string()
+            .starts_with("can't read the clipboard (false"));
+        assert_eq!(
+            "the clipboard is empty",
+            read_from(&[&["printf", " \n"]]).unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,655 @@
+use super::markdown::{to_header, to_inline_code, to_table_row, to_table_separator};
+use super::schema::{Input, Schema, Type, TypeRef};
+use super::sdl::BUILT_IN_SCALARS;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    str::FromStr,
+};
+
+// What a field, input field, or enum value looks like in one of the types
+#[derive(Debug, PartialEq)]
+struct Member {
+    name: String,
+    signature: String,
+    is_deprecated: bool,
+}
+
+/// Returns a page with a table comparing the fields, input fields, or enum
+/// values of two types side by side, in the order of the first type and
+/// then the members only the second has
+///
+/// # Arguments
+///
+/// * `schema` - the schema that defines both types
+/// * `from` - the name of the type to compare from, such as the old version
+/// * `to` - the name of the type to compare to, such as the new version
+pub fn compare_types(schema: &Schema, from: &str, to: &str) -> Result<String, Box<dyn Error>> {
+    let from_members = get_members(schema, from)?;
+    let to_members = get_members(schema, to)?;
+
+    let mut s = to_header(
+        1,
+        &format!("{} vs. {}", to_inline_code(from), to_inline_code(to)),
+    );
+    s.push_str(&to_table_row(&[
+        "Name".to_string(),
+        to_inline_code(from),
+        to_inline_code(to),
+        "Change".to_string(),
+    ]));
+    s.push_str(&to_table_separator(4));
+
+    let find = |members: &[Member], name: &str| -> Option<usize> {
+        members.iter().position(|member| member.name == name)
+    };
+    let mut names: Vec<&str> = from_members.iter().map(|m| m.name.as_str()).collect();
+    names.extend(
+        to_members
+            .iter()
+            .filter(|m| find(&from_members, &m.name).is_none())
+            .map(|m| m.name.as_str()),
+    );
+    for name in names {
+        let before = find(&from_members, name).map(|i| &from_members[i]);
+        let after = find(&to_members, name).map(|i| &to_members[i]);
+        s.push_str(&to_table_row(&[
+            to_inline_code(name),
+            to_cell(before),
+            to_cell(after),
+            get_change(before, after)
+                .map_or("", Change::label)
+                .to_string(),
+        ]));
+    }
+    Ok(s)
+}
+
+// Returns how a member changed from one type to the other, if it did
+fn get_change(before: Option<&Member>, after: Option<&Member>) -> Option<Change> {
+    match (before, after) {
+        (Some(_), None) => Some(Change::Removed),
+        (None, Some(_)) => Some(Change::Added),
+        (Some(before), Some(after)) if before.signature != after.signature => Some(Change::Changed),
+        (Some(before), Some(after)) if !before.is_deprecated && after.is_deprecated => {
+            Some(Change::Deprecated)
+        }
+        _ => None,
+    }
+}
+
+/// How an element changed between two versions of a schema, from most to
+/// least likely to break clients
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Change {
+    Removed,
+    Changed,
+    Deprecated,
+    Added,
+}
+
+impl Change {
+    fn label(self) -> &'static str {
+        match self {
+            Change::Removed => "Removed",
+            Change::Changed => "Changed",
+            Change::Deprecated => "Deprecated",
+            Change::Added => "Added",
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Change::Removed => "🔴",
+            Change::Changed => "🟠",
+            Change::Deprecated => "🟡",
+            Change::Added => "🟢",
+        }
+    }
+}
+
+/// A type, field, input field, or enum value that differs between two
+/// versions of a schema
+#[derive(Debug, PartialEq)]
+pub struct SchemaChange {
+    /// The schema coordinate, such as `Player.name`
+    pub coordinate: String,
+    pub change: Change,
+    /// The kind of a type, or the signature of a member, in the old schema
+    pub before: Option<String>,
+    /// The kind of a type, or the signature of a member, in the new schema
+    pub after: Option<String>,
+}
+
+/// How `diff` writes the changes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffFormat {
+    /// A page with a table of the changes
+    Markdown,
+    /// A pull request comment: a count of each kind of change, then a
+    /// collapsible section for each, with breaking changes expanded
+    GithubComment,
+}
+
+impl FromStr for DiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" => Ok(DiffFormat::Markdown),
+            "github-comment" => Ok(DiffFormat::GithubComment),
+            _ => Err(format!("invalid diff format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for DiffFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiffFormat::Markdown => write!(f, "markdown"),
+            DiffFormat::GithubComment => write!(f, "github-comment"),
+        }
+    }
+}
+
+// Starts every GitHub comment, so a bot can find its earlier comment and
+// update it instead of adding another
+const COMMENT_MARKER: &str = "<!-- gumwood-schema-diff -->";
+
+/// Returns the types and members that were removed, changed, deprecated,
+/// or added between two versions of a schema, sorted by kind of change and
+/// then by coordinate. The members of removed and added types are left to
+/// the type's change, and built-in scalars, which schemas only list when
+/// they use them, aren't compared.
+///
+/// # Arguments
+///
+/// * `old` - the schema to compare from, such as the main branch's
+/// * `new` - the schema to compare to, such as a pull request's
+pub fn diff_schemas(old: &Schema, new: &Schema) -> Vec<SchemaChange> {
+    let names = |schema: &Schema| -> Vec<String> {
+        schema
+            .types
+            .iter()
+            .flatten()
+            .filter_map(|typ| typ.name.clone())
+            .filter(|name| !name.starts_with("__") && !BUILT_IN_SCALARS.contains(&name.as_str()))
+            .collect()
+    };
+    let mut names: Vec<String> = names(old).into_iter().chain(names(new)).collect();
+    names.sort();
+    names.dedup();
+
+    let mut changes = Vec::new();
+    for name in names {
+        let kind = |typ: &Type| typ.kind.clone().unwrap_or_default();
+        match (old.get_type(&name), new.get_type(&name)) {
+            (Some(before), None) => changes.push(SchemaChange {
+                coordinate: name,
+                change: Change::Removed,
+                before: Some(kind(before)),
+                after: None,
+            }),
+            (None, Some(after)) => changes.push(SchemaChange {
+                coordinate: name,
+                change: Change::Added,
+                before: None,
+                after: Some(kind(after)),
+            }),
+            (Some(before), Some(after)) if before.kind != after.kind => {
+                changes.push(SchemaChange {
+                    coordinate: name,
+                    change: Change::Changed,
+                    before: Some(kind(before)),
+                    after: Some(kind(after)),
+                })
+            }
+            (Some(before), Some(after)) => {
+                let before = to_members(before);
+                let after = to_members(after);
+                let find = |members: &[Member], name: &str| -> Option<usize> {
+                    members.iter().position(|member| member.name == name)
+                };
+                let mut members: Vec<&str> = before.iter().map(|m| m.name.as_str()).collect();
+                members.extend(
+                    after
+                        .iter()
+                        .filter(|m| find(&before, &m.name).is_none())
+                        .map(|m| m.name.as_str()),
+                );
+                for member in members {
+                    let before = find(&before, member).map(|i| &before[i]);
+                    let after = find(&after, member).map(|i| &after[i]);
+                    if let Some(change) = get_change(before, after) {
+                        changes.push(SchemaChange {
+                            coordinate: format!("{}.{}", name, member),
+                            change,
+                            before: before.map(|member| member.signature.clone()),
+                            after: after.map(|member| member.signature.clone()),
+                        });
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    changes.sort_by(|a, b| (a.change, &a.coordinate).cmp(&(b.change, &b.coordinate)));
+    changes
+}
+
+/// Returns the changes as a page with a table, or as a comment to post on
+/// a pull request
+///
+/// # Arguments
+///
+/// * `changes` - the changes, from `diff_schemas`
+/// * `format` - how to write them
+pub fn diff_to_markdown(changes: &[SchemaChange], format: DiffFormat) -> String {
+    match format {
+        DiffFormat::Markdown => {
+            let mut s = to_header(1, "Schema Changes");
+            if changes.is_empty() {
+                s.push_str("No changes\n");
+                return s;
+            }
+            let changes: Vec<&SchemaChange> = changes.iter().collect();
+            s.push_str(&to_change_table(&changes, true));
+            s
+        }
+        DiffFormat::GithubComment => to_github_comment(changes),
+    }
+}
+
+fn to_github_comment(changes: &[SchemaChange]) -> String {
+    let mut s = format!(
+        "{}\n{}",
+        COMMENT_MARKER,
+        to_header(3, "GraphQL schema changes")
+    );
+    if changes.is_empty() {
+        s.push_str("No changes\n");
+        return s;
+    }
+    let kinds = [
+        Change::Removed,
+        Change::Changed,
+        Change::Deprecated,
+        Change::Added,
+    ];
+    let counts: Vec<String> = kinds
+        .iter()
+        .map(|kind| (kind, changes.iter().filter(|c| c.change == *kind).count()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| format!("{} {} {}", kind.emoji(), count, kind.label().to_lowercase()))
+        .collect();
+    s.push_str(&format!("{}\n", counts.join(" · ")));
+    for kind in kinds.iter() {
+        let section: Vec<&SchemaChange> = changes.iter().filter(|c| c.change == *kind).collect();
+        if section.is_empty() {
+            continue;
+        }
+        // Readers should see what breaks clients without clicking
+        let open = if *kind <= Change::Changed {
+            " open"
+        } else {
+            ""
+        };
+        s.push_str(&format!(
+            "\n<details{}>\n<summary>{} {} ({})</summary>\n\n",
+            open,
+            kind.emoji(),
+            kind.label(),
+            section.len()
+        ));
+        s.push_str(&to_change_table(&section, false));
+        s.push_str("\n</details>\n");
+    }
+    s
+}
+
+/// Compares the schemas of several environments and returns a note, such
+/// as `_Only in staging_`, for each element that only some of them have,
+/// keyed by schema coordinate. Members of a type that only some have are
+/// left to the type's note, and built-in scalars, which schemas only list
+/// when they use them, aren't noted.
+///
+/// # Arguments
+///
+/// * `schemas` - each environment's name and schema
+pub fn get_environment_notes(schemas: &mut [(String, Schema)]) -> HashMap<String, String> {
+    let mut found: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, schema) in schemas.iter_mut() {
+        let mut coordinates = HashSet::new();
+        schema.for_each_element_mut(&mut |coordinate, _| {
+            coordinates.insert(coordinate.to_string());
+        });
+        for coordinate in coordinates {
+            found.entry(coordinate).or_default().push(name.clone());
+        }
+    }
+
+    let partial: HashMap<String, Vec<String>> = found
+        .into_iter()
+        .filter(|(coordinate, names)| {
+            names.len() < schemas.len() && !BUILT_IN_SCALARS.contains(&coordinate.as_str())
+        })
+        .collect();
+    partial
+        .iter()
+        .filter(|(coordinate, _)| match get_parent(coordinate) {
+            Some(parent) => !partial.contains_key(parent),
+            None => true,
+        })
+        .map(|(coordinate, names)| {
+            let names: Vec<&str> = schemas
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .filter(|name| names.iter().any(|n| n == name))
+                .collect();
+            (
+                coordinate.clone(),
+                format!("_Only in {}_", to_series(&names)),
+            )
+        })
+        .collect()
+}
+
+// Returns the coordinate of the element that holds this one: the type of a
+// field, or the field or directive of an argument
+fn get_parent(coordinate: &str) -> Option<&str> {
+    match coordinate.split_once('(') {
+        Some((parent, _)) => Some(parent),
+        None => coordinate.rsplit_once('.').map(|(parent, _)| parent),
+    }
+}
+
+// Joins the names as in prose: a, b, and c
+fn to_series(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.to_string(),
+        [first, second] => format!("{} and {}", first, second),
+        [rest @ .., last] => format!("{}, and {}", rest.join(", "), last),
+    }
+}
+
+// Returns a table of the changes, with a column for the kind of change if
+// they aren't all of one kind
+fn to_change_table(changes: &[&SchemaChange], with_change: bool) -> String {
+    let mut headers = vec![
+        "Element".to_string(),
+        "Before".to_string(),
+        "After".to_string(),
+    ];
+    if with_change {
+        headers.push("Change".to_string());
+    }
+    let mut s = to_table_row(&headers);
+    s.push_str(&to_table_separator(headers.len()));
+    let to_cell = |signature: &Option<String>| {
+        signature
+            .as_deref()
+            .map_or("&mdash;".to_string(), to_inline_code)
+    };
+    for change in changes {
+        let mut row = vec![
+            to_inline_code(&change.coordinate),
+            to_cell(&change.before),
+            to_cell(&change.after),
+        ];
+        if with_change {
+            row.push(change.change.label().to_string());
+        }
+        s.push_str(&to_table_row(&row));
+    }
+    s
+}
+
+fn get_members(schema: &Schema, name: &str) -> Result<Vec<Member>, Box<dyn Error>> {
+    schema
+        .get_type(name)
+        .map(to_members)
+        .ok_or_else(|| format!("type {} not in schema", name).into())
+}
+
+fn to_members(typ: &Type) -> Vec<Member> {
+    let mut members = Vec::new();
+    for field in typ.fields.iter().flatten() {
+        let field_type = get_type_name(&field.field_type);
+        let signature = match field.args.as_deref() {
+            Some(args) if !args.is_empty() => format!("({}): {}", to_args(args), field_type),
+            _ => field_type,
+        };
+        members.push(Member {
+            name: field.name.clone().unwrap_or_default(),
+            signature,
+            is_deprecated: field.is_deprecated.unwrap_or(false),
+        });
+    }
+    for input in typ.inputs.iter().flatten() {
+        members.push(Member {
+            name: input.name.clone().unwrap_or_default(),
+            signature: get_type_name(&input.input_type),
+            is_deprecated: false,
+        });
+    }
+    for value in typ.enums.iter().flatten() {
+        let name = value.name.clone().unwrap_or_default();
+        members.push(Member {
+            signature: name.clone(),
+            name,
+            is_deprecated: value.is_deprecated.unwrap_or(false),
+        });
+    }
+    members
+}
+
+fn get_type_name(type_ref: &Option<TypeRef>) -> String {
+    type_ref
+        .as_ref()
+        .map(|type_ref| type_ref.get_decorated_name())
+        .unwrap_or_default()
+}
+
+fn to_args(args: &[Input]) -> String {
+    args.iter()
+        .map(|arg| {
+            format!(
+                "{}: {}",
+                arg.name.as_deref().unwrap_or_default(),
+                get_type_name(&arg.input_type)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn to_cell(member: Option<&Member>) -> String {
+    match member {
+        Some(member) if member.is_deprecated => {
+            format!("{} (deprecated)", to_inline_code(&member.signature))
+        }
+        Some(member) => to_inline_code(&member.signature),
+        None => "&mdash;".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_types_should_show_fields_side_by_side() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type PlayerV1 {
+              id: ID!
+              name: String
+              age: Int
+              teams(first: Int): [String]
+              number: Int
+            }
+            type Player {
+              id: ID!
+              name: String!
+              teams(first: Int, after: String): [String]
+              number: Int @deprecated
+              position: String
+            }
+            "#,
+        )?;
+        assert_eq!(
+            r#"# `PlayerV1` vs. `Player`
+
+| Name | `PlayerV1` | `Player` | Change |
+| --- | --- | --- | --- |
+| `id` | `ID!` | `ID!` |  |
+| `name` | `String` | `String!` | Changed |
+| `age` | `Int` | &mdash; | Removed |
+| `teams` | `(first: Int): [String]` | `(first: Int, after: String): [String]` | Changed |
+| `number` | `Int` | `Int` (deprecated) | Deprecated |
+| `position` | &mdash; | `String` | Added |
+"#,
+            compare_types(&schema, "PlayerV1", "Player")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compare_types_should_compare_inputs_and_enum_values() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            input FilterV1 { name: String }
+            input Filter { name: [String!] }
+            enum Status { ACTIVE RETIRED }
+            "#,
+        )?;
+        assert!(compare_types(&schema, "FilterV1", "Filter")?
+            .contains("| `name` | `String` | `[String!]` | Changed |\n"));
+        assert!(compare_types(&schema, "Status", "FilterV1")?
+            .contains("| `ACTIVE` | `ACTIVE` | &mdash; | Removed |\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn get_environment_notes_should_note_elements_only_some_have() -> Result<(), Box<dyn Error>> {
+        let mut schemas = vec![
+            (
+                "staging".to_string(),
+                Schema::from_sdl(
+                    "type Query { player(id: ID, season: Int): Player }\ntype Player { name: String rookie: Boolean }\ntype Team { name: String }",
+                )?,
+            ),
+            (
+                "acme".to_string(),
+                Schema::from_sdl(
+                    "type Query { player(id: ID, season: Int): Player }\ntype Player { name: String rookie: Boolean }",
+                )?,
+            ),
+            (
+                "production".to_string(),
+                Schema::from_sdl(
+                    "type Query { player(id: ID): Player }\ntype Player { name: String }",
+                )?,
+            ),
+        ];
+        let notes = get_environment_notes(&mut schemas);
+        assert_eq!(
+            Some("_Only in staging and acme_"),
+            notes.get("Player.rookie").map(String::as_str)
+        );
+        assert_eq!(
+            Some("_Only in staging and acme_"),
+            notes.get("Query.player(season:)").map(String::as_str)
+        );
+        assert_eq!(
+            Some("_Only in staging_"),
+            notes.get("Team").map(String::as_str)
+        );
+        assert!(!notes.contains_key("Team.name"));
+        assert!(!notes.contains_key("Player.name"));
+        assert_eq!(3, notes.len());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_schemas_should_write_a_github_comment() -> Result<(), Box<dyn Error>> {
+        let old = Schema::from_sdl(
+            r#"
+            type Query { player(id: ID!): Player }
+            type Player { name: String number: Int }
+            type Team { name: String }
+            "#,
+        )?;
+        let new = Schema::from_sdl(
+            r#"
+            type Query { player(id: ID!): Player, active: Boolean }
+            type Player { name: String! number: Int @deprecated }
+            "#,
+        )?;
+        assert_eq!(
+            r#"<!-- gumwood-schema-diff -->
+### GraphQL schema changes
+
+🔴 1 removed · 🟠 1 changed · 🟡 1 deprecated · 🟢 1 added
+
+<details open>
+<summary>🔴 Removed (1)</summary>
+
+| Element | Before | After |
+| --- | --- | --- |
+| `Team` | `OBJECT` | &mdash; |
+
+</details>
+
+<details open>
+<summary>🟠 Changed (1)</summary>
+
+| Element | Before | After |
+| --- | --- | --- |
+| `Player.name` | `String` | `String!` |
+
+</details>
+
+<details>
+<summary>🟡 Deprecated (1)</summary>
+
+| Element | Before | After |
+| --- | --- | --- |
+| `Player.number` | `Int` | `Int` |
+
+</details>
+
+<details>
+<summary>🟢 Added (1)</summary>
+
+| Element | Before | After |
+| --- | --- | --- |
+| `Query.active` | &mdash; | `Boolean` |
+
+</details>
+"#,
+            diff_to_markdown(&diff_schemas(&old, &new), DiffFormat::GithubComment)
+        );
+        assert_eq!(
+            "# Schema Changes\n\nNo changes\n",
+            diff_to_markdown(&diff_schemas(&new, &new), DiffFormat::Markdown)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_series_should_join_names_as_prose() {
+        assert_eq!("a", to_series(&["a"]));
+        assert_eq!("a and b", to_series(&["a", "b"]));
+        assert_eq!("a, b, and c", to_series(&["a", "b", "c"]));
+    }
+
+    #[test]
+    fn compare_types_should_fail_when_type_missing() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl("type Player { id: ID }")?;
+        assert!(compare_types(&schema, "Player", "Team").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+use super::links::AnchorStyle;
+use super::lint::Rules;
+use super::redact::Redact;
+use super::sanitize::Sanitize;
+use super::schema::{Schema, Type};
+use super::slug::SlugStyle;
+use super::tables::Sort;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The config file gumwood looks for in the current directory when
+/// --config isn't specified
+pub const DEFAULT_CONFIG_FILE: &str = "gumwood.toml";
+
+/// Settings that are too detailed for command-line flags, read from a
+/// TOML file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Display names for types, keyed by the name in the schema
+    pub aliases: HashMap<String, String>,
+
+    /// Icons to use instead of the icon set's, keyed by icon name
+    pub icons: HashMap<String, String>,
+
+    /// Type names and `Type.field` coordinates that require authorization
+    pub auth_required: Vec<String>,
+
+    /// Removal dates (YYYY-MM-DD) for deprecated fields and enum values,
+    /// keyed by schema coordinate
+    pub removals: HashMap<String, String>,
+
+    /// How names become anchors and page file names: lowercase, kebab, or
+    /// github
+    pub slugifier: SlugStyle,
+
+    /// How anchors are written on headings: html or heading-id
+    pub anchor_style: AnchorStyle,
+
+    /// The HTML --sanitize lets through descriptions
+    pub sanitize: Sanitize,
+
+    /// Default values to keep out of the docs
+    pub redact: Redact,
+
+    /// Which rules --lint runs
+    pub lint: Rules,
+
+    /// Deployments of the API to generate docs for with --env
+    pub environments: Vec<Environment>,
+
+    /// Types to treat as the root operation types, whatever the schema says
+    pub roots: Roots,
+
+    /// Which tables keep the schema's order instead of sorting by name
+    pub sort: Sort,
+}
+
+/// The names of the types that hold the queries, mutations, and
+/// subscriptions, for schemas whose introspection leaves them out
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Roots {
+    pub query: Option<String>,
+    pub mutation: Option<String>,
+    pub subscription: Option<String>,
+}
+
+/// A deployment of the API, such as staging or a tenant's, with where to
+/// introspect it and where to write its docs
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Environment {
+    pub name: String,
+    pub url: String,
+    /// `Name:Value` headers to send along with any --header flags
+    pub headers: Vec<String>,
+    pub out_dir: PathBuf,
+}
+
+impl Roots {
+    /// Makes the named types the schema's root operation types
+    pub fn apply(&self, schema: &mut Schema) -> Result<(), Box<dyn Error>> {
+        let types = schema.types.as_deref().unwrap_or_default();
+        for (key, name, root) in [
+            ("query", &self.query, &mut schema.query_type),
+            ("mutation", &self.mutation, &mut schema.mutation_type),
+            (
+                "subscription",
+                &self.subscription,
+                &mut schema.subscription_type,
+            ),
+        ] {
+            if let Some(name) = name {
+                if !types.iter().any(|typ| typ.name.as_ref() == Some(name)) {
+                    return Err(
+                        format!("roots.{} is {}, which isn't in the schema", key, name).into(),
+                    );
+                }
+                *root = Some(Type {
+                    name: Some(name.clone()),
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Loads the config from the specified file, or from gumwood.toml in the
+    /// current directory if it exists, or returns the default config
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - the config file specified on the command line, if any
+    pub fn load(file: &Option<PathBuf>) -> Result<Config, Box<dyn Error>> {
+        match file {
+            Some(file) => Config::from_file(file),
+            None => {
+                let default = Path::new(DEFAULT_CONFIG_FILE);
+                if default.exists() {
+                    Config::from_file(default)
+                } else {
+                    Ok(Config::default())
+                }
+            }
+        }
+    }
+
+    pub fn from_file(file: &Path) -> Result<Config, Box<dyn Error>> {
+        let contents = fs::read_to_string(file)
+            .map_err(|err| format!("can't read config {}: {}", file.display(), err))?;
+        Config::from_str(&contents)
+            .map_err(|err| format!("invalid config {}: {}", file.display(), err).into())
+    }
+
+    pub fn from_str(text: &str) -> Result<Config, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::SortOrder;
+
+    #[test]
+    fn from_str_should_return_default_when_empty() -> Result<(), Box<dyn Error>> {
+        let config = Config::from_str("")?;
+        assert!(config.aliases.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_aliases() -> Result<(), Box<dyn Error>> {
+        let config = Config::from_str("[aliases]\nPlayerV2 = \"Player\"\n")?;
+        assert_eq!("Player", config.aliases["PlayerV2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_icons_and_auth_required() -> Result<(), Box<dyn Error>> {
+        let config =
+            Config::from_str("auth_required = [\"Query.viewer\"]\n[icons]\ndeprecated = \"!!\"\n")?;
+        assert_eq!(vec!["Query.viewer".to_string()], config.auth_required);
+        assert_eq!("!!", config.icons["deprecated"]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_removals() -> Result<(), Box<dyn Error>> {
+        let config = Config::from_str("[removals]\n\"Player.number\" = \"2025-01-01\"\n")?;
+        assert_eq!("2025-01-01", config.removals["Player.number"]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_slugifier() -> Result<(), Box<dyn Error>> {
+        assert_eq!(SlugStyle::Lowercase, Config::from_str("")?.slugifier);
+        let config = Config::from_str("slugifier = \"kebab\"\n")?;
+        assert_eq!(SlugStyle::Kebab, config.slugifier);
+        assert!(Config::from_str("slugifier = \"snake\"\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_anchor_style() -> Result<(), Box<dyn Error>> {
+        assert_eq!(AnchorStyle::Html, Config::from_str("")?.anchor_style);
+        let config = Config::from_str("anchor_style = \"heading-id\"\n")?;
+        assert_eq!(AnchorStyle::HeadingId, config.anchor_style);
+        assert!(Config::from_str("anchor_style = \"kramdown\"\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_sanitize_allow_list() -> Result<(), Box<dyn Error>> {
+        assert!(Config::from_str("")?
+            .sanitize
+            .allowed_tags
+            .contains(&"b".to_string()));
+        let config = Config::from_str("[sanitize]\nallowed_tags = [\"div\"]\n")?;
+        assert_eq!(vec!["div".to_string()], config.sanitize.allowed_tags);
+        assert!(config
+            .sanitize
+            .allowed_attributes
+            .contains(&"href".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_redact() -> Result<(), Box<dyn Error>> {
+        let config =
+            Config::from_str("[redact]\npatterns = [\"^\\\"sk_\"]\nnames = [\"password\"]\n")?;
+        assert_eq!(vec!["^\"sk_".to_string()], config.redact.patterns);
+        assert_eq!(vec!["password".to_string()], config.redact.names);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_lint_rules() -> Result<(), Box<dyn Error>> {
+        assert!(Config::from_str("")?.lint.boolean_prefix);
+        let config = Config::from_str("[lint]\nboolean_prefix = false\n")?;
+        assert!(!config.lint.boolean_prefix);
+        assert!(config.lint.query_get_prefix);
+        assert!(Config::from_str("[lint]\nbooleans = false\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_environments() -> Result<(), Box<dyn Error>> {
+        let config = Config::from_str(
+            "[[environments]]\nname = \"staging\"\nurl = \"https://staging.example.com/graphql\"\nheaders = [\"X-Tenant: acme\"]\nout_dir = \"docs/staging\"\n\n[[environments]]\nname = \"production\"\nurl = \"https://example.com/graphql\"\nout_dir = \"docs/production\"\n",
+        )?;
+        assert_eq!(2, config.environments.len());
+        assert_eq!(
+            Environment {
+                name: "staging".to_string(),
+                url: "https://staging.example.com/graphql".to_string(),
+                headers: vec!["X-Tenant: acme".to_string()],
+                out_dir: PathBuf::from("docs/staging"),
+            },
+            config.environments[0]
+        );
+        assert!(config.environments[1].headers.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_read_sort_orders() -> Result<(), Box<dyn Error>> {
+        assert_eq!(Sort::default(), Config::from_str("")?.sort);
+        let config = Config::from_str("[sort]\narguments = \"schema\"\n")?;
+        assert_eq!(SortOrder::Schema, config.sort.arguments);
+        assert_eq!(SortOrder::Alpha, config.sort.fields);
+        assert!(Config::from_str("[sort]\narguments = \"random\"\n").is_err());
+        assert!(Config::from_str("[sort]\ntypes = \"schema\"\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn roots_should_set_root_types_missing_from_introspection() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_str(
+            r#"{"types": [{"kind": "OBJECT", "name": "QueryRoot", "fields": []}]}"#,
+        )?;
+        assert!(schema.get_query_name().is_none());
+        let config = Config::from_str("[roots]\nquery = \"QueryRoot\"\n")?;
+        config.roots.apply(&mut schema)?;
+        assert_eq!(Some("QueryRoot".to_string()), schema.get_query_name());
+        let config = Config::from_str("[roots]\nmutation = \"MutationRoot\"\n")?;
+        assert!(config.roots.apply(&mut schema).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_fail_on_unknown_keys() {
+        assert!(Config::from_str("[alias]\nPlayerV2 = \"Player\"\n").is_err());
+    }
+
+    #[test]
+    fn from_file_should_fail_when_missing() {
+        match Config::from_file(Path::new("testdata/missing.toml")) {
+            Ok(_) => panic!("missing config should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .starts_with("can't read config testdata/missing.toml")),
+        }
+    }
+
+    #[test]
+    fn load_should_read_specified_file() -> Result<(), Box<dyn Error>> {
+        let config = Config::load(&Some(PathBuf::from("testdata/gumwood.toml")))?;
+        assert_eq!("Player", config.aliases["PlayerV2"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use super::links::Links;
+use super::markdown::{self, Block, Inline};
+
+// The XML entities Confluence accepts without a DTD
+const XML_ENTITIES: &[&str] = &["amp;", "lt;", "gt;", "quot;", "apos;"];
+
+/// Returns the title of a page in Confluence. Titles must be unique within a
+/// space, so they start with the site name.
+///
+/// # Arguments
+///
+/// * `site_name` - the name of the documentation site
+/// * `page` - the name of the page
+/// * `links` - how page titles are capitalized
+pub fn get_page_title(site_name: &str, page: &str, links: &Links) -> String {
+    format!("{}: {}", site_name, links.page_title(page))
+}
+
+/// Converts a page of gumwood's markdown to Confluence storage format:
+/// descriptions become info panels, fenced code becomes code macros, anchors
+/// become anchor macros, and links between pages become links to the pages
+/// by title
+///
+/// # Arguments
+///
+/// * `markdown` - the page's markdown
+/// * `site_name` - the name of the documentation site, which page titles
+///   start with
+/// * `links` - how pages and anchors are named
+pub fn to_storage(markdown: &str, site_name: &str, links: &Links) -> String {
+    Converter { site_name, links }.to_blocks(&markdown::parse_blocks(markdown))
+}
+
+struct Converter<'a> {
+    site_name: &'a str,
+    links: &'a Links,
+}
+
+impl Converter<'_> {
+    fn to_blocks(&self, blocks: &[Block]) -> String {
+        let mut s = String::new();
+        for block in blocks {
+            match block {
+                Block::Heading(level, text) => s.push_str(&self.to_heading(*level, text)),
+                Block::Paragraph(text) => {
+                    s.push_str(&format!("<p>{}</p>\n", self.to_inline(text)))
+                }
+                Block::Notice(text) => {
+                    s.push_str(&format!("<p><em>{}</em></p>\n", self.to_inline(text)))
+                }
+                Block::List(items) => {
+                    s.push_str("<ul>");
+                    for item in items {
+                        s.push_str(&format!("<li>{}</li>", self.to_inline(item)));
+                    }
+                    s.push_str("</ul>\n");
+                }
+                Block::Table(rows) => s.push_str(&self.to_table(rows)),
+                Block::Quote(blocks) => s.push_str(&format!(
+                    "<ac:structured-macro ac:name=\"info\"><ac:rich-text-body>{}</ac:rich-text-body></ac:structured-macro>\n",
+                    self.to_blocks(blocks).trim_end()
+                )),
+                Block::Code { language, code } => s.push_str(&to_code_macro(language, code)),
+            }
+        }
+        s
+    }
+
+    fn to_heading(&self, level: usize, text: &str) -> String {
+        let level = level.min(6);
+        let mut s = format!("<h{}>", level);
+        // Confluence doesn't give headings the anchors markdown renderers
+        // do, so operations, whose headings gumwood leaves bare, get one
+        if level == 2 && !text.contains("<a name=\"") && self.links.anchor_prefix.is_empty() {
+            if let Some(name) = text.split_whitespace().last() {
+                s.push_str(&to_anchor_macro(&name.to_lowercase()));
+            }
+        }
+        s.push_str(&self.to_inline(text));
+        s.push_str(&format!("</h{}>\n", level));
+        s
+    }
+
+    fn to_table(&self, rows: &[Vec<String>]) -> String {
+        let mut s = "<table><tbody>".to_string();
+        for (i, row) in rows.iter().enumerate() {
+            let tag = if i == 0 { "th" } else { "td" };
+            s.push_str("<tr>");
+            for cell in row {
+                s.push_str(&format!("<{}>{}</{}>", tag, self.to_inline(cell), tag));
+            }
+            s.push_str("</tr>");
+        }
+        s.push_str("</tbody></table>\n");
+        s
+    }
+
+    fn to_inline(&self, text: &str) -> String {
+        self.to_inlines(&markdown::parse_inline(text))
+    }
+
+    fn to_inlines(&self, inlines: &[Inline]) -> String {
+        let mut s = String::new();
+        for inline in inlines {
+            match inline {
+                Inline::Text(text) => s.push_str(&escape(text)),
+                Inline::Code(code) => s.push_str(&format!("<code>{}</code>", escape(code))),
+                Inline::Strong(inlines) => {
+                    s.push_str(&format!("<strong>{}</strong>", self.to_inlines(inlines)))
+                }
+                Inline::Link { text, destination } => {
+                    s.push_str(&self.to_link(&self.to_inlines(text), destination))
+                }
+                Inline::Anchor(name) => s.push_str(&to_anchor_macro(name)),
+            }
+        }
+        s
+    }
+
+    fn to_link(&self, body: &str, destination: &str) -> String {
+        let (target, fragment) = match destination.split_once('#') {
+            Some((target, fragment)) => (target, Some(fragment)),
+            None => (destination, None),
+        };
+        if target.contains(':') || !(target.is_empty() || self.links.is_page_link(target)) {
+            return format!("<a href=\"{}\">{}</a>", escape(destination), body);
+        }
+        let mut s = "<ac:link".to_string();
+        if let Some(fragment) = fragment {
+            s.push_str(&format!(" ac:anchor=\"{}\"", escape(fragment)));
+        }
+        s.push('>');
+        if let Some(page) = target.strip_suffix(&format!(".{}", self.links.page_extension)) {
+            s.push_str(&format!(
+                "<ri:page ri:content-title=\"{}\" />",
+                escape(&get_page_title(self.site_name, page, self.links))
+            ));
+        }
+        s.push_str(&format!("<ac:link-body>{}</ac:link-body></ac:link>", body));
+        s
+    }
+}
+
+fn to_anchor_macro(name: &str) -> String {
+    format!(
+        "<ac:structured-macro ac:name=\"anchor\"><ac:parameter ac:name=\"\">{}</ac:parameter></ac:structured-macro>",
+        escape(name)
+    )
+}
+
+fn to_code_macro(language: &str, code: &str) -> String {
+    let mut s = "<ac:structured-macro ac:name=\"code\">".to_string();
+    if !language.is_empty() {
+        s.push_str(&format!(
+            "<ac:parameter ac:name=\"language\">{}</ac:parameter>",
+            escape(language)
+        ));
+    }
+    // CDATA can't contain its own terminator, so split it across sections
+    s.push_str(&format!(
+        "<ac:plain-text-body><![CDATA[{}]]></ac:plain-text-body></ac:structured-macro>\n",
+        code.replace("]]>", "]]]]><![CDATA[>")
+    ));
+    s
+}
+
+// Escapes text for XHTML, keeping the XML entities and character references
+// descriptions may already use
+fn escape(text: &str) -> String {
+    let mut s = String::new();
+    for (i, c) in text.char_indices() {
+        match c {
+            '&' if is_entity(&text[i + 1..]) => s.push('&'),
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            _ => s.push(c),
+        }
+    }
+    s
+}
+
+fn is_entity(text: &str) -> bool {
+    if XML_ENTITIES.iter().any(|entity| text.starts_with(entity)) {
+        return true;
+    }
+    match text.strip_prefix('#') {
+        Some(reference) => {
+            let digits = reference.len()
+                - reference
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            digits > 0 && reference[digits..].starts_with(';')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_storage(markdown: &str) -> String {
+        super::to_storage(
+            markdown,
+            "League API",
+            &Links {
+                page_extension: "xml",
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn to_storage_should_convert_headings_and_tables() {
+        assert_eq!(
+            concat!(
+                "<h1>Objects</h1>\n",
+                "<h2>📄 <ac:structured-macro ac:name=\"anchor\"><ac:parameter ac:name=\"\">player</ac:parameter></ac:structured-macro>Player</h2>\n",
+                "<table><tbody>",
+                "<tr><th>Name</th><th>Type</th><th>Description</th></tr>",
+                "<tr><td><code>team</code></td><td><ac:link ac:anchor=\"team\"><ri:page ri:content-title=\"League API: Objects\" /><ac:link-body><code>Team</code></ac:link-body></ac:link></td><td>The player&#39;s &lt;team&gt;</td></tr>",
+                "</tbody></table>\n",
+            ),
+            to_storage(concat!(
+                "# Objects\n\n",
+                "## 📄 <a name=\"player\"></a>Player\n\n",
+                "| Name | Type | Description |\n",
+                "| --- | --- | --- |\n",
+                "| `team` | [`Team`](objects.xml#team) | The player&#39;s <team> |\n",
+            ))
+        );
+    }
+
+    #[test]
+    fn to_storage_should_put_descriptions_in_info_panels() {
+        assert_eq!(
+            concat!(
+                "<ac:structured-macro ac:name=\"info\"><ac:rich-text-body><p>A hockey player</p>\n",
+                "<ac:structured-macro ac:name=\"code\"><ac:parameter ac:name=\"language\">graphql</ac:parameter><ac:plain-text-body><![CDATA[{ player { name } }]]></ac:plain-text-body></ac:structured-macro></ac:rich-text-body></ac:structured-macro>\n",
+                "<p><em>Deprecated</em></p>\n",
+            ),
+            to_storage("> A hockey player\n```graphql\n{ player { name } }\n```\n\n_Deprecated_\n")
+        );
+    }
+
+    #[test]
+    fn to_storage_should_anchor_operation_headings() {
+        assert_eq!(
+            "<h2><ac:structured-macro ac:name=\"anchor\"><ac:parameter ac:name=\"\">players</ac:parameter></ac:structured-macro>players</h2>\n<p><strong>Type:</strong> <a href=\"https://example.com/a?b&amp;c\">docs</a></p>\n",
+            to_storage("## players\n\n**Type:** [docs](https://example.com/a?b&c)\n")
+        );
+    }
+
+    #[test]
+    fn to_storage_should_link_within_the_page() {
+        assert_eq!(
+            "<ul><li><ac:link ac:anchor=\"team\"><ac:link-body>Team</ac:link-body></ac:link></li><li>a * b</li></ul>\n",
+            to_storage("* [Team](#team)\n* a * b\n")
+        );
+    }
+
+    #[test]
+    fn to_code_macro_should_split_cdata_terminator() {
+        assert!(to_code_macro("", "a]]>b").contains("<![CDATA[a]]]]><![CDATA[>b]]>"));
+    }
+
+    #[test]
+    fn get_page_title_should_start_with_site_name() {
+        assert_eq!(
+            "League API: Largest Types",
+            get_page_title("League API", "largest-types", &Links::default())
+        );
+    }
+}
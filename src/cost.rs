@@ -0,0 +1,89 @@
+use super::descriptions::get_unmatched;
+use super::schema::{ElementMut, Schema};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+};
+
+/// What operations cost and how the API limits them, for consumers
+/// budgeting their requests
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Costs {
+    /// Prose describing the rate limits, for the top of the cost page
+    pub rate_limit: Option<String>,
+
+    /// Estimated costs, keyed by `Type.field` coordinate
+    pub costs: HashMap<String, u32>,
+}
+
+impl Costs {
+    pub fn from_file(file: &Path) -> Result<Costs, Box<dyn Error>> {
+        let contents = fs::read_to_string(file)
+            .map_err(|err| format!("can't read costs {}: {}", file.display(), err))?;
+        Costs::from_str(&contents)
+            .map_err(|err| format!("invalid costs {}: {}", file.display(), err).into())
+    }
+
+    pub fn from_str(text: &str) -> Result<Costs, Box<dyn Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Records the costs on the schema's fields, replacing any from @cost
+    /// directives, and returns the coordinates that didn't match a field,
+    /// sorted
+    pub fn apply(&self, schema: &mut Schema) -> Vec<String> {
+        let mut applied = HashSet::new();
+
+        schema.for_each_element_mut(&mut |coordinate, element| {
+            if let (Some(cost), ElementMut::Field(field)) = (self.costs.get(coordinate), element) {
+                field.cost = Some(*cost);
+                applied.insert(coordinate.to_string());
+            }
+        });
+
+        get_unmatched(self.costs.keys(), &applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_should_set_costs() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"type Query { players: [String] @cost(weight: "10") teams: [String] score: Int }"#,
+        )?;
+        let costs = Costs::from_str(
+            r#"
+            rate_limit = "5,000 points per hour"
+            [costs]
+            "Query.players" = 20
+            "Query.teams" = 5
+            "Query.coaches" = 5
+            Query = 1
+            "#,
+        )?;
+        let unmatched = costs.apply(&mut schema);
+
+        let fields = schema.get_type("Query").unwrap().fields.as_ref().unwrap();
+        assert_eq!(Some(20), fields[0].cost);
+        assert_eq!(Some(5), fields[1].cost);
+        assert_eq!(None, fields[2].cost);
+        assert_eq!(
+            vec!["Query".to_string(), "Query.coaches".to_string()],
+            unmatched
+        );
+        assert_eq!(Some("5,000 points per hour".to_string()), costs.rate_limit);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_fail_on_negative_costs() {
+        assert!(Costs::from_str("[costs]\n\"Query.players\" = -1\n").is_err());
+    }
+}
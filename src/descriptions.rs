@@ -0,0 +1,200 @@
+use super::schema::Schema;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+};
+
+/// A new description for a schema element, or prose to add to its existing one
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum DescriptionOverride {
+    Replace(String),
+    Edit {
+        replace: Option<String>,
+        append: Option<String>,
+    },
+}
+
+impl DescriptionOverride {
+    fn apply(&self, description: &mut Option<String>) {
+        let (replace, append) = match self {
+            DescriptionOverride::Replace(replace) => (Some(replace), None),
+            DescriptionOverride::Edit { replace, append } => (replace.as_ref(), append.as_ref()),
+        };
+        if let Some(replace) = replace {
+            *description = Some(replace.clone());
+        }
+        if let Some(append) = append {
+            *description = Some(match description.as_deref().map(str::trim) {
+                Some(existing) if !existing.is_empty() => format!("{} {}", existing, append),
+                _ => append.clone(),
+            });
+        }
+    }
+}
+
+/// Descriptions that supplement the schema's own, keyed by schema
+/// coordinate: `Type`, `Type.field`, `Type.field(arg:)`, `@directive`, or
+/// `@directive(arg:)`
+#[derive(Clone, Debug, Default)]
+pub struct Descriptions {
+    overrides: HashMap<String, DescriptionOverride>,
+}
+
+impl Descriptions {
+    pub fn from_file(file: &Path) -> Result<Descriptions, Box<dyn Error>> {
+        let contents = fs::read_to_string(file)
+            .map_err(|err| format!("can't read descriptions {}: {}", file.display(), err))?;
+        Descriptions::from_str(&contents)
+            .map_err(|err| format!("invalid descriptions {}: {}", file.display(), err).into())
+    }
+
+    pub fn from_str(text: &str) -> Result<Descriptions, Box<dyn Error>> {
+        Ok(Descriptions {
+            overrides: toml::from_str(text)?,
+        })
+    }
+
+    /// Returns descriptions that add the prose to each element's own,
+    /// keyed by schema coordinate
+    pub fn appending(appends: HashMap<String, String>) -> Descriptions {
+        Descriptions {
+            overrides: appends
+                .into_iter()
+                .map(|(coordinate, append)| {
+                    (
+                        coordinate,
+                        DescriptionOverride::Edit {
+                            replace: None,
+                            append: Some(append),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies the descriptions to the schema and returns the coordinates
+    /// that didn't match anything in it, sorted
+    pub fn apply(&self, schema: &mut Schema) -> Vec<String> {
+        let mut applied = HashSet::new();
+
+        schema.for_each_element_mut(&mut |coordinate, mut element| {
+            if let Some(description_override) = self.overrides.get(coordinate) {
+                description_override.apply(element.description_mut());
+                applied.insert(coordinate.to_string());
+            }
+        });
+
+        get_unmatched(self.overrides.keys(), &applied)
+    }
+}
+
+/// Returns the coordinates that weren't applied, sorted
+pub fn get_unmatched<'a>(
+    coordinates: impl Iterator<Item = &'a String>,
+    applied: &HashSet<String>,
+) -> Vec<String> {
+    let mut unmatched: Vec<String> = coordinates
+        .filter(|coordinate| !applied.contains(*coordinate))
+        .cloned()
+        .collect();
+    unmatched.sort();
+    unmatched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+        type Query {
+            "Finds a player"
+            player(id: ID): Player
+        }
+        type Player { name: String position: Position }
+        enum Position { GOALIE WING }
+        input PlayerInput { name: String }
+        directive @auth(role: String) on FIELD_DEFINITION
+    "#;
+
+    const DESCRIPTIONS: &str = r#"
+        Player = "Someone on a team"
+        "Player.name" = { append = "Unique within a team." }
+        "Query.player" = { append = "Returns null if there's no such player." }
+        "Query.player(id:)" = "The player's ID"
+        "Position.GOALIE" = "Guards the net"
+        "PlayerInput.name" = { replace = "The name", append = "(required)" }
+        "@auth" = "Requires a role"
+        "@auth(role:)" = "The role"
+        "Player.age" = "No such field"
+    "#;
+
+    #[test]
+    fn apply_should_replace_and_append_descriptions() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        Descriptions::from_str(DESCRIPTIONS)?.apply(&mut schema);
+
+        let player = schema.get_type("Player").unwrap();
+        assert_eq!(Some("Someone on a team".to_string()), player.description);
+        assert_eq!(
+            Some("Unique within a team.".to_string()),
+            player.fields.as_ref().unwrap()[0].description
+        );
+
+        let query_player = &schema.get_type("Query").unwrap().fields.as_ref().unwrap()[0];
+        assert_eq!(
+            Some("Finds a player Returns null if there's no such player.".to_string()),
+            query_player.description
+        );
+        assert_eq!(
+            Some("The player's ID".to_string()),
+            query_player.args.as_ref().unwrap()[0].description
+        );
+
+        let goalie = &schema.get_type("Position").unwrap().enums.as_ref().unwrap()[0];
+        assert_eq!(Some("Guards the net".to_string()), goalie.description);
+
+        let input = &schema
+            .get_type("PlayerInput")
+            .unwrap()
+            .inputs
+            .as_ref()
+            .unwrap()[0];
+        assert_eq!(Some("The name (required)".to_string()), input.description);
+
+        let auth = &schema.directives.as_ref().unwrap()[0];
+        assert_eq!(Some("Requires a role".to_string()), auth.description);
+        assert_eq!(
+            Some("The role".to_string()),
+            auth.args.as_ref().unwrap()[0].description
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_return_unmatched_coordinates() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        let unmatched = Descriptions::from_str(DESCRIPTIONS)?.apply(&mut schema);
+        assert_eq!(vec!["Player.age".to_string()], unmatched);
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_fail_on_invalid_values() {
+        assert!(Descriptions::from_str("Player = 42").is_err());
+    }
+
+    #[test]
+    fn from_file_should_fail_when_missing() {
+        match Descriptions::from_file(Path::new("testdata/missing.toml")) {
+            Ok(_) => panic!("missing descriptions should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .starts_with("can't read descriptions testdata/missing.toml")),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use super::schema::{Field, Schema, Type};
+
+/// A way a mutation reports errors in its result rather than in the
+/// response's `errors`
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ErrorConvention {
+    /// The mutation returns a union of its result and error types, such as
+    /// `union AddPlayerResult = AddPlayerSuccess | UserError`
+    Union,
+    /// The mutation returns a payload with an `errors` or `userErrors` field
+    /// that lists what went wrong
+    Field,
+}
+
+/// A type a mutation can report errors with, and the mutations that can
+/// return it
+#[derive(Debug, PartialEq)]
+pub struct ErrorType {
+    pub name: String,
+    pub kind: String,
+    /// The mutations that can return the type, sorted, with how each does
+    pub mutations: Vec<(String, ErrorConvention)>,
+}
+
+/// Returns the types the schema's mutations report errors with, sorted by
+/// name: the error members of union payloads, and the types of payloads'
+/// `errors` and `userErrors` fields
+pub fn get_error_types(schema: &Schema) -> Vec<ErrorType> {
+    let mut error_types: Vec<ErrorType> = Vec::new();
+    let mutation = match schema
+        .get_mutation_name()
+        .and_then(|name| schema.get_type(&name))
+    {
+        Some(mutation) => mutation,
+        None => return error_types,
+    };
+    for field in mutation.fields.iter().flatten() {
+        let (mutation_name, payload) = match (&field.name, get_payload(schema, field)) {
+            (Some(name), Some(payload)) => (name, payload),
+            _ => continue,
+        };
+        for (typ, convention) in get_errors(schema, payload) {
+            let (name, kind) = match (&typ.name, &typ.kind) {
+                (Some(name), Some(kind)) => (name, kind),
+                _ => continue,
+            };
+            let index = match error_types.iter().position(|e| &e.name == name) {
+                Some(index) => index,
+                None => {
+                    error_types.push(ErrorType {
+                        name: name.clone(),
+                        kind: kind.clone(),
+                        mutations: Vec::new(),
+                    });
+                    error_types.len() - 1
+                }
+            };
+            let mutations = &mut error_types[index].mutations;
+            if !mutations.iter().any(|(m, _)| m == mutation_name) {
+                mutations.push((mutation_name.clone(), convention));
+            }
+        }
+    }
+    for error_type in error_types.iter_mut() {
+        error_type.mutations.sort();
+    }
+    error_types.sort_by(|a, b| a.name.cmp(&b.name));
+    error_types
+}
+
+fn get_payload<'a>(schema: &'a Schema, field: &Field) -> Option<&'a Type> {
+    let name = field.field_type.as_ref()?.get_actual_name();
+    schema.get_type(&name)
+}
+
+// Returns the error types a payload can hold and how it holds them
+fn get_errors<'a>(schema: &'a Schema, payload: &Type) -> Vec<(&'a Type, ErrorConvention)> {
+    if payload.kind.as_deref() == Some("UNION") {
+        return get_possible_types(schema, payload)
+            .into_iter()
+            .filter(|member| is_error_type(member))
+            .map(|member| (member, ErrorConvention::Union))
+            .collect();
+    }
+    let mut errors = Vec::new();
+    for field in payload.fields.iter().flatten() {
+        if !matches!(field.name.as_deref(), Some("errors") | Some("userErrors")) {
+            continue;
+        }
+        if let Some(typ) = get_payload(schema, field) {
+            errors.push((typ, ErrorConvention::Field));
+            for member in get_possible_types(schema, typ) {
+                errors.push((member, ErrorConvention::Field));
+            }
+        }
+    }
+    errors
+}
+
+// Returns the members of a union or the implementations of an interface
+fn get_possible_types<'a>(schema: &'a Schema, typ: &Type) -> Vec<&'a Type> {
+    typ.possible_types
+        .iter()
+        .flatten()
+        .filter_map(|member| member.name.as_deref())
+        .filter_map(|name| schema.get_type(name))
+        .collect()
+}
+
+// An error type is named for errors, as in `UserError`, or implements an
+// interface that is
+fn is_error_type(typ: &Type) -> bool {
+    let is_error_name = |name: Option<&str>| name.is_some_and(|name| name.ends_with("Error"));
+    is_error_name(typ.name.as_deref())
+        || typ
+            .interfaces
+            .iter()
+            .flatten()
+            .any(|interface| is_error_name(interface.name.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn get_error_types_should_detect_conventions() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Mutation {
+                addPlayer(name: String): AddPlayerResult
+                trade(id: ID): TradePayload
+                retire(id: ID): RetirePayload
+                rename(name: String): Player
+            }
+            type Player { name: String }
+            interface Error { message: String }
+            type UserError implements Error { message: String }
+            type NotFound implements Error { message: String }
+            type Taken { message: String }
+            union AddPlayerResult = Player | UserError | Taken
+            type TradePayload { player: Player, userErrors: [UserError!]! }
+            type RetirePayload { errors: [Error!]! }
+            "#,
+        )?;
+        let found: Vec<(String, Vec<(String, ErrorConvention)>)> = get_error_types(&schema)
+            .into_iter()
+            .map(|e| (e.name, e.mutations))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "Error".to_string(),
+                    vec![("retire".to_string(), ErrorConvention::Field)]
+                ),
+                (
+                    "NotFound".to_string(),
+                    vec![("retire".to_string(), ErrorConvention::Field)]
+                ),
+                (
+                    "UserError".to_string(),
+                    vec![
+                        ("addPlayer".to_string(), ErrorConvention::Union),
+                        ("retire".to_string(), ErrorConvention::Field),
+                        ("trade".to_string(), ErrorConvention::Field),
+                    ]
+                ),
+            ],
+            found
+        );
+        Ok(())
+    }
+}
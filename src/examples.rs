@@ -0,0 +1,509 @@
+use super::links::Links;
+use super::markdown::{split_heading_id, to_header};
+use super::model::{DocField, DocInput, DocTypeRef, Model};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Adds an example operation to each operation's section, after its
+/// arguments, that passes each argument as a variable and selects what the
+/// operation returns down to the depth, with example variables for it and
+/// the shape of its response
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name
+/// * `model` - the documentation model the pages were rendered from
+/// * `depth` - how many levels of objects to select fields from
+/// * `links` - how headings are capitalized
+pub fn add_examples(
+    contents: &mut HashMap<String, String>,
+    model: &Model,
+    depth: usize,
+    links: &Links,
+) {
+    for (page, keyword, operations) in [
+        ("queries", "query", &model.queries),
+        ("mutations", "mutation", &model.mutations),
+        ("subscriptions", "subscription", &model.subscriptions),
+    ] {
+        if let Some(markdown) = contents.get_mut(page) {
+            *markdown = insert_examples(markdown, keyword, operations, model, depth, links);
+        }
+    }
+}
+
+fn insert_examples(
+    markdown: &str,
+    keyword: &str,
+    operations: &[DocField],
+    model: &Model,
+    depth: usize,
+    links: &Links,
+) -> String {
+    let mut s = String::new();
+    // The example for the operation being read, and its heading's level,
+    // until the section ends
+    let mut pending: Option<(u8, String)> = None;
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        let fence = line.trim_start().starts_with("```");
+        if let (false, false, Some((level, text))) = (fence, in_fence, to_heading(line)) {
+            // The arguments table is the only part of an operation's section
+            // under a heading of its own
+            if let Some((operation_level, example)) = &pending {
+                if level <= *operation_level || text != "Arguments" {
+                    s.push_str(example);
+                    pending = None;
+                }
+            }
+            let name = text.rsplit([' ', '>']).next().unwrap_or_default();
+            if let Some(operation) = operations.iter().find(|operation| operation.name == name) {
+                let mut example = format!(
+                    "{}```graphql\n{}\n```\n\n",
+                    to_header(level + 1, &links.section_title("Example")),
+                    to_document(keyword, operation, model, depth)
+                );
+                if !operation.arguments.is_empty() {
+                    let variables = Value::Object(to_variables(&operation.arguments, model));
+                    example.push_str(&format!(
+                        "```json\n{}\n```\n\n",
+                        serde_json::to_string_pretty(&variables).unwrap_or_default()
+                    ));
+                }
+                example.push_str(&format!(
+                    "```json\n{}\n```\n\n",
+                    serde_json::to_string_pretty(&to_response(operation, model, depth))
+                        .unwrap_or_default()
+                ));
+                pending = Some((level, example));
+            }
+        }
+        if fence {
+            in_fence = !in_fence;
+        }
+        s.push_str(line);
+    }
+    if let Some((_, example)) = pending {
+        s.push_str(&example);
+    }
+    s
+}
+
+// Returns the level and text of a heading, without any heading ID
+fn to_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?.trim_end();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    Some((
+        level as u8,
+        split_heading_id(text).map_or(text, |(text, _)| text),
+    ))
+}
+
+/// Returns a document that passes each argument as a variable and selects
+/// the scalar and enum fields of what the operation returns, and of the
+/// objects under it down to the depth
+///
+/// # Arguments
+///
+/// * `keyword` - `query`, `mutation`, or `subscription`
+/// * `operation` - the operation
+/// * `model` - the documentation model, for the types the operation returns
+/// * `depth` - how many levels of objects to select fields from
+pub fn to_document(keyword: &str, operation: &DocField, model: &Model, depth: usize) -> String {
+    let mut s = format!("{} {}", keyword, operation.name);
+    if !operation.arguments.is_empty() {
+        let variables: Vec<String> = operation
+            .arguments
+            .iter()
+            .map(|argument| format!("${}: {}", argument.name, get_decorated_name(argument)))
+            .collect();
+        s.push_str(&format!("({})", variables.join(", ")));
+    }
+    s.push_str(&format!(" {{\n  {}", operation.name));
+    if !operation.arguments.is_empty() {
+        let arguments: Vec<String> = operation
+            .arguments
+            .iter()
+            .map(|argument| format!("{}: ${}", argument.name, argument.name))
+            .collect();
+        s.push_str(&format!("({})", arguments.join(", ")));
+    }
+    if let Some(type_ref) = operation.field_type.as_ref().filter(|t| is_composite(t)) {
+        let mut selection = get_selection(type_ref, model, depth);
+        if selection.is_empty() {
+            selection.push("__typename".to_string());
+        }
+        s.push_str(" {\n");
+        for line in selection {
+            s.push_str(&format!("    {}\n", line));
+        }
+        s.push_str("  }");
+    }
+    s.push_str("\n}");
+    s
+}
+
+// Returns the lines that select the type's scalar and enum fields that need
+// no arguments, then, while the depth lasts, its object fields with theirs
+fn get_selection(type_ref: &DocTypeRef, model: &Model, depth: usize) -> Vec<String> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let mut lines = Vec::new();
+    for field in get_selectable_fields(type_ref, model) {
+        match &field.field_type {
+            Some(field_type) if is_composite(field_type) => {
+                let nested = get_selection(field_type, model, depth - 1);
+                if !nested.is_empty() {
+                    lines.push(format!("{} {{", field.name));
+                    lines.extend(nested.iter().map(|line| format!("  {}", line)));
+                    lines.push("}".to_string());
+                }
+            }
+            Some(_) => lines.push(field.name.clone()),
+            None => {}
+        }
+    }
+    lines
+}
+
+// Returns the type's fields that need no arguments
+fn get_selectable_fields<'a>(
+    type_ref: &DocTypeRef,
+    model: &'a Model,
+) -> impl Iterator<Item = &'a DocField> {
+    model
+        .types
+        .iter()
+        .find(|typ| typ.name == type_ref.name)
+        .and_then(|typ| typ.fields.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|field| !field.arguments.iter().any(is_required))
+}
+
+/// Returns the shape of the response to the operation's example, as
+/// `to_document` selects it: objects with the selected fields, lists of one,
+/// nulls for nullable scalar and enum fields, and placeholders for the rest
+///
+/// # Arguments
+///
+/// * `operation` - the operation
+/// * `model` - the documentation model, for the types the operation returns
+/// * `depth` - how many levels of objects the example selects fields from
+pub fn to_response(operation: &DocField, model: &Model, depth: usize) -> Value {
+    let value = match &operation.field_type {
+        Some(type_ref) if is_composite(type_ref) => {
+            let mut fields = get_response(type_ref, model, depth);
+            if fields.is_empty() {
+                fields.insert("__typename".to_string(), json!(type_ref.name));
+            }
+            to_shape(&type_ref.decorated_name, &|_| Value::Object(fields.clone()))
+        }
+        Some(type_ref) => to_leaf_shape(type_ref, model),
+        None => Value::Null,
+    };
+    json!({ "data": { operation.name.clone(): value } })
+}
+
+// Returns the fields of the type that get_selection selects, with their
+// shapes
+fn get_response(type_ref: &DocTypeRef, model: &Model, depth: usize) -> Map<String, Value> {
+    let mut fields = Map::new();
+    if depth == 0 {
+        return fields;
+    }
+    for field in get_selectable_fields(type_ref, model) {
+        match &field.field_type {
+            Some(field_type) if is_composite(field_type) => {
+                let nested = get_response(field_type, model, depth - 1);
+                if !nested.is_empty() {
+                    fields.insert(
+                        field.name.clone(),
+                        to_shape(&field_type.decorated_name, &|_| {
+                            Value::Object(nested.clone())
+                        }),
+                    );
+                }
+            }
+            Some(field_type) => {
+                fields.insert(field.name.clone(), to_leaf_shape(field_type, model));
+            }
+            None => {}
+        }
+    }
+    fields
+}
+
+fn to_leaf_shape(type_ref: &DocTypeRef, model: &Model) -> Value {
+    to_shape(&type_ref.decorated_name, &|non_null| {
+        if non_null {
+            to_scalar_placeholder(type_ref, model)
+        } else {
+            Value::Null
+        }
+    })
+}
+
+// Returns the value for a decorated type name such as `[Player!]`, a list of
+// one for each level of list, with the value for whether the named type is
+// non-null at the bottom
+fn to_shape(decorated_name: &str, value: &dyn Fn(bool) -> Value) -> Value {
+    let (name, non_null) = match decorated_name.strip_suffix('!') {
+        Some(name) => (name, true),
+        None => (decorated_name, false),
+    };
+    match name
+        .strip_prefix('[')
+        .and_then(|name| name.strip_suffix(']'))
+    {
+        Some(item) => json!([to_shape(item, value)]),
+        None => value(non_null),
+    }
+}
+
+/// Returns variables for an example of the operation: each argument's first
+/// example, or its default value, or a placeholder of its type
+///
+/// # Arguments
+///
+/// * `arguments` - the operation's arguments
+/// * `model` - the documentation model, for the enums and input objects the
+///   arguments take
+pub fn to_variables(arguments: &[DocInput], model: &Model) -> Map<String, Value> {
+    arguments
+        .iter()
+        .map(|argument| {
+            (
+                argument.name.clone(),
+                to_example_value(argument, model, &mut Vec::new()),
+            )
+        })
+        .collect()
+}
+
+// Returns the input's first example, or its default value if it's also
+// JSON, or a placeholder of its type
+fn to_example_value(input: &DocInput, model: &Model, input_objects: &mut Vec<String>) -> Value {
+    if let Some(example) = input.examples.iter().flatten().next() {
+        return serde_json::from_str(example).unwrap_or_else(|_| Value::from(example.as_str()));
+    }
+    if let Some(value) = input
+        .default_value
+        .as_deref()
+        .and_then(|default_value| serde_json::from_str(default_value).ok())
+    {
+        return value;
+    }
+    match &input.input_type {
+        Some(type_ref) => to_placeholder(type_ref, model, input_objects),
+        None => Value::Null,
+    }
+}
+
+// Returns an empty value of the type: an empty string, zero, false, an
+// enum's first value, or an input object with a value for each field, in a
+// list of one if the type is a list. An input object that contains itself
+// is null the second time, since it can't nest forever.
+fn to_placeholder(type_ref: &DocTypeRef, model: &Model, input_objects: &mut Vec<String>) -> Value {
+    let typ = model.types.iter().find(|typ| typ.name == type_ref.name);
+    let value = match (type_ref.kind.as_str(), type_ref.name.as_str()) {
+        ("INPUT_OBJECT", name) if input_objects.iter().any(|seen| seen == name) => Value::Null,
+        ("INPUT_OBJECT", name) => {
+            input_objects.push(name.to_string());
+            let fields: Map<String, Value> = typ
+                .and_then(|typ| typ.inputs.as_ref())
+                .into_iter()
+                .flatten()
+                .map(|input| {
+                    (
+                        input.name.clone(),
+                        to_example_value(input, model, input_objects),
+                    )
+                })
+                .collect();
+            input_objects.pop();
+            Value::Object(fields)
+        }
+        _ => to_scalar_placeholder(type_ref, model),
+    };
+    if type_ref.decorated_name.starts_with('[') {
+        json!([value])
+    } else {
+        value
+    }
+}
+
+// Returns an empty value of a scalar or enum type
+fn to_scalar_placeholder(type_ref: &DocTypeRef, model: &Model) -> Value {
+    match (type_ref.kind.as_str(), type_ref.name.as_str()) {
+        ("ENUM", _) => model
+            .types
+            .iter()
+            .find(|typ| typ.name == type_ref.name)
+            .and_then(|typ| typ.values.as_ref())
+            .and_then(|values| values.first())
+            .map_or_else(|| json!(""), |value| json!(value.name)),
+        (_, "Int") => json!(0),
+        (_, "Float") => json!(0.0),
+        (_, "Boolean") => json!(false),
+        _ => json!(""),
+    }
+}
+
+fn is_composite(type_ref: &DocTypeRef) -> bool {
+    matches!(type_ref.kind.as_str(), "OBJECT" | "INTERFACE" | "UNION")
+}
+
+// Returns whether a client must pass the argument
+fn is_required(argument: &DocInput) -> bool {
+    get_decorated_name(argument).ends_with('!') && argument.default_value.is_none()
+}
+
+fn get_decorated_name(input: &DocInput) -> &str {
+    input
+        .input_type
+        .as_ref()
+        .map_or("", |type_ref| type_ref.decorated_name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icons::Icons;
+    use crate::schema::Schema;
+    use std::error::Error;
+
+    const SDL: &str = r#"
+        type Query {
+          player(id: ID!): Player
+          count: Int
+        }
+        type Player {
+          name: String
+          team: Team
+          stats(season: Int!): Stats
+        }
+        type Team { name: String, captain: Player, league: League }
+        type League { teams: [Team] }
+        type Stats { goals: Int }
+    "#;
+
+    #[test]
+    fn to_document_should_select_objects_down_to_the_depth() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(SDL)?;
+        let model = Model::from_schema(&schema, &Icons::default(), &Links::default());
+        let player = &model.queries[0];
+        assert_eq!(
+            "query player($id: ID!) {\n  player(id: $id) {\n    name\n  }\n}",
+            to_document("query", player, &model, 1)
+        );
+        assert_eq!(
+            "query player($id: ID!) {\n  player(id: $id) {\n    name\n    team {\n      captain {\n        name\n      }\n      name\n    }\n  }\n}",
+            to_document("query", player, &model, 3)
+        );
+        assert_eq!(
+            "query count {\n  count\n}",
+            to_document("query", &model.queries[1], &model, 2)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_variables_should_fill_in_placeholders_of_each_type() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+              players(filter: PlayerFilter, first: Int = 10, ids: [ID!]): [String]
+            }
+            input PlayerFilter {
+              position: Position
+              ratings: [Float]
+              active: Boolean
+              and: PlayerFilter
+            }
+            enum Position { CENTER WING }
+            "#,
+        )?;
+        let model = Model::from_schema(&schema, &Icons::default(), &Links::default());
+        assert_eq!(
+            json!({
+                "filter": {
+                    "active": false,
+                    "and": null,
+                    "position": "CENTER",
+                    "ratings": [0.0]
+                },
+                "first": 10,
+                "ids": [""]
+            }),
+            Value::Object(to_variables(&model.queries[0].arguments, &model))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_response_should_shape_what_the_document_selects() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+              teams: [Team!]!
+              count: Int!
+              search: [Result]
+            }
+            type Team { name: String!, tags: [String!], captain: Player, roster: [[Player]] }
+            type Player { number: Int, position: Position! }
+            enum Position { CENTER WING }
+            union Result = Team | Player
+            "#,
+        )?;
+        let model = Model::from_schema(&schema, &Icons::default(), &Links::default());
+        assert_eq!(
+            json!({
+                "data": {
+                    "teams": [{
+                        "captain": { "number": null, "position": "CENTER" },
+                        "name": "",
+                        "roster": [[{ "number": null, "position": "CENTER" }]],
+                        "tags": [""]
+                    }]
+                }
+            }),
+            to_response(&model.queries[0], &model, 2)
+        );
+        assert_eq!(
+            json!({ "data": { "teams": [{ "name": "", "tags": [""] }] } }),
+            to_response(&model.queries[0], &model, 1)
+        );
+        assert_eq!(
+            json!({ "data": { "count": 0 } }),
+            to_response(&model.queries[1], &model, 1)
+        );
+        assert_eq!(
+            json!({ "data": { "search": [{ "__typename": "Result" }] } }),
+            to_response(&model.queries[2], &model, 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add_examples_should_add_example_after_arguments() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(SDL)?;
+        let links = Links::default();
+        let model = Model::from_schema(&schema, &Icons::default(), &links);
+        let mut contents = HashMap::new();
+        contents.insert(
+            "queries".to_string(),
+            "# Query\n\n## count\n\n**Type:** `Int`\n\n## Players\n\n### player\n\n#### Arguments\n\n| Name |\n\n```graphql\n## count\n```\n\n".to_string(),
+        );
+        add_examples(&mut contents, &model, 1, &links);
+        assert_eq!(
+            "# Query\n\n## count\n\n**Type:** `Int`\n\n### Example\n\n```graphql\nquery count {\n  count\n}\n```\n\n```json\n{\n  \"data\": {\n    \"count\": null\n  }\n}\n```\n\n## Players\n\n### player\n\n#### Arguments\n\n| Name |\n\n```graphql\n## count\n```\n\n#### Example\n\n```graphql\nquery player($id: ID!) {\n  player(id: $id) {\n    name\n  }\n}\n```\n\n```json\n{\n  \"id\": \"\"\n}\n```\n\n```json\n{\n  \"data\": {\n    \"player\": {\n      \"name\": null\n    }\n  }\n}\n```\n\n",
+            contents["queries"]
+        );
+        Ok(())
+    }
+}
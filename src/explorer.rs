@@ -0,0 +1,277 @@
+use super::budget::Budget;
+use super::model::Model;
+use std::{error::Error, fs, path::Path};
+
+// The page, with {title} and {data} to fill in. It loads nothing, so it
+// works opened from disk or served as a static file.
+const TEMPLATE: &str = r##"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width, initial-scale=1" />
+    <title>{title}</title>
+    <style>
+      body { display: flex; height: 100vh; margin: 0; font: 14px/1.5 system-ui, sans-serif; color: #222; }
+      nav { display: flex; flex-direction: column; width: 20em; border-right: 1px solid #ddd; }
+      nav input, nav select { margin: 0.5em 0.5em 0; padding: 0.3em; font: inherit; }
+      nav ul { flex: 1; overflow-y: auto; margin: 0.5em 0 0; padding: 0; list-style: none; }
+      nav li a { display: block; padding: 0.15em 0.75em; }
+      nav li a.selected { background: #e8eefc; }
+      main { flex: 1; overflow-y: auto; padding: 0 1.5em 1.5em; }
+      a { color: #1f5fbf; text-decoration: none; cursor: pointer; }
+      .kind { color: #777; font-size: 0.85em; }
+      .deprecated { text-decoration: line-through; }
+      table { border-collapse: collapse; }
+      td { padding: 0.2em 1em 0.2em 0; vertical-align: top; border-top: 1px solid #eee; }
+      code { font-family: ui-monospace, monospace; }
+      svg { display: block; max-width: 100%; }
+      svg text { font: 12px system-ui, sans-serif; text-anchor: middle; cursor: pointer; }
+      svg .center { font-weight: bold; }
+      svg line { stroke: #ccc; }
+    </style>
+  </head>
+  <body>
+    <nav>
+      <input id="search" type="search" placeholder="Search types and fields" />
+      <select id="kind"></select>
+      <ul id="list"></ul>
+    </nav>
+    <main id="detail"></main>
+    <script>
+      const model = {data};
+
+      // Everything the list shows, keyed by id: types by name, and
+      // operations by their root and name, such as query.player
+      const items = new Map();
+      for (const [kind, operations] of [
+        ["QUERY", model.queries],
+        ["MUTATION", model.mutations],
+        ["SUBSCRIPTION", model.subscriptions],
+      ]) {
+        for (const operation of operations) {
+          items.set(kind.toLowerCase() + "." + operation.name, { ...operation, kind });
+        }
+      }
+      for (const type of model.types) {
+        if (!type.name.startsWith("__")) {
+          items.set(type.name, type);
+        }
+      }
+
+      // The types each item refers to, and the items that refer to each type
+      const refs = new Map();
+      const referrers = new Map();
+      for (const [id, item] of items) {
+        const names = new Set();
+        const add = (ref) => ref && items.has(ref.name) && names.add(ref.name);
+        for (const member of [...(item.fields || []), ...(item.inputs || []), item]) {
+          add(member.type);
+          (member.arguments || []).forEach((argument) => add(argument.type));
+        }
+        (item.interfaces || []).forEach(add);
+        (item.possibleTypes || []).forEach(add);
+        names.delete(id);
+        refs.set(id, [...names].sort());
+        for (const name of names) {
+          referrers.set(name, [...(referrers.get(name) || []), id]);
+        }
+      }
+
+      const el = (tag, attributes, ...children) => {
+        const node = document.createElement(tag);
+        Object.assign(node, attributes);
+        node.append(...children.filter((child) => child !== null));
+        return node;
+      };
+      const svg = (tag, attributes, ...children) => {
+        const node = document.createElementNS("http://www.w3.org/2000/svg", tag);
+        Object.entries(attributes).forEach(([key, value]) => node.setAttribute(key, value));
+        node.append(...children);
+        return node;
+      };
+      const link = (id, text) => el("a", { href: "#" + encodeURIComponent(id) }, text);
+      const typeLink = (ref) =>
+        items.has(ref.name)
+          ? el("code", {}, ...ref.decoratedName.split(ref.name).flatMap((part, i) =>
+              i === 0 ? [part] : [link(ref.name, ref.name), part]))
+          : el("code", {}, ref.decoratedName);
+      const label = (id) => (items.get(id).kind.match(/QUERY|MUTATION|SUBSCRIPTION/) ? id : items.get(id).name);
+
+      const kinds = ["", ...new Set([...items.values()].map((item) => item.kind))];
+      const kindSelect = document.getElementById("kind");
+      kindSelect.append(...kinds.map((kind) => el("option", { value: kind }, kind ? kind.toLowerCase().replace("_", " ") : "all kinds")));
+      const search = document.getElementById("search");
+
+      // An item matches if its name, a member's name, or its description
+      // has the text
+      function matches(item, text) {
+        const members = [...(item.fields || []), ...(item.inputs || []), ...(item.values || []), ...(item.arguments || [])];
+        return [item.name, item.description || "", ...members.map((member) => member.name)]
+          .some((s) => s.toLowerCase().includes(text));
+      }
+
+      function renderList() {
+        const text = search.value.trim().toLowerCase();
+        const selected = decodeURIComponent(location.hash.slice(1));
+        const list = document.getElementById("list");
+        list.replaceChildren(
+          ...[...items]
+            .filter(([, item]) => !kindSelect.value || item.kind === kindSelect.value)
+            .filter(([, item]) => !text || matches(item, text))
+            .map(([id, item]) => {
+              const a = link(id, label(id));
+              a.className = id === selected ? "selected" : "";
+              return el("li", {}, a, " ", el("span", { className: "kind" }, item.kind.toLowerCase().replace("_", " ")));
+            })
+        );
+      }
+
+      // Draws the item in the middle with what it refers to and what refers
+      // to it around it, each a link
+      function renderGraph(id) {
+        const limit = 24;
+        const around = [...new Set([...refs.get(id), ...(referrers.get(id) || [])])];
+        const shown = around.slice(0, limit);
+        const size = 520, middle = size / 2, radius = 200;
+        const graph = svg("svg", { width: size, height: size, viewBox: `0 0 ${size} ${size}` });
+        const nodes = shown.map((other, i) => {
+          const angle = (2 * Math.PI * i) / shown.length - Math.PI / 2;
+          const x = middle + radius * Math.cos(angle), y = middle + radius * Math.sin(angle);
+          graph.append(svg("line", { x1: middle, y1: middle, x2: x, y2: y }));
+          const text = svg("text", { x, y }, label(other));
+          text.addEventListener("click", () => (location.hash = encodeURIComponent(other)));
+          return text;
+        });
+        graph.append(...nodes, svg("text", { x: middle, y: middle, class: "center" }, label(id)));
+        const more = around.length > limit ? el("p", {}, `and ${around.length - limit} more`) : null;
+        return el("div", {}, el("h2", {}, "Graph"), graph, more);
+      }
+
+      function renderMembers(title, members) {
+        if (!members || members.length === 0) {
+          return null;
+        }
+        return el("div", {}, el("h2", {}, title), el("table", {}, ...members.map((member) => {
+          const args = (member.arguments || []).flatMap((argument, i) =>
+            [i === 0 ? "(" : ", ", argument.name + ": ", argument.type ? typeLink(argument.type) : ""]);
+          if (args.length > 0) {
+            args.push(")");
+          }
+          return el("tr", {},
+            el("td", {}, el("code", { className: member.deprecated ? "deprecated" : "" }, member.name), ...args),
+            el("td", {}, member.type ? typeLink(member.type) : ""),
+            el("td", {}, member.description || "", member.deprecationReason ? ` Deprecated: ${member.deprecationReason}` : ""));
+        })));
+      }
+
+      function renderDetail() {
+        const id = decodeURIComponent(location.hash.slice(1));
+        const item = items.get(id);
+        const show = (...children) => document.getElementById("detail")
+          .replaceChildren(...children.filter((child) => child !== null));
+        if (!item) {
+          show(el("h1", {}, document.title), model.description ? el("p", {}, model.description) : null,
+            el("p", {}, `${items.size} types and operations. Pick one, or search for a type or field.`));
+          return;
+        }
+        const related = (title, refs) => refs && refs.length > 0
+          ? el("p", {}, title + ": ", ...refs.flatMap((ref, i) => [i === 0 ? "" : ", ", typeLink(ref)]))
+          : null;
+        show(
+          el("h1", {}, label(id), " ", el("span", { className: "kind" }, item.kind.toLowerCase().replace("_", " "))),
+          item.description ? el("p", {}, item.description) : null,
+          item.type ? el("p", {}, "Returns ", typeLink(item.type)) : null,
+          related("Implements", item.interfaces),
+          related("Possible types", item.possibleTypes),
+          renderMembers("Arguments", item.arguments),
+          renderMembers("Fields", item.fields),
+          renderMembers("Input fields", item.inputs),
+          renderMembers("Values", item.values),
+          renderGraph(id)
+        );
+      }
+
+      search.addEventListener("input", renderList);
+      kindSelect.addEventListener("change", renderList);
+      window.addEventListener("hashchange", () => {
+        renderList();
+        renderDetail();
+        document.getElementById("detail").scrollTop = 0;
+      });
+      renderList();
+      renderDetail();
+    </script>
+  </body>
+</html>
+"##;
+
+/// Writes a schema explorer: one HTML page that embeds the documentation
+/// model and lets readers filter and search its types and operations, read
+/// each one's members, and click through a graph of the types it's
+/// connected to, all without a server
+///
+/// # Arguments
+///
+/// * `model` - the documentation model
+/// * `file` - the file to write
+/// * `site_name` - the name of the documentation site, for the page title
+/// * `budget` - the output budget to count the file against
+pub fn write(
+    model: &Model,
+    file: &Path,
+    site_name: &str,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    let html = to_html(model, site_name)?;
+    budget.spend(html.len())?;
+    fs::write(file, html)
+        .map_err(|err| format!("can't write explorer {}: {}", file.display(), err).into())
+}
+
+fn to_html(model: &Model, site_name: &str) -> Result<String, Box<dyn Error>> {
+    // "</" would end the script early, and JSON allows escaping the slash
+    let data = serde_json::to_string(model)?.replace("</", "<\\/");
+    let title = site_name
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    // Split first, so the title can't add a placeholder
+    let (head, tail) = TEMPLATE.split_once("{data}").unwrap_or((TEMPLATE, ""));
+    Ok(format!(
+        "{}{}{}",
+        head.replace("{title}", &title),
+        data,
+        tail
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icons::Icons;
+    use crate::links::Links;
+    use crate::schema::Schema;
+    use serde_json::Value;
+
+    #[test]
+    fn to_html_should_embed_model_safely() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            "Ends with </script>"
+            type Player { name: String }
+            "#,
+        )?;
+        let model = Model::from_schema(&schema, &Icons::default(), &Links::default());
+        let html = to_html(&model, "League <API> {data}")?;
+        assert!(html.contains("<title>League &lt;API&gt; {data}</title>"));
+        assert_eq!(1, html.matches("</script>").count());
+        let start = html.find("const model = ").unwrap() + 14;
+        let end = html[start..].find(";\n").unwrap() + start;
+        assert_eq!(
+            serde_json::to_value(&model)?,
+            serde_json::from_str::<Value>(&html[start..end])?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+const INVALID_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Returns a file name that is safe to create on Windows, macOS, and Linux
+///
+/// Invalid characters and path separators are replaced with underscores,
+/// trailing dots and spaces are removed, and reserved Windows device names
+/// get an underscore after the stem. Because two different names can sanitize to
+/// the same string, any name that had to change gets a short hash of the
+/// original appended, so the result is deterministic and collision-free.
+///
+/// # Arguments
+///
+/// * `name` - the name to turn into a file name (without extension)
+pub fn to_safe_file_name(name: &str) -> String {
+    let mut safe: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || INVALID_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    let trimmed_len = safe.trim_end_matches(['.', ' ']).len();
+    safe.truncate(trimmed_len);
+
+    if safe.is_empty() {
+        safe.push('_');
+    }
+
+    let stem_len = safe.find('.').unwrap_or(safe.len());
+    if RESERVED_NAMES.contains(&safe[..stem_len].to_uppercase().as_str()) {
+        safe.insert(stem_len, '_');
+    }
+
+    if safe != name {
+        safe.push_str(&format!("-{:08x}", fnv1a(name.as_bytes())));
+    }
+
+    safe
+}
+
+/// Returns the 32-bit FNV-1a hash of the bytes, which (unlike `DefaultHasher`)
+/// is stable across Rust releases
+pub fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash: u32, byte| {
+        (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_safe_file_name_should_return_name_when_already_safe() {
+        assert_eq!("objects", to_safe_file_name("objects"));
+    }
+
+    #[test]
+    fn to_safe_file_name_should_replace_invalid_characters() {
+        let safe = to_safe_file_name("what?:now");
+        assert!(safe.starts_with("what__now-"));
+        assert_eq!("what__now-".len() + 8, safe.len());
+    }
+
+    #[test]
+    fn to_safe_file_name_should_replace_path_separators() {
+        assert!(to_safe_file_name("a/b\\c").starts_with("a_b_c-"));
+    }
+
+    #[test]
+    fn to_safe_file_name_should_trim_trailing_dots_and_spaces() {
+        assert!(to_safe_file_name("name. .").starts_with("name-"));
+    }
+
+    #[test]
+    fn to_safe_file_name_should_suffix_reserved_names() {
+        assert!(to_safe_file_name("con").starts_with("con_-"));
+        assert!(to_safe_file_name("LPT1.notes").starts_with("LPT1_.notes-"));
+    }
+
+    #[test]
+    fn to_safe_file_name_should_handle_empty() {
+        assert!(to_safe_file_name("").starts_with("_-"));
+    }
+
+    #[test]
+    fn to_safe_file_name_should_be_deterministic() {
+        assert_eq!(to_safe_file_name("a?b"), to_safe_file_name("a?b"));
+    }
+
+    #[test]
+    fn to_safe_file_name_should_not_collide_when_sanitized_names_match() {
+        assert_ne!(to_safe_file_name("a?b"), to_safe_file_name("a:b"));
+    }
+
+    #[test]
+    fn fnv1a_should_hash_known_value() {
+        assert_eq!(0x811c_9dc5, fnv1a(b""));
+        assert_eq!(0xe40c_292c, fnv1a(b"a"));
+    }
+}
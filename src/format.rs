@@ -0,0 +1,1244 @@
+use super::confluence;
+use super::links::Links;
+use super::markdown::split_code;
+use super::obsidian;
+use super::slate;
+use super::term;
+use std::{collections::HashMap, fmt, path::PathBuf, str::FromStr};
+use titlecase::titlecase;
+
+/// The section, or directory under the site's content, the pages are
+/// written to
+const SECTION: &str = "graphql";
+
+/// The order pages appear in navigation; pages not listed sort after these
+const PAGE_ORDER: &[&str] = &[
+    "queries",
+    "mutations",
+    "subscriptions",
+    "objects",
+    "inputs",
+    "interfaces",
+    "enums",
+    "unions",
+    "scalars",
+    "directives",
+];
+
+/// How the output files are laid out, so they drop straight into a
+/// documentation site generator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// One markdown file per page, and nothing else
+    Markdown,
+    /// A Backstage TechDocs site: `mkdocs.yml` plus a `docs` directory
+    Techdocs,
+    /// Docusaurus docs: `.mdx` pages with Docusaurus front matter, plus a
+    /// `_category_.json` for the sidebar
+    Docusaurus,
+    /// Hugo page bundles: `content/graphql/<page>/_index.md` with `weight`
+    /// and `menu` front matter
+    Hugo,
+    /// An mdBook book: `book.toml` plus the pages and a `SUMMARY.md` in
+    /// `src`
+    Mdbook,
+    /// VitePress docs: the pages and an `index.md` in `graphql`, plus a
+    /// `sidebar.json` and `sidebar.ts` to add to the theme config
+    Vitepress,
+    /// VuePress docs, laid out like VitePress's
+    Vuepress,
+    /// Confluence storage format: one `.xml` page per page, ready to paste
+    /// into Confluence's source editor or upload through its REST API
+    Confluence,
+    /// The documentation model as one JSON document, in place of the
+    /// pages, for other tools to consume
+    Json,
+    /// The documentation model as one YAML document, for pipelines that
+    /// ingest YAML data files
+    Yaml,
+    /// The pages with ANSI colors for reading in a terminal, written to
+    /// stdout only
+    Term,
+    /// A GitHub wiki: a page per page, named as the wiki names them, plus
+    /// `Home.md` and `_Sidebar.md`, ready to push to the wiki's repo
+    GithubWiki,
+    /// An Obsidian vault: a note per type, in a folder per page, and a note
+    /// per page, linked with wikilinks
+    Obsidian,
+    /// A Zola section: `content/graphql/_index.md` with `sort_by`, and the
+    /// pages with `title` and `weight` front matter, linked with Zola's
+    /// `@/` internal links
+    Zola,
+    /// A Slate site's `source/index.html.md`: every page in one document,
+    /// with example requests in GraphQL and cURL tabs beside each operation
+    Slate,
+    /// DocFX conceptual docs: the pages in `graphql` with `uid` and `title`
+    /// front matter, plus a `toc.yml` and an `index.md`
+    Docfx,
+    /// The schema as compact plain text, in place of the pages, for AI
+    /// assistants and embedding pipelines
+    LlmsTxt,
+}
+
+// Pages that document operations rather than types
+const OPERATION_PAGES: &[&str] = &["queries", "mutations", "subscriptions"];
+
+/// How page titles and the headings gumwood writes are capitalized
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TitleCase {
+    /// As gumwood names them: pages by their names, such as
+    /// `largest types`, and headings as written, such as `Most Fields`
+    AsIs,
+    /// `Largest Types`
+    #[default]
+    Title,
+    /// `Largest types`
+    Sentence,
+    /// `LARGEST TYPES`
+    Upper,
+}
+
+impl TitleCase {
+    /// Returns the text capitalized in this style
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            TitleCase::AsIs => text.to_string(),
+            TitleCase::Title => titlecase(text),
+            TitleCase::Sentence => {
+                let mut chars = text.chars();
+                match chars.next() {
+                    Some(first) => first
+                        .to_uppercase()
+                        .chain(chars.as_str().to_lowercase().chars())
+                        .collect(),
+                    None => String::new(),
+                }
+            }
+            TitleCase::Upper => text.to_uppercase(),
+        }
+    }
+}
+
+impl FromStr for TitleCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "as-is" => Ok(TitleCase::AsIs),
+            "title" => Ok(TitleCase::Title),
+            "sentence" => Ok(TitleCase::Sentence),
+            "upper" => Ok(TitleCase::Upper),
+            _ => Err(format!("invalid title case: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for TitleCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TitleCase::AsIs => write!(f, "as-is"),
+            TitleCase::Title => write!(f, "title"),
+            TitleCase::Sentence => write!(f, "sentence"),
+            TitleCase::Upper => write!(f, "upper"),
+        }
+    }
+}
+
+/// The language front matter is written in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FrontMatterStyle {
+    /// Between `---` lines
+    #[default]
+    Yaml,
+    /// Between `+++` lines
+    Toml,
+    /// A JSON object, which Hugo reads at the start of a page
+    Json,
+}
+
+impl Format {
+    /// Returns whether the format needs --out-dir because it writes more
+    /// than the pages
+    pub fn needs_out_dir(self) -> bool {
+        !matches!(self, Format::Markdown | Format::Term) && !self.is_model()
+    }
+
+    /// Returns whether the format writes the documentation model in place
+    /// of the pages
+    pub fn is_model(self) -> bool {
+        matches!(self, Format::Json | Format::Yaml | Format::LlmsTxt)
+    }
+
+    /// Returns the directory, relative to --out-dir, to write the pages to
+    pub fn pages_dir(self) -> PathBuf {
+        match self {
+            Format::Markdown => PathBuf::new(),
+            Format::Techdocs => PathBuf::from("docs"),
+            Format::Docusaurus => PathBuf::new(),
+            Format::Hugo | Format::Zola => PathBuf::from("content").join(SECTION),
+            Format::Mdbook => PathBuf::from("src"),
+            Format::Vitepress | Format::Vuepress | Format::Docfx => PathBuf::from(SECTION),
+            Format::Slate => PathBuf::from("source"),
+            Format::Confluence
+            | Format::Json
+            | Format::Yaml
+            | Format::LlmsTxt
+            | Format::Term
+            | Format::GithubWiki
+            | Format::Obsidian => PathBuf::new(),
+        }
+    }
+
+    /// Returns whether pages can start with front matter
+    pub fn has_front_matter(self) -> bool {
+        !matches!(
+            self,
+            Format::Confluence
+                | Format::Term
+                | Format::GithubWiki
+                | Format::Obsidian
+                | Format::Slate
+        ) && !self.is_model()
+    }
+
+    /// Returns whether the pages are written as they are, rather than split
+    /// up into the format's extra files
+    pub fn writes_pages(self) -> bool {
+        !matches!(self, Format::Obsidian | Format::Slate)
+    }
+
+    /// Returns the only language of front matter the site generator reads,
+    /// if it reads just one
+    pub fn required_front_matter(self) -> Option<FrontMatterStyle> {
+        match self {
+            Format::Docusaurus | Format::Vitepress | Format::Vuepress | Format::Docfx => {
+                Some(FrontMatterStyle::Yaml)
+            }
+            Format::Zola => Some(FrontMatterStyle::Toml),
+            _ => None,
+        }
+    }
+
+    /// Returns whether each page is written as the `_index` of its own
+    /// directory
+    pub fn page_bundles(self) -> bool {
+        self == Format::Hugo
+    }
+
+    /// Returns whether pages are named and linked as GitHub wiki pages
+    pub fn wiki_pages(self) -> bool {
+        self == Format::GithubWiki
+    }
+
+    /// Returns what goes before a page's file in links between pages
+    pub fn page_link_prefix(self) -> String {
+        match self {
+            Format::Zola => format!("@/{}/", SECTION),
+            _ => String::new(),
+        }
+    }
+
+    /// Returns whether pages are MDX, whose `<` and braces are escaped
+    /// when the markdown is converted
+    pub fn is_mdx(self) -> bool {
+        self == Format::Docusaurus
+    }
+
+    /// Returns the extension for page files
+    pub fn page_extension(self) -> &'static str {
+        match self {
+            Format::Docusaurus => "mdx",
+            Format::Confluence => "xml",
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Term | Format::LlmsTxt => "txt",
+            _ => "md",
+        }
+    }
+
+    /// Returns the front matter lines the format needs on a page, which
+    /// can't be nested under --front-matter-key-prefix
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the page
+    /// * `contents` - the pages, keyed by name
+    /// * `links` - how pages are named
+    /// * `style` - the language of the front matter
+    pub fn front_matter(
+        self,
+        name: &str,
+        contents: &HashMap<String, String>,
+        links: &Links,
+        style: FrontMatterStyle,
+    ) -> Vec<String> {
+        let position = get_nav_pages(contents)
+            .iter()
+            .position(|page| *page == name)
+            .map_or(0, |position| position + 1);
+        match self {
+            Format::Docusaurus => vec![
+                style.string("id", &links.page_id(name)),
+                style.string("title", &links.page_title(name)),
+                style.number("sidebar_position", position),
+            ],
+            Format::Hugo => vec![
+                style.string("title", &links.page_title(name)),
+                style.number("weight", position),
+                to_hugo_menu(
+                    &format!("{}-{}", SECTION, links.page_id(name)),
+                    Some(position),
+                    style,
+                ),
+            ],
+            Format::Zola => vec![
+                style.string("title", &links.page_title(name)),
+                style.number("weight", position),
+            ],
+            // The uid lets other DocFX pages link to the page with xref
+            Format::Docfx => vec![
+                style.string("uid", &format!("{}-{}", SECTION, links.page_id(name))),
+                style.string("title", &links.page_title(name)),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the page's markdown with anything the format can't handle
+    /// escaped, or converted to the format's own markup
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - the page's markdown
+    /// * `site_name` - the name of the documentation site
+    /// * `links` - how pages and anchors are named
+    pub fn convert(self, markdown: &str, site_name: &str, links: &Links) -> String {
+        match self {
+            Format::Docusaurus => escape_mdx(markdown),
+            // Vue would otherwise compile {{ }} in descriptions as templates
+            Format::Vitepress | Format::Vuepress => format!("::: v-pre\n{}\n:::\n", markdown),
+            Format::Confluence => confluence::to_storage(markdown, site_name, links),
+            Format::Term => term::to_ansi(markdown),
+            _ => markdown.to_string(),
+        }
+    }
+
+    /// Returns the files the format needs besides the pages, as paths
+    /// relative to --out-dir and their contents
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - the pages, keyed by name
+    /// * `site_name` - the name of the documentation site
+    /// * `links` - how pages are named
+    /// * `style` - the language of any front matter
+    pub fn extra_files(
+        self,
+        contents: &HashMap<String, String>,
+        site_name: &str,
+        links: &Links,
+        style: FrontMatterStyle,
+    ) -> Vec<(PathBuf, String)> {
+        match self {
+            Format::Markdown
+            | Format::Confluence
+            | Format::Json
+            | Format::Yaml
+            | Format::LlmsTxt
+            | Format::Term => Vec::new(),
+            Format::Techdocs => {
+                let pages = get_nav_pages(contents);
+                vec![
+                    (
+                        PathBuf::from("mkdocs.yml"),
+                        to_mkdocs_yml(&pages, site_name, links),
+                    ),
+                    (
+                        self.pages_dir().join("index.md"),
+                        to_index(&pages, site_name, links),
+                    ),
+                ]
+            }
+            Format::Docusaurus => vec![(
+                self.pages_dir().join("_category_.json"),
+                to_category_json(site_name),
+            )],
+            Format::Hugo => vec![(
+                self.pages_dir().join("_index.md"),
+                style.wrap(&[
+                    style.string("title", site_name),
+                    to_hugo_menu(SECTION, None, style),
+                ]),
+            )],
+            Format::Mdbook => {
+                let pages = get_nav_pages(contents);
+                vec![
+                    (PathBuf::from("book.toml"), to_book_toml(site_name)),
+                    (
+                        self.pages_dir().join("SUMMARY.md"),
+                        to_summary(&pages, links),
+                    ),
+                ]
+            }
+            Format::Obsidian => obsidian::to_vault(contents, links),
+            Format::Docfx => {
+                let pages = get_nav_pages(contents);
+                vec![
+                    (
+                        self.pages_dir().join("toc.yml"),
+                        to_docfx_toc(&pages, site_name, links),
+                    ),
+                    (
+                        self.pages_dir().join("index.md"),
+                        to_index(&pages, site_name, links),
+                    ),
+                ]
+            }
+            Format::Slate => vec![(
+                self.pages_dir().join("index.html.md"),
+                slate::to_slate(contents, site_name, links),
+            )],
+            Format::Zola => vec![(
+                self.pages_dir().join("_index.md"),
+                style.wrap(&[
+                    style.string("title", site_name),
+                    style.string("sort_by", "weight"),
+                ]),
+            )],
+            Format::GithubWiki => {
+                let pages = get_nav_pages(contents);
+                vec![
+                    (PathBuf::from("Home.md"), to_index(&pages, site_name, links)),
+                    (
+                        PathBuf::from("_Sidebar.md"),
+                        to_wiki_sidebar(&pages, site_name, links),
+                    ),
+                ]
+            }
+            Format::Vitepress | Format::Vuepress => {
+                let pages = get_nav_pages(contents);
+                let children = if self == Format::Vitepress {
+                    "items"
+                } else {
+                    "children"
+                };
+                let sidebar = to_sidebar(&pages, site_name, links, children);
+                vec![
+                    (
+                        self.pages_dir().join("index.md"),
+                        to_index(&pages, site_name, links),
+                    ),
+                    (
+                        self.pages_dir().join("sidebar.json"),
+                        format!("{}\n", sidebar),
+                    ),
+                    (
+                        self.pages_dir().join("sidebar.ts"),
+                        format!("export default {};\n", sidebar),
+                    ),
+                ]
+            }
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" => Ok(Format::Markdown),
+            "techdocs" => Ok(Format::Techdocs),
+            "docusaurus" => Ok(Format::Docusaurus),
+            "hugo" => Ok(Format::Hugo),
+            "mdbook" => Ok(Format::Mdbook),
+            "vitepress" => Ok(Format::Vitepress),
+            "vuepress" => Ok(Format::Vuepress),
+            "confluence" => Ok(Format::Confluence),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "term" => Ok(Format::Term),
+            "github-wiki" => Ok(Format::GithubWiki),
+            "obsidian" => Ok(Format::Obsidian),
+            "zola" => Ok(Format::Zola),
+            "slate" => Ok(Format::Slate),
+            "docfx" => Ok(Format::Docfx),
+            "llms-txt" => Ok(Format::LlmsTxt),
+            _ => Err(format!("invalid format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Markdown => write!(f, "markdown"),
+            Format::Techdocs => write!(f, "techdocs"),
+            Format::Docusaurus => write!(f, "docusaurus"),
+            Format::Hugo => write!(f, "hugo"),
+            Format::Mdbook => write!(f, "mdbook"),
+            Format::Vitepress => write!(f, "vitepress"),
+            Format::Vuepress => write!(f, "vuepress"),
+            Format::Confluence => write!(f, "confluence"),
+            Format::Json => write!(f, "json"),
+            Format::Yaml => write!(f, "yaml"),
+            Format::Term => write!(f, "term"),
+            Format::GithubWiki => write!(f, "github-wiki"),
+            Format::Obsidian => write!(f, "obsidian"),
+            Format::Zola => write!(f, "zola"),
+            Format::Slate => write!(f, "slate"),
+            Format::Docfx => write!(f, "docfx"),
+            Format::LlmsTxt => write!(f, "llms-txt"),
+        }
+    }
+}
+
+impl FrontMatterStyle {
+    /// Returns the front matter with the lines between its delimiters
+    pub fn wrap(self, lines: &[String]) -> String {
+        let delimiter = match self {
+            FrontMatterStyle::Yaml => "---",
+            FrontMatterStyle::Toml => "+++",
+            FrontMatterStyle::Json => return format!("{}\n", to_json_object(lines)),
+        };
+        format!("{}\n{}\n{}\n", delimiter, lines.join("\n"), delimiter)
+    }
+
+    /// Returns a line setting the key to a string
+    pub fn string(self, key: &str, value: &str) -> String {
+        match self {
+            FrontMatterStyle::Yaml => format!("{}: {}", key, to_yaml_string(value)),
+            FrontMatterStyle::Toml => format!("{} = {}", key, to_toml_string(value)),
+            FrontMatterStyle::Json => format!("{}: {}", to_json_string(key), to_json_string(value)),
+        }
+    }
+
+    /// Returns a line setting the key to a number
+    pub fn number(self, key: &str, value: usize) -> String {
+        match self {
+            FrontMatterStyle::Yaml => format!("{}: {}", key, value),
+            FrontMatterStyle::Toml => format!("{} = {}", key, value),
+            FrontMatterStyle::Json => format!("{}: {}", to_json_string(key), value),
+        }
+    }
+
+    /// Converts a `key: value` line, as --front-matter is written, to this
+    /// language, leaving numbers and booleans unquoted
+    pub fn convert(self, line: &str) -> String {
+        match (self, line.split_once(':')) {
+            (FrontMatterStyle::Toml, Some((key, value))) => {
+                let value = value.trim();
+                if value.parse::<f64>().is_ok() || value == "true" || value == "false" {
+                    format!("{} = {}", key.trim(), value)
+                } else {
+                    format!("{} = {}", key.trim(), to_toml_string(value))
+                }
+            }
+            (FrontMatterStyle::Json, Some((key, value))) => {
+                let value = value.trim();
+                if value.parse::<f64>().is_ok() || value == "true" || value == "false" {
+                    format!("{}: {}", to_json_string(key.trim()), value)
+                } else {
+                    FrontMatterStyle::Json.string(key.trim(), value)
+                }
+            }
+            _ => line.to_string(),
+        }
+    }
+}
+
+impl FromStr for FrontMatterStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yaml" => Ok(FrontMatterStyle::Yaml),
+            "toml" => Ok(FrontMatterStyle::Toml),
+            "json" => Ok(FrontMatterStyle::Json),
+            _ => Err(format!("invalid front matter format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for FrontMatterStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrontMatterStyle::Yaml => write!(f, "yaml"),
+            FrontMatterStyle::Toml => write!(f, "toml"),
+            FrontMatterStyle::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Returns the names of the non-empty pages in navigation order
+pub fn get_nav_pages(contents: &HashMap<String, String>) -> Vec<&str> {
+    let mut pages: Vec<&str> = contents
+        .iter()
+        .filter(|(_, markdown)| !markdown.is_empty())
+        .map(|(name, _)| name.as_str())
+        .collect();
+    pages.sort_by_key(|name| {
+        (
+            PAGE_ORDER
+                .iter()
+                .position(|page| page == name)
+                .unwrap_or(PAGE_ORDER.len()),
+            *name,
+        )
+    });
+    pages
+}
+
+fn to_mkdocs_yml(pages: &[&str], site_name: &str, links: &Links) -> String {
+    let mut s = String::new();
+    s.push_str(&format!("site_name: {}\n", to_yaml_string(site_name)));
+    s.push_str("nav:\n");
+    s.push_str("  - Home: index.md\n");
+    for page in pages {
+        s.push_str(&format!(
+            "  - {}: {}\n",
+            to_yaml_string(&links.page_title(page)),
+            links.page_file(page)
+        ));
+    }
+    s.push_str("plugins:\n");
+    s.push_str("  - techdocs-core\n");
+    s
+}
+
+fn to_index(pages: &[&str], site_name: &str, links: &Links) -> String {
+    let mut s = format!("# {}\n\n", site_name);
+    for page in pages {
+        s.push_str(&format!(
+            "* [{}]({})\n",
+            links.page_title(page),
+            links.page_link(page)
+        ));
+    }
+    s
+}
+
+fn to_docfx_toc(pages: &[&str], site_name: &str, links: &Links) -> String {
+    let mut s = format!("- name: {}\n  href: index.md\n", to_yaml_string(site_name));
+    for page in pages {
+        s.push_str(&format!(
+            "- name: {}\n  href: {}\n",
+            to_yaml_string(&links.page_title(page)),
+            links.page_file(page)
+        ));
+    }
+    s
+}
+
+// GitHub shows the sidebar next to every page of the wiki
+fn to_wiki_sidebar(pages: &[&str], site_name: &str, links: &Links) -> String {
+    let mut s = format!("**[{}](Home)**\n\n", site_name);
+    for page in pages {
+        s.push_str(&format!(
+            "* [{}]({})\n",
+            links.page_title(page),
+            links.page_link(page)
+        ));
+    }
+    s
+}
+
+fn to_book_toml(site_name: &str) -> String {
+    format!(
+        "[book]\ntitle = {}\nsrc = \"src\"\n",
+        to_toml_string(site_name)
+    )
+}
+
+// mdBook builds its table of contents, and decides which files are chapters,
+// from this list
+fn to_summary(pages: &[&str], links: &Links) -> String {
+    let mut s = "# Summary\n\n".to_string();
+    for page in pages {
+        s.push_str(&format!(
+            "- [{}]({})\n",
+            links.page_title(page),
+            links.page_file(page)
+        ));
+    }
+    s
+}
+
+// Returns the sidebar for the section, with the pages grouped into
+// operations, types, and everything else; `children` is the key the theme
+// nests items under
+fn to_sidebar(pages: &[&str], site_name: &str, links: &Links, children: &str) -> String {
+    let section = format!("/{}/", SECTION);
+    let mut groups = vec![
+        (
+            site_name,
+            vec![serde_json::json!({ "text": "Overview", "link": section })],
+        ),
+        ("Types", Vec::new()),
+        ("More", Vec::new()),
+    ];
+    for page in pages {
+        let group = if OPERATION_PAGES.contains(page) {
+            0
+        } else if PAGE_ORDER.contains(page) {
+            1
+        } else {
+            2
+        };
+        groups[group].1.push(serde_json::json!({
+            "text": links.page_title(page),
+            "link": format!("{}{}", section, links.page_id(page)),
+        }));
+    }
+    let groups: Vec<serde_json::Value> = groups
+        .into_iter()
+        .filter(|(_, items)| !items.is_empty())
+        .map(|(text, items)| {
+            let mut group = serde_json::json!({ "text": text });
+            group[children] = serde_json::Value::from(items);
+            group
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({ section: groups })).unwrap()
+}
+
+// Returns a `main` menu entry; pages are entries under the section's entry
+fn to_hugo_menu(identifier: &str, weight: Option<usize>, style: FrontMatterStyle) -> String {
+    let mut entries = vec![("identifier", to_toml_string(identifier))];
+    if let Some(weight) = weight {
+        entries.push(("parent", to_toml_string(SECTION)));
+        entries.push(("weight", weight.to_string()));
+    }
+    match style {
+        FrontMatterStyle::Yaml => {
+            let mut s = "menu:\n  main:".to_string();
+            for (key, value) in entries {
+                s.push_str(&format!("\n    {}: {}", key, value));
+            }
+            s
+        }
+        FrontMatterStyle::Toml => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{} = {}", key, value))
+                .collect();
+            format!("menu = {{ main = {{ {} }} }}", entries.join(", "))
+        }
+        FrontMatterStyle::Json => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", to_json_string(key), value))
+                .collect();
+            format!("\"menu\": {{ \"main\": {{ {} }} }}", entries.join(", "))
+        }
+    }
+}
+
+fn to_category_json(site_name: &str) -> String {
+    let category = serde_json::json!({
+        "label": site_name,
+        "link": { "type": "generated-index" },
+    });
+    format!("{}\n", serde_json::to_string_pretty(&category).unwrap())
+}
+
+// Escapes the braces and angle brackets MDX would read as JSX, leaving code
+// and gumwood's own anchors alone
+fn escape_mdx(markdown: &str) -> String {
+    let mut s = String::new();
+    for (piece, is_code) in split_code(markdown) {
+        if is_code {
+            s.push_str(piece);
+        } else {
+            s.push_str(&escape_mdx_text(piece));
+        }
+    }
+    s
+}
+
+fn escape_mdx_text(text: &str) -> String {
+    let mut s = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some(anchor) = ["<a name=\"", "</a>"]
+            .iter()
+            .find(|anchor| rest.starts_with(**anchor))
+        {
+            s.push_str(anchor);
+            rest = &rest[anchor.len()..];
+            continue;
+        }
+        match c {
+            '{' => s.push_str("\\{"),
+            '}' => s.push_str("\\}"),
+            '<' => s.push_str("&lt;"),
+            _ => s.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    s
+}
+
+fn to_toml_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn to_json_string(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+/// Returns a JSON object with the `"key": value` entries, indenting any
+/// that span lines
+pub fn to_json_object(entries: &[String]) -> String {
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+    let entries: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .lines()
+                .map(|line| format!("  {}", line))
+                .collect::<Vec<String>>()
+                .join("\n")
+        })
+        .collect();
+    format!("{{\n{}\n}}", entries.join(",\n"))
+}
+
+/// Quotes the string if YAML would otherwise misread it
+pub fn to_yaml_string(s: &str) -> String {
+    if s.is_empty() || s.contains(|c: char| ":#{}[],&*!|>'\"%@`".contains(c)) {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents() -> HashMap<String, String> {
+        let mut contents = HashMap::new();
+        for name in &["objects", "queries", "largest-types", "about", "unions"] {
+            contents.insert(name.to_string(), format!("# {}\n", name));
+        }
+        contents.insert("mutations".to_string(), "".to_string());
+        contents
+    }
+
+    #[test]
+    fn get_nav_pages_should_order_pages_and_skip_empty() {
+        assert_eq!(
+            vec!["queries", "objects", "unions", "about", "largest-types"],
+            get_nav_pages(&contents())
+        );
+    }
+
+    #[test]
+    fn markdown_should_have_no_extra_files() {
+        assert!(Format::Markdown
+            .extra_files(
+                &contents(),
+                "API",
+                &Links::default(),
+                FrontMatterStyle::Yaml
+            )
+            .is_empty());
+        assert_eq!(PathBuf::new(), Format::Markdown.pages_dir());
+    }
+
+    #[test]
+    fn techdocs_should_write_mkdocs_yml_and_index() {
+        let files = Format::Techdocs.extra_files(
+            &contents(),
+            "League API",
+            &Links::default(),
+            FrontMatterStyle::Yaml,
+        );
+        assert_eq!(PathBuf::from("mkdocs.yml"), files[0].0);
+        assert_eq!(
+            r#"site_name: League API
+nav:
+  - Home: index.md
+  - Queries: queries.md
+  - Objects: objects.md
+  - Unions: unions.md
+  - About: about.md
+  - Largest Types: largest-types.md
+plugins:
+  - techdocs-core
+"#,
+            files[0].1
+        );
+        assert_eq!(PathBuf::from("docs").join("index.md"), files[1].0);
+        assert!(files[1]
+            .1
+            .starts_with("# League API\n\n* [Queries](queries.md)\n"));
+    }
+
+    #[test]
+    fn docfx_should_write_toc_yml_and_uids() {
+        let links = Links::default();
+        let files =
+            Format::Docfx.extra_files(&contents(), "League API", &links, FrontMatterStyle::Yaml);
+        assert_eq!(PathBuf::from("graphql").join("toc.yml"), files[0].0);
+        assert!(files[0].1.starts_with(
+            "- name: League API\n  href: index.md\n- name: Queries\n  href: queries.md\n- name: Objects\n  href: objects.md\n"
+        ));
+        assert_eq!(PathBuf::from("graphql").join("index.md"), files[1].0);
+        assert_eq!(
+            vec![
+                "uid: graphql-objects".to_string(),
+                "title: Objects".to_string()
+            ],
+            Format::Docfx.front_matter("objects", &contents(), &links, FrontMatterStyle::Yaml)
+        );
+    }
+
+    #[test]
+    fn docusaurus_should_write_category_json() {
+        let files = Format::Docusaurus.extra_files(
+            &contents(),
+            "League API",
+            &Links::default(),
+            FrontMatterStyle::Yaml,
+        );
+        assert_eq!(PathBuf::from("_category_.json"), files[0].0);
+        assert_eq!(
+            r#"{
+  "label": "League API",
+  "link": {
+    "type": "generated-index"
+  }
+}
+"#,
+            files[0].1
+        );
+    }
+
+    #[test]
+    fn docusaurus_should_add_front_matter() {
+        assert_eq!(
+            vec![
+                "id: largest-types",
+                "title: Largest Types",
+                "sidebar_position: 5"
+            ],
+            Format::Docusaurus.front_matter(
+                "largest-types",
+                &contents(),
+                &Links::default(),
+                FrontMatterStyle::Yaml
+            )
+        );
+        assert!(Format::Markdown
+            .front_matter(
+                "objects",
+                &contents(),
+                &Links::default(),
+                FrontMatterStyle::Yaml
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn mdbook_should_write_book_toml_and_summary() {
+        let files = Format::Mdbook.extra_files(
+            &contents(),
+            "League \"API\"",
+            &Links::default(),
+            FrontMatterStyle::Yaml,
+        );
+        assert_eq!(PathBuf::from("book.toml"), files[0].0);
+        assert_eq!(
+            "[book]\ntitle = \"League \\\"API\\\"\"\nsrc = \"src\"\n",
+            files[0].1
+        );
+        assert_eq!(PathBuf::from("src").join("SUMMARY.md"), files[1].0);
+        assert_eq!(
+            r#"# Summary
+
+- [Queries](queries.md)
+- [Objects](objects.md)
+- [Unions](unions.md)
+- [About](about.md)
+- [Largest Types](largest-types.md)
+"#,
+            files[1].1
+        );
+    }
+
+    #[test]
+    fn github_wiki_should_write_home_and_sidebar() {
+        let links = Links {
+            wiki_pages: true,
+            ..Default::default()
+        };
+        let files = Format::GithubWiki.extra_files(
+            &contents(),
+            "League API",
+            &links,
+            FrontMatterStyle::Yaml,
+        );
+        assert_eq!(PathBuf::from("Home.md"), files[0].0);
+        assert!(files[0]
+            .1
+            .starts_with("# League API\n\n* [Queries](Queries)\n"));
+        assert_eq!(PathBuf::from("_Sidebar.md"), files[1].0);
+        assert_eq!(
+            r#"**[League API](Home)**
+
+* [Queries](Queries)
+* [Objects](Objects)
+* [Unions](Unions)
+* [About](About)
+* [Largest Types](Largest-Types)
+"#,
+            files[1].1
+        );
+        assert!(!Format::GithubWiki.has_front_matter());
+    }
+
+    #[test]
+    fn vitepress_should_write_index_and_sidebar() {
+        let files = Format::Vitepress.extra_files(
+            &contents(),
+            "League API",
+            &Links::default(),
+            FrontMatterStyle::Yaml,
+        );
+        assert_eq!(PathBuf::from("graphql").join("index.md"), files[0].0);
+        assert!(files[0]
+            .1
+            .starts_with("# League API\n\n* [Queries](queries.md)\n"));
+        assert_eq!(PathBuf::from("graphql").join("sidebar.json"), files[1].0);
+        assert_eq!(
+            r#"{
+  "/graphql/": [
+    {
+      "items": [
+        {
+          "link": "/graphql/",
+          "text": "Overview"
+        },
+        {
+          "link": "/graphql/queries",
+          "text": "Queries"
+        }
+      ],
+      "text": "League API"
+    },
+    {
+      "items": [
+        {
+          "link": "/graphql/objects",
+          "text": "Objects"
+        },
+        {
+          "link": "/graphql/unions",
+          "text": "Unions"
+        }
+      ],
+      "text": "Types"
+    },
+    {
+      "items": [
+        {
+          "link": "/graphql/about",
+          "text": "About"
+        },
+        {
+          "link": "/graphql/largest-types",
+          "text": "Largest Types"
+        }
+      ],
+      "text": "More"
+    }
+  ]
+}
+"#,
+            files[1].1
+        );
+        assert_eq!(PathBuf::from("graphql").join("sidebar.ts"), files[2].0);
+        assert_eq!(
+            format!("export default {};\n", files[1].1.trim_end()),
+            files[2].1
+        );
+    }
+
+    #[test]
+    fn vuepress_should_nest_sidebar_under_children() {
+        let files = Format::Vuepress.extra_files(
+            &contents(),
+            "League API",
+            &Links::default(),
+            FrontMatterStyle::Yaml,
+        );
+        assert!(files[1].1.contains("\"children\": ["));
+        assert!(!files[1].1.contains("\"items\""));
+    }
+
+    #[test]
+    fn vitepress_should_escape_vue_interpolation() {
+        assert_eq!(
+            "::: v-pre\n# Objects\n\nReturns {{ id }}\n\n:::\n",
+            Format::Vitepress.convert("# Objects\n\nReturns {{ id }}\n", "API", &Links::default())
+        );
+    }
+
+    #[test]
+    fn hugo_should_add_weight_and_menu() {
+        let links = Links {
+            page_bundles: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                "title: Objects",
+                "weight: 2",
+                "menu:\n  main:\n    identifier: \"graphql-objects\"\n    parent: \"graphql\"\n    weight: 2"
+            ],
+            Format::Hugo.front_matter("objects", &contents(), &links, FrontMatterStyle::Yaml)
+        );
+        assert_eq!(
+            vec![
+                "title = \"Objects\"",
+                "weight = 2",
+                "menu = { main = { identifier = \"graphql-objects\", parent = \"graphql\", weight = 2 } }"
+            ],
+            Format::Hugo.front_matter("objects", &contents(), &links, FrontMatterStyle::Toml)
+        );
+    }
+
+    #[test]
+    fn hugo_should_write_section_index() {
+        let files = Format::Hugo.extra_files(
+            &contents(),
+            "League API",
+            &Links::default(),
+            FrontMatterStyle::Toml,
+        );
+        assert_eq!(
+            PathBuf::from("content").join("graphql").join("_index.md"),
+            files[0].0
+        );
+        assert_eq!(
+            "+++\ntitle = \"League API\"\nmenu = { main = { identifier = \"graphql\" } }\n+++\n",
+            files[0].1
+        );
+    }
+
+    #[test]
+    fn zola_should_write_section_and_weights() {
+        assert_eq!(
+            vec!["title = \"Objects\"", "weight = 2"],
+            Format::Zola.front_matter(
+                "objects",
+                &contents(),
+                &Links::default(),
+                FrontMatterStyle::Toml
+            )
+        );
+        let files = Format::Zola.extra_files(
+            &contents(),
+            "League API",
+            &Links::default(),
+            FrontMatterStyle::Toml,
+        );
+        assert_eq!(
+            PathBuf::from("content").join("graphql").join("_index.md"),
+            files[0].0
+        );
+        assert_eq!(
+            "+++\ntitle = \"League API\"\nsort_by = \"weight\"\n+++\n",
+            files[0].1
+        );
+        assert_eq!(
+            Some(FrontMatterStyle::Toml),
+            Format::Zola.required_front_matter()
+        );
+        assert_eq!("@/graphql/", Format::Zola.page_link_prefix());
+    }
+
+    #[test]
+    fn front_matter_style_should_convert_lines() {
+        assert_eq!("kind: enums", FrontMatterStyle::Yaml.convert("kind: enums"));
+        assert_eq!(
+            "kind = \"enums\"",
+            FrontMatterStyle::Toml.convert("kind: enums")
+        );
+        assert_eq!("count = 3", FrontMatterStyle::Toml.convert("count: 3"));
+        assert_eq!(
+            "draft = true",
+            FrontMatterStyle::Toml.convert("draft: true")
+        );
+        assert_eq!(
+            "\"kind\": \"enums\"",
+            FrontMatterStyle::Json.convert("kind: enums")
+        );
+        assert_eq!("\"count\": 3", FrontMatterStyle::Json.convert("count: 3"));
+    }
+
+    #[test]
+    fn hugo_front_matter_should_write_json() {
+        let links = Links::default();
+        assert_eq!(
+            vec![
+                "\"title\": \"Objects\"".to_string(),
+                "\"weight\": 2".to_string(),
+                "\"menu\": { \"main\": { \"identifier\": \"graphql-objects\", \"parent\": \"graphql\", \"weight\": 2 } }".to_string(),
+            ],
+            Format::Hugo.front_matter("objects", &contents(), &links, FrontMatterStyle::Json)
+        );
+    }
+
+    #[test]
+    fn escape_mdx_should_escape_jsx_outside_code() {
+        assert_eq!(
+            "Returns \\{id\\} when x &lt; 3 or &lt;b>, not `{id} < 3`\n",
+            escape_mdx("Returns {id} when x < 3 or <b>, not `{id} < 3`\n")
+        );
+        assert_eq!(
+            "## <a name=\"player\"></a>Player\n",
+            escape_mdx("## <a name=\"player\"></a>Player\n")
+        );
+        assert_eq!(
+            "```graphql\ntype A { b: B }\n```\n\\{\n",
+            escape_mdx("```graphql\ntype A { b: B }\n```\n{\n")
+        );
+        assert_eq!("`` a ` { ``\n", escape_mdx("`` a ` { ``\n"));
+    }
+
+    #[test]
+    fn to_yaml_string_should_quote_when_needed() {
+        assert_eq!("League API", to_yaml_string("League API"));
+        assert_eq!("\"API: v2\"", to_yaml_string("API: v2"));
+    }
+
+    #[test]
+    fn format_should_round_trip() {
+        for format in &[
+            Format::Markdown,
+            Format::Techdocs,
+            Format::Docusaurus,
+            Format::Hugo,
+            Format::Mdbook,
+            Format::Vitepress,
+            Format::Vuepress,
+            Format::Confluence,
+            Format::Json,
+            Format::Yaml,
+            Format::Term,
+            Format::GithubWiki,
+            Format::Obsidian,
+            Format::Zola,
+            Format::Slate,
+            Format::Docfx,
+            Format::LlmsTxt,
+        ] {
+            assert_eq!(*format, format.to_string().parse().unwrap());
+        }
+    }
+}
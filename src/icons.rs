@@ -0,0 +1,234 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    str::FromStr,
+};
+
+/// The names of the icons that can be configured: one for each page, plus
+/// markers for deprecated and auth-required elements
+pub const ICON_NAMES: &[&str] = &[
+    "queries",
+    "mutations",
+    "subscriptions",
+    "objects",
+    "inputs",
+    "interfaces",
+    "enums",
+    "unions",
+    "scalars",
+    "directives",
+    "deprecated",
+    "auth",
+];
+
+/// Which icons to decorate headings and tables with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IconSet {
+    None,
+    Emoji,
+    Ascii,
+}
+
+impl IconSet {
+    fn default_icon(self, name: &str) -> Option<&'static str> {
+        let (emoji, ascii) = match name {
+            "queries" => ("🔍", "[Q]"),
+            "mutations" => ("✏️", "[M]"),
+            "subscriptions" => ("📡", "[S]"),
+            "objects" => ("📄", "[O]"),
+            "inputs" => ("📝", "[IN]"),
+            "interfaces" => ("🔌", "[IF]"),
+            "enums" => ("🔢", "[E]"),
+            "unions" => ("🔀", "[U]"),
+            "scalars" => ("🔤", "[SC]"),
+            "directives" => ("🏷️", "[DIR]"),
+            "deprecated" => ("⚠️", "[!]"),
+            "auth" => ("🔒", "[auth]"),
+            _ => return None,
+        };
+        match self {
+            IconSet::None => None,
+            IconSet::Emoji => Some(emoji),
+            IconSet::Ascii => Some(ascii),
+        }
+    }
+}
+
+impl FromStr for IconSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(IconSet::None),
+            "emoji" => Ok(IconSet::Emoji),
+            "ascii" => Ok(IconSet::Ascii),
+            _ => Err(format!("invalid icon set: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for IconSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IconSet::None => write!(f, "none"),
+            IconSet::Emoji => write!(f, "emoji"),
+            IconSet::Ascii => write!(f, "ascii"),
+        }
+    }
+}
+
+/// The icons in effect for a run: an icon set, any icons overridden in the
+/// config file, and the types and fields that require authorization
+#[derive(Debug)]
+pub struct Icons {
+    set: IconSet,
+    overrides: HashMap<String, String>,
+    auth_required: HashSet<String>,
+}
+
+impl Default for Icons {
+    fn default() -> Self {
+        Icons {
+            set: IconSet::None,
+            overrides: HashMap::new(),
+            auth_required: HashSet::new(),
+        }
+    }
+}
+
+impl Icons {
+    /// Creates the icons for a run
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the icon set to use
+    /// * `overrides` - icons to use instead of the set's, keyed by icon name
+    /// * `auth_required` - type names and `Type.field` coordinates that require authorization
+    pub fn new(
+        set: IconSet,
+        overrides: &HashMap<String, String>,
+        auth_required: &[String],
+    ) -> Result<Icons, Box<dyn Error>> {
+        if let Some(name) = overrides
+            .keys()
+            .find(|name| !ICON_NAMES.contains(&name.as_str()))
+        {
+            return Err(format!(
+                "unknown icon {} (expected one of {})",
+                name,
+                ICON_NAMES.join(", ")
+            )
+            .into());
+        }
+
+        Ok(Icons {
+            set,
+            overrides: overrides.clone(),
+            auth_required: auth_required.iter().cloned().collect(),
+        })
+    }
+
+    /// Returns a description of the icons that's the same from run to run
+    /// for the same settings
+    pub fn fingerprint(&self) -> String {
+        let mut overrides: Vec<_> = self.overrides.iter().collect();
+        overrides.sort();
+        let mut auth_required: Vec<_> = self.auth_required.iter().collect();
+        auth_required.sort();
+        format!("{:?} {:?} {:?}", self.set, overrides, auth_required)
+    }
+
+    /// Returns the icon with the specified name, or None if icons are off
+    pub fn get(&self, name: &str) -> Option<&str> {
+        if self.set == IconSet::None {
+            return None;
+        }
+        match self.overrides.get(name) {
+            Some(icon) => Some(icon),
+            None => self.set.default_icon(name),
+        }
+    }
+
+    /// Returns whether the type or `Type.field` coordinate requires authorization
+    pub fn is_auth_required(&self, coordinate: &str) -> bool {
+        self.auth_required.contains(coordinate)
+    }
+
+    /// Returns the text prefixed with the named icons that are in effect
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - the names of the icons to prefix
+    /// * `text` - the text to decorate
+    pub fn decorate(&self, names: &[&str], text: &str) -> String {
+        let mut s = String::new();
+        for icon in names.iter().filter_map(|name| self.get(name)) {
+            s.push_str(icon);
+            s.push(' ');
+        }
+        s.push_str(text);
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decorate_should_not_change_text_when_icons_are_off() {
+        let icons = Icons::default();
+        assert_eq!("Objects", icons.decorate(&["objects"], "Objects"));
+    }
+
+    #[test]
+    fn decorate_should_prefix_emoji() {
+        let icons = Icons::new(IconSet::Emoji, &HashMap::new(), &[]).unwrap();
+        assert_eq!(
+            "🔒 ⚠️ viewer",
+            icons.decorate(&["auth", "deprecated"], "viewer")
+        );
+    }
+
+    #[test]
+    fn decorate_should_prefix_ascii() {
+        let icons = Icons::new(IconSet::Ascii, &HashMap::new(), &[]).unwrap();
+        assert_eq!("[O] Objects", icons.decorate(&["objects"], "Objects"));
+    }
+
+    #[test]
+    fn get_should_prefer_overrides() {
+        let mut overrides = HashMap::new();
+        overrides.insert("deprecated".to_string(), "🚧".to_string());
+        let icons = Icons::new(IconSet::Emoji, &overrides, &[]).unwrap();
+        assert_eq!(Some("🚧"), icons.get("deprecated"));
+        assert_eq!(Some("📄"), icons.get("objects"));
+    }
+
+    #[test]
+    fn new_should_fail_on_unknown_icon() {
+        let mut overrides = HashMap::new();
+        overrides.insert("widgets".to_string(), "🧩".to_string());
+        assert!(Icons::new(IconSet::Emoji, &overrides, &[]).is_err());
+    }
+
+    #[test]
+    fn is_auth_required_should_match_coordinates() {
+        let icons = Icons::new(
+            IconSet::Emoji,
+            &HashMap::new(),
+            &["Query.viewer".to_string()],
+        )
+        .unwrap();
+        assert!(icons.is_auth_required("Query.viewer"));
+        assert!(!icons.is_auth_required("Query.player"));
+    }
+
+    #[test]
+    fn icon_set_should_round_trip() {
+        for set in &[IconSet::None, IconSet::Emoji, IconSet::Ascii] {
+            assert_eq!(*set, set.to_string().parse().unwrap());
+        }
+    }
+}
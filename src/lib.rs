@@ -1,46 +1,249 @@
+mod archive;
+mod ast;
+mod budget;
+mod cache;
+mod catalog;
+mod clipboard;
+mod compare;
+mod config;
+mod confluence;
+mod cost;
+mod descriptions;
+mod errors;
+mod examples;
+mod explorer;
+mod filename;
+mod format;
+mod icons;
+mod links;
+mod lint;
+mod llms_txt;
 mod markdown;
+mod metadata;
+mod model;
+mod netrc;
+mod notion;
+mod obsidian;
+mod pager;
+mod pagination;
+mod prose;
+mod publish;
+mod redact;
+mod report;
+mod sanitize;
 mod schema;
 mod schema_markdown;
+mod sdl;
+mod search;
+mod single_file;
+mod slate;
+mod slug;
+mod stats;
+mod sunset;
+mod tables;
+mod tags;
+mod tape;
+mod term;
+mod versions;
+mod voyager;
 
-use schema::Schema;
-use schema_markdown::generate_from_schema;
+use budget::Budget;
+use cache::RenderCache;
+use catalog::Catalog;
+use chrono::{SecondsFormat, Utc};
+use compare::DiffFormat;
+use config::Config;
+use cost::Costs;
+use descriptions::Descriptions;
+use format::{to_json_object, Format, FrontMatterStyle, TitleCase};
+use icons::{IconSet, Icons};
+use links::Links;
+use lint::lint;
+use metadata::Metadata;
+use model::Model;
+use netrc::Credentials;
+use pager::Output;
+use prose::Prose;
+use publish::{Auth, Confluence, Notion};
+use report::{find_dangling_links, Report};
+use reqwest::Url;
+use schema::{
+    introspect, to_capabilities_request, to_introspection_request, Capabilities, Endpoint,
+    MergeConflict, MergeStrategy, Schema, DEFAULT_TYPE_DEPTH,
+};
+use schema_markdown::{
+    about_to_markdown, cost_to_markdown, cycles_to_markdown, defaults_to_markdown,
+    errors_to_markdown, generate_from_schema, get_page_stats, get_unknown_kinds,
+    interface_conformance_to_markdown, largest_types_to_markdown, merge_conflicts_to_markdown,
+    pagination_to_markdown, scalar_usage_to_markdown,
+};
+use single_file::{add_contents, to_single_file};
+use stats::{get_coverage, get_cycles, set_referenced_by, set_used_by, PageStats};
 use std::{
-    collections::HashMap,
+    collections::{hash_map::DefaultHasher, HashMap},
+    env,
     error::Error,
-    fs::File,
-    io::{self, Read, Write},
-    path::PathBuf,
+    fmt,
+    fs::{self, File, OpenOptions, TryLockError},
+    hash::{Hash, Hasher},
+    io::{self, IsTerminal, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
 };
 use structopt::StructOpt;
-use titlecase::titlecase;
+use tables::Tables;
+use tape::Tape;
+use versions::Versions;
 
 /// Convert a GraphQL schema to Markdown
 ///
-/// Specify the source of the schema using --json, --url, or --schema.{n}
-/// If you don't specify a source, gumwood will read from stdin.{n}
+/// Specify the source of the schema using --json, --url, --schema, or{n}
+/// --from-clipboard;{n}
+/// repeat --url and --json to merge several services into one schema.{n}
+/// If you don't specify a source, gumwood will read from stdin when{n}
+/// something is piped or redirected to it; use --stdin to read it{n}
+/// along with other sources, or to type a schema in.{n}
 /// If you specify --out-dir, gumwood will split the output into{n}
 /// multiple files by type and write them to the specified directory.{n}
 /// If you don't specify --out-dir, gumwood will write to stdout.
-#[derive(Debug, StructOpt)]
+#[derive(Clone, Debug, StructOpt)]
 #[structopt(author)]
 pub struct Options {
-    #[structopt(short, long, help("URL to introspect"))]
-    url: Option<String>,
+    #[structopt(short, long, number_of_values(1), help("URL to introspect"))]
+    url: Vec<String>,
 
     #[structopt(
         short,
         long,
+        number_of_values(1),
         help("File containing introspection response"),
         parse(from_os_str)
     )]
-    json: Option<PathBuf>,
+    json: Vec<PathBuf>,
 
     #[structopt(short, long, help("GraphQL schema file"), parse(from_os_str))]
     schema: Option<PathBuf>,
 
-    #[structopt(short = "H", long, help("Header to send in URL request"))]
+    #[structopt(
+        long,
+        help("Read introspection JSON or SDL from the clipboard, such as a schema copied out of GraphiQL")
+    )]
+    from_clipboard: bool,
+
+    #[structopt(
+        long,
+        help("Read introspection JSON or SDL from stdin, even from a terminal or along with other sources")
+    )]
+    stdin: bool,
+
+    #[structopt(
+        long,
+        help("What to do when sources define a type differently"),
+        value_name("STRATEGY"),
+        default_value("first-wins"),
+        possible_values(&["error", "first-wins", "suffix-rename"])
+    )]
+    merge_conflicts: MergeStrategy,
+
+    #[structopt(
+        short = "H",
+        long,
+        number_of_values(1),
+        help("Header to send in URL request; ${VAR} expands environment variables")
+    )]
     header: Vec<String>,
 
+    #[structopt(
+        long,
+        help("Operation name to give the introspection query and send as operationName")
+    )]
+    operation_name: Option<String>,
+
+    #[structopt(
+        long,
+        number_of_values(1),
+        help("Variable to send with the introspection query, as name=value; JSON values are sent as JSON")
+    )]
+    variable: Vec<String>,
+
+    #[structopt(
+        long,
+        help("File of headers to send in URL request, one 'Name: Value' per line"),
+        parse(from_os_str)
+    )]
+    headers_file: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        number_of_values(1),
+        help("GraphQL schema file of types and extensions to merge on top of the schema"),
+        parse(from_os_str)
+    )]
+    overlay: Vec<PathBuf>,
+
+    #[structopt(
+        long,
+        help(
+            "Levels of list and non-null wrappers to introspect in type references (defaults to 7)"
+        )
+    )]
+    type_depth: Option<u32>,
+
+    #[structopt(
+        long,
+        help(
+            "Don't verify the URL's TLS certificate, for endpoints with self-signed certificates"
+        )
+    )]
+    insecure: bool,
+
+    #[structopt(
+        long,
+        help(
+            "Seconds to wait in all for a URL that's rate limiting requests (HTTP 429) before giving up; 0 to not retry"
+        ),
+        default_value("60")
+    )]
+    max_wait: u64,
+
+    #[structopt(
+        long,
+        help("Stop before rendering if the schema, once read and merged, has more than this many types"),
+        value_name("COUNT")
+    )]
+    max_types: Option<usize>,
+
+    #[structopt(
+        long,
+        help("Stop if the docs would be bigger than this, in bytes or with a K, M, or G suffix"),
+        value_name("SIZE"),
+        parse(try_from_str = parse_size)
+    )]
+    max_output_size: Option<u64>,
+
+    #[structopt(
+        long,
+        help("Save each URL's introspection request and response to this file"),
+        parse(from_os_str),
+        conflicts_with("replay")
+    )]
+    record: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Read the URLs' responses from a file saved with --record instead of the network"),
+        parse(from_os_str)
+    )]
+    replay: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("User-Agent to send in URL request"),
+        default_value(DEFAULT_USER_AGENT)
+    )]
+    user_agent: String,
+
     #[structopt(
         short,
         long,
@@ -49,84 +252,1521 @@ pub struct Options {
     )]
     out_dir: Option<PathBuf>,
 
+    #[structopt(
+        long,
+        help("Write every page to this one markdown file, after a table of contents"),
+        parse(from_os_str),
+        conflicts_with("out-dir"),
+        value_name("FILE")
+    )]
+    single_file: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Write the files --out-dir would get to this .zip, .tar, .tar.gz, or .tgz archive instead"),
+        parse(from_os_str),
+        conflicts_with_all(&["out-dir", "single-file"]),
+        value_name("FILE")
+    )]
+    archive: Option<PathBuf>,
+
+    #[structopt(
+        short,
+        long,
+        help("Config file (defaults to gumwood.toml if present)"),
+        parse(from_os_str)
+    )]
+    config: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Generate docs for this environment in the config file, or for every environment with all"),
+        conflicts_with_all(&["url", "out-dir", "replay", "archive"])
+    )]
+    env: Option<String>,
+
+    #[structopt(
+        long,
+        help("Note the types and fields that only some of the config file's environments have"),
+        requires("env")
+    )]
+    env_badges: bool,
+
+    // The environment's introspection responses, when --env-badges has
+    // already fetched them
+    #[structopt(skip)]
+    tape: Option<Tape>,
+
+    // Notes on the elements only some environments have
+    #[structopt(skip)]
+    env_notes: Descriptions,
+
+    #[structopt(
+        long,
+        help("Layout of the output files"),
+        default_value("markdown"),
+        possible_values(&[
+            "markdown",
+            "techdocs",
+            "docusaurus",
+            "hugo",
+            "mdbook",
+            "vitepress",
+            "vuepress",
+            "confluence",
+            "json",
+            "yaml",
+            "term",
+            "github-wiki",
+            "obsidian",
+            "zola",
+            "slate",
+            "docfx",
+            "llms-txt"
+        ])
+    )]
+    format: Format,
+
+    #[structopt(
+        long,
+        help("Name of the documentation site, for formats that have one"),
+        default_value("GraphQL API")
+    )]
+    site_name: String,
+
+    #[structopt(
+        long,
+        help("TOML file of descriptions that replace or add to the schema's"),
+        parse(from_os_str)
+    )]
+    descriptions: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Directory of markdown files, such as Player.md and Query.players.md, to add to the types and fields they're named for"),
+        parse(from_os_str)
+    )]
+    docs_dir: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("SpectaQL-style JSON metadata file of examples, undocumented elements, and operation groups"),
+        parse(from_os_str)
+    )]
+    metadata: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help(
+            "Keep a stub of each undocumented type that's still referenced, so links to it resolve"
+        )
+    )]
+    stub_undocumented: bool,
+
+    #[structopt(
+        long,
+        help("TOML or JSON file of the versions types and fields were added in"),
+        parse(from_os_str)
+    )]
+    versions: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("TOML file of operation costs and rate limits, for a Cost page"),
+        parse(from_os_str)
+    )]
+    costs: Option<PathBuf>,
+
     #[structopt(short, long, help("Front matter for output files"))]
     front_matter: Option<String>,
 
+    #[structopt(
+        long,
+        alias("frontmatter-key-prefix"),
+        help("Nest front matter under this key (use dots for deeper nesting)")
+    )]
+    front_matter_key_prefix: Option<String>,
+
+    #[structopt(
+        long,
+        help("Add type_count, field_count, and deprecated_count to each page's front matter")
+    )]
+    front_matter_stats: bool,
+
+    #[structopt(
+        long,
+        help("Language of the front matter (defaults to toml for zola, yaml otherwise)"),
+        possible_values(&["yaml", "toml", "json"])
+    )]
+    front_matter_format: Option<FrontMatterStyle>,
+
     #[structopt(short, long, help("Don't add titles to each page"))]
     no_titles: bool,
+
+    #[structopt(
+        long,
+        help("Start each page with links to its types or operations"),
+        conflicts_with("single-file")
+    )]
+    toc: bool,
+
+    #[structopt(
+        long,
+        help("Write the pages' document model instead of the pages, as ast.json with --out-dir"),
+        value_name("FORMAT"),
+        possible_values(&["json"]),
+        conflicts_with("single-file")
+    )]
+    emit_ast: Option<String>,
+
+    #[structopt(
+        long,
+        help(
+            "Start each type with a table of its kind, size, interfaces, users, and deprecations"
+        )
+    )]
+    quick_facts: bool,
+
+    #[structopt(
+        long,
+        help("End each type with links to the operations and types that refer to it")
+    )]
+    referenced_by: bool,
+
+    #[structopt(
+        long,
+        help("Show the fields of a mutation's FooInput and FooPayload types under the mutation")
+    )]
+    expand_mutations: bool,
+
+    #[structopt(
+        long,
+        help(
+            "Turn tags like [beta] at the start of descriptions into badges and operation groups"
+        )
+    )]
+    description_tags: bool,
+
+    #[structopt(
+        long,
+        help("Add an example operation to each query, mutation, and subscription")
+    )]
+    examples: bool,
+
+    #[structopt(
+        long,
+        help("How many levels of objects the example operations select fields from"),
+        default_value("2"),
+        value_name("DEPTH")
+    )]
+    example_depth: usize,
+
+    #[structopt(
+        long,
+        help("How to capitalize page titles and headings"),
+        default_value("title"),
+        possible_values(&["as-is", "title", "sentence", "upper"])
+    )]
+    title_case: TitleCase,
+
+    #[structopt(
+        long,
+        help("Add a page ranking the N largest and most-referenced types"),
+        value_name("N")
+    )]
+    largest_types: Option<usize>,
+
+    #[structopt(
+        long,
+        help("Add a page summarizing the pagination patterns the schema uses")
+    )]
+    pagination: bool,
+
+    #[structopt(
+        long,
+        help("Add a page cataloging the error types mutations can return")
+    )]
+    errors: bool,
+
+    #[structopt(
+        long,
+        help("Add a page listing the types that reference each other in cycles")
+    )]
+    cycles: bool,
+
+    #[structopt(
+        long,
+        help(
+            "Add a page with a matrix of which fields of each interface its implementations have"
+        )
+    )]
+    interface_conformance: bool,
+
+    #[structopt(
+        long,
+        help(
+            "Add a page auditing how often each custom scalar is used and whether it's documented"
+        )
+    )]
+    scalar_usage: bool,
+
+    #[structopt(
+        long,
+        help("Add a page listing every argument and input field with a default value")
+    )]
+    defaults: bool,
+
+    #[structopt(
+        long,
+        help("Write a JSON catalog of operations for API portals to this file"),
+        parse(from_os_str)
+    )]
+    catalog: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Write an HTML page with GraphQL Voyager's graph of the schema to this file (or just its data, if it ends in .json)"),
+        parse(from_os_str)
+    )]
+    voyager: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Write an HTML page for exploring the schema, with search and a graph of each type's connections, to this file"),
+        parse(from_os_str)
+    )]
+    explorer: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Write a JSON search index of every type, field, and value, for client-side search, to this file"),
+        parse(from_os_str)
+    )]
+    search_index: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Write Algolia DocSearch records for every type, field, and enum value to this file"),
+        parse(from_os_str)
+    )]
+    algolia_records: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("URL the pages are published at, to make --search-index and --algolia-records URLs absolute")
+    )]
+    docs_url: Option<String>,
+
+    #[structopt(
+        long,
+        help(
+            "Write a JSON report of warnings, description coverage, cycles, dangling links, and timing to this file"
+        ),
+        parse(from_os_str)
+    )]
+    report: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Keep rendered types in this directory and re-render only the types that changed"),
+        parse(from_os_str),
+        value_name("DIR")
+    )]
+    cache_dir: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("Line ending for output"),
+        default_value("lf"),
+        possible_values(&["lf", "crlf"])
+    )]
+    line_ending: LineEnding,
+
+    #[structopt(
+        long,
+        help("Icons to decorate headings and tables with"),
+        default_value("none"),
+        possible_values(&["none", "emoji", "ascii"])
+    )]
+    icons: IconSet,
+
+    #[structopt(
+        long,
+        help("Render type and operation names as plain inline code instead of links")
+    )]
+    no_links: bool,
+
+    #[structopt(
+        long,
+        help(
+            "Prefix for every generated anchor, such as gql-, to avoid collisions when embedding"
+        ),
+        value_name("PREFIX")
+    )]
+    anchor_prefix: Option<String>,
+
+    #[structopt(
+        long,
+        help("Wait for other gumwood processes writing to --out-dir instead of failing")
+    )]
+    wait_for_lock: bool,
+
+    #[structopt(
+        long,
+        help("Report lint findings, such as overdue removals or naming problems, and fail if there are any")
+    )]
+    lint: bool,
+
+    #[structopt(
+        long,
+        help("Escape HTML in descriptions that isn't on the config file's allow-list")
+    )]
+    sanitize: bool,
+
+    #[structopt(
+        long,
+        help("Show descriptions as plain text, escaping any markdown or HTML in them")
+    )]
+    escape_descriptions: bool,
+
+    #[structopt(long, help("Page output to stdout through $PAGER"))]
+    paginate: bool,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+/// Something to do with the schema other than generating its docs
+#[derive(Clone, Debug, StructOpt)]
+pub enum Command {
+    /// Print a table comparing two types' fields side by side, such as the
+    /// old and new versions of a type
+    CompareTypes {
+        #[structopt(help("Type to compare from"))]
+        from: String,
+
+        #[structopt(help("Type to compare to"))]
+        to: String,
+    },
+
+    Publish {
+        #[structopt(subcommand)]
+        target: PublishTarget,
+    },
+
+    /// Print the types and fields that were removed, changed, deprecated,
+    /// or added since a baseline version of the schema, such as for a pull
+    /// request comment
+    Diff {
+        #[structopt(
+            help("Baseline schema: a GraphQL schema file, or an introspection response ending in .json"),
+            parse(from_os_str)
+        )]
+        baseline: PathBuf,
+
+        #[structopt(
+            long,
+            help("How to write the changes"),
+            default_value("markdown"),
+            possible_values(&["markdown", "github-comment"])
+        )]
+        diff_format: DiffFormat,
+    },
+}
+
+/// Publish the docs to a wiki instead of writing them to stdout
+#[derive(Clone, Debug, StructOpt)]
+pub enum PublishTarget {
+    /// Create or update a Confluence page for each page, under a parent
+    /// page
+    Confluence {
+        #[structopt(
+            long,
+            help("URL of the Confluence site, such as https://example.atlassian.net/wiki")
+        )]
+        base_url: String,
+
+        #[structopt(long, help("Key of the space to publish to"))]
+        space: String,
+
+        #[structopt(long, help("ID of the page to publish under"))]
+        parent: String,
+
+        #[structopt(
+            long,
+            help("Email address to send with --token, for Confluence Cloud; without it, the token is sent as a bearer token")
+        )]
+        user: Option<String>,
+
+        #[structopt(
+            long,
+            env("CONFLUENCE_TOKEN"),
+            hide_env_values(true),
+            help("API token or personal access token (defaults to .netrc credentials)")
+        )]
+        token: Option<String>,
+    },
+
+    /// Create a Notion page for each page, under a parent page, archiving
+    /// the ones an earlier run created
+    Notion {
+        #[structopt(
+            long,
+            visible_alias("notion-token"),
+            env("NOTION_TOKEN"),
+            hide_env_values(true),
+            help("Token of a Notion integration the parent page is shared with")
+        )]
+        token: String,
+
+        #[structopt(
+            long,
+            visible_alias("notion-parent"),
+            help("ID or URL of the page to publish under")
+        )]
+        parent: String,
+    },
+}
+
+const DEFAULT_USER_AGENT: &str = concat!("gumwood/", env!("CARGO_PKG_VERSION"));
+
+/// The line ending to write, so output is byte-for-byte identical no matter
+/// which platform generated it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
 }
 
-fn get_schema(args: &Options) -> Result<Schema, Box<dyn Error>> {
-    let schema: Schema;
-    if args.url.is_some() {
-        schema = Schema::from_url(&args.url.as_ref().unwrap(), &args.header)?;
-    } else if args.json.is_some() {
-        schema = Schema::from_json(&args.json.as_ref().unwrap())?;
-    } else if args.schema.is_some() {
-        schema = Schema::from_schema(&args.schema.as_ref().unwrap())?;
-    } else {
-        // Read from stdin
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        schema = Schema::from_str(&buffer)?;
+impl LineEnding {
+    fn apply(self, text: &str) -> String {
+        let normalized = text.replace("\r\n", "\n");
+        match self {
+            LineEnding::Lf => normalized,
+            LineEnding::Crlf => normalized.replace('\n', "\r\n"),
+        }
+    }
+}
+
+impl FromStr for LineEnding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::Crlf),
+            _ => Err(format!("invalid line ending: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineEnding::Lf => write!(f, "lf"),
+            LineEnding::Crlf => write!(f, "crlf"),
+        }
+    }
+}
+
+// The merged schema, the endpoints introspected for it, and what its
+// sources define differently
+type Sources = (Schema, Vec<Endpoint>, Vec<MergeConflict>);
+
+fn get_schema(args: &Options, report: &mut Report) -> Result<Sources, Box<dyn Error>> {
+    let mut sources: Vec<(String, Schema)> = Vec::new();
+    let mut endpoints = Vec::new();
+
+    let tape = match (&args.tape, &args.replay) {
+        (Some(tape), _) => tape.clone(),
+        (None, Some(file)) => Tape::from_file(file)?,
+        (None, None) => record_tape(args, report)?,
+    };
+    // Without --url, replay every response on the tape
+    let urls: Vec<&str> = if args.url.is_empty() {
+        tape.exchanges
+            .iter()
+            .map(|exchange| exchange.url.as_str())
+            .collect()
+    } else {
+        args.url.iter().map(String::as_str).collect()
+    };
+    for url in urls {
+        let (schema, endpoint) = Schema::from_exchange(tape.find(url)?)?;
+        sources.push((url.to_string(), schema));
+        endpoints.push(endpoint);
+    }
+    for json in &args.json {
+        sources.push((json.display().to_string(), Schema::from_json(json)?));
+    }
+    if let Some(file) = &args.schema {
+        sources.push((file.display().to_string(), Schema::from_schema(file)?));
+    }
+    if args.from_clipboard {
+        sources.push((
+            "clipboard".to_string(),
+            Schema::from_text(&clipboard::read()?)?,
+        ));
+    }
+    if should_read_stdin(args.stdin, !sources.is_empty(), io::stdin().is_terminal())? {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        sources.push(("stdin".to_string(), Schema::from_text(&buffer)?));
+    }
+
+    let mut conflicts = Vec::new();
+    let mut sources = sources.into_iter();
+    let (_, mut schema) = sources.next().unwrap();
+    // Renamed types are suffixed with the number of their source
+    for (number, (source, other)) in (2..).zip(sources) {
+        let (coordinates, renames) = match args.merge_conflicts {
+            MergeStrategy::SuffixRename => schema.merge_renaming(other, &format!("_{}", number)),
+            _ => (schema.merge(other), HashMap::new()),
+        };
+        if args.merge_conflicts == MergeStrategy::Error && !coordinates.is_empty() {
+            return Err(format!(
+                "{} defines {} differently from an earlier source",
+                source,
+                coordinates.join(", ")
+            )
+            .into());
+        }
+        for coordinate in coordinates {
+            let type_name = coordinate.split('.').next().unwrap_or_default();
+            let renamed = renames.get(type_name).cloned();
+            report.warn(match &renamed {
+                Some(renamed) => format!(
+                    "{} in {} conflicts with an earlier source; keeping both, renaming its type to {}",
+                    coordinate, source, renamed
+                ),
+                None => format!(
+                    "{} in {} conflicts with an earlier source; keeping the earlier definition",
+                    coordinate, source
+                ),
+            });
+            conflicts.push(MergeConflict {
+                coordinate,
+                source: source.clone(),
+                renamed,
+            });
+        }
+    }
+
+    Ok((schema, endpoints, conflicts))
+}
+
+// Parses a size in bytes, with an optional K, M, or G suffix for
+// kibibytes, mebibytes, or gibibytes
+fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, multiplier) = match text.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&text[..text.len() - 1], 1 << 10),
+        Some('M') => (&text[..text.len() - 1], 1 << 20),
+        Some('G') => (&text[..text.len() - 1], 1 << 30),
+        _ => (text, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid size: {}", text))
+}
+
+// Reads stdin when asked to, or when there's no other source and something
+// is piped in; waiting at a terminal for a schema no one knew to type would
+// look like a hang
+fn should_read_stdin(
+    stdin: bool,
+    has_sources: bool,
+    is_terminal: bool,
+) -> Result<bool, Box<dyn Error>> {
+    match (stdin, has_sources, is_terminal) {
+        (true, _, _) => Ok(true),
+        (false, true, _) => Ok(false),
+        (false, false, false) => Ok(true),
+        (false, false, true) => Err("no schema to document; pass --url, --json, --schema, or \
+             --from-clipboard, pipe a schema to gumwood, or add --stdin to type one \
+             (see gumwood --help)"
+            .into()),
+    }
+}
+
+// Introspects each URL, first asking it which optional fields it supports,
+// and saves the responses to the --record file before they're read, so a
+// response gumwood can't read can still be debugged
+fn record_tape(args: &Options, report: &mut Report) -> Result<Tape, Box<dyn Error>> {
+    let mut tape = Tape::default();
+    if args.url.is_empty() {
+        return Ok(tape);
+    }
+    let type_depth = args.type_depth.unwrap_or(DEFAULT_TYPE_DEPTH);
+    if type_depth == 0 {
+        return Err("--type-depth must be at least 1".into());
+    }
+    let headers = get_headers(args)?;
+    let probe = to_capabilities_request(args.operation_name.as_deref(), &args.variable)?;
+    for url in &args.url {
+        if args.insecure {
+            let warning = format!(
+                "--insecure disables TLS certificate verification for {}; \
+                 anyone on the network can read or alter the schema and any headers you send",
+                url
+            );
+            eprintln!("WARNING: {}", warning);
+            report.warnings.push(warning);
+        }
+        let credentials = get_netrc_credentials(url, &headers)?;
+        // Only the introspection itself is recorded; the probe's answer is
+        // already in the request
+        let capabilities = Capabilities::from_response(
+            &introspect(
+                url,
+                &headers,
+                &probe,
+                args.insecure,
+                &args.user_agent,
+                credentials.as_ref(),
+                Duration::from_secs(args.max_wait),
+            )?
+            .body,
+        );
+        let request = to_introspection_request(
+            type_depth,
+            &capabilities,
+            args.operation_name.as_deref(),
+            &args.variable,
+        )?;
+        tape.exchanges.push(introspect(
+            url,
+            &headers,
+            &request,
+            args.insecure,
+            &args.user_agent,
+            credentials.as_ref(),
+            Duration::from_secs(args.max_wait),
+        )?);
+    }
+    if let Some(file) = &args.record {
+        tape.write(file)?;
+    }
+    Ok(tape)
+}
+
+// Like curl, only falls back to .netrc when no Authorization header is set
+fn get_netrc_credentials(
+    url: &str,
+    headers: &[String],
+) -> Result<Option<Credentials>, Box<dyn Error>> {
+    let has_auth = headers.iter().any(|header| {
+        header
+            .split(':')
+            .next()
+            .is_some_and(|name| name.trim().eq_ignore_ascii_case("authorization"))
+    });
+    if has_auth {
+        return Ok(None);
+    }
+    match Url::parse(url)?.host_str() {
+        Some(host) => netrc::find_credentials(host),
+        None => Ok(None),
+    }
+}
+
+fn get_confluence_auth(
+    base_url: &str,
+    user: &Option<String>,
+    token: &Option<String>,
+) -> Result<Auth, Box<dyn Error>> {
+    match (user, token) {
+        (Some(user), Some(token)) => Ok(Auth::Basic(Credentials {
+            login: user.to_string(),
+            password: token.to_string(),
+        })),
+        (None, Some(token)) => Ok(Auth::Bearer(token.to_string())),
+        (_, None) => match get_netrc_credentials(base_url, &[])? {
+            Some(credentials) => Ok(Auth::Basic(credentials)),
+            None => Err(
+                "publishing to Confluence needs --token, $CONFLUENCE_TOKEN, or .netrc credentials"
+                    .into(),
+            ),
+        },
+    }
+}
+
+fn get_headers(args: &Options) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut headers = match &args.headers_file {
+        Some(file) => read_headers_file(file)?,
+        None => Vec::new(),
+    };
+    headers.extend(args.header.iter().cloned());
+    Ok(headers)
+}
+
+// Reads one header per line, skipping blank lines and # comments
+fn read_headers_file(file: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let contents = fs::read_to_string(file)?;
+    Ok(parse_headers(&contents))
+}
+
+fn parse_headers(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn write_to_files(
+    contents: &HashMap<String, String>,
+    page_stats: &HashMap<String, PageStats>,
+    args: &Options,
+    links: &Links,
+    out_dir: &Path,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    let files = get_files(contents, page_stats, args, links);
+    // Count them all first, so going over budget doesn't leave half the docs
+    for (_, text) in &files {
+        budget.spend(text.len())?;
+    }
+    fs::create_dir_all(out_dir.join(args.format.pages_dir()))?;
+    for (path, text) in files {
+        let path = out_dir.join(path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+    }
+    Ok(())
+}
+
+// Returns the pages and the format's other files, as paths relative to
+// --out-dir and their contents, sorted by path
+fn get_files(
+    contents: &HashMap<String, String>,
+    page_stats: &HashMap<String, PageStats>,
+    args: &Options,
+    links: &Links,
+) -> Vec<(PathBuf, String)> {
+    if args.emit_ast.is_some() {
+        return vec![(
+            PathBuf::from("ast.json"),
+            args.line_ending.apply(&ast::to_json(contents, links)),
+        )];
+    }
+    let mut files = Vec::new();
+    for (name, markdown) in contents {
+        if !markdown.is_empty() && args.format.writes_pages() {
+            let fm = create_front_matter(
+                &args.front_matter,
+                &args.front_matter_key_prefix,
+                name,
+                page_stats.get(name),
+                &args.format.front_matter(
+                    name,
+                    contents,
+                    links,
+                    args.front_matter_format.unwrap_or_default(),
+                ),
+                args.front_matter_format.unwrap_or_default(),
+                args.title_case,
+            );
+            let contents = format!(
+                "{}{}",
+                fm,
+                args.format.convert(markdown, &args.site_name, links)
+            );
+            files.push((
+                args.format.pages_dir().join(links.page_file(name)),
+                args.line_ending.apply(&contents),
+            ));
+        }
+    }
+
+    for (path, text) in args.format.extra_files(
+        contents,
+        &args.site_name,
+        links,
+        args.front_matter_format.unwrap_or_default(),
+    ) {
+        files.push((path, args.line_ending.apply(&text)));
+    }
+    files.sort();
+    files
+}
+
+// Returns the file to lock for an output directory. It's in the temp
+// directory, named for the output directory's full path, so it's never
+// published along with the docs.
+fn get_lock_file(out_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let mut hasher = DefaultHasher::new();
+    fs::canonicalize(out_dir)?.hash(&mut hasher);
+    Ok(env::temp_dir().join(format!("gumwood-{:016x}.lock", hasher.finish())))
+}
+
+// Takes an advisory lock on the output directory so concurrent runs
+// writing to the same directory can't interleave their files. The lock
+// is released when the returned file is dropped.
+fn lock_out_dir(out_dir: &Path, wait: bool) -> Result<File, Box<dyn Error>> {
+    fs::create_dir_all(out_dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(get_lock_file(out_dir)?)?;
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) if wait => {
+            eprintln!(
+                "waiting for another gumwood process to finish writing to {}",
+                out_dir.display()
+            );
+            file.lock()?;
+            Ok(file)
+        }
+        Err(TryLockError::WouldBlock) => Err(format!(
+            "{} is locked by another gumwood process; use --wait-for-lock to wait for it",
+            out_dir.display()
+        )
+        .into()),
+        Err(TryLockError::Error(err)) => Err(err.into()),
+    }
+}
+
+fn write_to_stdout(
+    contents: &HashMap<String, String>,
+    page_stats: &HashMap<String, PageStats>,
+    args: &Options,
+    links: &Links,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    let mut output = Output::new(args.paginate);
+    if args.emit_ast.is_some() {
+        let json = args.line_ending.apply(&ast::to_json(contents, links));
+        budget.spend(json.len())?;
+        output.write(&json)?;
+        return output.finish();
+    }
+    let mut keys: Vec<_> = contents.keys().collect();
+    keys.sort();
+
+    for key in keys.iter() {
+        let markdown = contents.get(*key).unwrap();
+        if !markdown.is_empty() {
+            let fm = create_front_matter(
+                &args.front_matter,
+                &args.front_matter_key_prefix,
+                key,
+                page_stats.get(*key),
+                &[],
+                args.front_matter_format.unwrap_or_default(),
+                args.title_case,
+            );
+            let text = args.format.convert(markdown, &args.site_name, links);
+            let text = args.line_ending.apply(&format!("{}{}\n", fm, text));
+            budget.spend(text.len())?;
+            output.write(&text)?;
+        }
+    }
+    output.finish()
+}
+
+// Generates the docs for each environment the name selects, as if it were
+// run with the environment's URL, headers, and output directory
+fn run_environments(args: Options, name: &str) -> Result<(), Box<dyn Error>> {
+    let config = Config::load(&args.config)?;
+    let selected = |environment: &str| name == "all" || environment == name;
+    if !config
+        .environments
+        .iter()
+        .any(|environment| selected(&environment.name))
+    {
+        return Err(format!("no environment named {} in the config file", name).into());
+    }
+    let mut runs: Vec<(String, Options)> = config
+        .environments
+        .into_iter()
+        .map(|environment| {
+            let mut args = args.clone();
+            args.url = vec![environment.url];
+            args.header.extend(environment.headers);
+            args.out_dir = Some(environment.out_dir);
+            (environment.name, args)
+        })
+        .collect();
+
+    if args.env_badges {
+        // Every environment is compared, even those not generated
+        let mut schemas = Vec::new();
+        for (name, args) in &mut runs {
+            let tape = record_tape(args, &mut Report::default())
+                .map_err(|err| format!("{}: {}", name, err))?;
+            let (schema, _) = Schema::from_exchange(tape.find(&args.url[0])?)
+                .map_err(|err| format!("{}: {}", name, err))?;
+            schemas.push((name.clone(), schema));
+            args.tape = Some(tape);
+        }
+        let notes = Descriptions::appending(compare::get_environment_notes(&mut schemas));
+        for (_, args) in &mut runs {
+            args.env_notes = notes.clone();
+        }
+    }
+
+    for (name, args) in runs.into_iter().filter(|(name, _)| selected(name)) {
+        eprintln!("generating docs for {}", name);
+        run(args).map_err(|err| format!("{}: {}", name, err))?;
+    }
+    Ok(())
+}
+
+fn create_front_matter(
+    front_matter: &Option<String>,
+    key_prefix: &Option<String>,
+    typ: &str,
+    stats: Option<&PageStats>,
+    format_lines: &[String],
+    style: FrontMatterStyle,
+    title_case: TitleCase,
+) -> String {
+    let mut lines = Vec::new();
+    if let Some(fm) = front_matter {
+        lines.push(
+            fm.replace("{type}", typ)
+                .replace("{TYPE}", &typ.to_uppercase())
+                .replace("{Type}", &title_case.apply(typ))
+                .replace(":", ": ")
+                .replace(";", "\n"),
+        );
+    }
+    if let Some(stats) = stats {
+        lines.push(format!(
+            "type_count: {}\nfield_count: {}\ndeprecated_count: {}",
+            stats.type_count, stats.field_count, stats.deprecated_count
+        ));
+    }
+
+    let mut body = format_lines.to_vec();
+    if !lines.is_empty() {
+        let lines = lines.join("\n");
+        body.push(match style {
+            FrontMatterStyle::Yaml => nest_front_matter(&lines, key_prefix),
+            FrontMatterStyle::Toml => to_toml_table(&lines, key_prefix),
+            FrontMatterStyle::Json => to_json_entries(&lines, key_prefix),
+        });
+    }
+
+    if body.is_empty() {
+        "".to_string()
+    } else {
+        style.wrap(&body)
+    }
+}
+
+// Converts the front matter lines to TOML, in a table named by the prefix if
+// there is one; the format's own lines come first, so they stay top-level
+fn to_toml_table(body: &str, key_prefix: &Option<String>) -> String {
+    let mut lines: Vec<String> = body
+        .lines()
+        .map(|line| FrontMatterStyle::Toml.convert(line))
+        .collect();
+    if let Some(prefix) = key_prefix {
+        let keys: Vec<&str> = prefix
+            .split('.')
+            .map(|key| key.trim())
+            .filter(|key| !key.is_empty())
+            .collect();
+        if !keys.is_empty() {
+            lines.insert(0, format!("[{}]", keys.join(".")));
+        }
+    }
+    lines.join("\n")
+}
+
+// Converts the front matter lines to JSON entries, nested in an object for
+// each dot-separated key in the prefix
+fn to_json_entries(body: &str, key_prefix: &Option<String>) -> String {
+    let mut entries: Vec<String> = body
+        .lines()
+        .map(|line| FrontMatterStyle::Json.convert(line))
+        .collect();
+    if let Some(prefix) = key_prefix {
+        for key in prefix
+            .split('.')
+            .map(|key| key.trim())
+            .filter(|key| !key.is_empty())
+            .rev()
+        {
+            entries = vec![format!(
+                "{}: {}",
+                serde_json::Value::from(key),
+                to_json_object(&entries)
+            )];
+        }
+    }
+    entries.join(",\n")
+}
+
+// Nests the front matter lines under each dot-separated key in the prefix,
+// so "params.graphql" puts everything under params: graphql:
+fn nest_front_matter(body: &str, key_prefix: &Option<String>) -> String {
+    let keys: Vec<&str> = match key_prefix {
+        Some(prefix) => prefix
+            .split('.')
+            .map(|key| key.trim())
+            .filter(|key| !key.is_empty())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut lines: Vec<String> = keys
+        .iter()
+        .enumerate()
+        .map(|(depth, key)| format!("{}{}:", "  ".repeat(depth), key))
+        .collect();
+    let indent = "  ".repeat(keys.len());
+    lines.extend(body.lines().map(|line| format!("{}{}", indent, line)));
+    lines.join("\n")
+}
+
+/// Takes the arguments from the Options struct and generates
+/// markdown for the specified schema.
+pub fn run(mut args: Options) -> Result<(), Box<dyn Error>> {
+    if let Some(name) = args.env.take() {
+        return run_environments(args, &name);
+    }
+    let publishing = matches!(args.command, Some(Command::Publish { .. }));
+    if let Some(Command::Publish { target }) = &args.command {
+        // Each target converts pages from one format
+        let (format, name) = match target {
+            PublishTarget::Confluence { .. } => (Format::Confluence, "Confluence"),
+            PublishTarget::Notion { .. } => (Format::Markdown, "Notion"),
+        };
+        if args.format != Format::Markdown && args.format != format {
+            return Err(format!("can't publish --format {} to {}", args.format, name).into());
+        }
+        args.format = format;
+    }
+    if args.format.needs_out_dir()
+        && args.out_dir.is_none()
+        && args.archive.is_none()
+        && !publishing
+    {
+        return Err(format!("--format {} requires --out-dir", args.format).into());
+    }
+    if args.emit_ast.is_some() && args.format != Format::Markdown {
+        return Err(format!("--emit-ast can't be used with --format {}", args.format).into());
+    }
+    if args.single_file.is_some() && args.format != Format::Markdown {
+        return Err(format!("--single-file can't be used with --format {}", args.format).into());
+    }
+    if args.single_file.is_some() && (args.front_matter.is_some() || args.front_matter_stats) {
+        return Err("--single-file doesn't support front matter".into());
+    }
+    if args.format == Format::Term && (args.out_dir.is_some() || args.archive.is_some()) {
+        return Err(
+            "--format term writes to stdout, so it can't be used with --out-dir or --archive"
+                .into(),
+        );
+    }
+    match (
+        args.format.required_front_matter(),
+        args.front_matter_format,
+    ) {
+        (Some(required), Some(style)) if required != style => {
+            return Err(format!(
+                "--format {} requires {} front matter",
+                args.format,
+                required.to_string().to_uppercase()
+            )
+            .into());
+        }
+        (Some(required), _) => args.front_matter_format = Some(required),
+        _ => {}
+    }
+    if !args.format.has_front_matter() && (args.front_matter.is_some() || args.front_matter_stats) {
+        return Err(format!("--format {} doesn't support front matter", args.format).into());
+    }
+    let config = Config::load(&args.config)?;
+    let mut report = Report::default();
+
+    let started = Instant::now();
+    let (mut schema, endpoints, conflicts) = get_schema(&args, &mut report)?;
+    // The schema's been read and merged by now, so this guards rendering,
+    // not the download or the parse
+    let type_count = schema.types.iter().flatten().count();
+    if let Some(max_types) = args.max_types.filter(|max| type_count > *max) {
+        return Err(format!(
+            "the schema has {} types, more than --max-types {}; check the source, or raise the limit",
+            type_count, max_types
+        )
+        .into());
     }
+    for overlay in &args.overlay {
+        schema.apply_overlay(overlay)?;
+    }
+    config.roots.apply(&mut schema)?;
+    report.time("load", started);
+    // Everything written counts against --max-output-size: pages and their
+    // front matter, the format's other files, side files, and archives
+    let budget = Budget::new(args.max_output_size);
 
-    Ok(schema)
-}
+    if let Some(Command::CompareTypes { from, to }) = &args.command {
+        let table = args
+            .line_ending
+            .apply(&compare::compare_types(&schema, from, to)?);
+        budget.spend(table.len())?;
+        print!("{}", table);
+        return Ok(());
+    }
+    if let Some(Command::Diff {
+        baseline,
+        diff_format,
+    }) = &args.command
+    {
+        let old = match baseline.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Schema::from_json(baseline)?,
+            _ => Schema::from_schema(baseline)?,
+        };
+        let changes = compare::diff_schemas(&old, &schema);
+        let markdown = args
+            .line_ending
+            .apply(&compare::diff_to_markdown(&changes, *diff_format));
+        budget.spend(markdown.len())?;
+        print!("{}", markdown);
+        return Ok(());
+    }
 
-fn write_to_files(
-    contents: &HashMap<String, String>,
-    front_matter: Option<String>,
-    out_dir: &PathBuf,
-) -> Result<(), Box<dyn Error>> {
-    for (name, markdown) in contents {
-        if !markdown.is_empty() {
-            let out_file = format!("{}.md", name);
-            let mut file = File::create(out_dir.join(out_file))?;
-            let fm = create_front_matter(&front_matter, name);
-            let contents = format!("{}{}", fm, markdown);
-            file.write_all(contents.as_bytes())?;
+    let started = Instant::now();
+    for coordinate in schema.get_truncated_type_refs() {
+        report.warn(format!(
+            "the type of {} is nested too deeply to document; introspect with a larger --type-depth",
+            coordinate
+        ));
+    }
+    for (coordinate, name, kind) in get_unknown_kinds(&schema) {
+        report.warn(if coordinate == name {
+            format!(
+                "{} has unknown kind {:?}; documenting it on the glossary page",
+                name, kind
+            )
+        } else {
+            format!(
+                "the type of {} is {}, of unknown kind {:?}; linking to the glossary page",
+                coordinate, name, kind
+            )
+        });
+    }
+    if let Some(file) = &args.metadata {
+        for coordinate in Metadata::from_file(file)?.apply(&mut schema, args.stub_undocumented) {
+            report.warn(format!(
+                "{} in {} doesn't match anything in the schema",
+                coordinate,
+                file.display()
+            ));
         }
     }
+    if let Some(file) = &args.descriptions {
+        for coordinate in Descriptions::from_file(file)?.apply(&mut schema) {
+            report.warn(format!(
+                "{} in {} doesn't match anything in the schema",
+                coordinate,
+                file.display()
+            ));
+        }
+    }
+    if let Some(dir) = &args.docs_dir {
+        for coordinate in Prose::from_dir(dir)?.apply(&mut schema) {
+            report.warn(format!(
+                "{}.md in {} doesn't match a type or field in the schema",
+                coordinate,
+                dir.display()
+            ));
+        }
+    }
+    if let Some(file) = &args.versions {
+        for coordinate in Versions::from_file(file)?.apply(&mut schema) {
+            report.warn(format!(
+                "{} in {} doesn't match anything in the schema",
+                coordinate,
+                file.display()
+            ));
+        }
+    }
+    // Other environments' elements won't match, so there's nothing to warn of
+    args.env_notes.apply(&mut schema);
+    if args.description_tags {
+        tags::apply(&mut schema);
+    }
+    let mut costs = Costs::default();
+    if let Some(file) = &args.costs {
+        costs = Costs::from_file(file)?;
+        for coordinate in costs.apply(&mut schema) {
+            report.warn(format!(
+                "{} in {} doesn't match a field in the schema",
+                coordinate,
+                file.display()
+            ));
+        }
+    }
+    if args.sanitize {
+        config.sanitize.apply(&mut schema);
+    }
+    config.redact.apply(&mut schema)?;
+    sunset::apply(&mut schema, &config.removals)?;
+    schema.apply_aliases(&config.aliases);
+    if args.quick_facts {
+        set_used_by(&mut schema);
+    }
+    if args.referenced_by {
+        set_referenced_by(&mut schema);
+    }
+    let icons = Icons::new(args.icons, &config.icons, &config.auth_required)?;
+    let links = Links {
+        plain: args.no_links,
+        anchor_prefix: args.anchor_prefix.clone().unwrap_or_default(),
+        slugifier: config.slugifier.slugifier(),
+        anchor_style: config.anchor_style,
+        page_extension: args.format.page_extension(),
+        page_bundles: args.format.page_bundles(),
+        wiki_pages: args.format.wiki_pages(),
+        page_link_prefix: args.format.page_link_prefix(),
+        title_case: args.title_case,
+        escape_descriptions: args.escape_descriptions,
+        mdx: args.format.is_mdx(),
+    };
+    let tables = Tables {
+        sort: config.sort,
+        expand_mutations: args.expand_mutations,
+    };
+    report.time("transform", started);
 
-    Ok(())
-}
-
-fn write_to_stdout(contents: &HashMap<String, String>, front_matter: Option<String>) {
-    let mut keys: Vec<_> = contents.keys().collect();
-    keys.sort();
+    let started = Instant::now();
+    let mut cache = match &args.cache_dir {
+        Some(dir) => RenderCache::load(
+            dir,
+            &format!("{} {:?} {:?}", icons.fingerprint(), links, tables),
+        ),
+        None => RenderCache::default(),
+    };
+    // Rendering stops as soon as the pages pass --max-output-size, rather
+    // than rendering a runaway schema only to refuse to write it
+    let render_budget = Budget::new(args.max_output_size);
+    let contents = if args.format.is_model() {
+        // The model is one document, in place of the pages
+        let model = Model::from_schema(&schema, &icons, &links);
+        let mut contents = HashMap::new();
+        match args.format {
+            Format::LlmsTxt => {
+                let roots: Vec<String> = [
+                    schema.get_query_name(),
+                    schema.get_mutation_name(),
+                    schema.get_subscription_name(),
+                ]
+                .iter()
+                .flatten()
+                .cloned()
+                .collect();
+                contents.insert(
+                    "llms".to_string(),
+                    llms_txt::to_llms_txt(&model, &roots, &args.site_name),
+                );
+            }
+            Format::Yaml => {
+                contents.insert("model".to_string(), model.to_yaml()?);
+            }
+            _ => {
+                contents.insert("model".to_string(), model.to_json()?);
+            }
+        }
+        render_budget.spend(contents.values().map(String::len).sum())?;
+        contents
+    } else {
+        let mut contents = generate_from_schema(
+            &schema,
+            !args.no_titles,
+            &icons,
+            &links,
+            &tables,
+            &mut cache,
+            &render_budget,
+        )?;
+        if !endpoints.is_empty() {
+            let generated_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+            contents.insert(
+                "about".to_string(),
+                about_to_markdown(&endpoints, &generated_at, !args.no_titles, &links),
+            );
+        }
+        if let Some(count) = args.largest_types {
+            contents.insert(
+                "largest-types".to_string(),
+                largest_types_to_markdown(&schema, count, !args.no_titles, &links),
+            );
+        }
+        if args.pagination {
+            contents.insert(
+                "pagination".to_string(),
+                pagination_to_markdown(&schema, !args.no_titles, &links),
+            );
+        }
+        if args.errors {
+            contents.insert(
+                "errors".to_string(),
+                errors_to_markdown(&schema, !args.no_titles, &links),
+            );
+        }
+        if args.cycles {
+            contents.insert(
+                "cycles".to_string(),
+                cycles_to_markdown(&schema, !args.no_titles, &links),
+            );
+        }
+        if args.interface_conformance {
+            contents.insert(
+                "interface-conformance".to_string(),
+                interface_conformance_to_markdown(&schema, !args.no_titles, &links),
+            );
+        }
+        if args.scalar_usage {
+            contents.insert(
+                "scalar-usage".to_string(),
+                scalar_usage_to_markdown(&schema, !args.no_titles, &links),
+            );
+        }
+        if args.defaults {
+            contents.insert(
+                "defaults".to_string(),
+                defaults_to_markdown(&schema, !args.no_titles, &links),
+            );
+        }
+        let merge_conflicts =
+            merge_conflicts_to_markdown(&conflicts, &schema, !args.no_titles, &links);
+        if !merge_conflicts.is_empty() {
+            contents.insert("merge-conflicts".to_string(), merge_conflicts);
+        }
+        let cost = cost_to_markdown(
+            &schema,
+            costs.rate_limit.as_deref(),
+            !args.no_titles,
+            &links,
+        );
+        if !cost.is_empty() {
+            contents.insert("cost".to_string(), cost);
+        }
+        if args.format == Format::Slate {
+            let model = Model::from_schema(&schema, &icons, &links);
+            slate::add_examples(&mut contents, &model, args.url.first().map(String::as_str));
+        }
+        if args.examples {
+            let model = Model::from_schema(&schema, &icons, &links);
+            examples::add_examples(&mut contents, &model, args.example_depth, &links);
+        }
+        if args.toc {
+            for markdown in contents.values_mut() {
+                *markdown = add_contents(markdown, &links);
+            }
+        }
+        contents
+    };
+    let page_stats = if args.front_matter_stats {
+        get_page_stats(&schema)
+    } else {
+        HashMap::new()
+    };
+    report.time("render", started);
 
-    for key in keys.iter() {
-        let markdown = contents.get(*key).unwrap();
-        if !markdown.is_empty() {
-            let fm = create_front_matter(&front_matter, key);
-            println!("{}{}", fm, markdown);
+    let started = Instant::now();
+    if let Some(file) = &args.catalog {
+        Catalog::from_schema(&schema).write(file, &budget)?;
+    }
+    if let Some(file) = &args.voyager {
+        voyager::write(&schema, file, &args.site_name, &budget)?;
+    }
+    if args.search_index.is_some() || args.algolia_records.is_some() || args.explorer.is_some() {
+        let model = Model::from_schema(&schema, &icons, &links);
+        if let Some(file) = &args.explorer {
+            explorer::write(&model, file, &args.site_name, &budget)?;
+        }
+        if let Some(file) = &args.search_index {
+            search::write_index(&model, &links, args.docs_url.as_deref(), file, &budget)?;
+        }
+        if let Some(file) = &args.algolia_records {
+            search::write_algolia_records(&model, &links, args.docs_url.as_deref(), file, &budget)?;
         }
     }
-}
+    match (&args.out_dir, &args.single_file) {
+        (Some(dir), _) => {
+            let _lock = lock_out_dir(dir, args.wait_for_lock)?;
+            write_to_files(&contents, &page_stats, &args, &links, dir, &budget)?
+        }
+        (None, _) if args.archive.is_some() => archive::write(
+            &get_files(&contents, &page_stats, &args, &links),
+            args.archive.as_deref().unwrap_or_else(|| Path::new("")),
+            &budget,
+        )?,
+        (None, Some(file)) => {
+            let text = args.line_ending.apply(&to_single_file(&contents, &links));
+            budget.spend(text.len())?;
+            fs::write(file, text)
+                .map_err(|err| format!("can't write {}: {}", file.display(), err))?
+        }
+        (None, None) if publishing => {}
+        (None, None) => write_to_stdout(&contents, &page_stats, &args, &links, &budget)?,
+    }
+    if let Some(dir) = &args.cache_dir {
+        cache.save(dir)?;
+    }
+    report.time("write", started);
 
-fn create_front_matter(front_matter: &Option<String>, typ: &str) -> String {
-    match front_matter {
-        Some(fm) => format!(
-            "---\n{}\n---\n",
-            fm.replace("{type}", typ)
-                .replace("{TYPE}", &typ.to_uppercase())
-                .replace("{Type}", &titlecase(typ))
-                .replace(":", ": ")
-                .replace(";", "\n")
-        ),
-        None => "".to_string(),
+    if let Some(Command::Publish { target }) = &args.command {
+        let started = Instant::now();
+        match target {
+            PublishTarget::Confluence {
+                base_url,
+                space,
+                parent,
+                user,
+                token,
+            } => {
+                let auth = get_confluence_auth(base_url, user, token)?;
+                Confluence::new(
+                    base_url,
+                    space,
+                    parent,
+                    auth,
+                    args.insecure,
+                    &args.user_agent,
+                )?
+                .publish_all(&contents, &args.site_name, &links)?;
+            }
+            PublishTarget::Notion { token, parent } => {
+                Notion::new(token, parent, args.insecure, &args.user_agent)?
+                    .publish_all(&contents, &links)?;
+            }
+        }
+        report.time("publish", started);
     }
-}
 
-/// Takes the arguments from the Options struct and generates
-/// markdown for the specified schema.
-pub fn run(args: Options) -> Result<(), Box<dyn Error>> {
-    let schema = get_schema(&args)?;
-    let contents = generate_from_schema(&schema, !args.no_titles);
-    match args.out_dir {
-        Some(dir) => write_to_files(&contents, args.front_matter, &dir)?,
-        None => write_to_stdout(&contents, args.front_matter),
+    if args.lint {
+        let started = Instant::now();
+        for finding in lint(&schema, Utc::now().date_naive(), &config.lint) {
+            eprintln!("lint: {}", finding);
+            report.lint.push(finding.to_string());
+        }
+        report.time("lint", started);
+    }
+    if let Some(file) = &args.report {
+        let started = Instant::now();
+        report.coverage = get_coverage(&schema);
+        report.cycles = get_cycles(&schema);
+        report.dangling_links = find_dangling_links(&contents, &links);
+        report.time("report", started);
+        report.write(file)?;
+    }
+    if !report.lint.is_empty() {
+        return Err(format!("lint found {} problem(s)", report.lint.len()).into());
     }
 
     Ok(())
@@ -135,6 +1775,8 @@ pub fn run(args: Options) -> Result<(), Box<dyn Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::env;
+    use tape::Exchange;
 
     #[test]
     fn it_should_return_ok_when_url_specified() -> Result<(), String> {
@@ -152,7 +1794,7 @@ mod tests {
             "a:b;c:d",
         ];
         let args = Options::from_iter(vec.iter());
-        assert_eq!(args.url.unwrap(), "https://example.com");
+        assert_eq!(args.url, vec!["https://example.com"]);
         assert_eq!(args.header.len(), 2);
         assert_eq!(args.header[0], "name1:value1");
         assert_eq!(args.header[1], "name2:value2");
@@ -164,6 +1806,16 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn get_netrc_credentials_should_skip_explicit_auth() -> Result<(), Box<dyn Error>> {
+        let headers = vec!["authorization: Bearer token".to_string()];
+        assert_eq!(
+            None,
+            get_netrc_credentials("https://example.com/graphql", &headers)?
+        );
+        Ok(())
+    }
+
     #[test]
     fn it_should_return_ok_when_json_specified() -> Result<(), String> {
         let vec = vec![
@@ -180,7 +1832,7 @@ mod tests {
             "a:b;c:d",
         ];
         let args = Options::from_iter(vec.iter());
-        assert_eq!(args.json.unwrap().display().to_string(), "foo.json");
+        assert_eq!(args.json, vec![PathBuf::from("foo.json")]);
         assert_eq!(args.header.len(), 2);
         assert_eq!(args.header[0], "name1:value1");
         assert_eq!(args.header[1], "name2:value2");
@@ -222,27 +1874,335 @@ mod tests {
 
     #[test]
     fn it_should_return_error_when_schema_is_passed() {
-        let vec = vec!["gumwood", "--schema", "graphql.schema"];
+        let vec = ["gumwood", "--schema", "graphql.schema"];
         let args = Options::from_iter(vec.iter());
         assert!(run(args).is_err());
     }
 
+    #[test]
+    fn it_should_process_testdata_schema_without_error() {
+        let vec = ["gumwood", "--schema", "testdata/schema.graphql"];
+        let args = Options::from_iter(vec.iter());
+        assert!(run(args).is_ok());
+    }
+
+    #[test]
+    fn run_should_fail_when_over_budget() {
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--schema",
+            "testdata/schema.graphql",
+            "--max-types",
+            "2",
+        ]);
+        match run(args) {
+            Ok(_) => panic!("more types than --max-types should fail"),
+            Err(err) => assert!(err.to_string().contains("more than --max-types 2")),
+        }
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--schema",
+            "testdata/schema.graphql",
+            "--max-output-size",
+            "1K",
+        ]);
+        match run(args) {
+            Ok(_) => panic!("more output than --max-output-size should fail"),
+            Err(err) => assert!(err.to_string().contains("more than --max-output-size 1024")),
+        }
+    }
+
+    #[test]
+    fn run_should_count_every_file_against_max_output_size() -> Result<(), Box<dyn Error>> {
+        fn size(path: &Path) -> io::Result<u64> {
+            if path.is_dir() {
+                fs::read_dir(path)?.map(|entry| size(&entry?.path())).sum()
+            } else {
+                Ok(fs::metadata(path)?.len())
+            }
+        }
+
+        let dir = env::temp_dir().join("gumwood-budget-test");
+        let (out_dir, catalog) = (dir.join("docs"), dir.join("catalog.json"));
+        let run_with = |max: &str| -> Result<(), Box<dyn Error>> {
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir)?;
+            run(Options::from_iter_safe(&[
+                "gumwood".as_ref(),
+                "--schema".as_ref(),
+                "testdata/schema.graphql".as_ref(),
+                "--format".as_ref(),
+                "docusaurus".as_ref(),
+                "--front-matter".as_ref(),
+                "tags: [graphql]".as_ref(),
+                "--out-dir".as_ref(),
+                out_dir.as_os_str(),
+                "--catalog".as_ref(),
+                catalog.as_os_str(),
+                "--max-output-size".as_ref(),
+                max.as_ref(),
+            ])?)
+        };
+        run_with("1G")?;
+        let written = size(&dir)?;
+        run_with(&written.to_string())?;
+        match run_with(&(written - 1).to_string()) {
+            Ok(_) => panic!("more output than --max-output-size should fail"),
+            Err(err) => assert!(err.to_string().contains("more than --max-output-size")),
+        }
+        // The pages would pass the limit, so none of them are written
+        assert!(catalog.exists());
+        assert!(fs::read_dir(&out_dir)?.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn run_should_apply_max_output_size_to_diff() {
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--schema",
+            "testdata/schema.graphql",
+            "--max-output-size",
+            "10",
+            "diff",
+            "testdata/response.json",
+        ]);
+        match run(args) {
+            Ok(_) => panic!("more output than --max-output-size should fail"),
+            Err(err) => assert!(err.to_string().contains("more than --max-output-size 10")),
+        }
+    }
+
+    #[test]
+    fn parse_size_should_accept_suffixes() {
+        assert_eq!(Ok(500), parse_size("500"));
+        assert_eq!(Ok(10 * 1024), parse_size("10K"));
+        assert_eq!(Ok(5 * 1024 * 1024), parse_size("5m"));
+        assert_eq!(Ok(1024 * 1024 * 1024), parse_size("1G"));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("M").is_err());
+    }
+
     #[test]
     fn it_should_process_testdata_response_without_error() {
-        let vec = vec!["gumwood", "--json", "testdata/response.json"];
+        let vec = ["gumwood", "--json", "testdata/response.json"];
         let args = Options::from_iter(vec.iter());
         assert!(run(args).is_ok());
     }
 
+    #[test]
+    fn get_schema_should_replay_recorded_responses() -> Result<(), Box<dyn Error>> {
+        let file = env::temp_dir().join("gumwood-replay-test.json");
+        Tape {
+            exchanges: vec![Exchange {
+                url: "https://example.com/graphql".to_string(),
+                status: 200,
+                headers: vec![("server".to_string(), "example".to_string())],
+                body: fs::read_to_string("testdata/response.json")?,
+                ..Default::default()
+            }],
+        }
+        .write(&file)?;
+        let args = Options::from_iter(&["gumwood", "--replay", file.to_str().unwrap()]);
+        let result = get_schema(&args, &mut Report::default());
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--replay",
+            file.to_str().unwrap(),
+            "--url",
+            "https://example.org/graphql",
+        ]);
+        let missing = get_schema(&args, &mut Report::default());
+        fs::remove_file(&file)?;
+
+        let (schema, endpoints, _) = result?;
+        assert!(schema.get_type("Repository").is_some());
+        assert_eq!("https://example.com/graphql", endpoints[0].url);
+        assert_eq!(
+            vec![("server".to_string(), "example".to_string())],
+            endpoints[0].headers
+        );
+        assert!(missing.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_headers_should_skip_blank_lines_and_comments() {
+        let headers = parse_headers("# tenant\nX-Tenant: acme\n\n  X-Trace: on  \n");
+        assert_eq!(vec!["X-Tenant: acme", "X-Trace: on"], headers);
+    }
+
+    #[test]
+    fn get_headers_should_merge_file_and_flags() -> Result<(), Box<dyn Error>> {
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--headers-file",
+            "testdata/headers.txt",
+            "--header",
+            "name1:value1",
+        ]);
+        let headers = get_headers(&args)?;
+        assert_eq!(
+            vec![
+                "X-Tenant: acme",
+                "X-Request-Source: gumwood",
+                "name1:value1"
+            ],
+            headers
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_headers_should_fail_when_file_missing() {
+        let args = Options::from_iter(&["gumwood", "--headers-file", "testdata/missing.txt"]);
+        assert!(get_headers(&args).is_err());
+    }
+
+    #[test]
+    fn lock_out_dir_should_fail_when_already_locked() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-lock-test");
+        fs::create_dir_all(&dir)?;
+        let _lock = lock_out_dir(&dir, false)?;
+        match lock_out_dir(&dir, false) {
+            Ok(_) => panic!("second lock should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .contains("locked by another gumwood process")),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lock_out_dir_should_create_missing_dir() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-missing-lock-test");
+        let _ = fs::remove_dir_all(&dir);
+        let _lock = lock_out_dir(&dir.join("docs"), false)?;
+        assert!(dir.join("docs").is_dir());
+        Ok(())
+    }
+
+    #[test]
+    fn lock_out_dir_should_leave_nothing_in_dir() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-clean-lock-test");
+        let _ = fs::remove_dir_all(&dir);
+        drop(lock_out_dir(&dir, false)?);
+        assert!(fs::read_dir(&dir)?.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn lock_out_dir_should_lock_same_dir_by_any_path() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-same-lock-test");
+        fs::create_dir_all(dir.join("docs"))?;
+        let _lock = lock_out_dir(&dir.join("docs"), false)?;
+        assert!(lock_out_dir(&dir.join("docs").join("..").join("docs"), false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn lock_out_dir_should_succeed_after_release() -> Result<(), Box<dyn Error>> {
+        let dir = env::temp_dir().join("gumwood-relock-test");
+        fs::create_dir_all(&dir)?;
+        drop(lock_out_dir(&dir, false)?);
+        assert!(lock_out_dir(&dir, false).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn run_should_fail_when_format_needs_out_dir() {
+        let args = Options::from_iter(&["gumwood", "--format", "techdocs"]);
+        match run(args) {
+            Ok(_) => panic!("techdocs without --out-dir should fail"),
+            Err(err) => assert_eq!("--format techdocs requires --out-dir", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn run_should_fail_when_front_matter_format_conflicts() {
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--format",
+            "zola",
+            "--out-dir",
+            "zola",
+            "--front-matter-format",
+            "yaml",
+        ]);
+        match run(args) {
+            Ok(_) => panic!("zola with YAML front matter should fail"),
+            Err(err) => assert_eq!("--format zola requires TOML front matter", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn type_depth_should_default_to_none() {
+        let args = Options::from_iter(&["gumwood"]);
+        assert_eq!(None, args.type_depth);
+    }
+
+    #[test]
+    fn type_depth_should_parse() {
+        let args = Options::from_iter(&["gumwood", "--type-depth", "12"]);
+        assert_eq!(Some(12), args.type_depth);
+    }
+
+    #[test]
+    fn max_wait_should_default_to_a_minute() {
+        let args = Options::from_iter(&["gumwood"]);
+        assert_eq!(60, args.max_wait);
+    }
+
+    #[test]
+    fn line_ending_should_default_to_lf() {
+        let args = Options::from_iter(&["gumwood"]);
+        assert_eq!(LineEnding::Lf, args.line_ending);
+    }
+
+    #[test]
+    fn line_ending_should_parse_crlf() {
+        let args = Options::from_iter(&["gumwood", "--line-ending", "crlf"]);
+        assert_eq!(LineEnding::Crlf, args.line_ending);
+    }
+
+    #[test]
+    fn line_ending_apply_should_convert_to_crlf() {
+        assert_eq!("a\r\nb\r\n", LineEnding::Crlf.apply("a\nb\r\n"));
+    }
+
+    #[test]
+    fn line_ending_apply_should_normalize_to_lf() {
+        assert_eq!("a\nb\n", LineEnding::Lf.apply("a\r\nb\n"));
+    }
+
     #[test]
     fn create_front_matter_should_return_empty_when_none() {
-        assert_eq!(create_front_matter(&None, ""), "");
+        assert_eq!(
+            create_front_matter(
+                &None,
+                &None,
+                "",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
+            ""
+        );
     }
 
     #[test]
     fn create_front_matter_should_return_front_matter_when_some() {
         assert_eq!(
-            create_front_matter(&Some("hello".to_string()), ""),
+            create_front_matter(
+                &Some("hello".to_string()),
+                &None,
+                "",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
             "---\nhello\n---\n"
         );
     }
@@ -250,7 +2210,15 @@ mod tests {
     #[test]
     fn create_front_matter_should_split_lines_on_semicolons() {
         assert_eq!(
-            create_front_matter(&Some("hello;hola;bonjour".to_string()), ""),
+            create_front_matter(
+                &Some("hello;hola;bonjour".to_string()),
+                &None,
+                "",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
             "---\nhello\nhola\nbonjour\n---\n"
         );
     }
@@ -258,7 +2226,15 @@ mod tests {
     #[test]
     fn create_front_matter_should_add_space_after_colons() {
         assert_eq!(
-            create_front_matter(&Some("en:hello;es:hola;fr:bonjour".to_string()), ""),
+            create_front_matter(
+                &Some("en:hello;es:hola;fr:bonjour".to_string()),
+                &None,
+                "",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
             "---\nen: hello\nes: hola\nfr: bonjour\n---\n"
         );
     }
@@ -268,9 +2244,167 @@ mod tests {
         assert_eq!(
             create_front_matter(
                 &Some("same:{type};title:{Type};upper:{TYPE}".to_string()),
-                "greeting"
+                &None,
+                "greeting",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
             ),
             "---\nsame: greeting\ntitle: Greeting\nupper: GREETING\n---\n"
         );
     }
+
+    #[test]
+    fn create_front_matter_should_add_stats() {
+        let stats = PageStats {
+            type_count: 2,
+            field_count: 5,
+            deprecated_count: 1,
+        };
+        assert_eq!(
+            create_front_matter(
+                &None,
+                &None,
+                "enums",
+                Some(&stats),
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
+            "---\ntype_count: 2\nfield_count: 5\ndeprecated_count: 1\n---\n"
+        );
+        assert_eq!(
+            create_front_matter(
+                &Some("kind:{type}".to_string()),
+                &Some("params".to_string()),
+                "enums",
+                Some(&stats),
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
+            "---\nparams:\n  kind: enums\n  type_count: 2\n  field_count: 5\n  deprecated_count: 1\n---\n"
+        );
+    }
+
+    #[test]
+    fn create_front_matter_should_not_nest_format_lines() {
+        assert_eq!(
+            create_front_matter(
+                &Some("kind:{type}".to_string()),
+                &Some("params".to_string()),
+                "enums",
+                None,
+                &["id: enums".to_string(), "sidebar_position: 3".to_string()],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
+            "---\nid: enums\nsidebar_position: 3\nparams:\n  kind: enums\n---\n"
+        );
+    }
+
+    #[test]
+    fn create_front_matter_should_write_toml() {
+        assert_eq!(
+            create_front_matter(
+                &Some("kind:{type};draft:false".to_string()),
+                &Some("params.graphql".to_string()),
+                "enums",
+                None,
+                &["weight = 3".to_string()],
+                FrontMatterStyle::Toml,
+                TitleCase::Title
+            ),
+            "+++\nweight = 3\n[params.graphql]\nkind = \"enums\"\ndraft = false\n+++\n"
+        );
+    }
+
+    #[test]
+    fn create_front_matter_should_write_json() {
+        assert_eq!(
+            create_front_matter(
+                &Some("kind:{type};draft:false".to_string()),
+                &Some("params.graphql".to_string()),
+                "enums",
+                None,
+                &["\"weight\": 3".to_string()],
+                FrontMatterStyle::Json,
+                TitleCase::Title
+            ),
+            "{\n  \"weight\": 3,\n  \"params\": {\n    \"graphql\": {\n      \"kind\": \"enums\",\n      \"draft\": false\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn create_front_matter_should_nest_under_key_prefix() {
+        assert_eq!(
+            create_front_matter(
+                &Some("kind:{type};title:{Type}".to_string()),
+                &Some("params".to_string()),
+                "enums",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
+            "---\nparams:\n  kind: enums\n  title: Enums\n---\n"
+        );
+    }
+
+    #[test]
+    fn create_front_matter_should_nest_under_dotted_key_prefix() {
+        assert_eq!(
+            create_front_matter(
+                &Some("kind:{type}".to_string()),
+                &Some("params.graphql".to_string()),
+                "enums",
+                None,
+                &[],
+                FrontMatterStyle::Yaml,
+                TitleCase::Title
+            ),
+            "---\nparams:\n  graphql:\n    kind: enums\n---\n"
+        );
+    }
+
+    #[test]
+    fn front_matter_key_prefix_should_accept_alias() {
+        let args = Options::from_iter(&["gumwood", "--frontmatter-key-prefix", "params"]);
+        assert_eq!(Some("params".to_string()), args.front_matter_key_prefix);
+    }
+
+    #[test]
+    fn should_read_stdin_should_not_wait_at_a_terminal() {
+        assert!(should_read_stdin(false, false, false).unwrap());
+        assert!(!should_read_stdin(false, true, false).unwrap());
+        assert!(should_read_stdin(true, true, true).unwrap());
+        assert!(should_read_stdin(false, false, true)
+            .unwrap_err()
+            .to_string()
+            .starts_with("no schema to document"));
+    }
+
+    #[test]
+    fn run_should_fail_on_unknown_environment() -> Result<(), Box<dyn Error>> {
+        let config = env::temp_dir().join("gumwood-env-test.toml");
+        fs::write(
+            &config,
+            "[[environments]]\nname = \"staging\"\nurl = \"https://staging.example.com/graphql\"\nout_dir = \"docs/staging\"\n",
+        )?;
+        let args = Options::from_iter(&[
+            "gumwood",
+            "--config",
+            config.to_str().unwrap_or_default(),
+            "--env",
+            "production",
+        ]);
+        let result = run(args);
+        fs::remove_file(&config)?;
+        assert_eq!(
+            "no environment named production in the config file",
+            result.unwrap_err().to_string()
+        );
+        Ok(())
+    }
 }
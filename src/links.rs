@@ -0,0 +1,370 @@
+use super::filename::to_safe_file_name;
+use super::format::TitleCase;
+use super::markdown::{escape, to_heading_id, to_link, to_named_anchor};
+use super::slug::{SlugStyle, Slugifier};
+use serde::Deserialize;
+
+/// How anchors are written on headings, chosen with `anchor_style` in the
+/// config file
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnchorStyle {
+    /// `## <a name="player"></a>Player`, which every renderer supports
+    #[default]
+    Html,
+    /// `## Player {#player}`, for kramdown, Pandoc, and Docusaurus
+    HeadingId,
+}
+
+/// How cross-links between pages and types are rendered
+#[derive(Debug)]
+pub struct Links {
+    /// Render names as plain text instead of links
+    pub plain: bool,
+
+    /// Added to every anchor so they don't collide with the anchors of a
+    /// page the output is embedded in
+    pub anchor_prefix: String,
+
+    /// Turns names into anchors and page file names
+    pub slugifier: Box<dyn Slugifier>,
+
+    /// How anchors are written on headings
+    pub anchor_style: AnchorStyle,
+
+    /// The extension of page files, without the dot
+    pub page_extension: &'static str,
+
+    /// Write each page as the `_index` of its own directory, as Hugo's page
+    /// bundles do, and link to the directory instead of the file
+    pub page_bundles: bool,
+
+    /// Name pages as GitHub wiki pages are named, with title-cased words
+    /// joined by hyphens, and link to them without the extension
+    pub wiki_pages: bool,
+
+    /// Goes before a page's file in links between pages, such as Zola's
+    /// `@/graphql/`
+    pub page_link_prefix: String,
+
+    /// How page titles and headings are capitalized
+    pub title_case: TitleCase,
+
+    /// Escape the markdown and HTML in descriptions and deprecation reasons,
+    /// so they render as plain text
+    pub escape_descriptions: bool,
+
+    /// Pages are MDX, which escapes `<` itself, so escaping it here would
+    /// show the backslash
+    pub mdx: bool,
+}
+
+impl Default for Links {
+    fn default() -> Self {
+        Links {
+            plain: false,
+            anchor_prefix: String::new(),
+            slugifier: SlugStyle::default().slugifier(),
+            anchor_style: AnchorStyle::default(),
+            page_extension: "md",
+            page_bundles: false,
+            wiki_pages: false,
+            page_link_prefix: String::new(),
+            title_case: TitleCase::default(),
+            escape_descriptions: false,
+            mdx: false,
+        }
+    }
+}
+
+impl Links {
+    /// Returns a link to the destination, or just the text if links are off
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - the text of the link
+    /// * `destination` - the destination of the link
+    pub fn link(&self, text: &str, destination: &str) -> String {
+        if self.plain {
+            text.to_string()
+        } else {
+            to_link(text, destination)
+        }
+    }
+
+    /// Returns the anchor name for a type or operation
+    pub fn fragment(&self, name: &str) -> String {
+        format!("{}{}", self.anchor_prefix, self.slugifier.slugify(name))
+    }
+
+    /// Returns the title to show for a page in navigation
+    pub fn page_title(&self, page: &str) -> String {
+        self.title_case.apply(&page.replace('-', " "))
+    }
+
+    /// Returns one of gumwood's own headings, such as `Most Fields`, in the
+    /// page titles' case. They're written in title case already, so only
+    /// sentence and upper case change them.
+    pub fn section_title(&self, text: &str) -> String {
+        match self.title_case {
+            TitleCase::AsIs | TitleCase::Title => text.to_string(),
+            title_case => title_case.apply(text),
+        }
+    }
+
+    /// Returns the file name for a page without its extension
+    pub fn page_id(&self, page: &str) -> String {
+        if self.wiki_pages {
+            to_safe_file_name(&self.page_title(page).replace(' ', "-"))
+        } else {
+            to_safe_file_name(&self.slugifier.slugify(page))
+        }
+    }
+
+    /// Returns the path of a page's file, relative to the pages directory
+    pub fn page_file(&self, page: &str) -> String {
+        if self.page_bundles {
+            format!("{}/_index.{}", self.page_id(page), self.page_extension)
+        } else {
+            format!("{}.{}", self.page_id(page), self.page_extension)
+        }
+    }
+
+    /// Returns what links from one page to another point at, which is the
+    /// page's file unless pages are bundles
+    pub fn page_link(&self, page: &str) -> String {
+        if self.page_bundles {
+            format!("../{}/", self.page_id(page))
+        } else if self.wiki_pages {
+            self.page_id(page)
+        } else {
+            format!("{}{}", self.page_link_prefix, self.page_file(page))
+        }
+    }
+
+    /// Returns whether the destination of a link is a page
+    pub fn is_page_link(&self, destination: &str) -> bool {
+        if self.page_bundles {
+            destination.starts_with("../") && destination.ends_with('/')
+        } else if self.wiki_pages {
+            !destination.is_empty() && !destination.contains(['.', '/'])
+        } else {
+            destination.starts_with(&self.page_link_prefix)
+                && destination.ends_with(&format!(".{}", self.page_extension))
+        }
+    }
+
+    /// Returns a description or deprecation reason to render, escaped if
+    /// descriptions render as plain text
+    pub fn description(&self, text: &str) -> String {
+        match (self.escape_descriptions, self.mdx) {
+            (false, _) => text.to_string(),
+            (true, false) => escape(text, &[]),
+            (true, true) => escape(text, &['<']),
+        }
+    }
+
+    /// Returns the text with a named anchor for it
+    pub fn anchor(&self, text: &str) -> String {
+        self.named_anchor(&self.fragment(text), text)
+    }
+
+    /// Returns a heading's text with an anchor of the given name, in the
+    /// anchor style
+    pub fn named_anchor(&self, name: &str, text: &str) -> String {
+        match self.anchor_style {
+            AnchorStyle::Html => to_named_anchor(name, text),
+            AnchorStyle::HeadingId => to_heading_id(name, text),
+        }
+    }
+
+    /// Returns the text for a heading that's linked to by its own anchor,
+    /// adding a named anchor when a prefix or slugifier means the heading's
+    /// anchor wouldn't match
+    pub fn heading(&self, text: &str) -> String {
+        if self.fragment(text) == text.to_lowercase() {
+            text.to_string()
+        } else {
+            self.anchor(text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::format::Format;
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn page_title_and_section_title_should_follow_title_case() {
+        let titles = |title_case| {
+            let links = Links {
+                title_case,
+                ..Links::default()
+            };
+            (
+                links.page_title("largest-types"),
+                links.section_title("Implemented by"),
+            )
+        };
+        assert_eq!(
+            ("largest types".to_string(), "Implemented by".to_string()),
+            titles(TitleCase::AsIs)
+        );
+        assert_eq!(
+            ("Largest Types".to_string(), "Implemented by".to_string()),
+            titles(TitleCase::Title)
+        );
+        assert_eq!(
+            ("Largest types".to_string(), "Implemented by".to_string()),
+            titles(TitleCase::Sentence)
+        );
+        assert_eq!(
+            ("LARGEST TYPES".to_string(), "IMPLEMENTED BY".to_string()),
+            titles(TitleCase::Upper)
+        );
+    }
+
+    #[test]
+    fn link_should_render_links_by_default() {
+        assert_eq!(
+            "[`Player`](objects.md#player)",
+            Links::default().link("`Player`", "objects.md#player")
+        );
+    }
+
+    #[test]
+    fn anchors_should_use_prefix() {
+        let links = Links {
+            anchor_prefix: "gql-".to_string(),
+            ..Default::default()
+        };
+        assert_eq!("gql-player", links.fragment("Player"));
+        assert_eq!("<a name=\"gql-player\"></a>Player", links.anchor("Player"));
+        assert_eq!(
+            "<a name=\"gql-players\"></a>players",
+            links.heading("players")
+        );
+        assert_eq!("players", Links::default().heading("players"));
+    }
+
+    #[test]
+    fn anchors_and_page_files_should_use_slugifier() {
+        let links = Links {
+            slugifier: SlugStyle::Kebab.slugifier(),
+            ..Default::default()
+        };
+        assert_eq!("add-player", links.fragment("addPlayer"));
+        assert_eq!(
+            "<a name=\"add-player\"></a>addPlayer",
+            links.heading("addPlayer")
+        );
+        assert_eq!("players", links.heading("players"));
+        assert_eq!("largest-types.md", links.page_file("largest-types"));
+        assert_eq!("objects.md", Links::default().page_file("objects"));
+        let links = Links {
+            page_extension: "mdx",
+            ..Default::default()
+        };
+        assert_eq!("objects.mdx", links.page_file("objects"));
+    }
+
+    #[test]
+    fn page_bundles_should_link_to_directories() {
+        let links = Links {
+            page_bundles: true,
+            ..Default::default()
+        };
+        assert_eq!("objects/_index.md", links.page_file("objects"));
+        assert_eq!("../objects/", links.page_link("objects"));
+        assert!(links.is_page_link("../objects/"));
+        assert!(!links.is_page_link("objects.md"));
+        assert!(Links::default().is_page_link("objects.md"));
+    }
+
+    #[test]
+    fn links_to_pages_in_subdirectories_should_use_forward_slashes() {
+        let format = Format::Zola;
+        let links = Links {
+            page_link_prefix: format.page_link_prefix(),
+            ..Default::default()
+        };
+        // The page's file is a platform path, with the platform's separators
+        let file = format.pages_dir().join(links.page_file("input-objects"));
+        assert_eq!(
+            PathBuf::from("content")
+                .join("graphql")
+                .join("input-objects.md"),
+            file
+        );
+        // but links to it are always written with forward slashes
+        assert_eq!(
+            "[Player](@/graphql/input-objects.md#player)",
+            links.link(
+                "Player",
+                &format!(
+                    "{}#{}",
+                    links.page_link("input-objects"),
+                    links.fragment("Player")
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn anchors_should_use_anchor_style() {
+        let links = Links {
+            anchor_style: AnchorStyle::HeadingId,
+            ..Default::default()
+        };
+        assert_eq!("Player {#player}", links.anchor("Player"));
+        assert_eq!("addPlayer", links.heading("addPlayer"));
+        assert_eq!("🔒 Player {#x}", links.named_anchor("x", "🔒 Player"));
+    }
+
+    #[test]
+    fn wiki_pages_should_link_without_extension() {
+        let links = Links {
+            wiki_pages: true,
+            ..Default::default()
+        };
+        assert_eq!("Largest-Types.md", links.page_file("largest-types"));
+        assert_eq!("Objects", links.page_link("objects"));
+        assert!(links.is_page_link("Objects"));
+        assert!(!links.is_page_link("objects.md"));
+    }
+
+    #[test]
+    fn description_should_escape_only_when_asked() {
+        let text = "Wins | losses for *this* <season>";
+        assert_eq!(text, Links::default().description(text));
+        let links = Links {
+            escape_descriptions: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            "Wins \\| losses for \\*this\\* \\<season\\>",
+            links.description(text)
+        );
+        // MDX pages escape < themselves
+        let links = Links {
+            escape_descriptions: true,
+            mdx: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            "Wins \\| losses for \\*this\\* <season\\>",
+            links.description(text)
+        );
+    }
+
+    #[test]
+    fn link_should_render_text_when_plain() {
+        let links = Links {
+            plain: true,
+            ..Default::default()
+        };
+        assert_eq!("`Player`", links.link("`Player`", "objects.md#player"));
+    }
+}
@@ -0,0 +1,471 @@
+use super::schema::{Field, Schema, TypeRef};
+use super::stats::is_introspection_type;
+use super::sunset::parse_date;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt};
+
+/// Which lint rules to run, set in the `lint` table of the config file
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    /// Deprecated elements must not be past their removal date
+    pub overdue_removal: bool,
+
+    /// Mutation names must be a verb followed by a noun, such as
+    /// `addPlayer`
+    pub mutation_verb_noun: bool,
+
+    /// The verbs mutation names may start with; any verb is allowed when
+    /// empty
+    pub mutation_verbs: Vec<String>,
+
+    /// Query names must not start with `get`
+    pub query_get_prefix: bool,
+
+    /// Boolean fields must start with one of the boolean prefixes
+    pub boolean_prefix: bool,
+
+    /// The prefixes boolean field names may start with
+    pub boolean_prefixes: Vec<String>,
+
+    /// Descriptions must not be repeated word for word across many
+    /// elements, which usually means they're placeholders
+    pub duplicate_description: bool,
+
+    /// How many elements can share a description before it's a duplicate
+    pub duplicate_description_threshold: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            overdue_removal: true,
+            mutation_verb_noun: true,
+            mutation_verbs: Vec::new(),
+            query_get_prefix: true,
+            boolean_prefix: true,
+            boolean_prefixes: vec!["is".to_string(), "has".to_string()],
+            duplicate_description: true,
+            duplicate_description_threshold: 3,
+        }
+    }
+}
+
+/// A problem a lint rule found in the schema
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub coordinate: String,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.coordinate, self.message, self.rule)
+    }
+}
+
+/// Checks the schema against the lint rules and returns what they found,
+/// sorted by coordinate
+///
+/// # Arguments
+///
+/// * `schema` - the schema to check
+/// * `today` - the date to check removal dates against
+/// * `rules` - which rules to run
+pub fn lint(schema: &Schema, today: NaiveDate, rules: &Rules) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if rules.overdue_removal {
+        findings.extend(overdue_removals(schema, today));
+    }
+    if rules.mutation_verb_noun {
+        findings.extend(mutations_without_verb_noun(schema, &rules.mutation_verbs));
+    }
+    if rules.query_get_prefix {
+        findings.extend(queries_with_get_prefix(schema));
+    }
+    if rules.boolean_prefix {
+        findings.extend(booleans_without_prefix(schema, &rules.boolean_prefixes));
+    }
+    if rules.duplicate_description {
+        findings.extend(duplicate_descriptions(
+            schema,
+            rules.duplicate_description_threshold,
+        ));
+    }
+    findings.sort_by(|a, b| a.coordinate.cmp(&b.coordinate));
+    findings
+}
+
+// Deprecated fields and enum values that should have been removed by now
+fn overdue_removals(schema: &Schema, today: NaiveDate) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for typ in schema.types.iter().flatten() {
+        let type_name = typ.name.as_deref().unwrap_or_default();
+        let fields = typ
+            .fields
+            .iter()
+            .flatten()
+            .map(|field| (&field.name, &field.removal_date));
+        let values = typ
+            .enums
+            .iter()
+            .flatten()
+            .map(|value| (&value.name, &value.removal_date));
+        for (name, removal_date) in fields.chain(values) {
+            if let Some(date) = removal_date.as_deref().and_then(parse_date) {
+                if date < today {
+                    findings.push(Finding {
+                        rule: "overdue-removal",
+                        coordinate: format!(
+                            "{}.{}",
+                            type_name,
+                            name.as_deref().unwrap_or_default()
+                        ),
+                        message: format!("was due to be removed after {}", date),
+                    });
+                }
+            }
+        }
+    }
+    findings
+}
+
+// Mutations whose names aren't a verb followed by a noun, such as `player`
+// or `playerAdd` when the verbs are limited
+fn mutations_without_verb_noun(schema: &Schema, verbs: &[String]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (coordinate, name) in get_operation_names(schema, schema.get_mutation_name()) {
+        let verb = get_first_word(name);
+        let message = if verb.len() == name.len() {
+            "should be a verb followed by a noun, such as addPlayer".to_string()
+        } else if !verbs.is_empty() && !verbs.iter().any(|allowed| allowed == verb) {
+            format!("should start with one of: {}", verbs.join(", "))
+        } else {
+            continue;
+        };
+        findings.push(Finding {
+            rule: "mutation-verb-noun",
+            coordinate,
+            message,
+        });
+    }
+    findings
+}
+
+// Queries whose names start with `get`, which adds nothing, since queries
+// only get
+fn queries_with_get_prefix(schema: &Schema) -> Vec<Finding> {
+    get_operation_names(schema, schema.get_query_name())
+        .into_iter()
+        .filter(|(_, name)| has_prefix(name, "get"))
+        .map(|(coordinate, name)| Finding {
+            rule: "query-get-prefix",
+            coordinate,
+            message: format!("shouldn't start with get, such as {}", &name[3..]),
+        })
+        .collect()
+}
+
+// Fields of types and interfaces that return a Boolean but don't read as a
+// question, such as `active` rather than `isActive`
+fn booleans_without_prefix(schema: &Schema, prefixes: &[String]) -> Vec<Finding> {
+    let operations = [
+        schema.get_query_name(),
+        schema.get_mutation_name(),
+        schema.get_subscription_name(),
+    ];
+    let mut findings = Vec::new();
+    for typ in schema.types.iter().flatten() {
+        let kind = typ.kind.as_deref().unwrap_or_default();
+        if (kind != "OBJECT" && kind != "INTERFACE") || operations.contains(&typ.name) {
+            continue;
+        }
+        for field in typ
+            .fields
+            .iter()
+            .flatten()
+            .filter(|field| is_boolean(field))
+        {
+            let name = field.name.as_deref().unwrap_or_default();
+            if !prefixes.iter().any(|prefix| has_prefix(name, prefix)) {
+                findings.push(Finding {
+                    rule: "boolean-prefix",
+                    coordinate: format!("{}.{}", typ.name.as_deref().unwrap_or_default(), name),
+                    message: format!("should start with one of: {}", prefixes.join(", ")),
+                });
+            }
+        }
+    }
+    findings
+}
+
+// Types, fields, arguments, input fields, and enum values that share their
+// description with at least threshold - 1 others
+fn duplicate_descriptions(schema: &Schema, threshold: usize) -> Vec<Finding> {
+    let mut coordinates: HashMap<String, Vec<String>> = HashMap::new();
+    let mut add = |description: &Option<String>, coordinate: String| {
+        if let Some(description) = description.as_deref().map(str::trim) {
+            if !description.is_empty() {
+                coordinates
+                    .entry(description.to_string())
+                    .or_default()
+                    .push(coordinate);
+            }
+        }
+    };
+    for typ in schema.types.iter().flatten() {
+        if is_introspection_type(typ) {
+            continue;
+        }
+        let type_name = typ.name.as_deref().unwrap_or_default();
+        add(&typ.description, type_name.to_string());
+        for field in typ.fields.iter().flatten() {
+            let coordinate = format!(
+                "{}.{}",
+                type_name,
+                field.name.as_deref().unwrap_or_default()
+            );
+            for arg in field.args.iter().flatten() {
+                add(
+                    &arg.description,
+                    format!(
+                        "{}({}:)",
+                        coordinate,
+                        arg.name.as_deref().unwrap_or_default()
+                    ),
+                );
+            }
+            add(&field.description, coordinate);
+        }
+        let inputs = typ
+            .inputs
+            .iter()
+            .flatten()
+            .map(|input| (&input.name, &input.description));
+        let values = typ
+            .enums
+            .iter()
+            .flatten()
+            .map(|value| (&value.name, &value.description));
+        for (name, description) in inputs.chain(values) {
+            add(
+                description,
+                format!("{}.{}", type_name, name.as_deref().unwrap_or_default()),
+            );
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (description, coordinates) in coordinates {
+        if coordinates.len() < threshold.max(2) {
+            continue;
+        }
+        for coordinate in coordinates.iter() {
+            findings.push(Finding {
+                rule: "duplicate-description",
+                coordinate: coordinate.to_string(),
+                message: format!(
+                    "shares the description \"{}\" with {} other elements",
+                    get_excerpt(&description),
+                    coordinates.len() - 1
+                ),
+            });
+        }
+    }
+    findings
+}
+
+// Returns the description's first line, shortened to fit in a finding
+fn get_excerpt(description: &str) -> String {
+    let line = description.lines().next().unwrap_or_default();
+    if line.chars().count() > 40 || line.len() < description.len() {
+        format!(
+            "{}...",
+            line.chars().take(40).collect::<String>().trim_end()
+        )
+    } else {
+        line.to_string()
+    }
+}
+
+// Returns the coordinates and names of the fields of the operation type
+fn get_operation_names(schema: &Schema, type_name: Option<String>) -> Vec<(String, &str)> {
+    let typ = match type_name.and_then(|name| schema.get_type(&name)) {
+        Some(typ) => typ,
+        None => return Vec::new(),
+    };
+    let type_name = typ.name.as_deref().unwrap_or_default();
+    typ.fields
+        .iter()
+        .flatten()
+        .filter_map(|field| field.name.as_deref())
+        .map(|name| (format!("{}.{}", type_name, name), name))
+        .collect()
+}
+
+// Returns the leading lowercase word of a camelCase name
+fn get_first_word(name: &str) -> &str {
+    let end = name
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !c.is_ascii_lowercase())
+        .map_or(name.len(), |(index, _)| index);
+    &name[..end]
+}
+
+// Whether the name starts with the prefix as a whole word, so `isActive`
+// has the prefix `is` but `issue` doesn't
+fn has_prefix(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| !c.is_ascii_lowercase())
+}
+
+// Whether the field returns a single Boolean, required or not
+fn is_boolean(field: &Field) -> bool {
+    let mut typ: Option<&TypeRef> = field.field_type.as_ref();
+    while let Some(type_ref) = typ.filter(|type_ref| type_ref.is_required()) {
+        typ = type_ref.of_type.as_deref();
+    }
+    typ.is_some_and(|type_ref| !type_ref.is_list() && type_ref.name.as_deref() == Some("Boolean"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sunset;
+    use std::{collections::HashMap, error::Error};
+
+    #[test]
+    fn lint_should_find_overdue_removals() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Player {
+                number: Int @deprecated(reason: "Removed after 2025-01-01")
+                jersey: Int @deprecated(reason: "Removed after 2025-06-01")
+            }
+            enum Position { LEFT_WING @deprecated(reason: "Sunset: 2024-12-31") WING }
+            "#,
+        )?;
+        sunset::apply(&mut schema, &HashMap::new())?;
+        let findings = lint(
+            &schema,
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            &Rules::default(),
+        );
+        assert_eq!(
+            vec![
+                "Player.number: was due to be removed after 2025-01-01 [overdue-removal]",
+                "Position.LEFT_WING: was due to be removed after 2024-12-31 [overdue-removal]",
+            ],
+            findings
+                .iter()
+                .map(|finding| finding.to_string())
+                .collect::<Vec<String>>()
+        );
+        Ok(())
+    }
+
+    const NAMING_SDL: &str = r#"
+        type Query {
+            getPlayer(id: ID!): Player
+            players: [Player!]!
+            getaway: String
+        }
+        type Mutation {
+            addPlayer(name: String): Player
+            player(name: String): Player
+            playerRemove(id: ID!): Boolean
+        }
+        type Player {
+            active: Boolean!
+            isCaptain: Boolean
+            hasLeft: Boolean
+            issue: Boolean
+            awards: [Boolean]
+        }
+    "#;
+
+    fn to_strings(findings: Vec<Finding>) -> Vec<String> {
+        findings.iter().map(|finding| finding.to_string()).collect()
+    }
+
+    #[test]
+    fn lint_should_find_naming_convention_problems() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(NAMING_SDL)?;
+        let findings = lint(
+            &schema,
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            &Rules::default(),
+        );
+        assert_eq!(
+            vec![
+                "Mutation.player: should be a verb followed by a noun, such as addPlayer [mutation-verb-noun]",
+                "Player.active: should start with one of: is, has [boolean-prefix]",
+                "Player.issue: should start with one of: is, has [boolean-prefix]",
+                "Query.getPlayer: shouldn't start with get, such as Player [query-get-prefix]",
+            ],
+            to_strings(findings)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lint_should_skip_disabled_rules_and_use_configured_words() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(NAMING_SDL)?;
+        let rules = Rules {
+            mutation_verbs: vec!["add".to_string(), "remove".to_string()],
+            query_get_prefix: false,
+            boolean_prefixes: vec!["is".to_string()],
+            ..Rules::default()
+        };
+        let findings = lint(
+            &schema,
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            &rules,
+        );
+        assert_eq!(
+            vec![
+                "Mutation.player: should be a verb followed by a noun, such as addPlayer [mutation-verb-noun]",
+                "Mutation.playerRemove: should start with one of: add, remove [mutation-verb-noun]",
+                "Player.active: should start with one of: is [boolean-prefix]",
+                "Player.hasLeft: should start with one of: is [boolean-prefix]",
+                "Player.issue: should start with one of: is [boolean-prefix]",
+            ],
+            to_strings(findings)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lint_should_find_duplicate_descriptions() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            "TODO"
+            type Player {
+                "TODO" name: String
+                "The team" team(" TODO " season: Int): String
+            }
+            type Team { "The team" id: ID! }
+            "#,
+        )?;
+        let today = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(
+            vec![
+                "Player: shares the description \"TODO\" with 2 other elements [duplicate-description]",
+                "Player.name: shares the description \"TODO\" with 2 other elements [duplicate-description]",
+                "Player.team(season:): shares the description \"TODO\" with 2 other elements [duplicate-description]",
+            ],
+            to_strings(lint(&schema, today, &Rules::default()))
+        );
+
+        let rules = Rules {
+            duplicate_description_threshold: 2,
+            ..Rules::default()
+        };
+        assert_eq!(5, lint(&schema, today, &rules).len());
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use super::markdown::{self, Block, Inline};
+use super::model::{DocField, DocInput, DocType, DocTypeRef, Model};
+
+/// Returns the schema as compact plain text for AI assistants and embedding
+/// pipelines, in the spirit of llms.txt: the operations, then every type
+/// with its members, then the directives, each on one line in GraphQL's
+/// notation with its description after a dash, and no markdown
+///
+/// # Arguments
+///
+/// * `model` - the documentation model
+/// * `roots` - the names of the root operation types, which are listed as
+///   operations rather than as types
+/// * `site_name` - the name of the documentation site
+pub fn to_llms_txt(model: &Model, roots: &[String], site_name: &str) -> String {
+    let mut s = format!("{}\n", site_name);
+    if let Some(description) = &model.description {
+        s.push_str(&format!("{}\n", to_plain(description)));
+    }
+
+    for (title, operations) in [
+        ("Queries", &model.queries),
+        ("Mutations", &model.mutations),
+        ("Subscriptions", &model.subscriptions),
+    ] {
+        if !operations.is_empty() {
+            s.push_str(&format!("\n{}:\n", title));
+            for operation in operations {
+                add_field(&mut s, operation, "");
+            }
+        }
+    }
+
+    let types: Vec<&DocType> = model
+        .types
+        .iter()
+        .filter(|typ| !typ.name.starts_with("__") && !roots.contains(&typ.name))
+        .collect();
+    if !types.is_empty() {
+        s.push_str("\nTypes:\n");
+        for typ in types {
+            add_type(&mut s, typ);
+        }
+    }
+
+    if !model.directives.is_empty() {
+        s.push_str("\nDirectives:\n");
+        for directive in &model.directives {
+            let mut signature =
+                format!("@{}{}", directive.name, to_arguments(&directive.arguments));
+            if directive.repeatable {
+                signature.push_str(" repeatable");
+            }
+            signature.push_str(&format!(" on {}", directive.locations.join(" | ")));
+            add_line(&mut s, "", &signature, &directive.description);
+            add_argument_descriptions(&mut s, &directive.arguments, "  ");
+        }
+    }
+    s
+}
+
+fn add_type(s: &mut String, typ: &DocType) {
+    let mut signature = format!("{} {}", to_keyword(&typ.kind), typ.name);
+    if let Some(interfaces) = typ.interfaces.as_ref().filter(|i| !i.is_empty()) {
+        signature.push_str(&format!(" implements {}", to_names(interfaces, " & ")));
+    }
+    if let Some(possible_types) = typ.possible_types.as_ref().filter(|p| !p.is_empty()) {
+        if typ.kind == "UNION" {
+            signature.push_str(&format!(" = {}", to_names(possible_types, " | ")));
+        }
+    }
+    if typ.one_of {
+        signature.push_str(" @oneOf");
+    }
+    add_line(s, "", &signature, &typ.description);
+    for field in typ.fields.iter().flatten() {
+        add_field(s, field, "  ");
+    }
+    for input in typ.inputs.iter().flatten() {
+        add_line(s, "  ", &to_input(input), &input.description);
+    }
+    for value in typ.values.iter().flatten() {
+        let mut signature = value.name.clone();
+        if value.deprecated {
+            signature.push_str(&to_deprecated(&value.deprecation_reason));
+        }
+        add_line(s, "  ", &signature, &value.description);
+    }
+}
+
+// Adds an operation or field, with the descriptions of its arguments on the
+// lines after it
+fn add_field(s: &mut String, field: &DocField, indent: &str) {
+    let mut signature = format!("{}{}", field.name, to_arguments(&field.arguments));
+    if let Some(field_type) = &field.field_type {
+        signature.push_str(&format!(": {}", field_type.decorated_name));
+    }
+    if field.deprecated {
+        signature.push_str(&to_deprecated(&field.deprecation_reason));
+    }
+    add_line(s, indent, &signature, &field.description);
+    add_argument_descriptions(s, &field.arguments, &format!("{}  ", indent));
+}
+
+fn add_argument_descriptions(s: &mut String, arguments: &[DocInput], indent: &str) {
+    for argument in arguments.iter().filter(|a| a.description.is_some()) {
+        add_line(s, indent, &argument.name, &argument.description);
+    }
+}
+
+fn add_line(s: &mut String, indent: &str, signature: &str, description: &Option<String>) {
+    let description = description.as_deref().map(to_plain).unwrap_or_default();
+    if description.is_empty() {
+        s.push_str(&format!("{}{}\n", indent, signature));
+    } else {
+        s.push_str(&format!("{}{} - {}\n", indent, signature, description));
+    }
+}
+
+fn to_arguments(arguments: &[DocInput]) -> String {
+    if arguments.is_empty() {
+        return String::new();
+    }
+    let arguments: Vec<String> = arguments.iter().map(to_input).collect();
+    format!("({})", arguments.join(", "))
+}
+
+fn to_input(input: &DocInput) -> String {
+    let mut s = input.name.clone();
+    if let Some(input_type) = &input.input_type {
+        s.push_str(&format!(": {}", input_type.decorated_name));
+    }
+    if let Some(default_value) = &input.default_value {
+        s.push_str(&format!(" = {}", default_value));
+    }
+    s
+}
+
+fn to_deprecated(reason: &Option<String>) -> String {
+    match reason.as_deref().map(to_plain) {
+        Some(reason) if !reason.is_empty() => format!(" @deprecated({})", reason),
+        _ => " @deprecated".to_string(),
+    }
+}
+
+fn to_names(type_refs: &[DocTypeRef], separator: &str) -> String {
+    type_refs
+        .iter()
+        .map(|type_ref| type_ref.name.as_str())
+        .collect::<Vec<&str>>()
+        .join(separator)
+}
+
+// Returns the SDL keyword for the kind, or the kind itself if SDL has none
+fn to_keyword(kind: &str) -> String {
+    match kind {
+        "OBJECT" => "type",
+        "INPUT_OBJECT" => "input",
+        "INTERFACE" => "interface",
+        "ENUM" => "enum",
+        "UNION" => "union",
+        "SCALAR" => "scalar",
+        _ => kind,
+    }
+    .to_string()
+}
+
+// Returns the text of a description with its markdown and HTML entities
+// removed, on one line
+fn to_plain(description: &str) -> String {
+    let mut texts = Vec::new();
+    add_blocks(&mut texts, &markdown::parse_blocks(description));
+    markdown::unescape(&texts.join(" "))
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn add_blocks(texts: &mut Vec<String>, blocks: &[Block]) {
+    for block in blocks {
+        match block {
+            Block::Heading(_, text) | Block::Paragraph(text) | Block::Notice(text) => {
+                texts.push(to_text(&markdown::parse_inline(text)));
+            }
+            Block::List(items) => {
+                texts.extend(
+                    items
+                        .iter()
+                        .map(|item| to_text(&markdown::parse_inline(item))),
+                );
+            }
+            Block::Table(rows) => {
+                for row in rows {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .map(|cell| to_text(&markdown::parse_inline(cell)))
+                        .collect();
+                    texts.push(cells.join(", "));
+                }
+            }
+            Block::Quote(blocks) => add_blocks(texts, blocks),
+            Block::Code { code, .. } => texts.push(code.clone()),
+        }
+    }
+}
+
+fn to_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) | Inline::Code(text) => text.clone(),
+            Inline::Strong(inlines) | Inline::Link { text: inlines, .. } => to_text(inlines),
+            Inline::Anchor(_) => String::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icons::Icons;
+    use crate::links::Links;
+    use crate::schema::Schema;
+    use std::error::Error;
+
+    #[test]
+    fn to_llms_txt_should_list_everything_as_plain_text() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query {
+              "Get a [player](https://example.com/players) by `id`"
+              player("The player's ID" id: ID!, active: Boolean = true): Player
+            }
+            "A hockey player"
+            type Player implements Node {
+              id: ID!
+              number: Int @deprecated(reason: "Use `jersey`")
+            }
+            interface Node { id: ID! }
+            enum Position { CENTER }
+            union Person = Player
+            directive @auth(role: String) on FIELD_DEFINITION | OBJECT
+            "#,
+        )?;
+        schema.description = Some("The **league's** API".to_string());
+        let links = Links::default();
+        let model = Model::from_schema(&schema, &Icons::default(), &links);
+        let text = to_llms_txt(&model, &["Query".to_string()], "League API");
+        for line in [
+            "League API\nThe league's API\n\nQueries:\n",
+            "player(active: Boolean = true, id: ID!): Player - Get a player by id\n  id - The player's ID\n",
+            "type Player implements Node - A hockey player\n  id: ID!\n  number: Int @deprecated(Use jersey)\n",
+            "union Person = Player\n",
+            "enum Position\n  CENTER\n",
+            "Directives:\n@auth(role: String) on FIELD_DEFINITION | OBJECT\n",
+        ] {
+            assert!(text.contains(line), "{:?} isn't in {}", line, text);
+        }
+        assert!(!text.contains("type Query"));
+        assert!(!text.contains("__"));
+        Ok(())
+    }
+}
@@ -12,13 +12,23 @@ pub fn to_header(level: u8, text: &str) -> String {
     )
 }
 
-/// Returns a Markdown description
+/// Returns a Markdown description, quoting every line so its paragraphs and
+/// code blocks stay in the quote
 ///
 /// # Arguments
 ///
 /// * `text` - the text of the description
 pub fn to_description(text: &str) -> String {
-    format!("> {}\n\n", text)
+    let mut s = String::new();
+    for line in text.trim().lines() {
+        if line.trim().is_empty() {
+            s.push_str(">\n");
+        } else {
+            s.push_str(&format!("> {}\n", line));
+        }
+    }
+    s.push('\n');
+    s
 }
 
 /// Returns text as Markdown inline code
@@ -77,9 +87,38 @@ pub fn to_list(items: &[String]) -> String {
 ///
 /// # Arguments
 ///
-/// * `text` - the text for the link, which is also used for the anchor
-pub fn to_named_anchor(text: &str) -> String {
-    format!("<a name=\"{}\"></a>{}", text.to_lowercase(), text)
+/// * `name` - the name of the anchor
+/// * `text` - the text for the link
+pub fn to_named_anchor(name: &str, text: &str) -> String {
+    format!("<a name=\"{}\"></a>{}", name, text)
+}
+
+/// Returns a heading's text with a heading ID attribute, which kramdown,
+/// Pandoc, and Docusaurus turn into the heading's anchor
+///
+/// # Arguments
+///
+/// * `name` - the name of the anchor
+/// * `text` - the text of the heading
+pub fn to_heading_id(name: &str, text: &str) -> String {
+    format!("{} {{#{}}}", text, name)
+}
+
+/// Splits a heading ID attribute off the end of a heading's text, returning
+/// the rest of the text and the ID
+///
+/// # Arguments
+///
+/// * `text` - the text of the heading
+pub fn split_heading_id(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_end().strip_suffix('}')?;
+    let start = text.rfind("{#")?;
+    let id = &text[start + 2..];
+    if id.is_empty() || id.contains(char::is_whitespace) {
+        None
+    } else {
+        Some((text[..start].trim_end(), id))
+    }
 }
 
 /// Returns a Markdown notice
@@ -109,10 +148,494 @@ pub fn to_table_separator(num: usize) -> String {
     to_table_row(&vec!["---".to_string(); num])
 }
 
+/// Returns the text of a table cell with its pipes escaped, so they don't
+/// end the cell. Pipes that are already escaped are left alone.
+///
+/// # Arguments
+///
+/// * `text` - the text of the cell
+pub fn escape_table_cell(text: &str) -> String {
+    let mut s = String::new();
+    let mut escaped = false;
+    for c in text.chars() {
+        if c == '|' && !escaped {
+            s.push('\\');
+        }
+        escaped = c == '\\' && !escaped;
+        s.push(c);
+    }
+    s
+}
+
+/// Returns the text with the characters markdown would read as formatting,
+/// links, or HTML escaped, so it renders as written
+///
+/// # Arguments
+///
+/// * `text` - the text to escape
+/// * `skip` - characters to leave alone, for a format that escapes them
+///   another way
+pub fn escape(text: &str, skip: &[char]) -> String {
+    let mut s = String::new();
+    for c in text.chars() {
+        if MARKDOWN_CHARS.contains(&c) && !skip.contains(&c) {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s
+}
+
+// The characters escape() escapes
+const MARKDOWN_CHARS: &[char] = &['\\', '`', '*', '_', '[', ']', '<', '>', '|', '~', '#'];
+
+/// Splits markdown into pieces of code (fenced blocks and code spans) and
+/// pieces of text, so text can be changed without touching code. Each piece
+/// is returned with whether it's code; together they make up the original.
+///
+/// # Arguments
+///
+/// * `markdown` - the markdown to split
+pub fn split_code(markdown: &str) -> Vec<(&str, bool)> {
+    let mut pieces = Vec::new();
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            pieces.push((line, true));
+        } else if in_fence {
+            pieces.push((line, true));
+        } else {
+            split_code_spans(line, &mut pieces);
+        }
+    }
+    pieces
+}
+
+// A code span runs from a run of backticks to the next run of the same
+// length; a run with no match is just text
+fn split_code_spans<'a>(line: &'a str, pieces: &mut Vec<(&'a str, bool)>) {
+    let mut text_start = 0;
+    let mut i = 0;
+    while let Some(offset) = line[i..].find('`') {
+        let start = i + offset;
+        let ticks = line[start..].len() - line[start..].trim_start_matches('`').len();
+        let after = start + ticks;
+        match find_tick_run(&line[after..], ticks) {
+            Some(close) => {
+                let end = after + close + ticks;
+                if start > text_start {
+                    pieces.push((&line[text_start..start], false));
+                }
+                pieces.push((&line[start..end], true));
+                text_start = end;
+                i = end;
+            }
+            None => i = after,
+        }
+    }
+    if text_start < line.len() {
+        pieces.push((&line[text_start..], false));
+    }
+}
+
+// Returns where the next run of exactly this many backticks starts
+fn find_tick_run(s: &str, ticks: usize) -> Option<usize> {
+    let mut i = 0;
+    while let Some(offset) = s[i..].find('`') {
+        let start = i + offset;
+        let run = s[start..].len() - s[start..].trim_start_matches('`').len();
+        if run == ticks {
+            return Some(start);
+        }
+        i = start + run;
+    }
+    None
+}
+
+/// A block of the markdown gumwood generates, for converting pages to
+/// other markup
+#[derive(Debug, PartialEq)]
+pub enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    /// A line in italics, such as "Deprecated"
+    Notice(String),
+    List(Vec<String>),
+    /// The rows of cells, headers first, without the separator row
+    Table(Vec<Vec<String>>),
+    /// A quoted description, which can hold other blocks
+    Quote(Vec<Block>),
+    Code {
+        language: String,
+        code: String,
+    },
+}
+
+/// A piece of the text in a block
+#[derive(Debug, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Code(String),
+    Strong(Vec<Inline>),
+    Link {
+        text: Vec<Inline>,
+        destination: String,
+    },
+    /// A named anchor, from `to_named_anchor`
+    Anchor(String),
+}
+
+/// Splits markdown into blocks. It handles the markdown gumwood generates,
+/// plus what's common in descriptions; anything else is a paragraph.
+///
+/// # Arguments
+///
+/// * `markdown` - the markdown to parse
+pub fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim().is_empty() {
+            i += 1;
+        } else if let Some(language) = line.strip_prefix("```") {
+            let start = i + 1;
+            i = start;
+            while i < lines.len() && !lines[i].starts_with("```") {
+                i += 1;
+            }
+            blocks.push(Block::Code {
+                language: language.trim().to_string(),
+                code: lines[start..i.min(lines.len())].join("\n"),
+            });
+            i += 1;
+        } else if line.starts_with('#') {
+            let level = line.chars().take_while(|c| *c == '#').count();
+            let text = line[level..].trim();
+            // Converters expect anchors, not heading IDs
+            let text = match split_heading_id(text) {
+                Some((text, id)) => to_named_anchor(id, text),
+                None => text.to_string(),
+            };
+            blocks.push(Block::Heading(level, text));
+            i += 1;
+        } else if line.starts_with('|') {
+            let mut rows = Vec::new();
+            while i < lines.len() && lines[i].starts_with('|') {
+                let cells = split_table_row(lines[i]);
+                if !cells
+                    .iter()
+                    .all(|cell| !cell.is_empty() && cell.chars().all(|c| "-:".contains(c)))
+                {
+                    rows.push(cells);
+                }
+                i += 1;
+            }
+            blocks.push(Block::Table(rows));
+        } else if is_list_item(line) {
+            let mut items = Vec::new();
+            while i < lines.len() && is_list_item(lines[i]) {
+                items.push(lines[i][2..].to_string());
+                i += 1;
+            }
+            blocks.push(Block::List(items));
+        } else if line.starts_with('>') {
+            // Descriptions are quoted, and run until a blank line
+            let mut quoted = Vec::new();
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                let line = lines[i];
+                quoted.push(
+                    line.strip_prefix("> ")
+                        .or_else(|| line.strip_prefix('>'))
+                        .unwrap_or(line),
+                );
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_blocks(&quoted.join("\n"))));
+        } else {
+            let mut paragraph = Vec::new();
+            while i < lines.len() && !lines[i].trim().is_empty() && !starts_block(lines[i]) {
+                paragraph.push(lines[i].trim());
+                i += 1;
+            }
+            let text = paragraph.join(" ");
+            match text.strip_prefix('_').and_then(|t| t.strip_suffix('_')) {
+                Some(notice) if !notice.is_empty() => {
+                    blocks.push(Block::Notice(notice.to_string()))
+                }
+                _ => blocks.push(Block::Paragraph(text)),
+            }
+        }
+    }
+    blocks
+}
+
+/// Splits the text of a block into code spans, bold text, links, anchors,
+/// and plain text
+///
+/// # Arguments
+///
+/// * `text` - the text to parse
+pub fn parse_inline(text: &str) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let (inline, len) = match c {
+            '`' => parse_code_span(rest),
+            '<' => parse_anchor(rest),
+            '*' => parse_strong(rest),
+            '[' => parse_link(rest),
+            _ => None,
+        }
+        .map_or((None, 0), |(inline, len)| (Some(inline), len));
+        match inline {
+            Some(inline) => {
+                if !plain.is_empty() {
+                    inlines.push(Inline::Text(std::mem::take(&mut plain)));
+                }
+                inlines.push(inline);
+                rest = &rest[len..];
+            }
+            None => {
+                // A run of backticks without a closing run is literal, and
+                // so is punctuation after a backslash
+                let len = match c {
+                    '`' => rest.len() - rest.trim_start_matches('`').len(),
+                    '\\' if rest[1..].starts_with(|c: char| c.is_ascii_punctuation()) => {
+                        rest = &rest[1..];
+                        1
+                    }
+                    _ => c.len_utf8(),
+                };
+                plain.push_str(&rest[..len]);
+                rest = &rest[len..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        inlines.push(Inline::Text(plain));
+    }
+    inlines
+}
+
+fn is_list_item(line: &str) -> bool {
+    line.starts_with("* ") || line.starts_with("- ")
+}
+
+fn starts_block(line: &str) -> bool {
+    line.starts_with("```")
+        || line.starts_with('#')
+        || line.starts_with('|')
+        || line.starts_with('>')
+        || is_list_item(line)
+}
+
+fn split_table_row(row: &str) -> Vec<String> {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = row.strip_suffix('|').unwrap_or(row);
+    row.split(" | ")
+        .map(|cell| cell.trim().replace("\\|", "|"))
+        .collect()
+}
+
+// Each parser returns the inline at the start of the text and how many bytes
+// of the text it took up, or None if the text doesn't start with one
+
+fn parse_code_span(text: &str) -> Option<(Inline, usize)> {
+    let ticks = text.len() - text.trim_start_matches('`').len();
+    let after = &text[ticks..];
+    let end = find_tick_run(after, ticks)?;
+    Some((
+        Inline::Code(after[..end].trim().to_string()),
+        ticks + end + ticks,
+    ))
+}
+
+fn parse_anchor(text: &str) -> Option<(Inline, usize)> {
+    let after = text.strip_prefix("<a name=\"")?;
+    let end = after.find("\"></a>")?;
+    Some((
+        Inline::Anchor(after[..end].to_string()),
+        text.len() - after.len() + end + 6,
+    ))
+}
+
+fn parse_strong(text: &str) -> Option<(Inline, usize)> {
+    let after = text.strip_prefix("**")?;
+    let end = after.find("**")?;
+    Some((Inline::Strong(parse_inline(&after[..end])), 2 + end + 2))
+}
+
+fn parse_link(text: &str) -> Option<(Inline, usize)> {
+    let after = text.strip_prefix('[')?;
+    let text_end = after.find("](")?;
+    let destination = &after[text_end + 2..];
+    // Destinations can hold balanced parentheses
+    let mut depth = 0;
+    let mut destination_end = None;
+    for (i, c) in destination.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                destination_end = Some(i);
+                break;
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    let destination = &destination[..destination_end?];
+    Some((
+        Inline::Link {
+            text: parse_inline(&after[..text_end]),
+            destination: destination.to_string(),
+        },
+        1 + text_end + 2 + destination.len() + 1,
+    ))
+}
+
+/// Decodes the HTML entities descriptions can hold, for formats that would
+/// otherwise show them as is
+///
+/// # Arguments
+///
+/// * `text` - the text to decode
+pub fn unescape(text: &str) -> String {
+    let mut s = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        s.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "mdash" => '—',
+                entity => entity
+                    .strip_prefix('#')
+                    .and_then(|number| number.parse().ok())
+                    .and_then(char::from_u32)?,
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                s.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                s.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    s.push_str(rest);
+    s
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn split_code_should_separate_code_spans_and_fences() {
+        assert_eq!(
+            vec![
+                ("a ", false),
+                ("`<b>`", true),
+                (" c ", false),
+                ("`` ` ``", true),
+                ("\n", false),
+                ("```\n", true),
+                ("<x>\n", true),
+                ("```\n", true),
+                ("d ` e\n", false),
+            ],
+            split_code("a `<b>` c `` ` ``\n```\n<x>\n```\nd ` e\n")
+        );
+    }
+
+    #[test]
+    fn parse_blocks_should_split_generated_markdown() {
+        assert_eq!(
+            vec![
+                Block::Heading(2, "<a name=\"player\"></a>Player".to_string()),
+                Block::Quote(vec![
+                    Block::Paragraph("A hockey player".to_string()),
+                    Block::Code {
+                        language: "graphql".to_string(),
+                        code: "{ player }".to_string(),
+                    },
+                ]),
+                Block::Notice("Deprecated".to_string()),
+                Block::Table(vec![
+                    vec!["Name".to_string(), "Type".to_string()],
+                    vec!["`id`".to_string(), "`ID!`".to_string()],
+                ]),
+                Block::List(vec!["`Node`".to_string(), "a * b".to_string()]),
+                Block::Paragraph("**Type:** [`Player`](objects.md#player)".to_string()),
+            ],
+            parse_blocks(concat!(
+                "## <a name=\"player\"></a>Player\n\n",
+                "> A hockey player\n```graphql\n{ player }\n```\n\n",
+                "_Deprecated_\n\n",
+                "| Name | Type |\n| --- | --- |\n| `id` | `ID!` |\n\n",
+                "* `Node`\n* a * b\n\n",
+                "**Type:** [`Player`](objects.md#player)\n",
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_inline_should_split_text() {
+        assert_eq!(
+            vec![
+                Inline::Strong(vec![Inline::Text("Type:".to_string())]),
+                Inline::Text(" ".to_string()),
+                Inline::Link {
+                    text: vec![Inline::Code("[Player]".to_string())],
+                    destination: "objects.md#player".to_string(),
+                },
+                Inline::Text(" a < b, ".to_string()),
+                Inline::Anchor("x".to_string()),
+                Inline::Text("x * 2 ``a` [c]".to_string()),
+            ],
+            parse_inline(
+                "**Type:** [`[Player]`](objects.md#player) a < b, <a name=\"x\"></a>x * 2 ``a` [c]"
+            )
+        );
+    }
+
+    #[test]
+    fn parse_inline_should_read_escapes_as_text() {
+        assert_eq!(
+            vec![Inline::Text("*a* | [b] C:\\dir".to_string())],
+            parse_inline(&escape("*a* | [b] C:\\dir", &[]))
+        );
+    }
+
+    #[test]
+    fn escape_table_cell_should_escape_bare_pipes() {
+        assert_eq!(
+            "`a \\| b` or c \\| d",
+            escape_table_cell("`a | b` or c \\| d")
+        );
+        assert_eq!(
+            vec!["a | b".to_string(), "c".to_string()],
+            split_table_row(&to_table_row(&[
+                escape_table_cell("a | b"),
+                "c".to_string()
+            ]))
+        );
+    }
+
     #[test]
     fn to_header_should_create_header_1() {
         assert_eq!("# My Header\n\n", to_header(1, "My Header"));
@@ -128,6 +651,14 @@ mod tests {
         assert_eq!("> My description\n\n", to_description("My description"));
     }
 
+    #[test]
+    fn to_description_should_quote_every_line() {
+        assert_eq!(
+            "> Finds a player.\n>\n> ```graphql\n> { player }\n> ```\n\n",
+            to_description("Finds a player.\n\n```graphql\n{ player }\n```\n")
+        );
+    }
+
     #[test]
     fn to_inline_code_should_wrap_in_backticks_when_not_empty() {
         assert_eq!("`foo`", to_inline_code("foo"));
@@ -153,12 +684,15 @@ mod tests {
 
     #[test]
     fn to_named_anchor_should_create_named_anchor() {
-        assert_eq!("<a name=\"foo\"></a>foo", to_named_anchor("foo"));
+        assert_eq!("<a name=\"foo\"></a>foo", to_named_anchor("foo", "foo"));
     }
 
     #[test]
-    fn to_named_anchor_should_create_named_anchor_when_mixed_case() {
-        assert_eq!("<a name=\"foo\"></a>Foo", to_named_anchor("Foo"));
+    fn to_named_anchor_should_create_named_anchor_when_name_differs() {
+        assert_eq!(
+            "<a name=\"gql-foo\"></a>Foo",
+            to_named_anchor("gql-foo", "Foo")
+        );
     }
 
     #[test]
@@ -168,14 +702,14 @@ mod tests {
 
     #[test]
     fn to_table_row_should_create_row_when_empty() {
-        assert_eq!("|  |\n", to_table_row(&vec![]));
+        assert_eq!("|  |\n", to_table_row(&[]));
     }
 
     #[test]
     fn to_table_row_should_create_row_when_not_empty() {
         assert_eq!(
             "| a | b | c |\n",
-            to_table_row(&vec!["a".to_string(), "b".to_string(), "c".to_string()])
+            to_table_row(&["a".to_string(), "b".to_string(), "c".to_string()])
         );
     }
 
@@ -191,14 +725,39 @@ mod tests {
 
     #[test]
     fn to_list_should_return_cr_when_empty() {
-        assert_eq!("\n", to_list(&vec![]));
+        assert_eq!("\n", to_list(&[]));
     }
 
     #[test]
     fn to_list_should_return_list_when_not_empty() {
         assert_eq!(
             "* a\n* b\n* c\n\n",
-            to_list(&vec!["a".to_string(), "b".to_string(), "c".to_string()])
+            to_list(&["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn unescape_should_decode_entities() {
+        assert_eq!(
+            "<a> & b's — c &x; &",
+            unescape("&lt;a&gt; &amp; b&#39;s &mdash; c &x; &")
+        );
+    }
+
+    #[test]
+    fn split_heading_id_should_find_trailing_id() {
+        assert_eq!(
+            Some(("🔒 Player", "player")),
+            split_heading_id(&to_heading_id("player", "🔒 Player"))
+        );
+        assert_eq!(None, split_heading_id("Player"));
+        assert_eq!(None, split_heading_id("Map {#}"));
+        assert_eq!(
+            vec![Block::Heading(
+                2,
+                "<a name=\"player\"></a>Player".to_string()
+            )],
+            parse_blocks("## Player {#player}\n")
         );
     }
 }
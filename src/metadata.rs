@@ -0,0 +1,340 @@
+use super::descriptions::get_unmatched;
+use super::schema::{ElementMut, Input, Schema, Type};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+};
+
+/// What an undocumented type that's still referenced says in its place
+pub const STUB_DESCRIPTION: &str = "Internal type, not documented.";
+
+/// Supplemental content from a SpectaQL-style metadata file: example values,
+/// elements to leave out of the docs, and groups for operations, keyed by
+/// schema coordinate
+#[derive(Debug, Default)]
+pub struct Metadata {
+    documentation: HashMap<String, Documentation>,
+}
+
+// What the metadata file says about one element
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+struct Documentation {
+    example: Option<Value>,
+    examples: Option<Vec<Value>>,
+    undocumented: bool,
+    group: Option<String>,
+}
+
+// An element in the file, which nests its members' metadata under it, as
+// introspection nests them
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Element {
+    documentation: Documentation,
+    #[serde(alias = "inputFields", alias = "enumValues")]
+    fields: HashMap<String, Element>,
+    arguments: HashMap<String, Element>,
+}
+
+impl Documentation {
+    // Returns the examples as JSON, the single example first
+    fn get_examples(&self) -> Vec<String> {
+        self.example
+            .iter()
+            .chain(self.examples.iter().flatten())
+            .map(Value::to_string)
+            .collect()
+    }
+}
+
+impl Metadata {
+    pub fn from_file(file: &Path) -> Result<Metadata, Box<dyn Error>> {
+        let contents = fs::read_to_string(file)
+            .map_err(|err| format!("can't read metadata {}: {}", file.display(), err))?;
+        Metadata::from_str(&contents)
+            .map_err(|err| format!("invalid metadata {}: {}", file.display(), err).into())
+    }
+
+    /// Reads the metadata from JSON keyed first by kind, such as `OBJECT`,
+    /// then by type name, with each element's metadata under
+    /// `documentation`
+    pub fn from_str(text: &str) -> Result<Metadata, Box<dyn Error>> {
+        let kinds: HashMap<String, HashMap<String, Element>> = serde_json::from_str(text)?;
+        let mut documentation = HashMap::new();
+        for (name, typ) in kinds.into_values().flatten() {
+            add_documentation(&mut documentation, name, typ);
+        }
+        Ok(Metadata { documentation })
+    }
+
+    /// Records the examples and groups on the schema, removes the elements
+    /// marked undocumented, and returns the coordinates that didn't match
+    /// anything in the schema, sorted
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - the schema to apply the metadata to
+    /// * `stubs` - whether to leave a stub, with just a name, kind, and
+    ///   description, for each undocumented type that's still referenced,
+    ///   so links to it resolve
+    pub fn apply(&self, schema: &mut Schema, stubs: bool) -> Vec<String> {
+        let mut applied = HashSet::new();
+        let mut undocumented = HashSet::new();
+
+        schema.for_each_element_mut(&mut |coordinate, mut element| {
+            let documentation = match self.documentation.get(coordinate) {
+                Some(documentation) => documentation,
+                None => return,
+            };
+            applied.insert(coordinate.to_string());
+            if documentation.undocumented {
+                undocumented.insert(coordinate.to_string());
+            }
+            let examples = documentation.get_examples();
+            if !examples.is_empty() {
+                if let Some(element_examples) = element.examples_mut() {
+                    *element_examples = Some(examples);
+                }
+            }
+            if let ElementMut::Field(field) = element {
+                field.group = documentation.group.clone();
+            }
+        });
+
+        if !undocumented.is_empty() {
+            let removed = remove(schema, &undocumented);
+            if stubs {
+                add_stubs(schema, removed);
+            }
+        }
+        get_unmatched(self.documentation.keys(), &applied)
+    }
+}
+
+fn add_documentation(
+    documentation: &mut HashMap<String, Documentation>,
+    coordinate: String,
+    element: Element,
+) {
+    for (name, field) in element.fields {
+        add_documentation(documentation, format!("{}.{}", coordinate, name), field);
+    }
+    for (name, arg) in element.arguments {
+        add_documentation(documentation, format!("{}({}:)", coordinate, name), arg);
+    }
+    if element.documentation != Documentation::default() {
+        documentation.insert(coordinate, element.documentation);
+    }
+}
+
+// Removes the elements at the coordinates from the schema and returns the
+// types it removed
+fn remove(schema: &mut Schema, coordinates: &HashSet<String>) -> Vec<Type> {
+    let keep = |coordinate: String| !coordinates.contains(&coordinate);
+    let keep_args = |coordinate: &str, args: &mut Option<Vec<Input>>| {
+        for args in args.iter_mut() {
+            args.retain(|arg| {
+                keep(format!(
+                    "{}({}:)",
+                    coordinate,
+                    arg.name.as_deref().unwrap_or_default()
+                ))
+            });
+        }
+    };
+
+    let mut removed = Vec::new();
+    if let Some(types) = &mut schema.types {
+        let (kept, gone) = std::mem::take(types)
+            .into_iter()
+            .partition(|typ| keep(typ.name.clone().unwrap_or_default()));
+        *types = kept;
+        removed = gone;
+        for typ in types.iter_mut() {
+            let type_name = typ.name.clone().unwrap_or_default();
+            let member = |name: &Option<String>| {
+                format!("{}.{}", type_name, name.as_deref().unwrap_or_default())
+            };
+            for fields in typ.fields.iter_mut() {
+                fields.retain(|field| keep(member(&field.name)));
+                for field in fields.iter_mut() {
+                    keep_args(&member(&field.name), &mut field.args);
+                }
+            }
+            for inputs in typ.inputs.iter_mut() {
+                inputs.retain(|input| keep(member(&input.name)));
+            }
+            for values in typ.enums.iter_mut() {
+                values.retain(|value| keep(member(&value.name)));
+            }
+        }
+    }
+    removed
+}
+
+// Puts back a stub of each removed type that the schema still refers to
+fn add_stubs(schema: &mut Schema, removed: Vec<Type>) {
+    let mut referenced = HashSet::new();
+    for typ in schema.types.iter_mut().flatten() {
+        typ.for_each_type_ref_mut(&mut |type_ref| {
+            referenced.insert(type_ref.get_actual_name());
+        });
+    }
+    for directive in schema.directives.iter().flatten() {
+        for arg in directive.args.iter().flatten() {
+            referenced.extend(arg.input_type.as_ref().map(|t| t.get_actual_name()));
+        }
+    }
+    let stubs = removed
+        .into_iter()
+        .filter(|typ| typ.name.as_ref().is_some_and(|n| referenced.contains(n)))
+        .map(|typ| Type {
+            name: typ.name,
+            kind: typ.kind,
+            description: Some(STUB_DESCRIPTION.to_string()),
+            ..Default::default()
+        });
+    schema.types.get_or_insert_with(Vec::new).extend(stubs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+        type Query {
+            players(first: Int, secret: String): [Player!]!
+            player(id: ID!): Player
+            internal: String
+        }
+        type Mutation { addPlayer(name: String): Player }
+        type Player { name: String position: Position }
+        enum Position { GOALIE WING }
+        input PlayerInput { name: String }
+        scalar Date
+    "#;
+
+    const METADATA: &str = r#"{
+        "OBJECT": {
+            "Query": {
+                "fields": {
+                    "players": {
+                        "documentation": { "example": [{ "name": "Wayne" }], "group": "Players" },
+                        "arguments": {
+                            "first": { "documentation": { "example": 10 } },
+                            "secret": { "documentation": { "undocumented": true } }
+                        }
+                    },
+                    "internal": { "documentation": { "undocumented": true } },
+                    "missing": { "documentation": { "example": 1 } }
+                }
+            },
+            "Mutation": {
+                "fields": { "addPlayer": { "documentation": { "group": "Players" } } }
+            }
+        },
+        "INPUT_OBJECT": {
+            "PlayerInput": {
+                "inputFields": {
+                    "name": { "documentation": { "examples": ["Wayne", "Mario"] } }
+                }
+            }
+        },
+        "ENUM": {
+            "Position": { "enumValues": { "WING": { "documentation": { "undocumented": true } } } }
+        },
+        "SCALAR": {
+            "Date": { "documentation": { "example": "2024-10-01" } }
+        }
+    }"#;
+
+    #[test]
+    fn apply_should_record_examples_and_groups() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        let unmatched = Metadata::from_str(METADATA)?.apply(&mut schema, false);
+        assert_eq!(vec!["Query.missing".to_string()], unmatched);
+
+        let query = schema.get_type("Query").unwrap();
+        let players = &query.fields.as_ref().unwrap()[0];
+        assert_eq!(
+            Some(vec!["[{\"name\":\"Wayne\"}]".to_string()]),
+            players.examples
+        );
+        assert_eq!(Some("Players".to_string()), players.group);
+        assert_eq!(
+            Some(vec!["10".to_string()]),
+            players.args.as_ref().unwrap()[0].examples
+        );
+
+        let input = schema.get_type("PlayerInput").unwrap();
+        assert_eq!(
+            Some(vec!["\"Wayne\"".to_string(), "\"Mario\"".to_string()]),
+            input.inputs.as_ref().unwrap()[0].examples
+        );
+        assert_eq!(
+            Some(vec!["\"2024-10-01\"".to_string()]),
+            schema.get_type("Date").unwrap().examples
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_remove_undocumented_elements() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        Metadata::from_str(METADATA)?.apply(&mut schema, false);
+
+        let query = schema.get_type("Query").unwrap();
+        let fields: Vec<&str> = query
+            .fields
+            .iter()
+            .flatten()
+            .filter_map(|field| field.name.as_deref())
+            .collect();
+        assert_eq!(vec!["players", "player"], fields);
+        assert_eq!(
+            1,
+            query.fields.as_ref().unwrap()[0]
+                .args
+                .as_ref()
+                .unwrap()
+                .len()
+        );
+
+        let values = schema.get_type("Position").unwrap().enums.as_ref().unwrap();
+        assert_eq!(1, values.len());
+        assert_eq!(Some("GOALIE".to_string()), values[0].name);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_stub_undocumented_types_still_referenced() -> Result<(), Box<dyn Error>> {
+        let metadata = Metadata::from_str(
+            r#"{
+                "OBJECT": { "Player": { "documentation": { "undocumented": true } } },
+                "INPUT_OBJECT": { "PlayerInput": { "documentation": { "undocumented": true } } }
+            }"#,
+        )?;
+        let mut schema = Schema::from_sdl(SDL)?;
+        metadata.apply(&mut schema, true);
+        let player = schema.get_type("Player").unwrap();
+        assert_eq!(Some("OBJECT".to_string()), player.kind);
+        assert_eq!(Some(STUB_DESCRIPTION.to_string()), player.description);
+        assert!(player.fields.is_none());
+        assert!(schema.get_type("PlayerInput").is_none());
+
+        let mut schema = Schema::from_sdl(SDL)?;
+        metadata.apply(&mut schema, false);
+        assert!(schema.get_type("Player").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_reject_invalid_metadata() {
+        assert!(Metadata::from_str("{ \"OBJECT\": [] }").is_err());
+    }
+}
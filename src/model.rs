@@ -0,0 +1,526 @@
+use super::icons::Icons;
+use super::links::Links;
+use super::schema::{Directive, Enum, Field, Input, Schema, Type, TypeRef};
+use super::schema_markdown::get_page_for_kind;
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+
+/// The documentation gumwood renders as markdown, with gumwood's work done:
+/// aliases, overlays, descriptions, versions, costs, and removal dates
+/// applied, types sorted, and links resolved to pages and anchors
+#[derive(Debug, Serialize)]
+pub struct Model {
+    pub description: Option<String>,
+    pub queries: Vec<DocField>,
+    pub mutations: Vec<DocField>,
+    pub subscriptions: Vec<DocField>,
+    pub types: Vec<DocType>,
+    pub directives: Vec<DocDirective>,
+}
+
+/// Where a link goes: the page and the anchor on it
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Link {
+    pub page: String,
+    pub anchor: String,
+}
+
+/// A reference to a type, such as a field's type
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocTypeRef {
+    /// The type's name
+    pub name: String,
+    /// The name with its list and non-null wrappers, such as `[Player!]!`
+    pub decorated_name: String,
+    pub kind: String,
+    pub link: Link,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocType {
+    pub name: String,
+    pub kind: String,
+    pub link: Link,
+    pub description: Option<String>,
+    pub since: Option<String>,
+    #[serde(rename = "specifiedByURL")]
+    pub specified_by_url: Option<String>,
+    pub auth_required: bool,
+    pub one_of: bool,
+    pub examples: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<DocField>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<DocInput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<DocValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interfaces: Option<Vec<DocTypeRef>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub possible_types: Option<Vec<DocTypeRef>>,
+}
+
+/// A field, or an operation on the query, mutation, or subscription type
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocField {
+    pub name: String,
+    /// Where the operation is documented; fields are documented with their
+    /// types
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub field_type: Option<DocTypeRef>,
+    pub arguments: Vec<DocInput>,
+    pub auth_required: bool,
+    pub deprecated: bool,
+    pub deprecation_reason: Option<String>,
+    pub removal_date: Option<String>,
+    pub since: Option<String>,
+    pub estimated_cost: Option<u32>,
+    pub examples: Option<Vec<String>>,
+    /// The group the operation is listed under on its page
+    pub group: Option<String>,
+}
+
+/// An argument or input field
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocInput {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub input_type: Option<DocTypeRef>,
+    pub default_value: Option<String>,
+    pub since: Option<String>,
+    pub examples: Option<Vec<String>>,
+}
+
+/// A value of an enum
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocValue {
+    pub name: String,
+    pub description: Option<String>,
+    pub deprecated: bool,
+    pub deprecation_reason: Option<String>,
+    pub removal_date: Option<String>,
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocDirective {
+    pub name: String,
+    pub link: Link,
+    pub description: Option<String>,
+    pub locations: Vec<String>,
+    pub repeatable: bool,
+    pub arguments: Vec<DocInput>,
+}
+
+impl Model {
+    /// Builds the model from the schema, in the order the markdown lists
+    /// things: operations as the schema declares them, and everything else
+    /// by name
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - the schema, with gumwood's transforms applied
+    /// * `icons` - which types and fields require authorization
+    /// * `links` - how anchors are named
+    pub fn from_schema(schema: &Schema, icons: &Icons, links: &Links) -> Model {
+        let operations = |type_name: Option<String>, page: &str| -> Vec<DocField> {
+            let typ = type_name.and_then(|name| schema.get_type(&name));
+            let parent = typ.and_then(|typ| typ.name.as_deref()).unwrap_or_default();
+            typ.and_then(|typ| typ.fields.as_ref())
+                .into_iter()
+                .flatten()
+                .map(|field| {
+                    let mut field = to_field(field, parent, icons, links);
+                    field.link = Some(to_link(page, &field.name, links));
+                    field
+                })
+                .collect()
+        };
+
+        let mut types: Vec<&Type> = schema.types.iter().flatten().collect();
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut directives: Vec<&Directive> = schema.directives.iter().flatten().collect();
+        directives.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Model {
+            description: schema.description.clone(),
+            queries: operations(schema.get_query_name(), "queries"),
+            mutations: operations(schema.get_mutation_name(), "mutations"),
+            subscriptions: operations(schema.get_subscription_name(), "subscriptions"),
+            types: types
+                .into_iter()
+                .map(|typ| to_type(typ, icons, links))
+                .collect(),
+            directives: directives
+                .into_iter()
+                .map(|directive| to_directive(directive, links))
+                .collect(),
+        }
+    }
+
+    /// Returns the model as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    /// Returns the model as block-style YAML, with the same keys as the
+    /// JSON, sorted
+    pub fn to_yaml(&self) -> Result<String, Box<dyn Error>> {
+        let mut yaml = String::new();
+        write_yaml(&serde_json::to_value(self)?, 0, &mut yaml);
+        Ok(yaml)
+    }
+}
+
+// Writes the object's members or the array's items, one per line, at the
+// indent; scalars and empty collections stay on their key's line
+fn write_yaml(value: &Value, indent: usize, yaml: &mut String) {
+    let padding = " ".repeat(indent);
+    match value {
+        Value::Object(members) => {
+            for (key, member) in members {
+                yaml.push_str(&format!("{}{}:", padding, to_yaml_scalar(key)));
+                write_yaml_member(member, indent, yaml);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                if is_yaml_block(item) {
+                    // The item's first line follows the dash
+                    let mut block = String::new();
+                    write_yaml(item, indent + 2, &mut block);
+                    yaml.push_str(&format!("{}- {}", padding, &block[indent + 2..]));
+                } else {
+                    yaml.push_str(&format!("{}-", padding));
+                    write_yaml_member(item, indent, yaml);
+                }
+            }
+        }
+        _ => yaml.push_str(&format!("{}{}\n", padding, to_yaml_inline(value))),
+    }
+}
+
+// Writes what follows a key or dash: an inline value or a nested block
+fn write_yaml_member(value: &Value, indent: usize, yaml: &mut String) {
+    if is_yaml_block(value) {
+        yaml.push('\n');
+        write_yaml(value, indent + 2, yaml);
+    } else {
+        yaml.push_str(&format!(" {}\n", to_yaml_inline(value)));
+    }
+}
+
+fn is_yaml_block(value: &Value) -> bool {
+    match value {
+        Value::Object(members) => !members.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn to_yaml_inline(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::String(s) => to_yaml_scalar(s),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+// Leaves the string plain if YAML would read it back as the same string,
+// and otherwise double-quotes it, with JSON's escapes, which YAML shares
+fn to_yaml_scalar(s: &str) -> String {
+    let plain = s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && !s.ends_with(' ')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || " _-./()".contains(c))
+        && !matches!(
+            s.to_lowercase().as_str(),
+            "true" | "false" | "null" | "yes" | "no" | "on" | "off" | "y" | "n"
+        );
+    if plain {
+        s.to_string()
+    } else {
+        Value::String(s.to_string()).to_string()
+    }
+}
+
+fn to_link(page: &str, name: &str, links: &Links) -> Link {
+    Link {
+        page: page.to_string(),
+        anchor: links.fragment(name),
+    }
+}
+
+fn to_type_ref(type_ref: &TypeRef, links: &Links) -> DocTypeRef {
+    let name = type_ref.get_actual_name();
+    let kind = type_ref.get_actual_kind();
+    DocTypeRef {
+        decorated_name: type_ref.get_decorated_name(),
+        link: to_link(get_page_for_kind(&kind), &name, links),
+        name,
+        kind,
+    }
+}
+
+fn to_type_refs(type_refs: &Option<Vec<TypeRef>>, links: &Links) -> Option<Vec<DocTypeRef>> {
+    type_refs.as_ref().map(|type_refs| {
+        let mut type_refs: Vec<DocTypeRef> = type_refs
+            .iter()
+            .map(|type_ref| to_type_ref(type_ref, links))
+            .collect();
+        type_refs.sort_by(|a, b| a.name.cmp(&b.name));
+        type_refs
+    })
+}
+
+fn to_type(typ: &Type, icons: &Icons, links: &Links) -> DocType {
+    let name = typ.name.clone().unwrap_or_default();
+    let kind = typ.kind.clone().unwrap_or_default();
+    let fields = typ.fields.as_ref().map(|fields| {
+        let mut fields: Vec<DocField> = fields
+            .iter()
+            .map(|field| to_field(field, &name, icons, links))
+            .collect();
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        fields
+    });
+    let inputs = typ.inputs.as_ref().map(|inputs| to_inputs(inputs, links));
+    let values = typ.enums.as_ref().map(|values| {
+        let mut values: Vec<DocValue> = values.iter().map(to_value).collect();
+        values.sort_by(|a, b| a.name.cmp(&b.name));
+        values
+    });
+    DocType {
+        link: to_link(get_page_for_kind(&kind), &name, links),
+        description: typ.description.clone(),
+        since: typ.since.clone(),
+        specified_by_url: typ.specified_by_url.clone(),
+        auth_required: icons.is_auth_required(&name),
+        one_of: typ.is_one_of == Some(true),
+        examples: typ.examples.clone(),
+        fields,
+        inputs,
+        values,
+        interfaces: to_type_refs(&typ.interfaces, links),
+        possible_types: to_type_refs(&typ.possible_types, links),
+        name,
+        kind,
+    }
+}
+
+fn to_field(field: &Field, parent: &str, icons: &Icons, links: &Links) -> DocField {
+    let name = field.name.clone().unwrap_or_default();
+    DocField {
+        link: None,
+        description: field.description.clone(),
+        field_type: field
+            .field_type
+            .as_ref()
+            .map(|type_ref| to_type_ref(type_ref, links)),
+        arguments: to_inputs(field.args.as_deref().unwrap_or_default(), links),
+        auth_required: icons.is_auth_required(parent)
+            || icons.is_auth_required(&format!("{}.{}", parent, name)),
+        deprecated: field.is_deprecated == Some(true),
+        deprecation_reason: field.deprecation_reason.clone(),
+        removal_date: field.removal_date.clone(),
+        since: field.since.clone(),
+        estimated_cost: field.cost,
+        examples: field.examples.clone(),
+        group: field.group.clone(),
+        name,
+    }
+}
+
+fn to_inputs(inputs: &[Input], links: &Links) -> Vec<DocInput> {
+    let mut inputs: Vec<DocInput> = inputs
+        .iter()
+        .map(|input| DocInput {
+            name: input.name.clone().unwrap_or_default(),
+            description: input.description.clone(),
+            input_type: input
+                .input_type
+                .as_ref()
+                .map(|type_ref| to_type_ref(type_ref, links)),
+            default_value: input.default_value.clone(),
+            since: input.since.clone(),
+            examples: input.examples.clone(),
+        })
+        .collect();
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    inputs
+}
+
+fn to_value(value: &Enum) -> DocValue {
+    DocValue {
+        name: value.name.clone().unwrap_or_default(),
+        description: value.description.clone(),
+        deprecated: value.is_deprecated == Some(true),
+        deprecation_reason: value.deprecation_reason.clone(),
+        removal_date: value.removal_date.clone(),
+        since: value.since.clone(),
+    }
+}
+
+fn to_directive(directive: &Directive, links: &Links) -> DocDirective {
+    let name = directive.name.clone().unwrap_or_default();
+    DocDirective {
+        link: to_link("directives", &name, links),
+        description: directive.description.clone(),
+        locations: directive.locations.clone().unwrap_or_default(),
+        repeatable: directive.is_repeatable == Some(true),
+        arguments: to_inputs(directive.args.as_deref().unwrap_or_default(), links),
+        name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icons::IconSet;
+    use std::collections::HashMap;
+
+    fn model() -> Result<Model, Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { viewer: Player players(first: Int = 10): [Player!]! }
+            type Player { team: Team name: String @deprecated(reason: "Use fullName") }
+            type Team { name: String }
+            enum Position { WING CENTER }
+            "#,
+        )?;
+        let icons = Icons::new(
+            IconSet::None,
+            &HashMap::new(),
+            &["Query.viewer".to_string(), "Team".to_string()],
+        )?;
+        let links = Links {
+            anchor_prefix: "gql-".to_string(),
+            ..Default::default()
+        };
+        Ok(Model::from_schema(&schema, &icons, &links))
+    }
+
+    #[test]
+    fn from_schema_should_resolve_operations() -> Result<(), Box<dyn Error>> {
+        let model = model()?;
+        let names: Vec<&str> = model.queries.iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(vec!["viewer", "players"], names);
+        assert!(model.queries[0].auth_required);
+        assert!(!model.queries[1].auth_required);
+
+        let players = &model.queries[1];
+        assert_eq!(
+            Some(Link {
+                page: "queries".to_string(),
+                anchor: "gql-players".to_string()
+            }),
+            players.link
+        );
+        let player = players.field_type.as_ref().unwrap();
+        assert_eq!("Player", player.name);
+        assert_eq!("[Player!]!", player.decorated_name);
+        assert_eq!("objects", player.link.page);
+        assert_eq!(Some("10".to_string()), players.arguments[0].default_value);
+        Ok(())
+    }
+
+    #[test]
+    fn from_schema_should_sort_types_and_members() -> Result<(), Box<dyn Error>> {
+        let model = model()?;
+        let names: Vec<&str> = model
+            .types
+            .iter()
+            .map(|typ| typ.name.as_str())
+            .filter(|name| ["Team", "Query", "Position", "Player"].contains(name))
+            .collect();
+        assert_eq!(vec!["Player", "Position", "Query", "Team"], names);
+
+        let player = model.types.iter().find(|typ| typ.name == "Player").unwrap();
+        let fields = player.fields.as_ref().unwrap();
+        assert_eq!("name", fields[0].name);
+        assert!(fields[0].deprecated);
+        assert_eq!(
+            Some("Use fullName".to_string()),
+            fields[0].deprecation_reason
+        );
+        assert!(fields[0].link.is_none());
+        assert!(!fields[1].auth_required && player.inputs.is_none());
+
+        let team = model.types.iter().find(|typ| typ.name == "Team").unwrap();
+        assert!(team.auth_required && team.fields.as_ref().unwrap()[0].auth_required);
+
+        let position = model
+            .types
+            .iter()
+            .find(|typ| typ.name == "Position")
+            .unwrap();
+        let values: Vec<&str> = position
+            .values
+            .iter()
+            .flatten()
+            .map(|value| value.name.as_str())
+            .collect();
+        assert_eq!(vec!["CENTER", "WING"], values);
+        Ok(())
+    }
+
+    #[test]
+    fn to_json_should_use_camel_case() -> Result<(), Box<dyn Error>> {
+        let json = model()?.to_json()?;
+        assert!(json.contains("\"decoratedName\": \"[Player!]!\""));
+        assert!(json.contains("\"specifiedByURL\": null"));
+        assert!(!json.contains("\"inputs\""));
+        assert!(json.ends_with("}\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn to_yaml_should_match_json() -> Result<(), Box<dyn Error>> {
+        let model = model()?;
+        let yaml = model.to_yaml()?;
+        assert!(yaml.contains("\ndirectives: []\n"));
+        assert!(yaml.contains("  decoratedName: \"[Player!]!\"\n"));
+        assert!(yaml.contains("  specifiedByURL: null\n"));
+        assert!(yaml.contains("\nqueries:\n  - arguments: []\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn to_yaml_should_write_nested_blocks() {
+        let mut yaml = String::new();
+        let value = serde_json::json!({
+            "name": "Player",
+            "fields": [{ "name": "number", "examples": ["9", "99"] }, "true"],
+            "empty": {},
+            "description": "Who plays: a person\non a team",
+        });
+        write_yaml(&value, 0, &mut yaml);
+        assert_eq!(
+            "description: \"Who plays: a person\\non a team\"\n\
+             empty: {}\n\
+             fields:\n\
+             \x20 - examples:\n\
+             \x20     - \"9\"\n\
+             \x20     - \"99\"\n\
+             \x20   name: number\n\
+             \x20 - \"true\"\n\
+             name: Player\n",
+            yaml
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::{env, error::Error, fs, io, path::PathBuf};
+
+/// A login and password for a machine, from a .netrc file
+#[derive(Debug, PartialEq)]
+pub struct Credentials {
+    pub login: String,
+    pub password: String,
+}
+
+/// Looks up the credentials for the host in the .netrc file curl would
+/// read: the file named by $NETRC, or .netrc in the home directory. Returns
+/// None if there's no such file or it has no entry for the host
+///
+/// # Arguments
+///
+/// * `host` - the host to find credentials for
+pub fn find_credentials(host: &str) -> Result<Option<Credentials>, Box<dyn Error>> {
+    let file = match get_netrc_file() {
+        Some(file) => file,
+        None => return Ok(None),
+    };
+    match fs::read_to_string(&file) {
+        Ok(contents) => Ok(parse(&contents, host)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("can't read {}: {}", file.display(), err).into()),
+    }
+}
+
+fn get_netrc_file() -> Option<PathBuf> {
+    if let Some(file) = env::var_os("NETRC") {
+        return Some(PathBuf::from(file));
+    }
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".netrc"))
+}
+
+/// Returns the credentials for the host from the contents of a .netrc
+/// file, falling back to its `default` entry
+pub fn parse(contents: &str, host: &str) -> Option<Credentials> {
+    let mut default = None;
+    let mut tokens = Tokens::new(contents);
+    let mut token = tokens.next();
+    while let Some(keyword) = token {
+        let matches = match keyword {
+            "machine" => tokens.next() == Some(host),
+            "default" => false,
+            "macdef" => {
+                tokens.skip_macro();
+                token = tokens.next();
+                continue;
+            }
+            _ => {
+                token = tokens.next();
+                continue;
+            }
+        };
+        let is_default = keyword == "default";
+
+        let mut login = None;
+        let mut password = None;
+        token = tokens.next();
+        while let Some(key) = token {
+            match key {
+                "machine" | "default" | "macdef" => break,
+                "login" => login = tokens.next(),
+                "password" => password = tokens.next(),
+                "account" => {
+                    tokens.next();
+                }
+                _ => {}
+            }
+            token = tokens.next();
+        }
+
+        let credentials = match (login, password) {
+            (Some(login), Some(password)) => Some(Credentials {
+                login: login.to_string(),
+                password: password.to_string(),
+            }),
+            _ => None,
+        };
+        if matches {
+            return credentials;
+        }
+        if is_default {
+            default = credentials;
+        }
+    }
+    default
+}
+
+// Splits a .netrc file into whitespace-separated tokens, skipping comments
+struct Tokens<'a> {
+    lines: std::str::Lines<'a>,
+    words: std::str::SplitWhitespace<'a>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(contents: &'a str) -> Tokens<'a> {
+        Tokens {
+            lines: contents.lines(),
+            words: "".split_whitespace(),
+        }
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            if let Some(word) = self.words.next() {
+                return Some(word);
+            }
+            let line = self.lines.next()?;
+            if !line.trim_start().starts_with('#') {
+                self.words = line.split_whitespace();
+            }
+        }
+    }
+
+    // A macro definition runs to the next blank line
+    fn skip_macro(&mut self) {
+        self.words = "".split_whitespace();
+        for line in self.lines.by_ref() {
+            if line.trim().is_empty() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETRC: &str = r#"
+# Staging
+machine staging.example.com
+  login ci
+  password s3cret
+
+macdef init
+cd /pub
+machine evil.example.com login nope password nope
+
+machine api.example.com login docs password hunter2 account team
+default login anonymous password guest
+"#;
+
+    fn credentials(login: &str, password: &str) -> Option<Credentials> {
+        Some(Credentials {
+            login: login.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    #[test]
+    fn parse_should_find_machine() {
+        assert_eq!(
+            credentials("ci", "s3cret"),
+            parse(NETRC, "staging.example.com")
+        );
+        assert_eq!(
+            credentials("docs", "hunter2"),
+            parse(NETRC, "api.example.com")
+        );
+    }
+
+    #[test]
+    fn parse_should_skip_macros() {
+        assert_eq!(
+            credentials("anonymous", "guest"),
+            parse(NETRC, "evil.example.com")
+        );
+    }
+
+    #[test]
+    fn parse_should_fall_back_to_default() {
+        assert_eq!(
+            credentials("anonymous", "guest"),
+            parse(NETRC, "other.example.com")
+        );
+    }
+
+    #[test]
+    fn parse_should_return_none_without_match() {
+        assert_eq!(
+            None,
+            parse("machine a.example.com login a password b", "b.example.com")
+        );
+    }
+}
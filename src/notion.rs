@@ -0,0 +1,356 @@
+use super::markdown::{self, Block, Inline};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// Notion's limits: characters in a piece of rich text, pieces of rich text
+// in a block, and items in an array, such as the rows of a table
+const MAX_TEXT_LENGTH: usize = 2000;
+const MAX_RICH_TEXTS: usize = 100;
+const MAX_TABLE_ROWS: usize = 100;
+
+// The code languages Notion highlights that gumwood's pages use
+const CODE_LANGUAGES: &[&str] = &["graphql", "json", "javascript", "typescript", "shell"];
+
+/// Converts a page of gumwood's markdown to Notion blocks: descriptions
+/// become quotes, tables become tables, and links between pages become links
+/// to the Notion pages. Notion can't link to a heading by name, so links to
+/// anchors go to the top of the page.
+///
+/// # Arguments
+///
+/// * `markdown` - the page's markdown
+/// * `page_urls` - the URLs of the Notion pages, keyed by what links to them
+///   in the markdown
+pub fn to_blocks(markdown: &str, page_urls: &HashMap<String, String>) -> Vec<Value> {
+    Converter { page_urls }.to_blocks(&markdown::parse_blocks(markdown))
+}
+
+/// Returns how many blocks a block counts as toward Notion's limit on the
+/// blocks in a request, which includes nested blocks
+pub fn count_blocks(block: &Value) -> usize {
+    let kind = block["type"].as_str().unwrap_or_default();
+    1 + block[kind]["children"]
+        .as_array()
+        .map_or(0, |children| children.iter().map(count_blocks).sum())
+}
+
+struct Converter<'a> {
+    page_urls: &'a HashMap<String, String>,
+}
+
+// How to style a piece of rich text
+#[derive(Clone, Copy, Default)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    code: bool,
+}
+
+impl Converter<'_> {
+    fn to_blocks(&self, blocks: &[Block]) -> Vec<Value> {
+        let mut notion = Vec::new();
+        for block in blocks {
+            match block {
+                Block::Heading(level, text) => {
+                    // Notion only has three levels of headings
+                    let kind = format!("heading_{}", (*level).clamp(1, 3));
+                    let rich_text = self.to_rich_text(text, Style::default());
+                    notion.push(json!({
+                        "type": &kind,
+                        &kind: { "rich_text": truncate(rich_text) },
+                    }));
+                }
+                Block::Paragraph(text) => notion.extend(to_text_blocks(
+                    "paragraph",
+                    self.to_rich_text(text, Style::default()),
+                )),
+                Block::Notice(text) => notion.extend(to_text_blocks(
+                    "paragraph",
+                    self.to_rich_text(
+                        text,
+                        Style {
+                            italic: true,
+                            ..Default::default()
+                        },
+                    ),
+                )),
+                Block::List(items) => {
+                    for item in items {
+                        notion.extend(to_text_blocks(
+                            "bulleted_list_item",
+                            self.to_rich_text(item, Style::default()),
+                        ));
+                    }
+                }
+                Block::Table(rows) => notion.extend(self.to_tables(rows)),
+                Block::Quote(blocks) => notion.push(self.to_quote(blocks)),
+                Block::Code { language, code } => notion.push(to_code(language, code)),
+            }
+        }
+        notion
+    }
+
+    // Splits tables longer than Notion allows, repeating the headers
+    fn to_tables(&self, rows: &[Vec<String>]) -> Vec<Value> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let to_row = |cells: &Vec<String>| {
+            let mut cells: Vec<Value> = cells
+                .iter()
+                .map(|cell| Value::from(truncate(self.to_rich_text(cell, Style::default()))))
+                .collect();
+            cells.resize(width, json!([]));
+            json!({ "type": "table_row", "table_row": { "cells": cells } })
+        };
+        let (headers, body) = match rows.split_first() {
+            Some(split) => split,
+            None => return Vec::new(),
+        };
+        let chunks: Vec<&[Vec<String>]> = if body.is_empty() {
+            vec![body]
+        } else {
+            body.chunks(MAX_TABLE_ROWS - 1).collect()
+        };
+        chunks
+            .into_iter()
+            .map(|chunk| {
+                let children: Vec<Value> =
+                    std::iter::once(headers).chain(chunk).map(to_row).collect();
+                json!({
+                    "type": "table",
+                    "table": {
+                        "table_width": width,
+                        "has_column_header": true,
+                        "has_row_header": false,
+                        "children": children,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    // The quote's first paragraph is its text, and the rest nests under it
+    fn to_quote(&self, blocks: &[Block]) -> Value {
+        let (rich_text, rest) = match blocks.split_first() {
+            Some((Block::Paragraph(text), rest)) => {
+                (self.to_rich_text(text, Style::default()), rest)
+            }
+            _ => (Vec::new(), blocks),
+        };
+        let mut quote = json!({ "rich_text": truncate(rich_text) });
+        let children = self.to_blocks(rest);
+        if !children.is_empty() {
+            quote["children"] = children.into();
+        }
+        json!({ "type": "quote", "quote": quote })
+    }
+
+    fn to_rich_text(&self, text: &str, style: Style) -> Vec<Value> {
+        let mut rich_text = Vec::new();
+        self.push_inlines(&mut rich_text, &markdown::parse_inline(text), style, None);
+        rich_text
+    }
+
+    fn push_inlines(
+        &self,
+        rich_text: &mut Vec<Value>,
+        inlines: &[Inline],
+        style: Style,
+        url: Option<&str>,
+    ) {
+        for inline in inlines {
+            match inline {
+                Inline::Text(text) => push_text(rich_text, &markdown::unescape(text), style, url),
+                Inline::Code(code) => push_text(
+                    rich_text,
+                    code,
+                    Style {
+                        code: true,
+                        ..style
+                    },
+                    url,
+                ),
+                Inline::Strong(inlines) => self.push_inlines(
+                    rich_text,
+                    inlines,
+                    Style {
+                        bold: true,
+                        ..style
+                    },
+                    url,
+                ),
+                Inline::Link { text, destination } => {
+                    let url = self.get_url(destination).or(url);
+                    self.push_inlines(rich_text, text, style, url);
+                }
+                Inline::Anchor(_) => {}
+            }
+        }
+    }
+
+    // Returns where a link goes in Notion, or None if it can't go anywhere,
+    // such as a link to a heading on the same page
+    fn get_url<'a>(&'a self, destination: &'a str) -> Option<&'a str> {
+        if destination.starts_with("https://") || destination.starts_with("http://") {
+            return Some(destination);
+        }
+        let page = destination.split('#').next().unwrap_or_default();
+        self.page_urls.get(page).map(String::as_str)
+    }
+}
+
+fn push_text(rich_text: &mut Vec<Value>, text: &str, style: Style, url: Option<&str>) {
+    let chars: Vec<char> = text.chars().collect();
+    for chunk in chars.chunks(MAX_TEXT_LENGTH) {
+        let mut text = json!({ "content": chunk.iter().collect::<String>() });
+        if let Some(url) = url {
+            text["link"] = json!({ "url": url });
+        }
+        rich_text.push(json!({
+            "type": "text",
+            "text": text,
+            "annotations": {
+                "bold": style.bold,
+                "italic": style.italic,
+                "code": style.code,
+            },
+        }));
+    }
+}
+
+// Splits text too long for one block across blocks of the same kind
+fn to_text_blocks(kind: &str, rich_text: Vec<Value>) -> Vec<Value> {
+    rich_text
+        .chunks(MAX_RICH_TEXTS)
+        .map(|chunk| json!({ "type": kind, kind: { "rich_text": chunk } }))
+        .collect()
+}
+
+// Headings and table cells can't be split, so they lose what won't fit
+fn truncate(mut rich_text: Vec<Value>) -> Vec<Value> {
+    rich_text.truncate(MAX_RICH_TEXTS);
+    rich_text
+}
+
+fn to_code(language: &str, code: &str) -> Value {
+    let language = if CODE_LANGUAGES.contains(&language) {
+        language
+    } else {
+        "plain text"
+    };
+    let mut rich_text = Vec::new();
+    push_text(&mut rich_text, code, Style::default(), None);
+    json!({
+        "type": "code",
+        "code": { "language": language, "rich_text": truncate(rich_text) },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page_urls() -> HashMap<String, String> {
+        let mut page_urls = HashMap::new();
+        page_urls.insert(
+            "objects.md".to_string(),
+            "https://www.notion.so/Objects-1".to_string(),
+        );
+        page_urls
+    }
+
+    fn content(block: &Value) -> Vec<&str> {
+        let kind = block["type"].as_str().unwrap();
+        block[kind]["rich_text"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|text| text["text"]["content"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn to_blocks_should_convert_headings_and_tables() {
+        let blocks = to_blocks(
+            concat!(
+                "# Objects\n\n",
+                "#### <a name=\"player\"></a>Player\n\n",
+                "| Name | Type | Description |\n",
+                "| --- | --- | --- |\n",
+                "| `team` | [`Team`](objects.md#team) |\n",
+            ),
+            &page_urls(),
+        );
+        assert_eq!(3, blocks.len());
+        assert_eq!("heading_1", blocks[0]["type"]);
+        assert_eq!("heading_3", blocks[1]["type"]);
+        assert_eq!(vec!["Player"], content(&blocks[1]));
+
+        let table = &blocks[2]["table"];
+        assert_eq!(3, table["table_width"]);
+        assert_eq!(true, table["has_column_header"]);
+        let cells = &table["children"][1]["table_row"]["cells"];
+        assert_eq!(3, cells.as_array().unwrap().len());
+        assert_eq!(true, cells[0][0]["annotations"]["code"]);
+        assert_eq!(
+            "https://www.notion.so/Objects-1",
+            cells[1][0]["text"]["link"]["url"]
+        );
+        assert_eq!(3, count_blocks(&blocks[2]));
+    }
+
+    #[test]
+    fn to_blocks_should_split_long_tables() {
+        let mut markdown = "| Name |\n| --- |\n".to_string();
+        for i in 0..150 {
+            markdown.push_str(&format!("| {} |\n", i));
+        }
+        let blocks = to_blocks(&markdown, &HashMap::new());
+        assert_eq!(2, blocks.len());
+        assert_eq!(101, count_blocks(&blocks[0]));
+        assert_eq!(
+            "Name",
+            blocks[1]["table"]["children"][0]["table_row"]["cells"][0][0]["text"]["content"]
+        );
+        assert_eq!(53, count_blocks(&blocks[1]));
+    }
+
+    #[test]
+    fn to_blocks_should_nest_description_under_quote() {
+        let blocks = to_blocks(
+            "> A hockey player&#39;s [team](#team)\n```graphql\n{ player }\n```\n\n_Deprecated_\n",
+            &page_urls(),
+        );
+        assert_eq!(2, blocks.len());
+        assert_eq!(vec!["A hockey player's ", "team"], content(&blocks[0]));
+        assert!(blocks[0]["quote"]["rich_text"][1]["text"]
+            .get("link")
+            .is_none());
+        let code = &blocks[0]["quote"]["children"][0]["code"];
+        assert_eq!("graphql", code["language"]);
+        assert_eq!("{ player }", code["rich_text"][0]["text"]["content"]);
+        assert_eq!(
+            true,
+            blocks[1]["paragraph"]["rich_text"][0]["annotations"]["italic"]
+        );
+        assert_eq!(2, count_blocks(&blocks[0]));
+    }
+
+    #[test]
+    fn to_blocks_should_split_long_text() {
+        let blocks = to_blocks(
+            &format!("**Type:** {}\n", "a".repeat(4500)),
+            &HashMap::new(),
+        );
+        assert_eq!(
+            vec![5, 2000, 2000, 501],
+            content(&blocks[0])
+                .iter()
+                .map(|text| text.chars().count())
+                .collect::<Vec<usize>>()
+        );
+        assert_eq!(
+            true,
+            blocks[0]["paragraph"]["rich_text"][0]["annotations"]["bold"]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use super::filename::to_safe_file_name;
+use super::format::get_nav_pages;
+use super::links::Links;
+use super::markdown::{split_code, split_heading_id, to_list};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::{collections::HashMap, path::PathBuf};
+
+lazy_static! {
+    static ref ANCHOR: Regex = Regex::new(r#"<a name="([^"]*)"></a>"#).unwrap();
+    static ref LINK: Regex = Regex::new(r"\[((?:`[^`]*`|[^\]`])*)\]\(([^)\s]*)\)").unwrap();
+}
+
+// Pages that are split into a note per type
+const TYPE_PAGES: &[&str] = &[
+    "objects",
+    "inputs",
+    "interfaces",
+    "enums",
+    "unions",
+    "scalars",
+];
+
+// A page, split at its second-level headings
+struct Page {
+    intro: String,
+    sections: Vec<Section>,
+}
+
+// A second-level heading and what follows it
+struct Section {
+    // The heading without its anchor
+    heading: String,
+    // The anchors on the heading and in the body
+    anchors: Vec<String>,
+    body: String,
+}
+
+/// Turns the pages into an Obsidian vault: a note per type, in a folder
+/// named for its page, and a note per page that lists its types or holds
+/// its operations. Links become wikilinks, so Obsidian's graph shows how
+/// the types reference each other.
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name
+/// * `links` - how pages and anchors are named
+pub fn to_vault(contents: &HashMap<String, String>, links: &Links) -> Vec<(PathBuf, String)> {
+    let pages: Vec<(&str, Page)> = get_nav_pages(contents)
+        .into_iter()
+        .map(|page| (page, parse_page(&contents[page], links)))
+        .collect();
+
+    // What each page and anchor's wikilinks point at
+    let mut targets = HashMap::new();
+    for (page, parsed) in &pages {
+        let title = links.page_title(page);
+        let page_link = links.page_link(page);
+        targets.insert((page_link.clone(), None), title.clone());
+        for section in &parsed.sections {
+            let target = if TYPE_PAGES.contains(page) {
+                get_type_name(&section.heading).to_string()
+            } else {
+                format!("{}#{}", title, section.heading)
+            };
+            for anchor in &section.anchors {
+                targets.insert((page_link.clone(), Some(anchor.clone())), target.clone());
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for (page, parsed) in &pages {
+        let title = links.page_title(page);
+        let note = PathBuf::from(format!("{}.md", to_safe_file_name(&title)));
+        if !TYPE_PAGES.contains(page) {
+            files.push((note, to_wikilinks(&contents[*page], &targets)));
+            continue;
+        }
+
+        let names: Vec<&str> = parsed
+            .sections
+            .iter()
+            .map(|section| get_type_name(&section.heading))
+            .collect();
+        let list: Vec<String> = names.iter().map(|name| format!("[[{}]]", name)).collect();
+        files.push((
+            note,
+            format!(
+                "{}{}",
+                to_wikilinks(&parsed.intro, &targets),
+                to_list(&list)
+            ),
+        ));
+        for (name, section) in names.iter().zip(&parsed.sections) {
+            files.push((
+                PathBuf::from(to_safe_file_name(&title))
+                    .join(format!("{}.md", to_safe_file_name(name))),
+                format!(
+                    "# {}\n{}",
+                    section.heading,
+                    to_wikilinks(&raise_headings(&section.body), &targets)
+                ),
+            ));
+        }
+    }
+    files
+}
+
+// Splits the page at its second-level headings, collecting the anchors in
+// each section; bare headings get the anchor markdown renderers give them
+fn parse_page(markdown: &str, links: &Links) -> Page {
+    let mut page = Page {
+        intro: String::new(),
+        sections: Vec::new(),
+    };
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let heading = match line.strip_prefix("## ") {
+            Some(text) if !in_fence => text.trim_end(),
+            _ => {
+                match page.sections.last_mut() {
+                    Some(section) => {
+                        if !in_fence {
+                            section.anchors.extend(
+                                ANCHOR
+                                    .captures_iter(line)
+                                    .map(|captures| captures[1].to_string()),
+                            );
+                        }
+                        section.body.push_str(line)
+                    }
+                    None => page.intro.push_str(line),
+                }
+                continue;
+            }
+        };
+
+        let (heading, anchors) = match split_heading_id(heading) {
+            Some((text, id)) => (text.to_string(), vec![id.to_string()]),
+            None if ANCHOR.is_match(heading) => (
+                ANCHOR.replace_all(heading, "").trim().to_string(),
+                ANCHOR
+                    .captures_iter(heading)
+                    .map(|captures| captures[1].to_string())
+                    .collect(),
+            ),
+            None => (
+                heading.to_string(),
+                vec![links.fragment(get_type_name(heading))],
+            ),
+        };
+        page.sections.push(Section {
+            heading,
+            anchors,
+            body: String::new(),
+        });
+    }
+    page
+}
+
+// Returns the name in a heading, after any icons
+fn get_type_name(heading: &str) -> &str {
+    heading.rsplit(' ').next().unwrap_or_default()
+}
+
+// Raises each heading a level, since the type's name is now the title
+fn raise_headings(markdown: &str) -> String {
+    let mut s = String::new();
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        match line.strip_prefix("##") {
+            Some(rest) if !in_fence && rest.starts_with(['#', ' ']) => {
+                s.push_str(&line[1..]);
+            }
+            _ => s.push_str(line),
+        }
+    }
+    s
+}
+
+// Replaces links to pages and anchors with wikilinks and drops the
+// anchors, which Obsidian doesn't need, leaving code alone
+fn to_wikilinks(markdown: &str, targets: &HashMap<(String, Option<String>), String>) -> String {
+    let mut s = String::new();
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        let fence = line.trim_start().starts_with("```");
+        if fence {
+            in_fence = !in_fence;
+        }
+        if fence || in_fence {
+            s.push_str(line);
+            continue;
+        }
+
+        // Obsidian links to headings by their text
+        let line = match split_heading_id(line) {
+            Some((text, _)) if line.starts_with('#') => {
+                format!("{}{}", text, &line[line.trim_end().len()..])
+            }
+            _ => line.to_string(),
+        };
+
+        // Links have code in their text, so find them in the whole line,
+        // skipping any that start in code
+        let mut code = Vec::new();
+        let mut offset = 0;
+        for (piece, is_code) in split_code(&line) {
+            if is_code {
+                code.push(offset..offset + piece.len());
+            }
+            offset += piece.len();
+        }
+        let line = LINK.replace_all(&line, |captures: &Captures| {
+            let start = captures.get(0).map_or(0, |m| m.start());
+            if code.iter().any(|range| range.contains(&start)) {
+                return captures[0].to_string();
+            }
+            let destination = &captures[2];
+            let key = match destination.split_once('#') {
+                Some((page, fragment)) => (page.to_string(), Some(fragment.to_string())),
+                None => (destination.to_string(), None),
+            };
+            match targets.get(&key) {
+                Some(target) => {
+                    // A pipe would end the cell in a table
+                    let separator = if line.starts_with('|') { "\\|" } else { "|" };
+                    to_wikilink(&captures[1], target, separator)
+                }
+                None => captures[0].to_string(),
+            }
+        });
+        s.push_str(&ANCHOR.replace_all(&line, ""));
+    }
+    s
+}
+
+// Returns a wikilink to the target showing the text; a type's decorations,
+// such as `[Player!]!`, stay code around the link
+fn to_wikilink(text: &str, target: &str, separator: &str) -> String {
+    let name = target.rsplit('#').next().unwrap_or(target);
+    let plain = text.trim_matches('`');
+    if plain == target {
+        return format!("[[{}]]", target);
+    }
+    match plain.find(name) {
+        Some(start) if text.starts_with('`') && !target.contains('#') => {
+            let code = |s: &str| {
+                if s.is_empty() {
+                    String::new()
+                } else {
+                    format!("`{}`", s)
+                }
+            };
+            format!(
+                "{}[[{}]]{}",
+                code(&plain[..start]),
+                target,
+                code(&plain[start + name.len()..])
+            )
+        }
+        _ => format!("[[{}{}{}]]", target, separator, plain),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents() -> HashMap<String, String> {
+        let mut contents = HashMap::new();
+        contents.insert(
+            "objects".to_string(),
+            "# Objects\n\n## <a name=\"player\"></a>Player\n\n> A player, not `[x](queries.md#player)`\n\n### Fields\n\n| Name | Type |\n| --- | --- |\n| `team` | [`Team!`](objects.md#team) |\n\n## <a name=\"team\"></a>Team\n\n### Fields\n\n| Name | Type |\n| --- | --- |\n| `players` | [`[Player!]!`](objects.md#player) |\n".to_string(),
+        );
+        contents.insert(
+            "queries".to_string(),
+            "# Queries\n\n## player\n\n**Type:** [`Player`](objects.md#player)\n\n```\n[`Player`](objects.md#player)\n```\n\nSee [the teams](queries.md#teams) and [Objects](objects.md).\n".to_string(),
+        );
+        contents
+    }
+
+    #[test]
+    fn to_vault_should_write_a_note_per_type_with_wikilinks() {
+        let files: HashMap<PathBuf, String> = to_vault(&contents(), &Links::default())
+            .into_iter()
+            .collect();
+        assert_eq!(4, files.len());
+        assert_eq!(
+            "# Objects\n\n* [[Player]]\n* [[Team]]\n\n",
+            files[&PathBuf::from("Objects.md")]
+        );
+        assert_eq!(
+            "# Player\n\n> A player, not `[x](queries.md#player)`\n\n## Fields\n\n| Name | Type |\n| --- | --- |\n| `team` | [[Team]]`!` |\n\n",
+            files[&PathBuf::from("Objects").join("Player.md")]
+        );
+        assert_eq!(
+            "# Team\n\n## Fields\n\n| Name | Type |\n| --- | --- |\n| `players` | `[`[[Player]]`!]!` |\n",
+            files[&PathBuf::from("Objects").join("Team.md")]
+        );
+        assert_eq!(
+            "# Queries\n\n## player\n\n**Type:** [[Player]]\n\n```\n[`Player`](objects.md#player)\n```\n\nSee [the teams](queries.md#teams) and [[Objects]].\n",
+            files[&PathBuf::from("Queries.md")]
+        );
+    }
+
+    #[test]
+    fn to_wikilink_should_alias_other_text() {
+        assert_eq!(
+            "[[Queries#player|player]]",
+            to_wikilink("player", "Queries#player", "|")
+        );
+        assert_eq!("[[Player]]`!`", to_wikilink("`Player!`", "Player", "|"));
+        assert_eq!(
+            "[[Player\\|the player]]",
+            to_wikilink("the player", "Player", "\\|")
+        );
+    }
+}
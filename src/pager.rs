@@ -0,0 +1,124 @@
+use std::{
+    env,
+    error::Error,
+    io::{self, ErrorKind, Write},
+    process::{Child, Command, Stdio},
+};
+
+// What to page through when $PAGER isn't set
+const DEFAULT_PAGER: &str = "less";
+
+// Like git: quit if the output fits on one screen, pass colors through, and
+// leave the output on the screen after quitting
+const DEFAULT_LESS: &str = "FRX";
+
+/// Where output to stdout goes: straight to stdout, or through a pager so a
+/// large schema doesn't flood the terminal
+pub struct Output {
+    pager: Option<Child>,
+    // Whether the reader quit the pager, so there's no point writing more
+    closed: bool,
+}
+
+impl Output {
+    /// Returns output that goes through $PAGER if `paginate` is set. Falls
+    /// back to stdout if it isn't, $PAGER is empty or `cat`, or the pager
+    /// won't start
+    ///
+    /// # Arguments
+    ///
+    /// * `paginate` - whether to page the output
+    pub fn new(paginate: bool) -> Output {
+        let pager = if paginate {
+            get_pager_command(env::var("PAGER").ok()).and_then(|command| spawn(&command))
+        } else {
+            None
+        };
+        Output {
+            pager,
+            closed: false,
+        }
+    }
+
+    /// Writes the text, ignoring the reader quitting the pager early
+    pub fn write(&mut self, text: &str) -> Result<(), Box<dyn Error>> {
+        if self.closed {
+            return Ok(());
+        }
+        let result = match self.pager.as_mut().and_then(|pager| pager.stdin.as_mut()) {
+            Some(stdin) => stdin.write_all(text.as_bytes()),
+            None => write_stdout(text),
+        };
+        match result {
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(())
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Waits for the reader to quit the pager, if there is one
+    pub fn finish(mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(mut pager) = self.pager.take() {
+            // Closing its input tells the pager there's no more to come
+            drop(pager.stdin.take());
+            pager.wait()?;
+        }
+        Ok(())
+    }
+}
+
+// print! goes through the test harness's output capture, which writing to
+// io::stdout() bypasses, but panics on a closed pipe instead of returning
+// the error
+#[cfg(test)]
+fn write_stdout(text: &str) -> io::Result<()> {
+    print!("{}", text);
+    Ok(())
+}
+
+#[cfg(not(test))]
+fn write_stdout(text: &str) -> io::Result<()> {
+    io::stdout().write_all(text.as_bytes())
+}
+
+// Returns the pager command and its arguments, or None if paging is off
+fn get_pager_command(pager: Option<String>) -> Option<Vec<String>> {
+    let pager = pager.unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let command: Vec<String> = pager.split_whitespace().map(String::from).collect();
+    match command.first().map(String::as_str) {
+        None | Some("cat") => None,
+        _ => Some(command),
+    }
+}
+
+fn spawn(command: &[String]) -> Option<Child> {
+    let mut pager = Command::new(&command[0]);
+    pager.args(&command[1..]).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        pager.env("LESS", DEFAULT_LESS);
+    }
+    pager.spawn().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_should_not_page_unless_asked() {
+        assert!(Output::new(false).pager.is_none());
+    }
+
+    #[test]
+    fn get_pager_command_should_split_pager() {
+        assert_eq!(Some(vec!["less".to_string()]), get_pager_command(None));
+        assert_eq!(
+            Some(vec!["less".to_string(), "-R".to_string()]),
+            get_pager_command(Some("less -R".to_string()))
+        );
+        assert_eq!(None, get_pager_command(Some("".to_string())));
+        assert_eq!(None, get_pager_command(Some("cat".to_string())));
+    }
+}
@@ -0,0 +1,116 @@
+use super::schema::{Field, Schema};
+
+/// A way the schema lets clients page through lists
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Pagination {
+    /// Relay-style connections: `first`/`after` or `last`/`before` arguments
+    /// on a field that returns a `...Connection` type
+    Connection,
+    /// `first`/`after` or `last`/`before` arguments on a field that returns
+    /// something other than a connection
+    Cursor,
+    /// `limit`/`offset` arguments
+    Offset,
+}
+
+/// A field that takes pagination arguments
+#[derive(Debug, PartialEq)]
+pub struct PaginatedField {
+    pub pagination: Pagination,
+    pub type_name: String,
+    pub type_kind: String,
+    pub field_name: String,
+}
+
+/// Returns the fields of the schema's objects and interfaces that take
+/// pagination arguments, sorted by pagination pattern, type, and field
+pub fn get_paginated_fields(schema: &Schema) -> Vec<PaginatedField> {
+    let mut paginated = Vec::new();
+    for typ in schema.types.iter().flatten() {
+        let (type_name, type_kind) = match (&typ.name, &typ.kind) {
+            (Some(name), Some(kind)) if !name.starts_with("__") => (name, kind),
+            _ => continue,
+        };
+        for field in typ.fields.iter().flatten() {
+            if let (Some(pagination), Some(field_name)) = (get_pagination(field), &field.name) {
+                paginated.push(PaginatedField {
+                    pagination,
+                    type_name: type_name.clone(),
+                    type_kind: type_kind.clone(),
+                    field_name: field_name.clone(),
+                });
+            }
+        }
+    }
+    paginated.sort_by(|a, b| {
+        (a.pagination, &a.type_name, &a.field_name).cmp(&(
+            b.pagination,
+            &b.type_name,
+            &b.field_name,
+        ))
+    });
+    paginated
+}
+
+fn get_pagination(field: &Field) -> Option<Pagination> {
+    let has_arg = |name: &str| {
+        field
+            .args
+            .iter()
+            .flatten()
+            .any(|arg| arg.name.as_deref() == Some(name))
+    };
+    let is_cursor =
+        (has_arg("first") && has_arg("after")) || (has_arg("last") && has_arg("before"));
+    let is_connection = field
+        .field_type
+        .as_ref()
+        .is_some_and(|typ| typ.get_actual_name().ends_with("Connection"));
+
+    if is_cursor && is_connection {
+        Some(Pagination::Connection)
+    } else if is_cursor {
+        Some(Pagination::Cursor)
+    } else if has_arg("limit") && has_arg("offset") {
+        Some(Pagination::Offset)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn get_paginated_fields_should_detect_patterns() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+                players(first: Int, after: String): PlayerConnection!
+                teams(last: Int, before: String): [Team]
+                games(limit: Int, offset: Int): [String]
+                player(id: ID): String
+                seasons(first: Int): [String]
+            }
+            type Team { players(first: Int, after: String, last: Int, before: String): PlayerConnection }
+            type PlayerConnection { nodes: [String] }
+            "#,
+        )?;
+        let found: Vec<(Pagination, String)> = get_paginated_fields(&schema)
+            .into_iter()
+            .map(|p| (p.pagination, format!("{}.{}", p.type_name, p.field_name)))
+            .collect();
+        assert_eq!(
+            vec![
+                (Pagination::Connection, "Query.players".to_string()),
+                (Pagination::Connection, "Team.players".to_string()),
+                (Pagination::Cursor, "Query.teams".to_string()),
+                (Pagination::Offset, "Query.games".to_string()),
+            ],
+            found
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use super::descriptions::get_unmatched;
+use super::schema::Schema;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    ffi::OsStr,
+    fs,
+    path::Path,
+};
+
+/// Long-form markdown kept in a directory of files named for the schema
+/// coordinates they document, such as `Player.md` and `Query.players.md`
+#[derive(Clone, Debug, Default)]
+pub struct Prose {
+    pages: HashMap<String, String>,
+}
+
+impl Prose {
+    /// Reads the `.md` files in the directory, ignoring anything else
+    pub fn from_dir(dir: &Path) -> Result<Prose, Box<dyn Error>> {
+        let entries = fs::read_dir(dir)
+            .map_err(|err| format!("can't read docs directory {}: {}", dir.display(), err))?;
+        let mut pages = HashMap::new();
+        for entry in entries {
+            let path = entry?.path();
+            if !path.is_file() || path.extension() != Some(OsStr::new("md")) {
+                continue;
+            }
+            let coordinate = match path.file_stem() {
+                Some(stem) => stem.to_string_lossy().to_string(),
+                None => continue,
+            };
+            let text = fs::read_to_string(&path)
+                .map_err(|err| format!("can't read {}: {}", path.display(), err))?;
+            if !text.trim().is_empty() {
+                pages.insert(coordinate, text.trim().to_string());
+            }
+        }
+        Ok(Prose { pages })
+    }
+
+    /// Adds the prose to the types and fields it's named for and returns the
+    /// coordinates that didn't match a type or field, sorted
+    pub fn apply(&self, schema: &mut Schema) -> Vec<String> {
+        let mut applied = HashSet::new();
+
+        schema.for_each_element_mut(&mut |coordinate, mut element| {
+            if let (Some(text), Some(prose)) = (self.pages.get(coordinate), element.prose_mut()) {
+                *prose = Some(text.clone());
+                applied.insert(coordinate.to_string());
+            }
+        });
+
+        get_unmatched(self.pages.keys(), &applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_should_add_prose_to_types_and_fields() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { players: [Player] }
+            type Player { name: String position: Position }
+            enum Position { GOALIE }
+            "#,
+        )?;
+        let prose = Prose {
+            pages: vec![
+                ("Player", "## Rosters\n\nPlayers join teams."),
+                ("Query.players", "Pages by 100."),
+                ("Position.GOALIE", "Enum values don't get prose."),
+                ("Team", "No such type."),
+            ]
+            .into_iter()
+            .map(|(coordinate, text)| (coordinate.to_string(), text.to_string()))
+            .collect(),
+        };
+        let unmatched = prose.apply(&mut schema);
+        assert_eq!(
+            vec!["Position.GOALIE".to_string(), "Team".to_string()],
+            unmatched
+        );
+        assert_eq!(
+            Some("## Rosters\n\nPlayers join teams.".to_string()),
+            schema.get_type("Player").unwrap().prose
+        );
+        assert_eq!(
+            Some("Pages by 100.".to_string()),
+            schema.get_type("Query").unwrap().fields.as_ref().unwrap()[0].prose
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_dir_should_fail_when_missing() {
+        match Prose::from_dir(Path::new("testdata/missing")) {
+            Ok(_) => panic!("missing docs directory should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .starts_with("can't read docs directory testdata/missing")),
+        }
+    }
+}
@@ -0,0 +1,722 @@
+use super::confluence;
+use super::format::{get_nav_pages, Format};
+use super::links::Links;
+use super::netrc::Credentials;
+use super::notion;
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    StatusCode,
+};
+use serde_json::{json, Value};
+use std::{collections::HashMap, error::Error, thread, time::Duration};
+
+const NOTION_API_URL: &str = "https://api.notion.com/v1";
+
+// The version of Notion's API the requests are written against
+const NOTION_VERSION: &str = "2022-06-28";
+
+// Notion's limits on appending blocks: blocks at the top level, blocks in
+// all, and bytes in a request, less room for the rest of the request
+const MAX_NOTION_BLOCKS: usize = 100;
+const MAX_NOTION_NESTED_BLOCKS: usize = 1000;
+const MAX_NOTION_BYTES: usize = 400_000;
+
+// How many times to retry when Notion says to slow down
+const MAX_NOTION_RETRIES: usize = 5;
+
+/// How to sign in to Confluence
+#[derive(Debug)]
+pub enum Auth {
+    /// An email address and API token, for Confluence Cloud, or a login and
+    /// password from .netrc
+    Basic(Credentials),
+    /// A personal access token, for Confluence Data Center
+    Bearer(String),
+}
+
+/// A Confluence site and the page to publish under
+#[derive(Debug)]
+pub struct Confluence {
+    client: Client,
+    base_url: String,
+    space: String,
+    parent: String,
+    auth: Auth,
+}
+
+/// What publishing did to a page
+#[derive(Debug, PartialEq)]
+pub enum Published {
+    Created,
+    Updated,
+}
+
+impl Confluence {
+    /// Returns a Confluence site to publish to
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - the site's URL, such as https://example.atlassian.net/wiki
+    /// * `space` - the key of the space to publish to
+    /// * `parent` - the ID of the page to publish under
+    /// * `auth` - how to sign in
+    /// * `insecure` - whether to skip verifying the site's TLS certificate
+    /// * `user_agent` - the User-Agent to send
+    pub fn new(
+        base_url: &str,
+        space: &str,
+        parent: &str,
+        auth: Auth,
+        insecure: bool,
+        user_agent: &str,
+    ) -> Result<Confluence, Box<dyn Error>> {
+        Ok(Confluence {
+            client: Client::builder()
+                .danger_accept_invalid_certs(insecure)
+                .user_agent(user_agent)
+                .build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            space: space.to_string(),
+            parent: parent.to_string(),
+            auth,
+        })
+    }
+
+    /// Creates or updates a page under the parent for each page, in
+    /// navigation order, printing what happened to each
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - the pages' markdown, keyed by name
+    /// * `site_name` - the name of the documentation site, which page titles
+    ///   start with
+    /// * `links` - how pages and anchors are named
+    pub fn publish_all(
+        &self,
+        contents: &HashMap<String, String>,
+        site_name: &str,
+        links: &Links,
+    ) -> Result<(), Box<dyn Error>> {
+        for page in get_nav_pages(contents) {
+            let title = confluence::get_page_title(site_name, page, links);
+            let body = Format::Confluence.convert(&contents[page], site_name, links);
+            match self.publish(&title, &body)? {
+                Published::Created => println!("created {}", title),
+                Published::Updated => println!("updated {}", title),
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the page under the parent, or updates it if the space already
+    /// has a page with its title
+    pub fn publish(&self, title: &str, body: &str) -> Result<Published, Box<dyn Error>> {
+        let url = format!("{}/rest/api/content", self.base_url);
+        let found = check(
+            self.sign(self.client.get(&url))
+                .query(&[
+                    ("spaceKey", self.space.as_str()),
+                    ("title", title),
+                    ("expand", "version"),
+                ])
+                .send()?,
+            "Confluence",
+        )?;
+        match get_page_version(&found) {
+            Some((id, version)) => {
+                let page = to_page(title, body, &self.space, &self.parent, Some(version + 1));
+                check(
+                    self.sign(self.client.put(&format!("{}/{}", url, id)))
+                        .json(&page)
+                        .send()?,
+                    "Confluence",
+                )?;
+                Ok(Published::Updated)
+            }
+            None => {
+                let page = to_page(title, body, &self.space, &self.parent, None);
+                check(
+                    self.sign(self.client.post(&url)).json(&page).send()?,
+                    "Confluence",
+                )?;
+                Ok(Published::Created)
+            }
+        }
+    }
+
+    fn sign(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.auth {
+            Auth::Basic(credentials) => {
+                request.basic_auth(&credentials.login, Some(&credentials.password))
+            }
+            Auth::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+}
+
+/// A Notion page to publish under
+#[derive(Debug)]
+pub struct Notion {
+    client: Client,
+    api_url: String,
+    token: String,
+    parent: String,
+}
+
+impl Notion {
+    /// Returns a Notion page to publish under
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - the token of an integration the page is shared with
+    /// * `parent` - the ID or URL of the page to publish under
+    /// * `insecure` - whether to skip verifying Notion's TLS certificate
+    /// * `user_agent` - the User-Agent to send
+    pub fn new(
+        token: &str,
+        parent: &str,
+        insecure: bool,
+        user_agent: &str,
+    ) -> Result<Notion, Box<dyn Error>> {
+        Ok(Notion {
+            client: Client::builder()
+                .danger_accept_invalid_certs(insecure)
+                .user_agent(user_agent)
+                .build()?,
+            api_url: NOTION_API_URL.to_string(),
+            token: token.to_string(),
+            parent: get_notion_page_id(parent),
+        })
+    }
+
+    /// Creates a page under the parent for each page, in navigation order,
+    /// printing what happened to each. Notion can't replace a page's blocks
+    /// in one go, so pages from earlier runs, found by title, are archived
+    /// once their replacements are written.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - the pages' markdown, keyed by name
+    /// * `links` - how pages link to each other
+    pub fn publish_all(
+        &self,
+        contents: &HashMap<String, String>,
+        links: &Links,
+    ) -> Result<(), Box<dyn Error>> {
+        let existing = self.get_child_pages()?;
+
+        // Create every page before writing any, so they can link to each
+        // other
+        let mut page_urls = HashMap::new();
+        let mut created = Vec::new();
+        for page in get_nav_pages(contents) {
+            let title = links.page_title(page);
+            let (id, url) = self.create_page(&title)?;
+            page_urls.insert(links.page_link(page), url);
+            created.push((page, title, id));
+        }
+
+        for (page, title, id) in created {
+            let blocks = notion::to_blocks(&contents[page], &page_urls);
+            for batch in to_batches(blocks) {
+                self.send(
+                    self.client
+                        .patch(&format!("{}/blocks/{}/children", self.api_url, id))
+                        .json(&json!({ "children": batch })),
+                )?;
+            }
+            let old_ids = existing.get(&title).map(Vec::as_slice).unwrap_or_default();
+            for old_id in old_ids {
+                self.send(
+                    self.client
+                        .patch(&format!("{}/pages/{}", self.api_url, old_id))
+                        .json(&json!({ "archived": true })),
+                )?;
+            }
+            if old_ids.is_empty() {
+                println!("created {}", title);
+            } else {
+                println!("updated {}", title);
+            }
+        }
+        Ok(())
+    }
+
+    // Returns the IDs of the parent's child pages, keyed by title
+    fn get_child_pages(&self) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+        let url = format!("{}/blocks/{}/children", self.api_url, self.parent);
+        let mut pages: HashMap<String, Vec<String>> = HashMap::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = self.client.get(&url).query(&[("page_size", "100")]);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("start_cursor", cursor)]);
+            }
+            let children = self.send(request)?;
+            for child in children["results"].as_array().into_iter().flatten() {
+                if let (Some(id), Some(title)) =
+                    (child["id"].as_str(), child["child_page"]["title"].as_str())
+                {
+                    pages
+                        .entry(title.to_string())
+                        .or_default()
+                        .push(id.to_string());
+                }
+            }
+            match children["next_cursor"].as_str() {
+                Some(next) if children["has_more"] == true => cursor = Some(next.to_string()),
+                _ => return Ok(pages),
+            }
+        }
+    }
+
+    // Creates an empty page under the parent, returning its ID and URL
+    fn create_page(&self, title: &str) -> Result<(String, String), Box<dyn Error>> {
+        let page = self.send(self.client.post(&format!("{}/pages", self.api_url)).json(
+            &json!({
+                "parent": { "page_id": self.parent },
+                "properties": {
+                    "title": { "title": [{ "type": "text", "text": { "content": title } }] }
+                },
+            }),
+        ))?;
+        match (page["id"].as_str(), page["url"].as_str()) {
+            (Some(id), Some(url)) => Ok((id.to_string(), url.to_string())),
+            _ => Err(format!("Notion didn't return the page it created for {}", title).into()),
+        }
+    }
+
+    // Sends the request, waiting and trying again while Notion says to
+    // slow down
+    fn send(&self, request: RequestBuilder) -> Result<Value, Box<dyn Error>> {
+        let request = request
+            .bearer_auth(&self.token)
+            .header("Notion-Version", NOTION_VERSION);
+        let mut retries = 0;
+        loop {
+            let response = request
+                .try_clone()
+                .ok_or("can't retry a request to Notion")?
+                .send()?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || retries == MAX_NOTION_RETRIES {
+                return check(response, "Notion");
+            }
+            let seconds = response
+                .headers()
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(1);
+            thread::sleep(Duration::from_secs(seconds));
+            retries += 1;
+        }
+    }
+}
+
+// Returns the ID in a Notion page's URL, or the parent as is if it isn't one
+fn get_notion_page_id(parent: &str) -> String {
+    let path = parent.split(&['?', '#'][..]).next().unwrap_or_default();
+    match path.get(path.len().saturating_sub(32)..) {
+        Some(id) if path.contains('/') && id.chars().all(|c| c.is_ascii_hexdigit()) => {
+            id.to_string()
+        }
+        _ => parent.to_string(),
+    }
+}
+
+// Splits blocks into batches small enough for Notion to append at once
+fn to_batches(blocks: Vec<Value>) -> Vec<Vec<Value>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let (mut nested, mut bytes) = (0, 0);
+    for block in blocks {
+        let block_nested = notion::count_blocks(&block);
+        let block_bytes = block.to_string().len();
+        if !batch.is_empty()
+            && (batch.len() == MAX_NOTION_BLOCKS
+                || nested + block_nested > MAX_NOTION_NESTED_BLOCKS
+                || bytes + block_bytes > MAX_NOTION_BYTES)
+        {
+            batches.push(std::mem::take(&mut batch));
+            nested = 0;
+            bytes = 0;
+        }
+        batch.push(block);
+        nested += block_nested;
+        bytes += block_bytes;
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+// Returns the response's JSON, or an error with the service's message
+fn check(response: Response, service: &str) -> Result<Value, Box<dyn Error>> {
+    let status = response.status();
+    let json: Value = response.json().unwrap_or(Value::Null);
+    if status.is_success() {
+        Ok(json)
+    } else {
+        let message = json["message"].as_str().unwrap_or_default();
+        Err(format!("{} returned {}: {}", service, status, message)
+            .trim_end_matches(&[':', ' '][..])
+            .to_string()
+            .into())
+    }
+}
+
+// Returns the ID and version of the first page a content search found
+fn get_page_version(found: &Value) -> Option<(String, u64)> {
+    let page = found["results"].get(0)?;
+    Some((
+        page["id"].as_str()?.to_string(),
+        page["version"]["number"].as_u64()?,
+    ))
+}
+
+// Returns the page to send: a new page has no version, and an update has the
+// version after the current one
+fn to_page(title: &str, body: &str, space: &str, parent: &str, version: Option<u64>) -> Value {
+    let mut page = json!({
+        "type": "page",
+        "title": title,
+        "space": { "key": space },
+        "ancestors": [{ "id": parent }],
+        "body": { "storage": { "value": body, "representation": "storage" } },
+    });
+    if let Some(version) = version {
+        page["version"] = json!({ "number": version });
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::JoinHandle,
+        time::Instant,
+    };
+
+    // A request the mock server received: its method and path, with the
+    // query, and its JSON body
+    type Received = (String, Value);
+
+    // Starts a server that answers each request with the next response, a
+    // status, headers, and body, returning its URL and a handle that yields
+    // the requests once they've all been answered
+    fn serve(responses: Vec<(u16, &'static str, Value)>) -> (String, JoinHandle<Vec<Received>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        listener.set_nonblocking(true).unwrap();
+        let handle = thread::spawn(move || {
+            let mut received = Vec::new();
+            let deadline = Instant::now() + Duration::from_secs(10);
+            for (status, headers, body) in responses {
+                let stream = loop {
+                    match listener.accept() {
+                        Ok((stream, _)) => break stream,
+                        Err(_) if Instant::now() < deadline => {
+                            thread::sleep(Duration::from_millis(10))
+                        }
+                        Err(_) => return received,
+                    }
+                };
+                stream.set_nonblocking(false).unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+                let mut parts = request_line.split_whitespace();
+                received.push((
+                    format!("{} {}", parts.next().unwrap(), parts.next().unwrap()),
+                    serde_json::from_slice(&request_body).unwrap_or(Value::Null),
+                ));
+
+                let body = body.to_string();
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                    status,
+                    body.len(),
+                    headers,
+                    body
+                )
+                .unwrap();
+            }
+            received
+        });
+        (url, handle)
+    }
+
+    fn pages(names: &[&str]) -> HashMap<String, String> {
+        names
+            .iter()
+            .map(|name| (name.to_string(), "## Player\n\nA player\n".to_string()))
+            .collect()
+    }
+
+    fn confluence(url: &str) -> Confluence {
+        Confluence::new(
+            &format!("{}/wiki/", url),
+            "DOCS",
+            "42",
+            Auth::Bearer("token".to_string()),
+            false,
+            "gumwood",
+        )
+        .unwrap()
+    }
+
+    fn notion(url: &str) -> Notion {
+        Notion {
+            api_url: url.to_string(),
+            ..Notion::new("token", "parent", false, "gumwood").unwrap()
+        }
+    }
+
+    #[test]
+    fn confluence_publish_all_should_create_missing_pages() {
+        let (url, server) = serve(vec![
+            (200, "", json!({ "results": [], "size": 0 })),
+            (200, "", json!({ "id": "7" })),
+        ]);
+        confluence(&url)
+            .publish_all(&pages(&["objects"]), "API", &Links::default())
+            .unwrap();
+        let received = server.join().unwrap();
+        assert_eq!(2, received.len());
+        assert!(received[0]
+            .0
+            .starts_with("GET /wiki/rest/api/content?spaceKey=DOCS&title=API%3A+Objects"));
+        assert_eq!("POST /wiki/rest/api/content", received[1].0);
+        assert_eq!("API: Objects", received[1].1["title"]);
+        assert_eq!("42", received[1].1["ancestors"][0]["id"]);
+        assert!(received[1].1.get("version").is_none());
+    }
+
+    #[test]
+    fn confluence_publish_all_should_update_existing_pages() {
+        let (url, server) = serve(vec![
+            (
+                200,
+                "",
+                json!({ "results": [{ "id": "7", "version": { "number": 3 } }], "size": 1 }),
+            ),
+            (200, "", json!({ "id": "7" })),
+        ]);
+        confluence(&url)
+            .publish_all(&pages(&["objects"]), "API", &Links::default())
+            .unwrap();
+        let received = server.join().unwrap();
+        assert_eq!("PUT /wiki/rest/api/content/7", received[1].0);
+        assert_eq!(4, received[1].1["version"]["number"]);
+    }
+
+    #[test]
+    fn confluence_publish_should_fail_with_message() {
+        let (url, server) = serve(vec![(
+            403,
+            "",
+            json!({ "message": "No permission to view space DOCS" }),
+        )]);
+        match confluence(&url).publish("API: Objects", "<p>x</p>") {
+            Ok(_) => panic!("forbidden should fail"),
+            Err(err) => assert_eq!(
+                "Confluence returned 403 Forbidden: No permission to view space DOCS",
+                err.to_string()
+            ),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn notion_publish_all_should_create_pages() {
+        let (url, server) = serve(vec![
+            (200, "", json!({ "results": [], "has_more": false })),
+            (
+                200,
+                "",
+                json!({ "id": "new", "url": "https://notion.so/new" }),
+            ),
+            (200, "", json!({ "results": [] })),
+        ]);
+        notion(&url)
+            .publish_all(&pages(&["objects"]), &Links::default())
+            .unwrap();
+        let received = server.join().unwrap();
+        let requests: Vec<&str> = received.iter().map(|(r, _)| r.as_str()).collect();
+        assert_eq!(
+            vec![
+                "GET /blocks/parent/children?page_size=100",
+                "POST /pages",
+                "PATCH /blocks/new/children",
+            ],
+            requests
+        );
+        assert_eq!("parent", received[1].1["parent"]["page_id"]);
+        assert_eq!(
+            "Objects",
+            received[1].1["properties"]["title"]["title"][0]["text"]["content"]
+        );
+        assert!(!received[2].1["children"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn notion_publish_all_should_archive_pages_it_replaces() {
+        let (url, server) = serve(vec![
+            (
+                200,
+                "",
+                json!({
+                    "results": [
+                        { "id": "old", "type": "child_page", "child_page": { "title": "Objects" } },
+                        { "id": "notes", "type": "child_page", "child_page": { "title": "Notes" } },
+                    ],
+                    "has_more": false,
+                    "next_cursor": null,
+                }),
+            ),
+            (
+                200,
+                "",
+                json!({ "id": "new", "url": "https://notion.so/new" }),
+            ),
+            (200, "", json!({ "results": [] })),
+            (200, "", json!({ "id": "old", "archived": true })),
+        ]);
+        notion(&url)
+            .publish_all(&pages(&["objects"]), &Links::default())
+            .unwrap();
+        let received = server.join().unwrap();
+        assert_eq!(4, received.len());
+        assert_eq!("PATCH /pages/old", received[3].0);
+        assert_eq!(json!({ "archived": true }), received[3].1);
+    }
+
+    #[test]
+    fn notion_send_should_retry_when_rate_limited() {
+        let (url, server) = serve(vec![
+            (
+                429,
+                "Retry-After: 0\r\n",
+                json!({ "code": "rate_limited", "message": "Slow down" }),
+            ),
+            (200, "", json!({ "results": [], "has_more": false })),
+        ]);
+        let notion = notion(&url);
+        assert_eq!(
+            json!({ "results": [], "has_more": false }),
+            notion
+                .send(
+                    notion
+                        .client
+                        .get(&format!("{}/blocks/parent/children", url))
+                )
+                .unwrap()
+        );
+        assert_eq!(2, server.join().unwrap().len());
+    }
+
+    #[test]
+    fn notion_send_should_fail_with_message() {
+        let (url, server) = serve(vec![(
+            404,
+            "",
+            json!({ "code": "object_not_found", "message": "Could not find block" }),
+        )]);
+        let notion = notion(&url);
+        match notion.send(
+            notion
+                .client
+                .get(&format!("{}/blocks/parent/children", url)),
+        ) {
+            Ok(_) => panic!("missing block should fail"),
+            Err(err) => assert_eq!(
+                "Notion returned 404 Not Found: Could not find block",
+                err.to_string()
+            ),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn to_page_should_add_version_for_updates() {
+        let page = to_page("API: Objects", "<p>x</p>", "DOCS", "42", None);
+        assert_eq!("DOCS", page["space"]["key"]);
+        assert_eq!("42", page["ancestors"][0]["id"]);
+        assert_eq!("<p>x</p>", page["body"]["storage"]["value"]);
+        assert!(page.get("version").is_none());
+        let page = to_page("API: Objects", "<p>x</p>", "DOCS", "42", Some(4));
+        assert_eq!(4, page["version"]["number"]);
+    }
+
+    #[test]
+    fn get_page_version_should_read_first_result() {
+        assert_eq!(
+            Some(("123".to_string(), 3)),
+            get_page_version(&json!({
+                "results": [{ "id": "123", "version": { "number": 3 } }],
+                "size": 1
+            }))
+        );
+        assert_eq!(None, get_page_version(&json!({ "results": [], "size": 0 })));
+    }
+
+    #[test]
+    fn get_notion_page_id_should_read_id_from_url() {
+        assert_eq!(
+            "0123456789abcdef0123456789abcdef",
+            get_notion_page_id(
+                "https://www.notion.so/acme/API-Docs-0123456789abcdef0123456789abcdef?pvs=4"
+            )
+        );
+        assert_eq!(
+            "01234567-89ab-cdef-0123-456789abcdef",
+            get_notion_page_id("01234567-89ab-cdef-0123-456789abcdef")
+        );
+    }
+
+    #[test]
+    fn to_batches_should_respect_notion_limits() {
+        let paragraph = json!({ "type": "paragraph", "paragraph": { "rich_text": [] } });
+        let batches = to_batches(vec![paragraph.clone(); 250]);
+        assert_eq!(
+            vec![100, 100, 50],
+            batches.iter().map(Vec::len).collect::<Vec<usize>>()
+        );
+
+        let rows = vec![json!({ "type": "table_row", "table_row": { "cells": [] } }); 99];
+        let table = json!({ "type": "table", "table": { "table_width": 1, "children": rows } });
+        let batches = to_batches(vec![table; 12]);
+        assert_eq!(
+            vec![10, 2],
+            batches.iter().map(Vec::len).collect::<Vec<usize>>()
+        );
+
+        let long =
+            json!({ "type": "paragraph", "paragraph": { "rich_text": ["a".repeat(300_000)] } });
+        let batches = to_batches(vec![long.clone(), long, paragraph]);
+        assert_eq!(
+            vec![1, 2],
+            batches.iter().map(Vec::len).collect::<Vec<usize>>()
+        );
+    }
+}
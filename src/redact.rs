@@ -0,0 +1,144 @@
+use super::schema::{ElementMut, Schema};
+use regex::RegexSet;
+use serde::Deserialize;
+use std::error::Error;
+
+/// What redacted default values are replaced with
+pub const REDACTED: &str = "«redacted»";
+
+/// Which default values to keep out of the docs, set in the `redact` table
+/// of the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Redact {
+    /// Regular expressions; defaults that match any of them are redacted
+    pub patterns: Vec<String>,
+
+    /// Argument and input field names, or their schema coordinates, whose
+    /// defaults are always redacted
+    pub names: Vec<String>,
+}
+
+impl Redact {
+    /// Replaces the sensitive default values of arguments and input fields
+    /// with «redacted»
+    pub fn apply(&self, schema: &mut Schema) -> Result<(), Box<dyn Error>> {
+        if self.patterns.is_empty() && self.names.is_empty() {
+            return Ok(());
+        }
+        let patterns = RegexSet::new(&self.patterns)
+            .map_err(|err| format!("invalid redact pattern: {}", err))?;
+        schema.for_each_element_mut(&mut |coordinate, element| {
+            if let ElementMut::Input(input) = element {
+                let named = self
+                    .names
+                    .iter()
+                    .any(|name| name == coordinate || Some(name.as_str()) == input.name.as_deref());
+                if let Some(value) = input.default_value.as_mut() {
+                    if named || patterns.is_match(value) {
+                        *value = REDACTED.to_string();
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+        type Query {
+          login(user: String = "admin", password: String = "hunter2"): String
+          search(key: String = "sk_live_1234", limit: Int = 10): String
+        }
+        input Settings {
+          apiToken: String = "abc"
+          theme: String = "dark"
+        }
+        directive @auth(secret: String = "s3cret") on FIELD_DEFINITION
+    "#;
+
+    fn get_default(schema: &Schema, typ: &str, field: &str, arg: Option<&str>) -> String {
+        let typ = schema.get_type(typ).unwrap();
+        let input = match arg {
+            Some(arg) => typ
+                .fields
+                .iter()
+                .flatten()
+                .find(|f| f.name.as_deref() == Some(field))
+                .unwrap()
+                .args
+                .iter()
+                .flatten()
+                .find(|a| a.name.as_deref() == Some(arg))
+                .unwrap(),
+            None => typ
+                .inputs
+                .iter()
+                .flatten()
+                .find(|i| i.name.as_deref() == Some(field))
+                .unwrap(),
+        };
+        input.default_value.clone().unwrap()
+    }
+
+    #[test]
+    fn apply_should_redact_matching_defaults() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        Redact {
+            patterns: vec!["sk_(live|test)_".to_string()],
+            names: vec![],
+        }
+        .apply(&mut schema)?;
+        assert_eq!(
+            REDACTED,
+            get_default(&schema, "Query", "search", Some("key"))
+        );
+        assert_eq!("10", get_default(&schema, "Query", "search", Some("limit")));
+        assert_eq!(
+            "\"admin\"",
+            get_default(&schema, "Query", "login", Some("user"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_redact_named_defaults() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        Redact {
+            patterns: vec![],
+            names: vec![
+                "Query.login(password:)".to_string(),
+                "apiToken".to_string(),
+                "@auth(secret:)".to_string(),
+            ],
+        }
+        .apply(&mut schema)?;
+        assert_eq!(
+            REDACTED,
+            get_default(&schema, "Query", "login", Some("password"))
+        );
+        assert_eq!(REDACTED, get_default(&schema, "Settings", "apiToken", None));
+        assert_eq!("\"dark\"", get_default(&schema, "Settings", "theme", None));
+        let auth = &schema.directives.as_ref().unwrap()[0];
+        assert_eq!(
+            Some(REDACTED),
+            auth.args.as_ref().unwrap()[0].default_value.as_deref()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_fail_on_invalid_pattern() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        let redact = Redact {
+            patterns: vec!["(".to_string()],
+            names: vec![],
+        };
+        assert!(redact.apply(&mut schema).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use super::links::Links;
+use super::markdown::split_heading_id;
+use super::slug::{Github, Slugifier};
+use super::stats::{Coverage, Cycle};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+    time::Instant,
+};
+
+/// What happened during a run, for dashboards that track the health of the
+/// docs over time
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub warnings: Vec<String>,
+    pub lint: Vec<String>,
+    pub coverage: Coverage,
+    pub cycles: Vec<Cycle>,
+    pub dangling_links: Vec<DanglingLink>,
+    pub phases: Vec<Phase>,
+}
+
+/// A link to a page or anchor that wasn't generated
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DanglingLink {
+    pub page: String,
+    pub destination: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Phase {
+    pub name: &'static str,
+    pub milliseconds: f64,
+}
+
+impl Report {
+    /// Prints the warning to stderr and records it
+    pub fn warn(&mut self, message: String) {
+        eprintln!("warning: {}", message);
+        self.warnings.push(message);
+    }
+
+    /// Records how long a phase took
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the phase
+    /// * `started` - when the phase started
+    pub fn time(&mut self, name: &'static str, started: Instant) {
+        self.phases.push(Phase {
+            name,
+            milliseconds: started.elapsed().as_secs_f64() * 1000.0,
+        });
+    }
+
+    /// Writes the report as pretty-printed JSON
+    pub fn write(&self, file: &Path) -> Result<(), Box<dyn Error>> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(file, json)
+            .map_err(|err| format!("can't write report {}: {}", file.display(), err).into())
+    }
+}
+
+/// Returns the links between pages that point at a page or anchor that
+/// wasn't generated, sorted by page
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name
+/// * `links` - how pages and anchors are named
+pub fn find_dangling_links(contents: &HashMap<String, String>, links: &Links) -> Vec<DanglingLink> {
+    let anchors: HashMap<String, HashSet<String>> = contents
+        .iter()
+        .filter(|(_, markdown)| !markdown.is_empty())
+        .map(|(name, markdown)| (links.page_link(name), get_anchors(markdown)))
+        .collect();
+
+    let mut pages: Vec<&String> = contents.keys().collect();
+    pages.sort();
+
+    let mut dangling = Vec::new();
+    for page in pages {
+        let link = links.page_link(page);
+        for destination in get_link_destinations(&contents[page]) {
+            let (target, fragment) = match destination.split_once('#') {
+                Some((target, fragment)) => (target, Some(fragment)),
+                None => (destination, None),
+            };
+            if target.contains(':') || (!target.is_empty() && !links.is_page_link(target)) {
+                continue;
+            }
+            let target = if target.is_empty() { &link } else { target };
+            let found = anchors
+                .get(target)
+                .is_some_and(|anchors| fragment.is_none_or(|fragment| anchors.contains(fragment)));
+            if !found {
+                dangling.push(DanglingLink {
+                    page: page.to_string(),
+                    destination: destination.to_string(),
+                });
+            }
+        }
+    }
+    dangling
+}
+
+// Named anchors, plus the anchors renderers generate for headings
+fn get_anchors(markdown: &str) -> HashSet<String> {
+    let mut anchors = HashSet::new();
+    for line in markdown.lines() {
+        let mut rest = line;
+        let mut text = String::new();
+        while let Some(start) = rest.find("<a name=\"") {
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 9..];
+            match after.find("\"></a>") {
+                Some(end) => {
+                    anchors.insert(after[..end].to_string());
+                    rest = &after[end + 6..];
+                }
+                None => {
+                    rest = after;
+                }
+            }
+        }
+        text.push_str(rest);
+        if text.starts_with('#') {
+            let heading = text.trim_start_matches('#');
+            match split_heading_id(heading) {
+                Some((_, id)) => anchors.insert(id.to_string()),
+                None => anchors.insert(Github.slugify(heading).trim_matches('-').to_string()),
+            };
+        }
+    }
+    anchors
+}
+
+fn get_link_destinations(markdown: &str) -> Vec<&str> {
+    let mut destinations = Vec::new();
+    let mut rest = markdown;
+    while let Some(start) = rest.find("](") {
+        let after = &rest[start + 2..];
+        match after.find(')') {
+            Some(end) => {
+                destinations.push(&after[..end]);
+                rest = &after[end + 1..];
+            }
+            None => break,
+        }
+    }
+    destinations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(pages: &[(&str, &str)]) -> HashMap<String, String> {
+        pages
+            .iter()
+            .map(|(name, markdown)| (name.to_string(), markdown.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn find_dangling_links_should_accept_named_and_heading_anchors() {
+        let contents = pages(&[
+            (
+                "objects",
+                "## <a name=\"player\"></a>Player\n\n[`Team`](objects.md#team)\n\n## Team\n",
+            ),
+            ("queries", "## players\n\n[`Player`](objects.md#player)\n"),
+        ]);
+        assert!(find_dangling_links(&contents, &Links::default()).is_empty());
+    }
+
+    #[test]
+    fn find_dangling_links_should_report_missing_pages_and_anchors() {
+        let contents = pages(&[
+            (
+                "objects",
+                "## Player\n\n[`Team`](objects.md#team) [`Int`](scalars.md#int) [top](#player)\n",
+            ),
+            ("scalars", ""),
+        ]);
+        assert_eq!(
+            vec![
+                DanglingLink {
+                    page: "objects".to_string(),
+                    destination: "objects.md#team".to_string(),
+                },
+                DanglingLink {
+                    page: "objects".to_string(),
+                    destination: "scalars.md#int".to_string(),
+                },
+            ],
+            find_dangling_links(&contents, &Links::default())
+        );
+    }
+
+    #[test]
+    fn find_dangling_links_should_use_page_extension() {
+        let contents = pages(&[("objects", "## Player\n\n[`Team`](objects.mdx#team)\n")]);
+        let links = Links {
+            page_extension: "mdx",
+            ..Default::default()
+        };
+        assert_eq!(1, find_dangling_links(&contents, &links).len());
+    }
+
+    #[test]
+    fn find_dangling_links_should_skip_external_links() {
+        let contents = pages(&[("about", "[docs](https://example.com/docs.md#x)\n")]);
+        assert!(find_dangling_links(&contents, &Links::default()).is_empty());
+    }
+}
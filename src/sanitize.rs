@@ -0,0 +1,335 @@
+use super::markdown::split_code;
+use super::schema::{ElementMut, Schema};
+use serde::Deserialize;
+
+// Tags that format text but can't run anything or pull anything in
+const DEFAULT_ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "br", "code", "em", "i", "kbd", "li", "ol", "p", "pre", "strong", "sub", "sup", "ul",
+];
+
+const DEFAULT_ALLOWED_ATTRIBUTES: &[&str] = &["href", "title"];
+
+// URL schemes that run code or embed content when a link is followed
+const UNSAFE_SCHEMES: &[&str] = &["javascript:", "vbscript:", "data:"];
+
+/// Which HTML may pass through descriptions untouched, set in the
+/// `sanitize` table of the config file
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sanitize {
+    /// Tags, in lowercase, that are kept; other tags are escaped so they
+    /// show as text
+    pub allowed_tags: Vec<String>,
+
+    /// Attributes, in lowercase, that are kept on allowed tags
+    pub allowed_attributes: Vec<String>,
+}
+
+impl Default for Sanitize {
+    fn default() -> Self {
+        Sanitize {
+            allowed_tags: DEFAULT_ALLOWED_TAGS.iter().map(|t| t.to_string()).collect(),
+            allowed_attributes: DEFAULT_ALLOWED_ATTRIBUTES
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Sanitize {
+    /// Sanitizes the schema's description and every description and
+    /// deprecation reason in it
+    pub fn apply(&self, schema: &mut Schema) {
+        if let Some(description) = schema.description.as_mut() {
+            *description = self.sanitize(description);
+        }
+        schema.for_each_element_mut(&mut |_, mut element| {
+            if let Some(description) = element.description_mut().as_mut() {
+                *description = self.sanitize(description);
+            }
+            let reason = match element {
+                ElementMut::Field(field) => &mut field.deprecation_reason,
+                ElementMut::Enum(value) => &mut value.deprecation_reason,
+                _ => return,
+            };
+            if let Some(reason) = reason.as_mut() {
+                *reason = self.sanitize(reason);
+            }
+        });
+    }
+
+    /// Returns the markdown with tags that aren't allowed escaped, attributes
+    /// that aren't allowed removed, and links to unsafe URLs disabled. Code
+    /// is left alone, since renderers show it as text.
+    pub fn sanitize(&self, markdown: &str) -> String {
+        let mut s = String::new();
+        for (piece, is_code) in split_code(markdown) {
+            if is_code {
+                s.push_str(piece);
+            } else {
+                s.push_str(&self.sanitize_text(piece));
+            }
+        }
+        s
+    }
+
+    fn sanitize_text(&self, text: &str) -> String {
+        let mut s = String::new();
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            if c == '<' {
+                match parse_tag(rest) {
+                    Some(tag) if self.allowed_tags.contains(&tag.name) => {
+                        s.push_str(&self.to_allowed_tag(&tag));
+                        rest = &rest[tag.len..];
+                    }
+                    _ => {
+                        s.push_str("&lt;");
+                        rest = &rest[1..];
+                    }
+                }
+            } else if rest.starts_with("](") {
+                s.push_str("](");
+                rest = &rest[2..];
+                if is_unsafe_url(rest) {
+                    // Keep the link text, but point it nowhere
+                    s.push('#');
+                    rest = &rest[get_destination_len(rest)..];
+                }
+            } else {
+                s.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        s
+    }
+
+    fn to_allowed_tag(&self, tag: &Tag) -> String {
+        let mut s = format!("<{}{}", if tag.closing { "/" } else { "" }, tag.name);
+        for (name, value) in &tag.attributes {
+            if !self.allowed_attributes.contains(name) {
+                continue;
+            }
+            match value {
+                Some(value) if is_unsafe_url(value) => {}
+                Some(value) => {
+                    s.push_str(&format!(" {}=\"{}\"", name, value.replace('"', "&quot;")))
+                }
+                None => s.push_str(&format!(" {}", name)),
+            }
+        }
+        if tag.self_closing {
+            s.push_str(" /");
+        }
+        s.push('>');
+        s
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attributes: Vec<(String, Option<String>)>,
+    // How many bytes of the text the tag takes up
+    len: usize,
+}
+
+// Parses the HTML tag at the start of the text, or returns None if it isn't
+// one, as with "a < b"
+fn parse_tag(text: &str) -> Option<Tag> {
+    let mut rest = text.strip_prefix('<')?;
+    let closing = rest.starts_with('/');
+    if closing {
+        rest = &rest[1..];
+    }
+    let name_len = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    if name_len == 0 || !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = rest[..name_len].to_lowercase();
+    rest = &rest[name_len..];
+
+    let mut attributes = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Some(Tag {
+                name,
+                closing,
+                self_closing: true,
+                attributes,
+                len: text.len() - after.len(),
+            });
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some(Tag {
+                name,
+                closing,
+                self_closing: false,
+                attributes,
+                len: text.len() - after.len(),
+            });
+        }
+        let attribute_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        if attribute_len == 0 {
+            return None;
+        }
+        let attribute = rest[..attribute_len].to_lowercase();
+        rest = rest[attribute_len..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote) if quote == '"' || quote == '\'' => {
+                        let end = after[1..].find(quote)? + 1;
+                        (&after[1..end], &after[end + 1..])
+                    }
+                    _ => {
+                        let end = after
+                            .find(|c: char| c.is_whitespace() || c == '>')
+                            .unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                Some(value.to_string())
+            }
+            None => None,
+        };
+        attributes.push((attribute, value));
+    }
+}
+
+// Returns the length of a link destination, which can hold balanced
+// parentheses, up to the parenthesis that closes it
+fn get_destination_len(text: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return i,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+fn is_unsafe_url(url: &str) -> bool {
+    // Browsers ignore whitespace and control characters inside a scheme
+    let scheme: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .take(16)
+        .collect::<String>()
+        .to_lowercase();
+    UNSAFE_SCHEMES
+        .iter()
+        .any(|unsafe_scheme| scheme.starts_with(unsafe_scheme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn sanitize_should_escape_disallowed_tags() {
+        assert_eq!(
+            "Hi &lt;script>alert(1)&lt;/script>",
+            Sanitize::default().sanitize("Hi <script>alert(1)</script>")
+        );
+        assert_eq!(
+            "&lt;img src=x onerror=alert(1)>",
+            Sanitize::default().sanitize("<img src=x onerror=alert(1)>")
+        );
+        assert_eq!(
+            "&lt;!-- hidden -->",
+            Sanitize::default().sanitize("<!-- hidden -->")
+        );
+    }
+
+    #[test]
+    fn sanitize_should_keep_allowed_tags_and_attributes() {
+        assert_eq!(
+            "<b>Bold</b><br /> <a href=\"https://example.com\">docs</a>",
+            Sanitize::default().sanitize(
+                "<b onclick=\"x()\">Bold</b><br/> <a href='https://example.com' style=\"x\">docs</a>"
+            )
+        );
+    }
+
+    #[test]
+    fn sanitize_should_drop_unsafe_urls() {
+        assert_eq!(
+            "<a>click</a> and [click](#)",
+            Sanitize::default().sanitize(
+                "<a href=\"java\tscript:alert(1)\">click</a> and [click](JavaScript:alert(1))"
+            )
+        );
+        assert_eq!(
+            "[docs](https://example.com)",
+            Sanitize::default().sanitize("[docs](https://example.com)")
+        );
+    }
+
+    #[test]
+    fn sanitize_should_leave_code_and_comparisons_alone() {
+        assert_eq!(
+            "Use `<script>` when a &lt; b\n```\n<div>\n```\n",
+            Sanitize::default().sanitize("Use `<script>` when a < b\n```\n<div>\n```\n")
+        );
+    }
+
+    #[test]
+    fn sanitize_should_use_configured_allow_list() {
+        let sanitize = Sanitize {
+            allowed_tags: vec!["div".to_string()],
+            allowed_attributes: vec!["class".to_string()],
+        };
+        assert_eq!(
+            "<div class=\"note\">&lt;b>x&lt;/b></div>",
+            sanitize.sanitize("<DIV class=note id=n><b>x</b></div>")
+        );
+    }
+
+    #[test]
+    fn apply_should_sanitize_descriptions_and_reasons() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            "A <script>x</script> player"
+            type Player {
+              "The <iframe> name"
+              name(
+                "How <object> to format"
+                format: String
+              ): String @deprecated(reason: "Use <embed> instead")
+            }
+            "#,
+        )?;
+        Sanitize::default().apply(&mut schema);
+        let player = schema.get_type("Player").unwrap();
+        assert_eq!(
+            Some("A &lt;script>x&lt;/script> player"),
+            player.description.as_deref()
+        );
+        let name = &player.fields.as_ref().unwrap()[0];
+        assert_eq!(Some("The &lt;iframe> name"), name.description.as_deref());
+        assert_eq!(
+            Some("Use &lt;embed> instead"),
+            name.deprecation_reason.as_deref()
+        );
+        assert_eq!(
+            Some("How &lt;object> to format"),
+            name.args.as_ref().unwrap()[0].description.as_deref()
+        );
+        Ok(())
+    }
+}
@@ -1,9 +1,39 @@
-use reqwest::blocking::Client;
-use serde::{Deserialize, Serialize};
+use super::netrc::Credentials;
+use super::sdl;
+use super::tape::Exchange;
+use chrono::{DateTime, Utc};
+use flate2::read::MultiGzDecoder;
+use reqwest::{
+    blocking::Client,
+    header::{HeaderMap, HeaderValue, ACCEPT, RETRY_AFTER},
+    StatusCode,
+};
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 use serde_json::Value;
-use std::{boxed::Box, error::Error, fmt, fs, path::PathBuf};
-
-const TYPE_LEVELS: u32 = 7;
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+/// How long to wait before retrying a rate-limited request that doesn't say
+/// when to retry; the wait doubles with each retry
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How many levels of `ofType` the introspection query requests by default,
+/// which covers `[[Thing!]!]!` with room to spare
+pub const DEFAULT_TYPE_DEPTH: u32 = 7;
 
 #[derive(Debug)]
 struct SchemaError {
@@ -26,7 +56,125 @@ impl fmt::Display for SchemaError {
 
 impl Error for SchemaError {}
 
-#[derive(Debug, Deserialize, Serialize)]
+// What an introspection result holds, found in a single pass over the JSON so
+// a large result is never held as a Value tree or a second string
+struct Introspection {
+    schema: Option<Schema>,
+    // A bare __schema object, which is used if there's no __schema key
+    bare: Option<Schema>,
+    // The data object's __schema, if there's a data object
+    data: Option<Option<Schema>>,
+    extensions: Option<Value>,
+    // The messages of the response's GraphQL errors
+    errors: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct IntrospectionData {
+    #[serde(rename = "__schema")]
+    schema: Option<Schema>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    message: Option<String>,
+}
+
+impl Introspection {
+    fn into_schema(self) -> Result<Schema, Box<dyn Error>> {
+        match (self.schema.or(self.bare), self.data) {
+            (Some(schema), _) => Ok(schema),
+            (None, Some(Some(schema))) => Ok(schema),
+            (None, Some(None)) if self.errors.is_empty() => {
+                Err(Box::new(SchemaError::new("schema not in response")))
+            }
+            (None, Some(None)) => Err(Box::new(SchemaError::new(&format!(
+                "schema not in response: {}",
+                self.errors.join("; ")
+            )))),
+            (None, None) => Err(Box::new(SchemaError::new("data not in response"))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Introspection {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(IntrospectionVisitor)
+    }
+}
+
+struct IntrospectionVisitor;
+
+impl<'de> Visitor<'de> for IntrospectionVisitor {
+    type Value = Introspection;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an introspection result object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Introspection, A::Error> {
+        let mut introspection = Introspection {
+            schema: None,
+            bare: None,
+            data: None,
+            extensions: None,
+            errors: Vec::new(),
+        };
+        let mut bare = Schema::empty();
+        // Any of the schema's keys but description marks the object as a
+        // bare __schema object
+        let mut is_bare = false;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "__schema" => introspection.schema = Some(map.next_value()?),
+                "data" => {
+                    // A response whose query failed has null data
+                    let data: Option<IntrospectionData> = map.next_value()?;
+                    introspection.data = Some(data.and_then(|data| data.schema));
+                }
+                "errors" => {
+                    let errors: Option<Vec<ResponseError>> = map.next_value()?;
+                    introspection.errors = errors
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|error| error.message)
+                        .collect();
+                }
+                "extensions" => introspection.extensions = Some(map.next_value()?),
+                "description" => bare.description = map.next_value()?,
+                "queryType" | "query_type" => {
+                    bare.query_type = map.next_value()?;
+                    is_bare = true;
+                }
+                "mutationType" | "mutation_type" => {
+                    bare.mutation_type = map.next_value()?;
+                    is_bare = true;
+                }
+                "subscriptionType" | "subscription_type" => {
+                    bare.subscription_type = map.next_value()?;
+                    is_bare = true;
+                }
+                "types" => {
+                    bare.types = map.next_value()?;
+                    is_bare = true;
+                }
+                "directives" => {
+                    bare.directives = map.next_value()?;
+                    is_bare = true;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        if is_bare {
+            introspection.bare = Some(bare);
+        }
+        Ok(introspection)
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Type {
     pub name: Option<String>,
     pub kind: Option<String>,
@@ -39,6 +187,162 @@ pub struct Type {
     pub enums: Option<Vec<Enum>>,
     #[serde(alias = "possibleTypes")]
     pub possible_types: Option<Vec<TypeRef>>,
+    #[serde(alias = "specifiedByURL", alias = "specifiedByUrl")]
+    pub specified_by_url: Option<String>,
+    #[serde(alias = "isOneOf")]
+    pub is_one_of: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Example values, as JSON, from a metadata file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<String>>,
+    /// Long-form markdown from the docs directory, shown after the type's
+    /// tables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prose: Option<String>,
+    /// How many other types refer to this one, for its quick facts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used_by: Option<usize>,
+    /// The other definitions of this type that merging kept under their own
+    /// names, because other sources define the type differently
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<Vec<TypeRef>>,
+    /// The operations and other types whose fields, arguments, or input
+    /// fields refer to this one, for its backlinks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referenced_by: Option<Vec<Referrer>>,
+    /// Tags such as `beta`, taken from the start of the description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+/// An operation or type that refers to another type
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Referrer {
+    pub name: String,
+    /// The type's kind, or `query`, `mutation`, or `subscription` for an
+    /// operation
+    pub kind: String,
+    /// What refers to the type, as `field`, `field(arg:)`, or `inputField`;
+    /// for an operation, only its arguments are listed
+    pub members: Vec<String>,
+}
+
+impl Type {
+    // Returns the coordinates this type and another definition of it define
+    // differently: the type itself if their kinds differ, or else the fields
+    // and input fields both have but with different types or arguments
+    fn get_conflicts(&self, other: &Type) -> Vec<String> {
+        let name = self.name.clone().unwrap_or_default();
+        if self.kind != other.kind {
+            return vec![name];
+        }
+        let mut conflicts = Vec::new();
+        for other_field in other.fields.iter().flatten() {
+            if let Some(field) = self
+                .fields
+                .iter()
+                .flatten()
+                .find(|f| f.name == other_field.name)
+            {
+                if field.field_type != other_field.field_type || field.args != other_field.args {
+                    conflicts.push(format!(
+                        "{}.{}",
+                        name,
+                        other_field.name.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        for other_input in other.inputs.iter().flatten() {
+            if let Some(input) = self
+                .inputs
+                .iter()
+                .flatten()
+                .find(|i| i.name == other_input.name)
+            {
+                if input.input_type != other_input.input_type {
+                    conflicts.push(format!(
+                        "{}.{}",
+                        name,
+                        other_input.name.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+
+    // Combines another definition of this type into this one, recording the
+    // coordinates the two define differently
+    fn merge(&mut self, other: Type, conflicts: &mut Vec<String>) {
+        conflicts.extend(self.get_conflicts(&other));
+        if self.kind != other.kind {
+            return;
+        }
+        if self.description.is_none() {
+            self.description = other.description;
+        }
+
+        for other_field in other.fields.into_iter().flatten() {
+            let fields = self.fields.get_or_insert_with(Vec::new);
+            if !fields.iter().any(|f| f.name == other_field.name) {
+                fields.push(other_field);
+            }
+        }
+
+        for other_input in other.inputs.into_iter().flatten() {
+            let inputs = self.inputs.get_or_insert_with(Vec::new);
+            if !inputs.iter().any(|i| i.name == other_input.name) {
+                inputs.push(other_input);
+            }
+        }
+
+        for other_value in other.enums.into_iter().flatten() {
+            let enums = self.enums.get_or_insert_with(Vec::new);
+            if !enums.iter().any(|v| v.name == other_value.name) {
+                enums.push(other_value);
+            }
+        }
+
+        for (refs, other_refs) in [
+            (&mut self.interfaces, other.interfaces),
+            (&mut self.possible_types, other.possible_types),
+        ] {
+            for other_ref in other_refs.into_iter().flatten() {
+                let refs = refs.get_or_insert_with(Vec::new);
+                if !refs.iter().any(|r| r.name == other_ref.name) {
+                    refs.push(other_ref);
+                }
+            }
+        }
+    }
+
+    /// Calls `f` with every type reference in the type: field types, field
+    /// argument types, input field types, interfaces, and possible types
+    pub fn for_each_type_ref_mut(&mut self, f: &mut dyn FnMut(&mut TypeRef)) {
+        for field in self.fields.iter_mut().flatten() {
+            if let Some(field_type) = &mut field.field_type {
+                f(field_type);
+            }
+            for arg in field.args.iter_mut().flatten() {
+                if let Some(input_type) = &mut arg.input_type {
+                    f(input_type);
+                }
+            }
+        }
+        for input in self.inputs.iter_mut().flatten() {
+            if let Some(input_type) = &mut input.input_type {
+                f(input_type);
+            }
+        }
+        for type_ref in self.interfaces.iter_mut().flatten() {
+            f(type_ref);
+        }
+        for type_ref in self.possible_types.iter_mut().flatten() {
+            f(type_ref);
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -52,6 +356,27 @@ pub struct Field {
     pub is_deprecated: Option<bool>,
     #[serde(alias = "deprecationReason")]
     pub deprecation_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// When a deprecated element will be removed, as YYYY-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removal_date: Option<String>,
+    /// The estimated cost of resolving the field, for rate limiting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<u32>,
+    /// Example values, as JSON, from a metadata file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<String>>,
+    /// The group an operation is listed under on its page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Long-form markdown from the docs directory, shown after the field's
+    /// section, or its type's tables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prose: Option<String>,
+    /// Tags such as `beta`, taken from the start of the description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -62,6 +387,11 @@ pub struct Input {
     pub input_type: Option<TypeRef>,
     #[serde(alias = "defaultValue")]
     pub default_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Example values, as JSON, from a metadata file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -72,6 +402,11 @@ pub struct Enum {
     pub is_deprecated: Option<bool>,
     #[serde(alias = "deprecationReason")]
     pub deprecation_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// When a deprecated element will be removed, as YYYY-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removal_date: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
@@ -92,38 +427,22 @@ impl TypeRef {
     }
 
     pub fn get_actual_name(&self) -> String {
-        self.recurse_actual_name(TYPE_LEVELS)
-    }
-
-    fn recurse_actual_name(&self, level: u32) -> String {
-        if level == 0 {
-            return "".to_string();
-        }
-
         match &self.name {
             Some(name) => name.to_string(),
             None => match &self.of_type {
-                Some(typ) => typ.recurse_actual_name(level - 1),
+                Some(typ) => typ.get_actual_name(),
                 None => "".to_string(),
             },
         }
     }
 
     pub fn get_decorated_name(&self) -> String {
-        self.recurse_decorated_name(TYPE_LEVELS)
-    }
-
-    fn recurse_decorated_name(&self, level: u32) -> String {
-        if level == 0 {
-            return "".to_string();
-        }
-
         let mut s = String::new();
 
         let name = match &self.name {
             Some(name) => name.clone(),
             None => match &self.of_type {
-                Some(typ) => typ.recurse_decorated_name(level - 1),
+                Some(typ) => typ.get_decorated_name(),
                 None => "".to_string(),
             },
         };
@@ -131,29 +450,42 @@ impl TypeRef {
         s.push_str(&name);
 
         if self.is_required() {
-            s.push_str("!");
+            s.push('!');
         }
 
         if self.is_list() {
-            s.insert_str(0, "[");
-            s.push_str("]");
+            s.insert(0, '[');
+            s.push(']');
         }
 
         s
     }
 
-    pub fn get_actual_kind(&self) -> String {
-        self.recurse_actual_kind(TYPE_LEVELS)
+    /// Renames the named type this reference ultimately points to
+    pub fn rename(&mut self, aliases: &HashMap<String, String>) {
+        match &mut self.of_type {
+            Some(typ) => typ.rename(aliases),
+            None => {
+                if let Some(alias) = self.name.as_ref().and_then(|name| aliases.get(name)) {
+                    self.name = Some(alias.clone());
+                }
+            }
+        }
     }
 
-    fn recurse_actual_kind(&self, level: u32) -> String {
-        if level == 0 {
-            return "".to_string();
+    /// Returns whether the reference was cut off by the depth of the
+    /// introspection query: a list or non-null wrapper with nothing inside
+    pub fn is_truncated(&self) -> bool {
+        match &self.of_type {
+            Some(typ) => typ.is_truncated(),
+            None => (self.is_list() || self.is_required()) && self.name.is_none(),
         }
+    }
 
+    pub fn get_actual_kind(&self) -> String {
         // When we encounter ofType: null, we have the kind
         match &self.of_type {
-            Some(typ) => typ.recurse_actual_kind(level - 1),
+            Some(typ) => typ.get_actual_kind(),
             None => match &self.kind {
                 Some(kind) => kind.to_string(),
                 None => "".to_string(),
@@ -164,14 +496,150 @@ impl TypeRef {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Directive {
-    name: Option<String>,
-    description: Option<String>,
-    locations: Option<Vec<String>>,
-    args: Option<Vec<Input>>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub locations: Option<Vec<String>>,
+    pub args: Option<Vec<Input>>,
+    #[serde(alias = "isRepeatable")]
+    pub is_repeatable: Option<bool>,
+}
+
+/// A schema element that has a schema coordinate
+pub enum ElementMut<'a> {
+    Type(&'a mut Type),
+    Field(&'a mut Field),
+    Input(&'a mut Input),
+    Enum(&'a mut Enum),
+    Directive(&'a mut Directive),
+}
+
+impl ElementMut<'_> {
+    pub fn description_mut(&mut self) -> &mut Option<String> {
+        match self {
+            ElementMut::Type(typ) => &mut typ.description,
+            ElementMut::Field(field) => &mut field.description,
+            ElementMut::Input(input) => &mut input.description,
+            ElementMut::Enum(value) => &mut value.description,
+            ElementMut::Directive(directive) => &mut directive.description,
+        }
+    }
+
+    /// Returns the version the element was added in, or None for elements
+    /// that can't record one
+    pub fn since_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            ElementMut::Type(typ) => Some(&mut typ.since),
+            ElementMut::Field(field) => Some(&mut field.since),
+            ElementMut::Input(input) => Some(&mut input.since),
+            ElementMut::Enum(value) => Some(&mut value.since),
+            ElementMut::Directive(_) => None,
+        }
+    }
+
+    /// Returns the element's example values, or None for elements that
+    /// can't have any
+    pub fn examples_mut(&mut self) -> Option<&mut Option<Vec<String>>> {
+        match self {
+            ElementMut::Type(typ) => Some(&mut typ.examples),
+            ElementMut::Field(field) => Some(&mut field.examples),
+            ElementMut::Input(input) => Some(&mut input.examples),
+            ElementMut::Enum(_) | ElementMut::Directive(_) => None,
+        }
+    }
+
+    /// Returns the element's long-form prose, or None for elements that
+    /// can't have any
+    pub fn prose_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            ElementMut::Type(typ) => Some(&mut typ.prose),
+            ElementMut::Field(field) => Some(&mut field.prose),
+            ElementMut::Input(_) | ElementMut::Enum(_) | ElementMut::Directive(_) => None,
+        }
+    }
+}
+
+/// What an introspected endpoint reported about itself, for provenance
+#[derive(Debug, Default)]
+pub struct Endpoint {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub extensions: Option<Value>,
+}
+
+/// What to do when sources define the same type differently
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MergeStrategy {
+    /// Stop with an error that lists the conflicts
+    Error,
+    /// Keep the earlier source's definitions
+    #[default]
+    FirstWins,
+    /// Keep both, renaming the later source's type with a suffix
+    SuffixRename,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(MergeStrategy::Error),
+            "first-wins" => Ok(MergeStrategy::FirstWins),
+            "suffix-rename" => Ok(MergeStrategy::SuffixRename),
+            _ => Err(format!("invalid merge strategy: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeStrategy::Error => write!(f, "error"),
+            MergeStrategy::FirstWins => write!(f, "first-wins"),
+            MergeStrategy::SuffixRename => write!(f, "suffix-rename"),
+        }
+    }
+}
+
+/// A type, field, or directive a source defines differently from an
+/// earlier source
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeConflict {
+    /// The coordinate, such as `Player.team` or `@auth`
+    pub coordinate: String,
+    pub source: String,
+    /// What the source's definition of the type was renamed to, if it was
+    /// kept
+    pub renamed: Option<String>,
+}
+
+// Response headers worth recording because they identify the server or
+// the GraphQL implementation and its version
+fn is_provenance_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name == "server"
+        || name == "x-powered-by"
+        || name.contains("version")
+        || name.contains("graphql")
+}
+
+// Returns how long a Retry-After header says to wait, given in seconds or
+// as an HTTP date
+fn get_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => DateTime::parse_from_rfc2822(value).ok().map(|date| {
+            (date.with_timezone(&Utc) - now)
+                .to_std()
+                .unwrap_or_default()
+        }),
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Schema {
+    pub description: Option<String>,
     #[serde(alias = "queryType")]
     pub query_type: Option<Type>,
     #[serde(alias = "mutationType")]
@@ -182,53 +650,179 @@ pub struct Schema {
     pub directives: Option<Vec<Directive>>,
 }
 
-impl Schema {
-    pub fn from_url(url: &str, headers: &[String]) -> Result<Schema, Box<dyn Error>> {
-        let client = Client::new();
-        let mut post = client.post(url);
-        for header in headers {
-            let split: Vec<&str> = header.split(':').collect();
-            if split.len() == 2 {
-                post = post.header(split[0], split[1]);
-            }
+/// Runs the introspection query against the URL and returns the request
+/// and response, for `Schema::from_exchange` to read or --record to save
+///
+/// # Arguments
+///
+/// * `url` - the GraphQL endpoint
+/// * `headers` - `Name:Value` headers to send, which may reference environment variables
+/// * `request` - the body to send, from `to_introspection_request`
+/// * `insecure` - whether to skip verifying the endpoint's TLS certificate
+/// * `user_agent` - the User-Agent to send unless `headers` has one
+/// * `credentials` - the login and password to send with basic auth
+/// * `max_wait` - how long to wait in all for a rate-limited endpoint
+///   before giving up
+pub fn introspect(
+    url: &str,
+    headers: &[String],
+    request: &str,
+    insecure: bool,
+    user_agent: &str,
+    credentials: Option<&Credentials>,
+    max_wait: Duration,
+) -> Result<Exchange, Box<dyn Error>> {
+    // Default headers only apply when the request doesn't set its own,
+    // so --header can still override them
+    let mut default_headers = HeaderMap::new();
+    default_headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    let client = Client::builder()
+        .danger_accept_invalid_certs(insecure)
+        .user_agent(user_agent)
+        .default_headers(default_headers)
+        .build()?;
+    let mut post = client.post(url);
+    if let Some(credentials) = credentials {
+        post = post.basic_auth(&credentials.login, Some(&credentials.password));
+    }
+    for header in headers {
+        let header = expand_env_vars(header)?;
+        let split: Vec<&str> = header.splitn(2, ':').collect();
+        if split.len() == 2 {
+            post = post.header(split[0].trim(), split[1].trim());
+        }
+    }
+    let post = post
+        .header("Content-Type", "application/json")
+        .body(request.to_string());
+
+    // Retry while the endpoint is rate limiting us, as long as it says
+    // to retry within --max-wait
+    let mut waited = Duration::ZERO;
+    let mut backoff = RETRY_BACKOFF;
+    let response = loop {
+        let response = post
+            .try_clone()
+            .ok_or("can't retry the introspection request")?
+            .send()?;
+        if response.status() != StatusCode::TOO_MANY_REQUESTS {
+            break response;
+        }
+        let wait = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| get_retry_after(value, Utc::now()))
+            .unwrap_or(backoff);
+        if waited + wait > max_wait {
+            return Err(format!(
+                "{} is rate limiting requests and asked to wait {}s, beyond --max-wait {}s",
+                url,
+                wait.as_secs(),
+                max_wait.as_secs()
+            )
+            .into());
         }
-        let text = post
-            .header("Content-Type", "application/json")
-            .body(format!("{{\"query\": \"{}\"}}", SCHEMA_QUERY).replace("\n", ""))
-            .send()?
-            .text()?;
+        eprintln!(
+            "{} is rate limiting requests; retrying in {}s",
+            url,
+            wait.as_secs()
+        );
+        thread::sleep(wait);
+        waited += wait;
+        backoff *= 2;
+    };
+
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter(|(name, _)| name.as_str() != "set-cookie")
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect();
+    Ok(Exchange {
+        url: url.to_string(),
+        request: request.to_string(),
+        status,
+        headers,
+        body: response.text()?,
+    })
+}
+
+impl Schema {
+    /// Reads the schema from an introspection response, along with what the
+    /// endpoint reported about itself
+    pub fn from_exchange(exchange: &Exchange) -> Result<(Schema, Endpoint), Box<dyn Error>> {
+        let mut introspection: Introspection = serde_json::from_str(&exchange.body)?;
+        let endpoint = Endpoint {
+            url: exchange.url.clone(),
+            headers: exchange
+                .headers
+                .iter()
+                .filter(|(name, _)| is_provenance_header(name))
+                .cloned()
+                .collect(),
+            extensions: introspection.extensions.take(),
+        };
+        Ok((introspection.into_schema()?, endpoint))
+    }
 
-        Schema::from_str(&text)
+    /// Reads an introspection result from a file, decompressing it if it
+    /// ends in `.gz` or `.zst`, without reading the whole file into memory
+    pub fn from_json(file: &Path) -> Result<Schema, Box<dyn Error>> {
+        let introspection: Introspection =
+            serde_json::from_reader(BufReader::new(open_file(file)?))?;
+        introspection.into_schema()
     }
 
-    pub fn from_json(file: &PathBuf) -> Result<Schema, Box<dyn Error>> {
-        let contents = fs::read_to_string(file)?;
-        Schema::from_str(&contents)
+    pub fn from_schema(file: &Path) -> Result<Schema, Box<dyn Error>> {
+        let contents = read_file(file)?;
+        Schema::from_sdl(&contents)
     }
 
-    pub fn from_schema(_file: &PathBuf) -> Result<Schema, Box<dyn Error>> {
-        Err(Box::new(SchemaError::new("not yet implemented")))
+    pub fn from_sdl(text: &str) -> Result<Schema, Box<dyn Error>> {
+        sdl::parse(text)
     }
 
+    /// Merges the type definitions and `extend` blocks in a GraphQL schema
+    /// file on top of this schema, to document additions (such as a
+    /// gateway's) that the source schema doesn't know about
+    pub fn apply_overlay(&mut self, file: &Path) -> Result<(), Box<dyn Error>> {
+        let contents = read_file(file)?;
+        sdl::apply(self, &contents)
+            .map_err(|err| format!("invalid overlay {}: {}", file.display(), err).into())
+    }
+
+    /// Parses text that is either an introspection response or SDL,
+    /// sniffing which one it is: SDL can never start with a brace
+    pub fn from_text(text: &str) -> Result<Schema, Box<dyn Error>> {
+        if text.trim_start().starts_with('{') {
+            Schema::from_str(text)
+        } else {
+            Schema::from_sdl(text)
+        }
+    }
+
+    /// Parses an introspection result, which can be a full response
+    /// (`{"data":{"__schema":…}}`), just the `{"__schema":…}` object, or the
+    /// bare schema object that tools like Apollo Rover emit
     pub fn from_str(text: &str) -> Result<Schema, Box<dyn Error>> {
-        match serde_json::from_str(&text)? {
-            Value::Object(map) => match map.get("data") {
-                Some(data) => match data.get("__schema") {
-                    Some(schema) => {
-                        let s: Schema = serde_json::from_str(&schema.to_string())?;
-                        Ok(s)
-                    }
-                    None => Err(Box::new(SchemaError::new("schema not in response"))),
-                },
-                None => Err(Box::new(SchemaError::new("data not in response"))),
-            },
-            _ => {
-                // I don't think this is reachable; as far as I can tell,
-                // serde_json::from_str() fails if text is not a JSON object.
-                // You can't pass it an array, for example. So if line 14 passes,
-                // we're already guaranteed to have an object.
-                Err(Box::new(SchemaError::new("response format not an object")))
-            }
+        serde_json::from_str::<Introspection>(text)?.into_schema()
+    }
+
+    fn empty() -> Schema {
+        Schema {
+            description: None,
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
         }
     }
 
@@ -246,43 +840,284 @@ impl Schema {
 
     pub fn get_type(&self, name: &str) -> Option<&Type> {
         match &self.types {
-            Some(types) => {
-                for typ in types.iter() {
-                    match &typ.name {
-                        Some(n) => {
-                            if n == name {
-                                return Some(&typ);
-                            }
-                        }
-                        None => {}
-                    }
-                }
-                None
-            }
+            Some(types) => types.iter().find(|typ| typ.name.as_deref() == Some(name)),
             None => None,
         }
     }
 
     pub fn get_types_of_kind(&self, kind: &str) -> Vec<&Type> {
-        let mut vec = Vec::new();
-
         match &self.types {
-            Some(types) => {
-                for typ in types.iter() {
-                    match &typ.kind {
-                        Some(k) => {
-                            if k == kind {
-                                vec.push(typ);
-                            }
-                        }
-                        None => {}
+            Some(types) => types
+                .iter()
+                .filter(|typ| typ.kind.as_deref() == Some(kind))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Renames types throughout the schema (definitions, references, and
+    /// root operation types) so aliases show up consistently everywhere
+    ///
+    /// # Arguments
+    ///
+    /// * `aliases` - the new names, keyed by the names in the schema
+    pub fn apply_aliases(&mut self, aliases: &HashMap<String, String>) {
+        if aliases.is_empty() {
+            return;
+        }
+
+        let rename = |name: &mut Option<String>| {
+            if let Some(alias) = name.as_ref().and_then(|n| aliases.get(n)) {
+                *name = Some(alias.clone());
+            }
+        };
+
+        for root in self
+            .query_type
+            .iter_mut()
+            .chain(self.mutation_type.iter_mut())
+            .chain(self.subscription_type.iter_mut())
+        {
+            rename(&mut root.name);
+        }
+
+        for typ in self.types.iter_mut().flatten() {
+            rename(&mut typ.name);
+            typ.for_each_type_ref_mut(&mut |type_ref| type_ref.rename(aliases));
+        }
+
+        for directive in self.directives.iter_mut().flatten() {
+            for arg in directive.args.iter_mut().flatten() {
+                if let Some(input_type) = &mut arg.input_type {
+                    input_type.rename(aliases);
+                }
+            }
+        }
+    }
+
+    /// Calls `f` with every type, field, argument, input field, enum value,
+    /// and directive in the schema, along with its schema coordinate:
+    /// `Type`, `Type.field`, `Type.field(arg:)`, `@directive`, or
+    /// `@directive(arg:)`
+    pub fn for_each_element_mut(&mut self, f: &mut dyn FnMut(&str, ElementMut)) {
+        for typ in self.types.iter_mut().flatten() {
+            let type_name = typ.name.clone().unwrap_or_default();
+            for field in typ.fields.iter_mut().flatten() {
+                let coordinate = format!(
+                    "{}.{}",
+                    type_name,
+                    field.name.as_deref().unwrap_or_default()
+                );
+                for_each_arg_mut(&coordinate, &mut field.args, f);
+                f(&coordinate, ElementMut::Field(field));
+            }
+            for input in typ.inputs.iter_mut().flatten() {
+                let coordinate = format!(
+                    "{}.{}",
+                    type_name,
+                    input.name.as_deref().unwrap_or_default()
+                );
+                f(&coordinate, ElementMut::Input(input));
+            }
+            for value in typ.enums.iter_mut().flatten() {
+                let coordinate = format!(
+                    "{}.{}",
+                    type_name,
+                    value.name.as_deref().unwrap_or_default()
+                );
+                f(&coordinate, ElementMut::Enum(value));
+            }
+            f(&type_name, ElementMut::Type(typ));
+        }
+
+        for directive in self.directives.iter_mut().flatten() {
+            let coordinate = format!("@{}", directive.name.as_deref().unwrap_or_default());
+            for_each_arg_mut(&coordinate, &mut directive.args, f);
+            f(&coordinate, ElementMut::Directive(directive));
+        }
+    }
+
+    /// Merges another schema into this one, so several services can be
+    /// documented together. The other schema's root types are renamed to
+    /// match this one's, and where both define the same type, their fields,
+    /// input fields, values, interfaces, and members are combined. Returns
+    /// the coordinates the schemas define differently, sorted; this schema's
+    /// definitions win
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the schema to merge into this one
+    pub fn merge(&mut self, mut other: Schema) -> Vec<String> {
+        let mut root_aliases = HashMap::new();
+        for (root, other_root) in [
+            (&mut self.query_type, &other.query_type),
+            (&mut self.mutation_type, &other.mutation_type),
+            (&mut self.subscription_type, &other.subscription_type),
+        ] {
+            match (
+                Schema::get_type_name(root),
+                Schema::get_type_name(other_root),
+            ) {
+                (Some(name), Some(other_name)) if name != other_name => {
+                    root_aliases.insert(other_name, name);
+                }
+                (None, Some(other_name)) => {
+                    *root = Some(Type {
+                        name: Some(other_name),
+                        ..Default::default()
+                    })
+                }
+                _ => {}
+            }
+        }
+        other.apply_aliases(&root_aliases);
+
+        if self.description.is_none() {
+            self.description = other.description;
+        }
+
+        let mut conflicts = Vec::new();
+        let types = self.types.get_or_insert_with(Vec::new);
+        for other_type in other.types.unwrap_or_default() {
+            match types.iter_mut().find(|typ| typ.name == other_type.name) {
+                Some(typ) => typ.merge(other_type, &mut conflicts),
+                None => types.push(other_type),
+            }
+        }
+
+        let directives = self.directives.get_or_insert_with(Vec::new);
+        for other_directive in other.directives.unwrap_or_default() {
+            match directives.iter().find(|d| d.name == other_directive.name) {
+                Some(directive) => {
+                    if directive.args != other_directive.args
+                        || directive.locations != other_directive.locations
+                    {
+                        conflicts.push(format!("@{}", other_directive.name.unwrap_or_default()));
                     }
                 }
+                None => directives.push(other_directive),
             }
-            None => {}
         }
 
-        vec
+        // Servers often differ in which introspection features they support,
+        // which isn't worth a warning
+        conflicts.retain(|coordinate| !coordinate.starts_with("__"));
+        conflicts.sort();
+        conflicts.dedup();
+        conflicts
+    }
+
+    /// Merges another schema into this one like `merge`, but first renames
+    /// the other schema's types that the two define differently by adding
+    /// the suffix, so both definitions are documented, each noting the
+    /// other. Root types can't be renamed, so this schema's definitions of
+    /// their fields win. Returns the conflicts, sorted, and the renamed
+    /// types' new names, keyed by their old ones
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the schema to merge into this one
+    /// * `suffix` - what to add to the names of the other schema's
+    ///   conflicting types, such as `_2`
+    pub fn merge_renaming(
+        &mut self,
+        mut other: Schema,
+        suffix: &str,
+    ) -> (Vec<String>, HashMap<String, String>) {
+        let roots: Vec<String> = vec![
+            self.get_query_name(),
+            self.get_mutation_name(),
+            self.get_subscription_name(),
+            other.get_query_name(),
+            other.get_mutation_name(),
+            other.get_subscription_name(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mut conflicts = Vec::new();
+        let mut renames = HashMap::new();
+        for other_type in other.types.iter().flatten() {
+            let name = other_type.name.as_deref().unwrap_or_default();
+            if name.starts_with("__") || roots.iter().any(|root| root == name) {
+                continue;
+            }
+            let type_conflicts = match self.get_type(name) {
+                Some(typ) => typ.get_conflicts(other_type),
+                None => continue,
+            };
+            if !type_conflicts.is_empty() {
+                let mut renamed = format!("{}{}", name, suffix);
+                while self.get_type(&renamed).is_some() || other.get_type(&renamed).is_some() {
+                    renamed.push_str(suffix);
+                }
+                conflicts.extend(type_conflicts);
+                renames.insert(name.to_string(), renamed);
+            }
+        }
+        other.apply_aliases(&renames);
+
+        conflicts.extend(self.merge(other));
+        for (name, renamed) in &renames {
+            self.add_duplicate(name, renamed);
+            self.add_duplicate(renamed, name);
+        }
+        conflicts.sort();
+        conflicts.dedup();
+        (conflicts, renames)
+    }
+
+    // Notes on a type another definition of it, under another name
+    fn add_duplicate(&mut self, name: &str, duplicate: &str) {
+        let kind = self.get_type(duplicate).and_then(|typ| typ.kind.clone());
+        if let Some(typ) = self
+            .types
+            .iter_mut()
+            .flatten()
+            .find(|typ| typ.name.as_deref() == Some(name))
+        {
+            typ.duplicates.get_or_insert_with(Vec::new).push(TypeRef {
+                name: Some(duplicate.to_string()),
+                kind,
+                of_type: None,
+            });
+        }
+    }
+
+    /// Returns the `Type.field` (or `Type.field(arg:)`) coordinates whose type
+    /// references were cut off by the depth of the introspection query
+    pub fn get_truncated_type_refs(&self) -> Vec<String> {
+        let mut coordinates = Vec::new();
+        for typ in self.types.iter().flatten() {
+            let type_name = typ.name.as_deref().unwrap_or_default();
+            for field in typ.fields.iter().flatten() {
+                let field_name = field.name.as_deref().unwrap_or_default();
+                if field.field_type.as_ref().is_some_and(TypeRef::is_truncated) {
+                    coordinates.push(format!("{}.{}", type_name, field_name));
+                }
+                for arg in field.args.iter().flatten() {
+                    if arg.input_type.as_ref().is_some_and(TypeRef::is_truncated) {
+                        coordinates.push(format!(
+                            "{}.{}({}:)",
+                            type_name,
+                            field_name,
+                            arg.name.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+            for input in typ.inputs.iter().flatten() {
+                if input.input_type.as_ref().is_some_and(TypeRef::is_truncated) {
+                    coordinates.push(format!(
+                        "{}.{}",
+                        type_name,
+                        input.name.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        coordinates
     }
 
     fn get_type_name(typ: &Option<Type>) -> Option<String> {
@@ -290,6 +1125,300 @@ impl Schema {
     }
 }
 
+/// Expands `${NAME}` and `$NAME` references to environment variables so
+/// secrets don't have to be passed literally on the command line. Use `$$`
+/// for a literal `$`.
+fn expand_env_vars(text: &str) -> Result<String, Box<dyn Error>> {
+    let mut s = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            s.push(c);
+            continue;
+        }
+
+        let name: String = match chars.peek() {
+            Some('$') => {
+                chars.next();
+                s.push('$');
+                continue;
+            }
+            Some('{') => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => {
+                            return Err(Box::new(SchemaError::new(&format!(
+                                "unterminated variable reference in header '{}'",
+                                text
+                            ))))
+                        }
+                    }
+                }
+                if name.is_empty() {
+                    return Err(Box::new(SchemaError::new(&format!(
+                        "empty variable reference in header '{}'",
+                        text
+                    ))));
+                }
+                name
+            }
+            _ => {
+                let mut name = String::new();
+                while let Some(c) = chars.peek() {
+                    if c.is_ascii_alphabetic()
+                        || *c == '_'
+                        || (!name.is_empty() && c.is_ascii_digit())
+                    {
+                        name.push(*c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if name.is_empty() {
+                    s.push('$');
+                    continue;
+                }
+                name
+            }
+        };
+
+        match env::var(&name) {
+            Ok(value) => s.push_str(&value),
+            Err(_) => {
+                return Err(Box::new(SchemaError::new(&format!(
+                    "environment variable {} is not set",
+                    name
+                ))))
+            }
+        }
+    }
+
+    Ok(s)
+}
+
+fn for_each_arg_mut(
+    coordinate: &str,
+    args: &mut Option<Vec<Input>>,
+    f: &mut dyn FnMut(&str, ElementMut),
+) {
+    for arg in args.iter_mut().flatten() {
+        let arg_coordinate = format!(
+            "{}({}:)",
+            coordinate,
+            arg.name.as_deref().unwrap_or_default()
+        );
+        f(&arg_coordinate, ElementMut::Input(arg));
+    }
+}
+
+/// Reads the file as text, decompressing it first if it ends in .gz or .zst
+fn read_file(file: &Path) -> Result<String, Box<dyn Error>> {
+    let mut contents = String::new();
+    open_file(file)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+// Opens the file, decompressing it as it's read if it ends in .gz or .zst
+fn open_file(file: &Path) -> Result<Box<dyn Read>, Box<dyn Error>> {
+    let extension = file
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    let reader = File::open(file)?;
+    Ok(match extension.as_deref() {
+        Some("gz") => Box::new(MultiGzDecoder::new(reader)),
+        Some("zst") => Box::new(zstd::Decoder::new(reader)?),
+        _ => Box::new(reader),
+    })
+}
+
+/// Returns the JSON body of the introspection request, with the operation
+/// name and variables that gateways multiplexing on them need
+///
+/// # Arguments
+///
+/// * `type_depth` - how many levels of `ofType` to request for type references
+/// * `operation_name` - the name to give the query and send as `operationName`
+/// * `variables` - `name=value` variables to send, where a value that isn't
+///   JSON is sent as a string
+pub fn to_introspection_request(
+    type_depth: u32,
+    capabilities: &Capabilities,
+    operation_name: Option<&str>,
+    variables: &[String],
+) -> Result<String, Box<dyn Error>> {
+    to_request(
+        &get_schema_query(type_depth, capabilities),
+        operation_name,
+        variables,
+    )
+}
+
+/// Returns the JSON body of the request that asks an endpoint which
+/// optional introspection fields it supports, for
+/// `Capabilities::from_response` to read
+///
+/// # Arguments
+///
+/// * `operation_name` - the name to give the query and send as `operationName`
+/// * `variables` - `name=value` variables to send, as with the introspection
+///   request
+pub fn to_capabilities_request(
+    operation_name: Option<&str>,
+    variables: &[String],
+) -> Result<String, Box<dyn Error>> {
+    to_request(CAPABILITIES_QUERY, operation_name, variables)
+}
+
+fn to_request(
+    query: &str,
+    operation_name: Option<&str>,
+    variables: &[String],
+) -> Result<String, Box<dyn Error>> {
+    let mut query = query.replace('\n', "");
+    let mut request = serde_json::Map::new();
+    if let Some(name) = operation_name {
+        if !is_name(name) {
+            return Err(format!("invalid operation name: {}", name).into());
+        }
+        query = query.replacen(SCHEMA_QUERY_NAME, name, 1);
+        request.insert("operationName".to_string(), Value::from(name));
+    }
+    request.insert("query".to_string(), Value::from(query));
+    if !variables.is_empty() {
+        let mut values = serde_json::Map::new();
+        for variable in variables {
+            let (name, value) = variable
+                .split_once('=')
+                .ok_or_else(|| format!("invalid variable {}; use name=value", variable))?;
+            let value = serde_json::from_str(value).unwrap_or_else(|_| Value::from(value));
+            values.insert(name.trim().to_string(), value);
+        }
+        request.insert("variables".to_string(), Value::Object(values));
+    }
+    Ok(Value::Object(request).to_string())
+}
+
+// Returns whether the text is a GraphQL name
+fn is_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+fn get_schema_query(type_depth: u32, capabilities: &Capabilities) -> String {
+    let mut type_ref = String::from("fragment TypeRef on __Type {\n  kind\n  name\n");
+    for level in 1..=type_depth as usize {
+        let indent = "  ".repeat(level);
+        type_ref.push_str(&format!(
+            "{}ofType {{\n{}  kind\n{}  name\n",
+            indent, indent, indent
+        ));
+    }
+    for level in (0..=type_depth as usize).rev() {
+        type_ref.push_str(&format!("{}}}\n", "  ".repeat(level)));
+    }
+    let mut query = SCHEMA_QUERY.to_string();
+    if capabilities.schema_description {
+        query = query.replacen("  __schema {\n", "  __schema {\n    description\n", 1);
+    }
+    if capabilities.is_repeatable {
+        query = query.replacen(
+            "      locations\n",
+            "      isRepeatable\n      locations\n",
+            1,
+        );
+    }
+    if let Some(field) = capabilities.specified_by_url {
+        query = query.replacen(
+            "  fields(includeDeprecated: true) {\n",
+            &format!("  {}\n  fields(includeDeprecated: true) {{\n", field),
+            1,
+        );
+    }
+    if capabilities.is_one_of {
+        query = query.replacen(
+            "  fields(includeDeprecated: true) {\n",
+            "  isOneOf\n  fields(includeDeprecated: true) {\n",
+            1,
+        );
+    }
+    format!("{}\n{}", query, type_ref.trim_end())
+}
+
+/// The optional introspection fields an endpoint supports. Asking for a
+/// field a server doesn't know fails the whole query, so the introspection
+/// query only asks for the ones the endpoint reports having.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Capabilities {
+    /// Whether `__Schema` has `description`
+    pub schema_description: bool,
+
+    /// The name `__Type` gives `specifiedByURL`, which older servers spell
+    /// `specifiedByUrl`, if it has it
+    pub specified_by_url: Option<&'static str>,
+
+    /// Whether `__Type` has `isOneOf`
+    pub is_one_of: bool,
+
+    /// Whether `__Directive` has `isRepeatable`
+    pub is_repeatable: bool,
+}
+
+impl Capabilities {
+    /// Reads the capabilities from the response to the request from
+    /// `to_capabilities_request`. A response it can't read, as from a server
+    /// that doesn't allow `__type`, means no optional fields.
+    pub fn from_response(body: &str) -> Capabilities {
+        let response: Value = serde_json::from_str(body).unwrap_or_default();
+        let has_field = |alias: &str, name: &str| {
+            response["data"][alias]["fields"]
+                .as_array()
+                .is_some_and(|fields| fields.iter().any(|field| field["name"] == name))
+        };
+        Capabilities {
+            schema_description: has_field("schema", "description"),
+            specified_by_url: ["specifiedByURL", "specifiedByUrl"]
+                .iter()
+                .find(|name| has_field("type", name))
+                .copied(),
+            is_one_of: has_field("type", "isOneOf"),
+            is_repeatable: has_field("directive", "isRepeatable"),
+        }
+    }
+}
+
+// Asks which fields the introspection types have, using the same name as
+// the introspection query so --operation-name renames both
+const CAPABILITIES_QUERY: &str = r#"query IntrospectionQuery {
+  schema: __type(name: "__Schema") {
+    fields {
+      name
+    }
+  }
+  type: __type(name: "__Type") {
+    fields {
+      name
+    }
+  }
+  directive: __type(name: "__Directive") {
+    fields {
+      name
+    }
+  }
+}
+"#;
+
+// The name of the introspection query unless --operation-name renames it
+const SCHEMA_QUERY_NAME: &str = "IntrospectionQuery";
+
 const SCHEMA_QUERY: &str = r#"query IntrospectionQuery {
   __schema {
     queryType {
@@ -356,87 +1485,259 @@ fragment InputValue on __InputValue {
   }
   defaultValue
 }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::{fs, io::Write};
+
+    #[test]
+    fn from_str_should_pass_when_empty_schema() {
+        let response = r#"{
+            "data": {
+                "__schema": {
+                }
+            }
+        }"#;
+        assert!(Schema::from_str(response).is_ok(), "schema should parse");
+    }
+
+    #[test]
+    fn from_str_should_fail_when_not_json() {
+        let response = "test";
+        match Schema::from_str(response) {
+            Ok(_) => panic!("plain text should fail"),
+            Err(err) => assert_eq!("expected ident at line 1 column 2", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn from_str_should_fail_when_no_data() {
+        let response = r#"{
+        }"#;
+        match Schema::from_str(response) {
+            Ok(_) => panic!("schema should have data"),
+            Err(err) => assert_eq!("data not in response", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn from_str_should_fail_with_errors_when_data_null() {
+        let response = r#"{
+            "data": null,
+            "errors": [
+                { "message": "Introspection is disabled" },
+                { "message": "Not authorized", "path": ["__schema"] }
+            ]
+        }"#;
+        match Schema::from_str(response) {
+            Ok(_) => panic!("null data has no schema"),
+            Err(err) => assert_eq!(
+                "schema not in response: Introspection is disabled; Not authorized",
+                err.to_string()
+            ),
+        }
+        match Schema::from_str(r#"{ "data": null }"#) {
+            Ok(_) => panic!("null data has no schema"),
+            Err(err) => assert_eq!("schema not in response", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn from_str_should_fail_when_no_schema() {
+        let response = r#"{
+            "data": {
+            }
+        }"#;
+        match Schema::from_str(response) {
+            Ok(_) => panic!("schema should have __schema"),
+            Err(err) => assert_eq!("schema not in response", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn from_json_should_decompress_gzip() -> Result<(), Box<dyn Error>> {
+        let file = env::temp_dir().join("gumwood-response.json.gz");
+        let mut encoder = GzEncoder::new(File::create(&file)?, Compression::default());
+        encoder.write_all(&fs::read("testdata/response.json")?)?;
+        encoder.finish()?;
+        let schema = Schema::from_json(&file)?;
+        fs::remove_file(&file)?;
+        assert!(schema.get_query_name().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn from_schema_should_decompress_zstd() -> Result<(), Box<dyn Error>> {
+        let file = env::temp_dir().join("gumwood-schema.graphql.zst");
+        fs::write(
+            &file,
+            zstd::encode_all(&fs::read("testdata/schema.graphql")?[..], 0)?,
+        )?;
+        let schema = Schema::from_schema(&file)?;
+        fs::remove_file(&file)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
+    }
 
-fragment TypeRef on __Type {
-  kind
-  name
-  ofType {
-    kind
-    name
-    ofType {
-      kind
-      name
-      ofType {
-        kind
-        name
-        ofType {
-          kind
-          name
-          ofType {
-            kind
-            name
-            ofType {
-              kind
-              name
-              ofType {
-                kind
-                name
-              }
-            }
-          }
+    #[test]
+    fn apply_overlay_should_merge_types_and_extensions() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_json(Path::new("testdata/response.json"))?;
+        schema.apply_overlay(Path::new("testdata/overlay.graphql"))?;
+        assert_eq!(
+            Some("OBJECT".to_string()),
+            schema.get_type("GatewayStatus").unwrap().kind
+        );
+        let status = schema
+            .get_type("Query")
+            .unwrap()
+            .fields
+            .iter()
+            .flatten()
+            .find(|field| field.name.as_deref() == Some("gatewayStatus"))
+            .unwrap();
+        assert_eq!(
+            "OBJECT",
+            status.field_type.as_ref().unwrap().get_actual_kind()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_overlay_should_fail_when_extending_unknown_type() {
+        let mut schema = Schema::from_sdl("type Query { a: Int }").unwrap();
+        let file = env::temp_dir().join("gumwood-bad-overlay.graphql");
+        fs::write(&file, "extend type Missing { b: Int }").unwrap();
+        let result = schema.apply_overlay(&file);
+        fs::remove_file(&file).unwrap();
+        match result {
+            Ok(_) => panic!("extending an unknown type should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .ends_with("cannot extend unknown type Missing")),
         }
-      }
     }
-  }
-}"#;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn from_text_should_parse_introspection_json() -> Result<(), Box<dyn Error>> {
+        let response = r#"
+            {"data": {"__schema": {"queryType": {"name": "Query"}}}}"#;
+        let schema = Schema::from_text(response)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
+    }
 
     #[test]
-    fn from_str_should_pass_when_empty_schema() {
+    fn from_text_should_parse_sdl() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_text("# players\ntype Query { players: [String] }")?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn from_text_should_report_json_errors_for_json() {
+        assert!(Schema::from_text("{ nope").is_err());
+    }
+
+    #[test]
+    fn from_str_should_fail_when_not_object() {
+        assert!(Schema::from_str("[]").is_err());
+    }
+
+    #[test]
+    fn from_str_should_skip_other_keys() -> Result<(), Box<dyn Error>> {
         let response = r#"{
+            "errors": [{ "message": "partial" }],
             "data": {
-                "__schema": {
-                }
-            }
+                "other": { "__schema": null },
+                "__schema": { "queryType": { "name": "Query" } }
+            },
+            "extensions": { "cost": 5 }
         }"#;
-        match Schema::from_str(&response) {
-            Err(_) => assert!(false, "schema should parse"),
-            Ok(_) => assert!(true),
-        }
+        let schema = Schema::from_str(response)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
     }
 
     #[test]
-    fn from_str_should_fail_when_not_json() {
-        let response = "test";
-        match Schema::from_str(response) {
-            Ok(_) => assert!(false, "plain text should fail"),
-            Err(err) => assert_eq!("expected ident at line 1 column 2", err.to_string()),
+    fn introspection_should_keep_extensions() -> Result<(), Box<dyn Error>> {
+        let introspection: Introspection =
+            serde_json::from_str(r#"{ "extensions": { "cost": 5 }, "data": { "__schema": {} } }"#)?;
+        assert_eq!(
+            Some(serde_json::json!({ "cost": 5 })),
+            introspection.extensions
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_not_treat_description_as_bare_schema() {
+        match Schema::from_str(r#"{ "description": "Not a schema" }"#) {
+            Ok(_) => panic!("description alone isn't a schema"),
+            Err(err) => assert_eq!("data not in response", err.to_string()),
         }
     }
 
     #[test]
-    fn from_str_should_fail_when_no_data() {
+    fn from_str_should_pass_when_schema_without_data() -> Result<(), Box<dyn Error>> {
         let response = r#"{
+            "__schema": {
+                "queryType": {
+                    "name": "Query"
+                }
+            }
         }"#;
-        match Schema::from_str(&response) {
-            Ok(_) => assert!(false, "schema should have data"),
-            Err(err) => assert_eq!("data not in response", err.to_string()),
-        }
+        let schema = Schema::from_str(response)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
     }
 
     #[test]
-    fn from_str_should_fail_when_no_schema() {
+    fn from_str_should_pass_when_bare_schema() -> Result<(), Box<dyn Error>> {
+        let response = r#"{
+            "queryType": {
+                "name": "Query"
+            },
+            "types": []
+        }"#;
+        let schema = Schema::from_str(response)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        assert!(schema.types.unwrap().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_should_parse_june_2021_fields() -> Result<(), Box<dyn Error>> {
         let response = r#"{
             "data": {
+                "__schema": {
+                    "description": "The league schema",
+                    "types": [
+                        {
+                            "kind": "SCALAR",
+                            "name": "UUID",
+                            "specifiedByURL": "https://tools.ietf.org/html/rfc4122"
+                        }
+                    ],
+                    "directives": [
+                        {
+                            "name": "tag",
+                            "isRepeatable": true
+                        }
+                    ]
+                }
             }
         }"#;
-        match Schema::from_str(&response) {
-            Ok(_) => assert!(false, "schema should have __schema"),
-            Err(err) => assert_eq!("schema not in response", err.to_string()),
-        }
+        let schema = Schema::from_str(response)?;
+        assert_eq!(Some("The league schema".to_string()), schema.description);
+        assert_eq!(
+            Some("https://tools.ietf.org/html/rfc4122".to_string()),
+            schema.get_type("UUID").unwrap().specified_by_url
+        );
+        assert_eq!(Some(true), schema.directives.unwrap()[0].is_repeatable);
+        Ok(())
     }
 
     #[test]
@@ -447,7 +1748,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.query_type.is_none());
         Ok(())
     }
@@ -462,7 +1763,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.query_type.is_some());
         Ok(())
     }
@@ -478,7 +1779,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert_eq!("Query", schema.query_type.unwrap().name.unwrap());
         Ok(())
     }
@@ -494,7 +1795,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_query_name().is_some());
         assert_eq!("Query", schema.get_query_name().unwrap());
         Ok(())
@@ -508,7 +1809,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_query_name().is_none());
         Ok(())
     }
@@ -523,7 +1824,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_query_name().is_none());
         Ok(())
     }
@@ -536,7 +1837,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.mutation_type.is_none());
         Ok(())
     }
@@ -551,7 +1852,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.mutation_type.is_some());
         Ok(())
     }
@@ -567,7 +1868,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert_eq!("mutation", schema.mutation_type.unwrap().name.unwrap());
         Ok(())
     }
@@ -583,7 +1884,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_mutation_name().is_some());
         assert_eq!("mutation", schema.get_mutation_name().unwrap());
         Ok(())
@@ -597,7 +1898,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_mutation_name().is_none());
         Ok(())
     }
@@ -612,7 +1913,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_mutation_name().is_none());
         Ok(())
     }
@@ -625,7 +1926,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.subscription_type.is_none());
         Ok(())
     }
@@ -640,7 +1941,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.subscription_type.is_some());
         Ok(())
     }
@@ -656,7 +1957,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert_eq!(
             "subscription",
             schema.subscription_type.unwrap().name.unwrap()
@@ -675,7 +1976,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_subscription_name().is_some());
         assert_eq!("subscription", schema.get_subscription_name().unwrap());
         Ok(())
@@ -689,7 +1990,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_subscription_name().is_none());
         Ok(())
     }
@@ -705,7 +2006,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_subscription_name().is_none());
         Ok(())
     }
@@ -718,7 +2019,7 @@ mod tests {
             }
         }
     }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_type("hello").is_none());
         Ok(())
     }
@@ -736,7 +2037,7 @@ mod tests {
             }
         }
     }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_type("hello").is_none());
         Ok(())
     }
@@ -754,7 +2055,7 @@ mod tests {
             }
         }
     }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_type("hello").is_none());
         Ok(())
     }
@@ -772,11 +2073,112 @@ mod tests {
             }
         }
     }"#;
-        let schema = Schema::from_str(&response)?;
+        let schema = Schema::from_str(response)?;
         assert!(schema.get_type("you're not my").is_some());
         Ok(())
     }
 
+    #[test]
+    fn expand_env_vars_should_return_text_when_no_variables() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            "Authorization:Bearer abc",
+            expand_env_vars("Authorization:Bearer abc")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expand_env_vars_should_expand_braced_variable() -> Result<(), Box<dyn Error>> {
+        env::set_var("GUMWOOD_TEST_BRACED_TOKEN", "secret");
+        assert_eq!(
+            "Authorization:Bearer secret",
+            expand_env_vars("Authorization:Bearer ${GUMWOOD_TEST_BRACED_TOKEN}")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expand_env_vars_should_expand_bare_variable() -> Result<(), Box<dyn Error>> {
+        env::set_var("GUMWOOD_TEST_BARE_TOKEN", "secret");
+        assert_eq!(
+            "X-Token:secret;",
+            expand_env_vars("X-Token:$GUMWOOD_TEST_BARE_TOKEN;")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expand_env_vars_should_keep_escaped_and_lone_dollars() -> Result<(), Box<dyn Error>> {
+        assert_eq!("X-Price:$5 $ $1", expand_env_vars("X-Price:$$5 $ $1")?);
+        Ok(())
+    }
+
+    #[test]
+    fn expand_env_vars_should_not_expand_escaped_variables() -> Result<(), Box<dyn Error>> {
+        env::set_var("GUMWOOD_TEST_ESCAPED_TOKEN", "secret");
+        assert_eq!(
+            "X-Token:$GUMWOOD_TEST_ESCAPED_TOKEN ${GUMWOOD_TEST_ESCAPED_TOKEN} $secret",
+            expand_env_vars(
+                "X-Token:$$GUMWOOD_TEST_ESCAPED_TOKEN $${GUMWOOD_TEST_ESCAPED_TOKEN} $$$GUMWOOD_TEST_ESCAPED_TOKEN"
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expand_env_vars_should_fail_when_braces_unterminated() {
+        env::set_var("GUMWOOD_TEST_UNTERMINATED_TOKEN", "secret");
+        match expand_env_vars("Authorization:Bearer ${GUMWOOD_TEST_UNTERMINATED_TOKEN") {
+            Ok(_) => panic!("unterminated reference should fail"),
+            Err(err) => assert_eq!(
+                "unterminated variable reference in header 'Authorization:Bearer ${GUMWOOD_TEST_UNTERMINATED_TOKEN'",
+                err.to_string()
+            ),
+        }
+        assert!(expand_env_vars("X-Price:${").is_err());
+    }
+
+    #[test]
+    fn expand_env_vars_should_fail_when_variable_not_set() {
+        match expand_env_vars("Authorization:Bearer ${GUMWOOD_TEST_NOT_SET}") {
+            Ok(_) => panic!("unset variable should fail"),
+            Err(err) => assert_eq!(
+                "environment variable GUMWOOD_TEST_NOT_SET is not set",
+                err.to_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn is_provenance_header_should_match_server_and_version_headers() {
+        assert!(is_provenance_header("Server"));
+        assert!(is_provenance_header("X-Powered-By"));
+        assert!(is_provenance_header("X-API-Version"));
+        assert!(is_provenance_header("x-graphql-engine"));
+        assert!(!is_provenance_header("content-type"));
+        assert!(!is_provenance_header("set-cookie"));
+    }
+
+    #[test]
+    fn get_retry_after_should_read_seconds_and_dates() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            Some(Duration::from_secs(120)),
+            get_retry_after(" 120 ", now)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            get_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now)
+        );
+        assert_eq!(
+            Some(Duration::ZERO),
+            get_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now)
+        );
+        assert_eq!(None, get_retry_after("soon", now));
+    }
+
     #[test]
     fn typeref_is_required_should_return_false_when_kind_is_none() {
         let tr = TypeRef {
@@ -941,45 +2343,24 @@ mod tests {
     }
 
     #[test]
-    fn typeref_decorated_name_should_short_circuit_when_nested_too_deep() {
-        let tr = TypeRef {
-            name: None,
-            kind: None,
-            of_type: Some(Box::new(TypeRef {
-                name: None,
-                kind: None,
-                of_type: Some(Box::new(TypeRef {
-                    name: None,
-                    kind: None,
-                    of_type: Some(Box::new(TypeRef {
-                        name: None,
-                        kind: None,
-                        of_type: Some(Box::new(TypeRef {
-                            name: None,
-                            kind: None,
-                            of_type: Some(Box::new(TypeRef {
-                                name: None,
-                                kind: None,
-                                of_type: Some(Box::new(TypeRef {
-                                    name: None,
-                                    kind: None,
-                                    of_type: Some(Box::new(TypeRef {
-                                        name: None,
-                                        kind: None,
-                                        of_type: Some(Box::new(TypeRef {
-                                            name: None,
-                                            kind: None,
-                                            of_type: None,
-                                        })),
-                                    })),
-                                })),
-                            })),
-                        })),
-                    })),
-                })),
-            })),
+    fn typeref_decorated_name_should_not_truncate_when_nested_deeper_than_default() {
+        // [[[[Thing!]!]!]!]! is nine levels deep, more than the default depth
+        let mut tr = TypeRef {
+            name: Some("Thing".to_string()),
+            kind: Some("OBJECT".to_string()),
+            of_type: None,
         };
-        assert_eq!("", tr.get_decorated_name());
+        for kind in &[
+            "NON_NULL", "LIST", "NON_NULL", "LIST", "NON_NULL", "LIST", "NON_NULL", "LIST",
+            "NON_NULL",
+        ] {
+            tr = TypeRef {
+                name: None,
+                kind: Some(kind.to_string()),
+                of_type: Some(Box::new(tr)),
+            };
+        }
+        assert_eq!("[[[[Thing!]!]!]!]!", tr.get_decorated_name());
     }
 
     #[test]
@@ -1035,45 +2416,357 @@ mod tests {
     }
 
     #[test]
-    fn typeref_actual_kind_should_short_circuit_when_nested_too_deep() {
-        let tr = TypeRef {
+    fn typeref_actual_kind_should_not_truncate_when_nested_deeper_than_default() {
+        // [[[[Thing!]!]!]!]! is nine levels deep, more than the default depth
+        let mut tr = TypeRef {
+            name: Some("Thing".to_string()),
+            kind: Some("OBJECT".to_string()),
+            of_type: None,
+        };
+        for kind in &[
+            "NON_NULL", "LIST", "NON_NULL", "LIST", "NON_NULL", "LIST", "NON_NULL", "LIST",
+            "NON_NULL",
+        ] {
+            tr = TypeRef {
+                name: None,
+                kind: Some(kind.to_string()),
+                of_type: Some(Box::new(tr)),
+            };
+        }
+        assert_eq!("OBJECT", tr.get_actual_kind());
+        assert_eq!("Thing", tr.get_actual_name());
+    }
+
+    #[test]
+    fn merge_should_combine_services() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { player(id: ID!): Player }
+            type Player { id: ID! name: String }
+            enum Position { GOALIE }
+            directive @auth on FIELD_DEFINITION
+            "#,
+        )?;
+        let other = Schema::from_sdl(
+            r#"
+            schema { query: TeamQuery mutation: TeamMutation }
+            type TeamQuery { team: Team player(id: ID!): Player }
+            type TeamMutation { addTeam: Team }
+            type Team { id: ID! players: [Player] }
+            type Player { id: ID! name: Int }
+            enum Position { GOALIE WING }
+            directive @auth on FIELD_DEFINITION
+            "#,
+        )?;
+        let conflicts = schema.merge(other);
+
+        assert_eq!(vec!["Player.name".to_string()], conflicts);
+        assert_eq!(Some("TeamMutation".to_string()), schema.get_mutation_name());
+        assert!(schema.get_type("TeamQuery").is_none());
+        let query_fields: Vec<&str> = schema
+            .get_type("Query")
+            .unwrap()
+            .fields
+            .iter()
+            .flatten()
+            .filter_map(|field| field.name.as_deref())
+            .collect();
+        assert_eq!(vec!["player", "team"], query_fields);
+        assert!(schema.get_type("Team").is_some());
+        assert_eq!(
+            2,
+            schema
+                .get_type("Position")
+                .unwrap()
+                .enums
+                .as_ref()
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            "String",
+            schema.get_type("Player").unwrap().fields.as_ref().unwrap()[1]
+                .field_type
+                .as_ref()
+                .unwrap()
+                .get_actual_name()
+        );
+        assert_eq!(1, schema.directives.as_ref().unwrap().len());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_renaming_should_keep_both_definitions() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Player { id: ID! name: String }
+            "#,
+        )?;
+        let other = Schema::from_sdl(
+            r#"
+            type Query { player: Int roster: [Player] }
+            type Player { id: ID! name: Int }
+            "#,
+        )?;
+        let (conflicts, renames) = schema.merge_renaming(other, "_2");
+
+        assert_eq!(
+            vec!["Player.name".to_string(), "Query.player".to_string()],
+            conflicts
+        );
+        assert_eq!(Some(&"Player_2".to_string()), renames.get("Player"));
+        assert_eq!(1, renames.len());
+        let roster = &schema.get_type("Query").unwrap().fields.as_ref().unwrap()[1];
+        assert_eq!(
+            "Player_2",
+            roster.field_type.as_ref().unwrap().get_actual_name()
+        );
+        let duplicate_names = |name: &str| -> Vec<String> {
+            schema
+                .get_type(name)
+                .unwrap()
+                .duplicates
+                .iter()
+                .flatten()
+                .map(TypeRef::get_actual_name)
+                .collect()
+        };
+        assert_eq!(vec!["Player_2".to_string()], duplicate_names("Player"));
+        assert_eq!(vec!["Player".to_string()], duplicate_names("Player_2"));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_aliases_should_rename_types_and_references() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { player(filter: PlayerV2Filter): [PlayerV2!] }
+            type PlayerV2 implements Named { name: String }
+            interface Named { name: String }
+            input PlayerV2Filter { best: PlayerV2Filter }
+            union Anyone = PlayerV2
+            "#,
+        )?;
+        let mut aliases = HashMap::new();
+        aliases.insert("PlayerV2".to_string(), "Player".to_string());
+        aliases.insert("PlayerV2Filter".to_string(), "PlayerFilter".to_string());
+        aliases.insert("Query".to_string(), "Root".to_string());
+        schema.apply_aliases(&aliases);
+
+        assert!(schema.get_type("PlayerV2").is_none());
+        assert_eq!("Root", schema.get_query_name().unwrap());
+        let player = &schema.get_type("Root").unwrap().fields.as_ref().unwrap()[0];
+        assert_eq!(
+            "[Player!]",
+            player.field_type.as_ref().unwrap().get_decorated_name()
+        );
+        assert_eq!(
+            "PlayerFilter",
+            player.args.as_ref().unwrap()[0]
+                .input_type
+                .as_ref()
+                .unwrap()
+                .get_decorated_name()
+        );
+        let named = schema.get_type("Named").unwrap();
+        assert_eq!(
+            Some("Player".to_string()),
+            named.possible_types.as_ref().unwrap()[0].name
+        );
+        let anyone = schema.get_type("Anyone").unwrap();
+        assert_eq!(
+            Some("Player".to_string()),
+            anyone.possible_types.as_ref().unwrap()[0].name
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn typeref_is_truncated_should_detect_empty_wrappers() {
+        let truncated = TypeRef {
             name: None,
-            kind: None,
+            kind: Some("NON_NULL".to_string()),
             of_type: Some(Box::new(TypeRef {
                 name: None,
-                kind: None,
-                of_type: Some(Box::new(TypeRef {
-                    name: None,
-                    kind: None,
-                    of_type: Some(Box::new(TypeRef {
-                        name: None,
-                        kind: None,
-                        of_type: Some(Box::new(TypeRef {
-                            name: None,
-                            kind: None,
-                            of_type: Some(Box::new(TypeRef {
-                                name: None,
-                                kind: None,
-                                of_type: Some(Box::new(TypeRef {
-                                    name: None,
-                                    kind: None,
-                                    of_type: Some(Box::new(TypeRef {
-                                        name: None,
-                                        kind: None,
-                                        of_type: Some(Box::new(TypeRef {
-                                            name: None,
-                                            kind: None,
-                                            of_type: None,
-                                        })),
-                                    })),
-                                })),
-                            })),
-                        })),
-                    })),
-                })),
+                kind: Some("LIST".to_string()),
+                of_type: None,
             })),
         };
-        assert_eq!("", tr.get_actual_kind());
+        assert!(truncated.is_truncated());
+        let complete = TypeRef {
+            name: None,
+            kind: Some("NON_NULL".to_string()),
+            of_type: Some(Box::new(TypeRef {
+                name: Some("ID".to_string()),
+                kind: Some("SCALAR".to_string()),
+                of_type: None,
+            })),
+        };
+        assert!(!complete.is_truncated());
+    }
+
+    #[test]
+    fn get_truncated_type_refs_should_return_coordinates() -> Result<(), Box<dyn Error>> {
+        let response = r#"{
+            "types": [
+                {
+                    "name": "Query",
+                    "fields": [
+                        {
+                            "name": "grid",
+                            "args": [
+                                {
+                                    "name": "size",
+                                    "type": {"kind": "SCALAR", "name": "Int"}
+                                }
+                            ],
+                            "type": {"kind": "LIST", "ofType": {"kind": "LIST"}}
+                        },
+                        {
+                            "name": "ok",
+                            "type": {"kind": "LIST", "ofType": {"kind": "SCALAR", "name": "Int"}}
+                        }
+                    ]
+                }
+            ]
+        }"#;
+        let schema = Schema::from_str(response)?;
+        assert_eq!(
+            vec!["Query.grid".to_string()],
+            schema.get_truncated_type_refs()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_schema_query_should_nest_type_refs_to_depth() {
+        let query = get_schema_query(2, &Capabilities::default());
+        assert!(query.ends_with(
+            r#"fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+    }
+  }
+}"#
+        ));
+        assert_eq!(
+            DEFAULT_TYPE_DEPTH as usize,
+            get_schema_query(DEFAULT_TYPE_DEPTH, &Capabilities::default())
+                .matches("ofType")
+                .count()
+        );
+    }
+
+    #[test]
+    fn to_introspection_request_should_add_operation_name_and_variables(
+    ) -> Result<(), Box<dyn Error>> {
+        let capabilities = Capabilities::default();
+        let request: Value =
+            serde_json::from_str(&to_introspection_request(2, &capabilities, None, &[])?)?;
+        assert_eq!(None, request.get("operationName"));
+        assert_eq!(None, request.get("variables"));
+        assert!(request["query"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("query IntrospectionQuery {"));
+
+        let request: Value = serde_json::from_str(&to_introspection_request(
+            2,
+            &capabilities,
+            Some("TenantSchema"),
+            &[
+                "tenant=acme".to_string(),
+                "limit=5".to_string(),
+                "id=\"123\"".to_string(),
+                "tags=[\"a\"]".to_string(),
+            ],
+        )?)?;
+        assert_eq!("TenantSchema", request["operationName"]);
+        assert!(request["query"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("query TenantSchema {"));
+        assert_eq!(
+            serde_json::json!({"tenant": "acme", "limit": 5, "id": "123", "tags": ["a"]}),
+            request["variables"]
+        );
+
+        assert!(to_introspection_request(2, &capabilities, Some("Tenant Schema"), &[]).is_err());
+        assert!(to_introspection_request(2, &capabilities, None, &["tenant".to_string()]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn get_schema_query_should_ask_only_for_supported_fields() {
+        let query = get_schema_query(2, &Capabilities::default());
+        assert!(!query.contains("    description\n    queryType"));
+        assert!(!query.contains("isRepeatable"));
+        assert!(!query.contains("specifiedBy"));
+        assert!(!query.contains("isOneOf"));
+
+        let query = get_schema_query(
+            2,
+            &Capabilities {
+                schema_description: true,
+                specified_by_url: Some("specifiedByUrl"),
+                is_one_of: true,
+                is_repeatable: true,
+            },
+        );
+        assert!(query.contains("  __schema {\n    description\n    queryType {"));
+        assert!(query.contains("      isRepeatable\n      locations\n"));
+        assert!(query.contains("  specifiedByUrl\n  isOneOf\n  fields(includeDeprecated: true) {"));
+    }
+
+    #[test]
+    fn capabilities_from_response_should_find_optional_fields() {
+        let response = r#"{
+            "data": {
+                "schema": {"fields": [{"name": "types"}, {"name": "description"}]},
+                "type": {"fields": [{"name": "kind"}, {"name": "specifiedByUrl"}, {"name": "isOneOf"}]},
+                "directive": {"fields": [{"name": "name"}, {"name": "locations"}]}
+            }
+        }"#;
+        assert_eq!(
+            Capabilities {
+                schema_description: true,
+                specified_by_url: Some("specifiedByUrl"),
+                is_one_of: true,
+                is_repeatable: false,
+            },
+            Capabilities::from_response(response)
+        );
+    }
+
+    #[test]
+    fn capabilities_from_response_should_be_empty_when_unreadable() {
+        assert_eq!(
+            Capabilities::default(),
+            Capabilities::from_response("<html>")
+        );
+        assert_eq!(
+            Capabilities::default(),
+            Capabilities::from_response(r#"{"errors": [{"message": "no __type"}]}"#)
+        );
+    }
+
+    #[test]
+    fn to_capabilities_request_should_use_operation_name() -> Result<(), Box<dyn Error>> {
+        let request: Value =
+            serde_json::from_str(&to_capabilities_request(Some("TenantSchema"), &[])?)?;
+        assert_eq!("TenantSchema", request["operationName"]);
+        assert!(request["query"]
+            .as_str()
+            .unwrap_or_default()
+            .starts_with("query TenantSchema {  schema: __type(name: \"__Schema\")"));
+        Ok(())
     }
 
     #[test]
@@ -1098,7 +2791,7 @@ mod tests {
             }
         }
     }"#;
-        let schema = Schema::from_str(&response).unwrap();
+        let schema = Schema::from_str(response).unwrap();
         assert_eq!(2, schema.get_types_of_kind("FOO").len());
     }
 
@@ -1110,7 +2803,7 @@ mod tests {
                 }
             }
         }"#;
-        let schema = Schema::from_str(&response).unwrap();
+        let schema = Schema::from_str(response).unwrap();
         assert_eq!(0, schema.get_types_of_kind("FOO").len());
     }
 
@@ -1133,7 +2826,7 @@ mod tests {
             }
         }
     }"#;
-        let schema = Schema::from_str(&response).unwrap();
+        let schema = Schema::from_str(response).unwrap();
         assert_eq!(0, schema.get_types_of_kind("BAR").len());
     }
 }
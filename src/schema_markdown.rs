@@ -1,8 +1,21 @@
+use super::budget::Budget;
+use super::cache::RenderCache;
+use super::errors::{get_error_types, ErrorConvention};
+use super::icons::Icons;
+use super::links::Links;
 use super::markdown::*;
-use super::schema::{Enum, Field, Input, Schema, Type, TypeRef};
+use super::pagination::{get_paginated_fields, PaginatedField, Pagination};
+use super::schema::{
+    Directive, Endpoint, Enum, Field, Input, MergeConflict, Referrer, Schema, Type, TypeRef,
+};
+use super::stats::{
+    get_cycles, get_scalar_usage, get_type_stats, is_introspection_type, PageStats, ScalarUsage,
+    TypeStats,
+};
+use super::tables::{SortOrder, Tables};
 use lazy_static::lazy_static;
-use std::collections::HashMap;
-use titlecase::titlecase;
+use serde_json::Value;
+use std::{cmp::Reverse, collections::HashMap, error::Error};
 
 lazy_static! {
     static ref GRAPHQL_TYPES: HashMap<&'static str, &'static str> = {
@@ -17,745 +30,3797 @@ lazy_static! {
     };
 }
 
-pub fn generate_from_schema(schema: &Schema, add_titles: bool) -> HashMap<String, String> {
+// The page for types of kinds no other page documents, so links to them
+// still land on the type
+const GLOSSARY_PAGE: &str = "glossary";
+
+pub fn generate_from_schema(
+    schema: &Schema,
+    add_titles: bool,
+    icons: &Icons,
+    links: &Links,
+    tables: &Tables,
+    cache: &mut RenderCache,
+    budget: &Budget,
+) -> Result<HashMap<String, String>, Box<dyn Error>> {
     let mut contents: HashMap<String, String> = HashMap::new();
+    let mut add = |page: &str, markdown: String| -> Result<(), Box<dyn Error>> {
+        budget.spend(markdown.len())?;
+        contents.insert(page.to_string(), markdown);
+        Ok(())
+    };
 
-    contents.insert(
-        "queries".to_string(),
-        schema_type_to_markdown(schema, schema.get_query_name(), add_titles),
-    );
-    contents.insert(
-        "mutations".to_string(),
-        schema_type_to_markdown(schema, schema.get_mutation_name(), add_titles),
-    );
-    contents.insert(
-        "subscriptions".to_string(),
-        schema_type_to_markdown(schema, schema.get_subscription_name(), add_titles),
-    );
-
+    add(
+        "queries",
+        schema_type_to_markdown(
+            schema,
+            schema.get_query_name(),
+            "queries",
+            add_titles,
+            icons,
+            links,
+            tables,
+        ),
+    )?;
+    add(
+        "mutations",
+        schema_type_to_markdown(
+            schema,
+            schema.get_mutation_name(),
+            "mutations",
+            add_titles,
+            icons,
+            links,
+            tables,
+        ),
+    )?;
+    add(
+        "subscriptions",
+        schema_type_to_markdown(
+            schema,
+            schema.get_subscription_name(),
+            "subscriptions",
+            add_titles,
+            icons,
+            links,
+            tables,
+        ),
+    )?;
+    add(
+        "directives",
+        directives_to_markdown(schema, add_titles, icons, links, tables),
+    )?;
+    add(
+        GLOSSARY_PAGE,
+        glossary_to_markdown(schema, add_titles, links),
+    )?;
+
+    // The type pages are the bulk of the docs, so they're counted a type at
+    // a time
     for (graphql, friendly) in GRAPHQL_TYPES.iter() {
         contents.insert(
             friendly.to_string(),
-            types_to_markdown(schema, &titlecase(friendly), graphql, add_titles),
+            types_to_markdown(
+                schema, graphql, add_titles, icons, links, tables, cache, budget,
+            )?,
         );
     }
 
-    contents
+    Ok(contents)
 }
 
-fn schema_type_to_markdown(schema: &Schema, type_name: Option<String>, add_titles: bool) -> String {
-    let mut s = String::new();
-
-    if let Some(typ) = type_name.and_then(|name| schema.get_type(&name)) {
-        if add_titles {
-            match &typ.name {
-                Some(name) => s.push_str(&to_header(1, &name)),
-                None => {}
+/// Returns each type, field, argument, and input field whose type has a
+/// kind no page documents, as its coordinate and the type's name and kind
+pub fn get_unknown_kinds(schema: &Schema) -> Vec<(String, String, String)> {
+    fn check(
+        unknown: &mut Vec<(String, String, String)>,
+        coordinate: String,
+        type_ref: &Option<TypeRef>,
+    ) {
+        if let Some(type_ref) = type_ref {
+            let (name, kind) = (type_ref.get_actual_name(), type_ref.get_actual_kind());
+            // Truncated references have no name, and are warned of already
+            if !name.is_empty() && !GRAPHQL_TYPES.contains_key(kind.as_str()) {
+                unknown.push((coordinate, name, kind));
             }
         }
+    }
 
-        match &typ.description {
-            Some(description) => s.push_str(&to_description(&description)),
-            None => {}
+    let mut unknown = Vec::new();
+    for typ in schema.types.iter().flatten() {
+        let type_name = typ.name.as_deref().unwrap_or_default();
+        let kind = typ.kind.as_deref().unwrap_or_default();
+        if !GRAPHQL_TYPES.contains_key(kind) {
+            unknown.push((
+                type_name.to_string(),
+                type_name.to_string(),
+                kind.to_string(),
+            ));
         }
-
-        match &typ.fields {
-            Some(fields) => {
-                for field in fields.iter() {
-                    s.push_str(&field_to_markdown(field));
-                }
+        for field in typ.fields.iter().flatten() {
+            let coordinate = format!(
+                "{}.{}",
+                type_name,
+                field.name.as_deref().unwrap_or_default()
+            );
+            for arg in field.args.iter().flatten() {
+                check(
+                    &mut unknown,
+                    format!(
+                        "{}({}:)",
+                        coordinate,
+                        arg.name.as_deref().unwrap_or_default()
+                    ),
+                    &arg.input_type,
+                );
             }
-            None => {}
+            check(&mut unknown, coordinate, &field.field_type);
+        }
+        for input in typ.inputs.iter().flatten() {
+            check(
+                &mut unknown,
+                format!(
+                    "{}.{}",
+                    type_name,
+                    input.name.as_deref().unwrap_or_default()
+                ),
+                &input.input_type,
+            );
+        }
+    }
+    for directive in schema.directives.iter().flatten() {
+        for arg in directive.args.iter().flatten() {
+            check(
+                &mut unknown,
+                format!(
+                    "@{}({}:)",
+                    directive.name.as_deref().unwrap_or_default(),
+                    arg.name.as_deref().unwrap_or_default()
+                ),
+                &arg.input_type,
+            );
         }
     }
+    unknown
+}
+
+// Lists the types of unknown kinds, with their descriptions if the schema
+// defines them, so links to them aren't broken
+fn glossary_to_markdown(schema: &Schema, add_titles: bool, links: &Links) -> String {
+    let mut types: Vec<(String, String)> = get_unknown_kinds(schema)
+        .into_iter()
+        .map(|(_, name, kind)| (name, kind))
+        .collect();
+    if types.is_empty() {
+        return String::new();
+    }
+    types.sort();
+    types.dedup_by(|a, b| a.0 == b.0);
 
+    let mut s = String::new();
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title(GLOSSARY_PAGE)));
+    }
+    for (name, kind) in &types {
+        s.push_str(&to_header(2, &links.anchor(name)));
+        if let Some(description) = schema
+            .get_type(name)
+            .and_then(|typ| typ.description.as_ref())
+        {
+            s.push_str(&to_description(&links.description(description)));
+        }
+        let kind = if kind.is_empty() { "none" } else { kind };
+        s.push_str(&to_label("Kind", &to_inline_code(kind)));
+    }
     s
 }
 
-fn types_to_markdown(schema: &Schema, title: &str, kind: &str, add_title: bool) -> String {
+/// Returns the counts of what each type and operation page documents, keyed
+/// by page name
+pub fn get_page_stats(schema: &Schema) -> HashMap<String, PageStats> {
+    let mut stats: HashMap<String, PageStats> = HashMap::new();
+
+    for (page, type_name) in &[
+        ("queries", schema.get_query_name()),
+        ("mutations", schema.get_mutation_name()),
+        ("subscriptions", schema.get_subscription_name()),
+    ] {
+        if let Some(typ) = type_name.as_ref().and_then(|name| schema.get_type(name)) {
+            stats.entry(page.to_string()).or_default().add(typ);
+        }
+    }
+
+    for (graphql, friendly) in GRAPHQL_TYPES.iter() {
+        for typ in schema.get_types_of_kind(graphql) {
+            stats.entry(friendly.to_string()).or_default().add(typ);
+        }
+    }
+
+    stats
+}
+
+/// Generates the About page, which records where the schema came from
+///
+/// # Arguments
+///
+/// * `endpoints` - what each introspected endpoint reported about itself
+/// * `generated_at` - when the documentation was generated
+/// * `add_titles` - whether to add a title to the page
+/// * `links` - how headings are capitalized
+pub fn about_to_markdown(
+    endpoints: &[Endpoint],
+    generated_at: &str,
+    add_titles: bool,
+    links: &Links,
+) -> String {
     let mut s = String::new();
 
-    let mut types = schema.get_types_of_kind(kind);
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("about")));
+    }
 
-    if !types.is_empty() {
-        if add_title {
-            s.push_str(&to_header(1, title));
+    for endpoint in endpoints {
+        s.push_str(&to_label("Endpoint", &endpoint.url));
+    }
+    s.push_str(&to_label("Generated", generated_at));
+    s.push_str(&to_label(
+        "Generated by",
+        &format!("gumwood {}", env!("CARGO_PKG_VERSION")),
+    ));
+
+    // With several endpoints, each section says which one it describes
+    let title = |title: &str, endpoint: &Endpoint| {
+        if endpoints.len() > 1 {
+            format!("{}: {}", title, endpoint.url)
+        } else {
+            title.to_string()
         }
+    };
 
-        types.sort_by(|a, b| a.name.cmp(&b.name));
+    for endpoint in endpoints {
+        if !endpoint.headers.is_empty() {
+            s.push_str(&to_header(
+                2,
+                &title(&links.section_title("Server"), endpoint),
+            ));
+            s.push_str(&to_name_value_table(&endpoint.headers));
+        }
 
-        for typ in types.iter() {
-            s.push_str(&type_to_markdown(typ));
+        if let Some(Value::Object(extensions)) = &endpoint.extensions {
+            if !extensions.is_empty() {
+                s.push_str(&to_header(
+                    2,
+                    &title(&links.section_title("Extensions"), endpoint),
+                ));
+                let rows: Vec<(String, String)> = extensions
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), to_inline_code(&value.to_string())))
+                    .collect();
+                s.push_str(&to_name_value_table(&rows));
+            }
         }
     }
 
     s
 }
-fn type_to_markdown(typ: &Type) -> String {
+
+/// Generates an appendix ranking the types with the most fields and the
+/// most inbound references, to show where refactoring would pay off
+///
+/// # Arguments
+///
+/// * `schema` - the schema to rank the types of
+/// * `count` - how many types to list in each ranking
+/// * `add_titles` - whether to add a title to the page
+pub fn largest_types_to_markdown(
+    schema: &Schema,
+    count: usize,
+    add_titles: bool,
+    links: &Links,
+) -> String {
     let mut s = String::new();
 
-    match &typ.name {
-        Some(name) => s.push_str(&to_header(2, &to_named_anchor(name))),
-        None => {}
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("largest-types")));
     }
 
-    match &typ.description {
-        Some(description) => s.push_str(&to_description(&description)),
-        None => {}
+    let mut stats = get_type_stats(schema);
+    let headers = vec![
+        "Rank".to_string(),
+        "Type".to_string(),
+        "Kind".to_string(),
+        "Fields".to_string(),
+        "Inbound References".to_string(),
+    ];
+
+    s.push_str(&to_header(2, &links.section_title("Most Fields")));
+    stats.sort_by_key(|stats| Reverse(stats.field_count));
+    s.push_str(&to_markdown_table(
+        headers.clone(),
+        &to_ranked(&stats, count),
+        links,
+    ));
+
+    s.push_str(&to_header(2, &links.section_title("Most Referenced")));
+    stats.sort_by_key(|stats| Reverse(stats.inbound_refs));
+    s.push_str(&to_markdown_table(
+        headers,
+        &to_ranked(&stats, count),
+        links,
+    ));
+
+    s
+}
+
+/// A type's stats and its place in a ranking
+struct Ranked<'a>(usize, &'a TypeStats);
+
+impl TableItem for Ranked<'_> {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let stats = self.1;
+        vec![
+            self.0.to_string(),
+            links.link(
+                &to_inline_code(&stats.name),
+                &get_link_for_type(&stats.kind, &stats.name, links),
+            ),
+            to_inline_code(&stats.kind),
+            stats.field_count.to_string(),
+            stats.inbound_refs.to_string(),
+        ]
     }
+}
 
-    match &typ.fields {
-        Some(fields) => {
-            s.push_str(&to_header(3, "Fields"));
-            let mut sorted = fields.to_vec();
-            sorted.sort_by(|a, b| a.name.cmp(&b.name));
-            s.push_str(&to_markdown_table(
-                vec![
-                    "Name".to_string(),
-                    "Type".to_string(),
-                    "Description".to_string(),
-                ],
-                &sorted,
-            ));
+fn to_ranked(stats: &[TypeStats], count: usize) -> Vec<Ranked<'_>> {
+    stats
+        .iter()
+        .take(count)
+        .enumerate()
+        .map(|(i, stats)| Ranked(i + 1, stats))
+        .collect()
+}
+
+/// Generates the Cost page, which lists the estimated cost of each
+/// operation so consumers can budget their requests, or returns an empty
+/// string if there's nothing to say about cost
+///
+/// # Arguments
+///
+/// * `schema` - the schema to list the operation costs of
+/// * `rate_limit` - prose describing the rate limits, if any
+/// * `add_titles` - whether to add a title to the page
+pub fn cost_to_markdown(
+    schema: &Schema,
+    rate_limit: Option<&str>,
+    add_titles: bool,
+    links: &Links,
+) -> String {
+    let mut operations: Vec<OperationCost> = Vec::new();
+    for (page, kind, type_name) in &[
+        ("queries", "query", schema.get_query_name()),
+        ("mutations", "mutation", schema.get_mutation_name()),
+        (
+            "subscriptions",
+            "subscription",
+            schema.get_subscription_name(),
+        ),
+    ] {
+        let fields = type_name
+            .as_ref()
+            .and_then(|name| schema.get_type(name))
+            .and_then(|typ| typ.fields.as_ref());
+        for field in fields.iter().copied().flatten() {
+            if let (Some(name), Some(cost)) = (&field.name, field.cost) {
+                operations.push(OperationCost {
+                    page,
+                    kind,
+                    name,
+                    cost,
+                });
+            }
         }
-        None => {}
+    }
+    if operations.is_empty() && rate_limit.is_none() {
+        return String::new();
     }
 
-    match &typ.inputs {
-        Some(inputs) => {
-            s.push_str(&to_header(3, "Inputs"));
-            let mut sorted = inputs.to_vec();
-            sorted.sort_by(|a, b| a.name.cmp(&b.name));
-            s.push_str(&to_markdown_table(
-                vec![
-                    "Name".to_string(),
-                    "Type".to_string(),
-                    "Description".to_string(),
-                    "Default Value".to_string(),
-                ],
-                &sorted,
-            ));
-        }
-        None => {}
+    let mut s = String::new();
+
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("cost")));
     }
 
-    match &typ.enums {
-        Some(enums) => {
-            s.push_str(&to_header(3, "Values"));
-            let mut sorted = enums.to_vec();
-            sorted.sort_by(|a, b| a.name.cmp(&b.name));
-            s.push_str(&to_markdown_table(
-                vec![
-                    "Name".to_string(),
-                    "Description".to_string(),
-                    "Deprecated".to_string(),
-                ],
-                &sorted,
-            ));
-        }
-        None => {}
+    if let Some(rate_limit) = rate_limit {
+        s.push_str(&to_header(2, &links.section_title("Rate Limits")));
+        s.push_str(&format!(
+            "{}
+
+",
+            rate_limit.trim()
+        ));
     }
 
-    match &typ.possible_types {
-        Some(possible_types) => {
-            s.push_str(&to_header(3, "Implemented by"));
-            let mut names: Vec<String> = possible_types
-                .iter()
-                .map(|typ| match &typ.name {
-                    Some(name) => to_inline_code(name),
-                    None => "".to_string(),
-                })
-                .collect();
-            names.sort();
-            s.push_str(&to_list(&names));
-        }
-        None => {}
+    if !operations.is_empty() {
+        s.push_str(&to_header(2, &links.section_title("Operations")));
+        operations.sort_by(|a, b| b.cost.cmp(&a.cost).then(a.name.cmp(b.name)));
+        s.push_str(&to_markdown_table(
+            vec![
+                "Operation".to_string(),
+                "Kind".to_string(),
+                "Estimated Cost".to_string(),
+            ],
+            &operations,
+            links,
+        ));
     }
 
     s
 }
 
-pub trait TableItem {
-    fn table_fields(&self) -> Vec<String>;
+/// An operation's estimated cost and the page it's documented on
+struct OperationCost<'a> {
+    page: &'a str,
+    kind: &'a str,
+    name: &'a str,
+    cost: u32,
 }
 
-impl TableItem for Field {
-    fn table_fields(&self) -> Vec<String> {
-        let type_name = match self.field_type.as_ref() {
-            Some(typ) => typ.get_decorated_name(),
-            None => "".to_string(),
-        };
-        let link = match self.field_type.as_ref() {
-            Some(typ) => get_link_for_type_ref(typ),
-            None => "".to_string(),
-        };
+impl TableItem for OperationCost<'_> {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
         vec![
-            to_inline_code(&to_safe_string(&self.name)),
-            to_link(&to_inline_code(&type_name), &link),
-            to_safe_string(&self.description),
+            links.link(
+                &to_inline_code(self.name),
+                &format!(
+                    "{}#{}",
+                    links.page_link(self.page),
+                    links.fragment(self.name)
+                ),
+            ),
+            self.kind.to_string(),
+            self.cost.to_string(),
         ]
     }
 }
 
-impl TableItem for Input {
-    fn table_fields(&self) -> Vec<String> {
-        let type_name = match self.input_type.as_ref() {
-            Some(typ) => typ.get_decorated_name(),
-            None => "".to_string(),
-        };
-        let link = match self.input_type.as_ref() {
-            Some(typ) => get_link_for_type_ref(typ),
-            None => "".to_string(),
-        };
-        vec![
-            to_inline_code(&to_safe_string(&self.name)),
-            to_link(&to_inline_code(&type_name), &link),
-            to_safe_string(&self.description),
-            to_inline_code(&to_safe_string(&self.default_value)),
-        ]
+/// Generates the Pagination page, which summarizes how the schema pages
+/// through lists and links to the fields that use each pattern, or returns
+/// an empty string if nothing paginates
+///
+/// # Arguments
+///
+/// * `schema` - the schema to find the pagination patterns of
+/// * `add_titles` - whether to add a title to the page
+pub fn pagination_to_markdown(schema: &Schema, add_titles: bool, links: &Links) -> String {
+    let paginated = get_paginated_fields(schema);
+    if paginated.is_empty() {
+        return String::new();
     }
-}
 
-impl TableItem for Enum {
-    fn table_fields(&self) -> Vec<String> {
-        let is_deprecated = match &self.is_deprecated {
-            Some(is_deprecated) => *is_deprecated,
-            None => false,
-        };
-        let deprecation_reason = to_safe_string(&self.deprecation_reason);
-        let dr = if is_deprecated {
-            deprecation_reason
-        } else {
-            "no".to_string()
+    let mut s = String::new();
+
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("pagination")));
+    }
+
+    for pagination in &[
+        Pagination::Connection,
+        Pagination::Cursor,
+        Pagination::Offset,
+    ] {
+        let links: Vec<String> = paginated
+            .iter()
+            .filter(|field| field.pagination == *pagination)
+            .map(|field| get_link_for_paginated_field(schema, field, links))
+            .collect();
+        if links.is_empty() {
+            continue;
+        }
+
+        let (title, usage) = match pagination {
+            Pagination::Connection => (
+                "Connections",
+                "Pass `first` and `after` to page forward, or `last` and `before` to page back. \
+                 Each connection's `pageInfo` has the cursors to pass next and says whether there are more pages.",
+            ),
+            Pagination::Cursor => (
+                "Cursors",
+                "Pass `first` and `after` to page forward, or `last` and `before` to page back, \
+                 using a cursor from the previous page.",
+            ),
+            Pagination::Offset => (
+                "Offsets",
+                "Pass `limit` for the page size and `offset` for how many items to skip.",
+            ),
         };
-        vec![
-            to_inline_code(&to_safe_string(&self.name)),
-            to_safe_string(&self.description),
-            dr,
-        ]
+        s.push_str(&to_header(2, title));
+        s.push_str(&format!("{}\n\n", usage));
+        s.push_str(&to_list(&links));
     }
+
+    s
 }
 
-fn to_safe_string(opt_s: &Option<String>) -> String {
-    match opt_s {
-        Some(s) => s.trim().replace("\n", ""),
-        None => "".to_string(),
+/// Generates the Interface Conformance page, which has a matrix for each
+/// interface: its fields down the side, the types that implement it across
+/// the top, and whether each type implements each field. A type that
+/// narrows a field's type shows its own. Returns an empty string if the
+/// schema has no interfaces.
+///
+/// # Arguments
+///
+/// * `schema` - the schema to check the interfaces of
+/// * `add_titles` - whether to add a title to the page
+pub fn interface_conformance_to_markdown(
+    schema: &Schema,
+    add_titles: bool,
+    links: &Links,
+) -> String {
+    let mut interfaces = schema.get_types_of_kind("INTERFACE");
+    if interfaces.is_empty() {
+        return String::new();
     }
-}
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
 
-fn to_markdown_table(headers: Vec<String>, items: &[impl TableItem]) -> String {
     let mut s = String::new();
-    s.push_str(&to_table_row(&headers));
-    s.push_str(&to_table_separator(headers.len()));
 
-    for item in items.iter() {
-        s.push_str(&to_table_row(&item.table_fields()));
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("interface-conformance")));
     }
-    s.push_str("\n");
+
+    for interface in interfaces {
+        let name = interface.name.as_deref().unwrap_or_default();
+        let mut implementations: Vec<&Type> = schema
+            .types
+            .iter()
+            .flatten()
+            .filter(|typ| {
+                typ.interfaces
+                    .iter()
+                    .flatten()
+                    .any(|type_ref| type_ref.get_actual_name() == name)
+            })
+            .collect();
+        implementations.sort_by(|a, b| a.name.cmp(&b.name));
+
+        s.push_str(&to_header(2, name));
+        if implementations.is_empty() {
+            s.push_str("Nothing implements this interface.\n\n");
+            continue;
+        }
+
+        let mut headers = vec![links.link(
+            &to_inline_code(name),
+            &get_link_for_type("INTERFACE", name, links),
+        )];
+        headers.extend(implementations.iter().map(|typ| {
+            let name = typ.name.as_deref().unwrap_or_default();
+            links.link(
+                &to_inline_code(name),
+                &get_link_for_type(typ.kind.as_deref().unwrap_or_default(), name, links),
+            )
+        }));
+        s.push_str(&to_table_row(&headers));
+        s.push_str(&to_table_separator(headers.len()));
+        for field in interface.fields.iter().flatten() {
+            let field_name = field.name.as_deref().unwrap_or_default();
+            let field_type = field.field_type.as_ref().map(TypeRef::get_decorated_name);
+            let mut row = vec![to_inline_code(field_name)];
+            row.extend(implementations.iter().map(|typ| {
+                let implementation = typ
+                    .fields
+                    .iter()
+                    .flatten()
+                    .find(|field| field.name.as_deref() == Some(field_name));
+                match implementation {
+                    None => "✗".to_string(),
+                    Some(field) => {
+                        let decorated = field.field_type.as_ref().map(TypeRef::get_decorated_name);
+                        if decorated == field_type {
+                            "✓".to_string()
+                        } else {
+                            format!("✓ {}", to_inline_code(&decorated.unwrap_or_default()))
+                        }
+                    }
+                }
+            }));
+            s.push_str(&to_table_row(&row));
+        }
+        s.push('\n');
+    }
+
     s
 }
 
-fn field_to_markdown(field: &Field) -> String {
+/// Generates the Scalar Usage page, which audits the custom scalars: how
+/// many fields and arguments use each one, and whether it's documented. It
+/// returns an empty string if the schema has no custom scalars.
+///
+/// # Arguments
+///
+/// * `schema` - the schema to audit the scalars of
+/// * `add_titles` - whether to add a title to the page
+pub fn scalar_usage_to_markdown(schema: &Schema, add_titles: bool, links: &Links) -> String {
+    let usage = get_scalar_usage(schema);
+    if usage.is_empty() {
+        return String::new();
+    }
+
     let mut s = String::new();
 
-    match &field.name {
-        Some(name) => s.push_str(&to_header(2, &name)),
-        None => {}
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("scalar-usage")));
+    }
+
+    s.push_str(&to_markdown_table(
+        vec![
+            "Scalar".to_string(),
+            "Fields".to_string(),
+            "Arguments".to_string(),
+            "Description".to_string(),
+            "Specified By".to_string(),
+        ],
+        &usage,
+        links,
+    ));
+
+    s
+}
+
+impl TableItem for ScalarUsage {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+        vec![
+            links.link(
+                &to_inline_code(&self.name),
+                &get_link_for_type("SCALAR", &self.name, links),
+            ),
+            self.field_count.to_string(),
+            self.argument_count.to_string(),
+            yes_no(self.described),
+            yes_no(self.specified_by),
+        ]
     }
+}
 
-    match &field.is_deprecated {
-        Some(deprecated) => {
-            if *deprecated {
-                s.push_str(&to_notice("Deprecated"));
+/// Generates the Defaults page, which lists every argument and input field
+/// with a default value, under the type or directive it belongs to, so
+/// clients can audit what they get when they leave them out. Returns an
+/// empty string if nothing has a default value.
+///
+/// # Arguments
+///
+/// * `schema` - the schema to find the default values in
+/// * `add_titles` - whether to add a title to the page
+pub fn defaults_to_markdown(schema: &Schema, add_titles: bool, links: &Links) -> String {
+    let mut types: Vec<&Type> = schema
+        .types
+        .iter()
+        .flatten()
+        .filter(|typ| !is_introspection_type(typ))
+        .collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut directives: Vec<&Directive> = schema.directives.iter().flatten().collect();
+    directives.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Each type's or directive's name, with its inputs that have defaults,
+    // named as they're referred to within it
+    let mut groups: Vec<(String, Vec<(String, &Input)>)> = Vec::new();
+    for typ in types {
+        let mut inputs: Vec<(String, &Input)> = Vec::new();
+        for field in typ.fields.iter().flatten() {
+            let field_name = field.name.as_deref().unwrap_or_default();
+            for arg in field.args.iter().flatten() {
+                let arg_name = arg.name.as_deref().unwrap_or_default();
+                inputs.push((format!("{}({}:)", field_name, arg_name), arg));
             }
         }
-        None => {}
+        for input in typ.inputs.iter().flatten() {
+            inputs.push((input.name.clone().unwrap_or_default(), input));
+        }
+        groups.push((typ.name.clone().unwrap_or_default(), inputs));
     }
-
-    match &field.description {
-        Some(description) => s.push_str(&to_description(&description)),
-        None => {}
+    for directive in directives {
+        let inputs = directive
+            .args
+            .iter()
+            .flatten()
+            .map(|arg| (arg.name.clone().unwrap_or_default(), arg))
+            .collect();
+        let name = directive.name.as_deref().unwrap_or_default();
+        groups.push((format!("@{}", name), inputs));
+    }
+    for (_, inputs) in groups.iter_mut() {
+        inputs.retain(|(_, input)| input.default_value.is_some());
+        inputs.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+    groups.retain(|(_, inputs)| !inputs.is_empty());
+    if groups.is_empty() {
+        return String::new();
     }
 
-    match &field.field_type {
-        Some(typ) => s.push_str(&to_label(
-            "Type",
-            &to_link(
-                &to_inline_code(&typ.get_decorated_name()),
-                &get_link_for_type_ref(&typ),
-            ),
-        )),
-        None => {}
+    let mut s = String::new();
+
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("defaults")));
     }
 
-    match &field.args {
-        Some(args) => {
-            if !args.is_empty() {
-                s.push_str(&to_header(3, "Arguments"));
-                let mut sorted = args.to_vec();
-                sorted.sort_by(|a, b| a.name.cmp(&b.name));
-                s.push_str(&to_markdown_table(
-                    vec![
-                        "Name".to_string(),
-                        "Type".to_string(),
-                        "Description".to_string(),
-                        "Default Value".to_string(),
-                    ],
-                    &sorted,
-                ));
-            }
+    for (name, inputs) in groups {
+        s.push_str(&to_header(2, &name));
+        let headers = ["Name", "Type", "Default Value"];
+        s.push_str(&to_table_row(&headers.map(String::from)));
+        s.push_str(&to_table_separator(headers.len()));
+        for (name, input) in inputs {
+            let type_cell = match &input.input_type {
+                Some(typ) => links.link(
+                    &to_inline_code(&typ.get_decorated_name()),
+                    &get_link_for_type_ref(typ, links),
+                ),
+                None => String::new(),
+            };
+            s.push_str(&to_table_row(&[
+                to_inline_code(&name),
+                type_cell,
+                to_inline_code(&to_safe_string(&input.default_value)),
+            ]));
         }
-        None => {}
+        s.push('\n');
     }
 
     s
 }
 
-fn get_link_for_type_ref(type_ref: &TypeRef) -> String {
-    let kind = type_ref.get_actual_kind();
-    let link_to: &str = match GRAPHQL_TYPES.get::<str>(&kind) {
-        Some(friendly) => friendly,
-        None => "",
+/// Generates the Merge Conflicts page, which lists what the sources define
+/// differently and how each conflict was resolved, or returns an empty
+/// string if they agree
+///
+/// # Arguments
+///
+/// * `conflicts` - the conflicts, in the order they were found
+/// * `schema` - the merged schema, for linking to the types
+/// * `add_titles` - whether to add a title to the page
+pub fn merge_conflicts_to_markdown(
+    conflicts: &[MergeConflict],
+    schema: &Schema,
+    add_titles: bool,
+    links: &Links,
+) -> String {
+    if conflicts.is_empty() {
+        return String::new();
+    }
+
+    let link_type = |text: &str, name: &str| match schema.get_type(name) {
+        Some(typ) => links.link(
+            &to_inline_code(text),
+            &get_link_for_type(typ.kind.as_deref().unwrap_or_default(), name, links),
+        ),
+        None => to_inline_code(text),
     };
-    format!(
-        "{}.md#{}",
-        link_to,
-        type_ref.get_actual_name().to_lowercase()
-    )
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::schema::TypeRef;
+    let mut s = String::new();
 
-    #[test]
-    fn generate_from_schema_should_return_empty_when_empty_schema() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: None,
-            types: None,
-            directives: None,
-        };
-        let map = generate_from_schema(schema, true);
-        assert_eq!(9, map.len());
-        assert_eq!("".to_string(), map["queries"]);
-        assert_eq!("".to_string(), map["mutations"]);
-        assert_eq!("".to_string(), map["subscriptions"]);
-        assert_eq!("".to_string(), map["inputs"]);
-        assert_eq!("".to_string(), map["objects"]);
-        assert_eq!("".to_string(), map["enums"]);
-        assert_eq!("".to_string(), map["interfaces"]);
-        assert_eq!("".to_string(), map["unions"]);
-        assert_eq!("".to_string(), map["scalars"]);
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("merge-conflicts")));
     }
 
-    #[test]
-    fn schema_type_to_markdown_for_query_should_return_empty_when_none() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: None,
-            types: None,
-            directives: None,
+    let headers = ["Coordinate", "Source", "Resolution"];
+    s.push_str(&to_table_row(&headers.map(String::from)));
+    s.push_str(&to_table_separator(headers.len()));
+    for conflict in conflicts {
+        let type_name = conflict.coordinate.split('.').next().unwrap_or_default();
+        let resolution = match &conflict.renamed {
+            Some(renamed) => format!("Kept both, as {}", link_type(renamed, renamed)),
+            None => "Kept the earlier definition".to_string(),
         };
-        assert_eq!(
-            "".to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name(), true)
-        );
+        s.push_str(&to_table_row(&[
+            link_type(&conflict.coordinate, type_name),
+            to_inline_code(&conflict.source),
+            resolution,
+        ]));
     }
+    s.push('\n');
 
-    #[test]
-    fn schema_type_to_markdown_for_query_should_return_empty_when_some_and_no_members() {
-        let schema = &Schema {
-            query_type: Some(Type {
-                name: None,
-                kind: None,
-                description: None,
-                fields: None,
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
-            }),
-            mutation_type: None,
-            subscription_type: None,
-            types: None,
-            directives: None,
-        };
-        assert_eq!(
-            "".to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name(), true)
-        );
+    s
+}
+
+/// Generates the Cycles page, which lists the types that reference each
+/// other, inputs first, or returns an empty string if nothing is recursive
+///
+/// # Arguments
+///
+/// * `schema` - the schema to find the cycles in
+/// * `add_titles` - whether to add a title to the page
+pub fn cycles_to_markdown(schema: &Schema, add_titles: bool, links: &Links) -> String {
+    let cycles = get_cycles(schema);
+    if cycles.is_empty() {
+        return String::new();
     }
 
-    #[test]
-    fn schema_type_to_markdown_for_query_should_return_markdown_when_some() {
-        let schema = &Schema {
-            query_type: Some(Type {
-                name: Some("Query".to_string()),
-                kind: None,
-                description: None,
-                fields: None,
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
-            }),
-            mutation_type: None,
-            subscription_type: None,
-            types: Some(vec![Type {
-                name: Some("Query".to_string()),
+    let mut s = String::new();
+
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("cycles")));
+    }
+
+    for (input, title, usage) in &[
+        (
+            true,
+            "Input Cycles",
+            "Clients can nest these inputs as deeply as they like, so code that reads them must handle any depth.",
+        ),
+        (
+            false,
+            "Output Cycles",
+            "Queries can follow these types' fields back around as far as they like, so generated types must be recursive.",
+        ),
+    ] {
+        let items: Vec<String> = cycles
+            .iter()
+            .filter(|cycle| cycle.input == *input)
+            .map(|cycle| {
+                let types: Vec<String> = cycle
+                    .types
+                    .iter()
+                    .map(|name| {
+                        let kind = schema
+                            .get_type(name)
+                            .and_then(|typ| typ.kind.clone())
+                            .unwrap_or_default();
+                        links.link(&to_inline_code(name), &get_link_for_type(&kind, name, links))
+                    })
+                    .collect();
+                if types.len() == 1 {
+                    format!("{} references itself", types[0])
+                } else {
+                    types.join(", ")
+                }
+            })
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        s.push_str(&to_header(2, title));
+        s.push_str(&format!("{}\n\n", usage));
+        s.push_str(&to_list(&items));
+    }
+
+    s
+}
+
+/// Generates the Errors page, which explains how mutations report errors
+/// and catalogs the error types with the mutations that can return them, or
+/// returns an empty string if no mutation reports errors in its result
+///
+/// # Arguments
+///
+/// * `schema` - the schema to find the error types of
+/// * `add_titles` - whether to add a title to the page
+pub fn errors_to_markdown(schema: &Schema, add_titles: bool, links: &Links) -> String {
+    let error_types = get_error_types(schema);
+    if error_types.is_empty() {
+        return String::new();
+    }
+
+    let mut s = String::new();
+
+    if add_titles {
+        s.push_str(&to_header(1, &links.page_title("errors")));
+    }
+
+    for convention in &[ErrorConvention::Union, ErrorConvention::Field] {
+        let used = error_types.iter().any(|error_type| {
+            error_type
+                .mutations
+                .iter()
+                .any(|(_, used)| used == convention)
+        });
+        if !used {
+            continue;
+        }
+
+        let (title, usage) = match convention {
+            ErrorConvention::Union => (
+                "Union Payloads",
+                "Some mutations return a union of their result and the errors they can report. \
+                 Select each error type with an inline fragment, as in `... on UserError { message }`.",
+            ),
+            ErrorConvention::Field => (
+                "Error Fields",
+                "Some mutations return a payload with an `errors` or `userErrors` field that lists what went wrong. \
+                 An empty list means the mutation succeeded.",
+            ),
+        };
+        s.push_str(&to_header(2, &links.section_title(title)));
+        s.push_str(&format!("{}\n\n", usage));
+    }
+
+    s.push_str(&to_header(2, &links.section_title("Error Types")));
+    s.push_str(&to_table_row(&[
+        "Error".to_string(),
+        "Returned By".to_string(),
+    ]));
+    s.push_str(&to_table_separator(2));
+    for error_type in &error_types {
+        let mutations: Vec<String> = error_type
+            .mutations
+            .iter()
+            .map(|(name, _)| {
+                links.link(
+                    &to_inline_code(name),
+                    &format!("{}#{}", links.page_file("mutations"), links.fragment(name)),
+                )
+            })
+            .collect();
+        s.push_str(&to_table_row(&[
+            links.link(
+                &to_inline_code(&error_type.name),
+                &get_link_for_type(&error_type.kind, &error_type.name, links),
+            ),
+            mutations.join(", "),
+        ]));
+    }
+    s.push('\n');
+
+    s
+}
+
+// Operations link to their own headings; other fields link to their types
+fn get_link_for_paginated_field(schema: &Schema, field: &PaginatedField, links: &Links) -> String {
+    let coordinate = to_inline_code(&format!("{}.{}", field.type_name, field.field_name));
+    let page = [
+        ("queries", schema.get_query_name()),
+        ("mutations", schema.get_mutation_name()),
+        ("subscriptions", schema.get_subscription_name()),
+    ]
+    .iter()
+    .find(|(_, name)| name.as_ref() == Some(&field.type_name))
+    .map(|(page, _)| *page);
+    let link = match page {
+        Some(page) => format!(
+            "{}#{}",
+            links.page_link(page),
+            links.fragment(&field.field_name)
+        ),
+        None => get_link_for_type(&field.type_kind, &field.type_name, links),
+    };
+    links.link(&coordinate, &link)
+}
+
+fn to_name_value_table(rows: &[(String, String)]) -> String {
+    let mut s = String::new();
+    s.push_str(&to_table_row(&["Name".to_string(), "Value".to_string()]));
+    s.push_str(&to_table_separator(2));
+    for (name, value) in rows {
+        s.push_str(&to_table_row(&[to_inline_code(name), value.to_string()]));
+    }
+    s.push('\n');
+    s
+}
+
+fn schema_type_to_markdown(
+    schema: &Schema,
+    type_name: Option<String>,
+    page: &str,
+    add_titles: bool,
+    icons: &Icons,
+    links: &Links,
+    tables: &Tables,
+) -> String {
+    let mut s = String::new();
+
+    if let Some(typ) = type_name.and_then(|name| schema.get_type(&name)) {
+        if add_titles {
+            if let Some(name) = &typ.name {
+                s.push_str(&to_header(1, &icons.decorate(&[page], name)));
+            }
+        }
+
+        // The schema's own description introduces the query type
+        if typ.name.is_some() && typ.name == schema.get_query_name() {
+            if let Some(description) = &schema.description {
+                s.push_str(&to_description(&links.description(description)));
+            }
+        }
+
+        if let Some(description) = &typ.description {
+            s.push_str(&to_description(&links.description(description)));
+        }
+
+        if let Some(fields) = &typ.fields {
+            let parent = typ.name.as_deref().unwrap_or_default();
+            // Grouped operations go under a heading for their group, after
+            // the ungrouped ones
+            let mut groups: Vec<Option<&str>> = vec![None];
+            for field in fields.iter() {
+                if !groups.contains(&field.group.as_deref()) {
+                    groups.push(field.group.as_deref());
+                }
+            }
+            for group in groups {
+                let level = match group {
+                    Some(group) => {
+                        s.push_str(&to_header(2, group));
+                        3
+                    }
+                    None => 2,
+                };
+                for field in fields
+                    .iter()
+                    .filter(|field| field.group.as_deref() == group)
+                {
+                    s.push_str(&field_to_markdown(
+                        field, parent, level, icons, links, tables,
+                    ));
+                    if page == "mutations" && tables.expand_mutations {
+                        s.push_str(&mutation_types_to_markdown(
+                            schema, field, level, links, tables,
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(prose) = &typ.prose {
+            s.push_str(&to_prose(prose));
+        }
+    }
+
+    s
+}
+
+#[allow(clippy::too_many_arguments)]
+fn types_to_markdown(
+    schema: &Schema,
+    kind: &str,
+    add_title: bool,
+    icons: &Icons,
+    links: &Links,
+    tables: &Tables,
+    cache: &mut RenderCache,
+    budget: &Budget,
+) -> Result<String, Box<dyn Error>> {
+    let mut s = String::new();
+
+    let mut types = schema.get_types_of_kind(kind);
+
+    if !types.is_empty() {
+        if add_title {
+            s.push_str(&to_header(
+                1,
+                &icons.decorate(
+                    &[get_page_for_kind(kind)],
+                    &links.page_title(get_page_for_kind(kind)),
+                ),
+            ));
+        }
+        budget.spend(s.len())?;
+
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for typ in types.iter() {
+            let markdown = cache.render(typ, || type_to_markdown(typ, icons, links, tables));
+            budget.spend(markdown.len())?;
+            s.push_str(&markdown);
+        }
+    }
+
+    Ok(s)
+}
+
+fn type_to_markdown(typ: &Type, icons: &Icons, links: &Links, tables: &Tables) -> String {
+    let mut s = String::new();
+
+    if let Some(name) = &typ.name {
+        let mut names = vec![get_page_for_kind(typ.kind.as_deref().unwrap_or_default())];
+        if icons.is_auth_required(name) {
+            names.push("auth");
+        }
+        s.push_str(&to_header(2, &icons.decorate(&names, &links.anchor(name))));
+    }
+
+    if let Some(used_by) = typ.used_by {
+        s.push_str(&to_quick_facts(typ, used_by));
+    }
+
+    if let Some(tags) = &typ.tags {
+        s.push_str(&to_label("Tags", &to_tags(tags)));
+    }
+
+    if let Some(description) = &typ.description {
+        s.push_str(&to_description(&links.description(description)));
+    }
+
+    if let Some(url) = &typ.specified_by_url {
+        s.push_str(&to_label("Specified by", &to_link(url, url)));
+    }
+
+    if let Some(examples) = &typ.examples {
+        s.push_str(&to_examples_label(examples));
+    }
+
+    if let Some(since) = &typ.since {
+        s.push_str(&to_notice(&to_since(since)));
+        s.push('\n');
+    }
+
+    if let Some(duplicates) = &typ.duplicates {
+        let duplicates: Vec<String> = duplicates
+            .iter()
+            .map(|duplicate| {
+                links.link(
+                    &to_inline_code(&duplicate.get_actual_name()),
+                    &get_link_for_type_ref(duplicate, links),
+                )
+            })
+            .collect();
+        s.push_str(&to_notice(&format!(
+            "Another source defines this type differently, as {}",
+            duplicates.join(", ")
+        )));
+        s.push('\n');
+    }
+
+    if let Some(fields) = &typ.fields {
+        s.push_str(&to_header(3, &links.section_title("Fields")));
+        let mut sorted = fields.to_vec();
+        if tables.sort.fields == SortOrder::Alpha {
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        let parent = typ.name.as_deref().unwrap_or_default();
+        let marked: Vec<Marked<Field>> = sorted
+            .iter()
+            .map(|field| {
+                let names = get_field_icon_names(field, parent, icons);
+                Marked(field, icons.decorate(&names, ""))
+            })
+            .collect();
+        s.push_str(&to_markdown_table(
+            vec![
+                "Name".to_string(),
+                "Type".to_string(),
+                "Description".to_string(),
+            ],
+            &marked,
+            links,
+        ));
+    }
+
+    if let Some(inputs) = &typ.inputs {
+        s.push_str(&to_header(3, &links.section_title("Inputs")));
+        let headers = vec![
+            "Name".to_string(),
+            "Type".to_string(),
+            "Description".to_string(),
+            "Default Value".to_string(),
+        ];
+        let mut sorted = inputs.to_vec();
+        if tables.sort.inputs == SortOrder::Alpha {
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        if typ.is_one_of == Some(true) {
+            s.push_str(&to_label(
+                "One of",
+                "exactly one of the following fields must be provided",
+            ));
+            let one_of: Vec<OneOfInput> = sorted.iter().map(OneOfInput).collect();
+            s.push_str(&to_markdown_table(headers, &one_of, links));
+        } else {
+            s.push_str(&to_markdown_table(headers, &sorted, links));
+        }
+    }
+
+    if let Some(enums) = &typ.enums {
+        s.push_str(&to_header(3, &links.section_title("Values")));
+        let mut sorted = enums.to_vec();
+        if tables.sort.values == SortOrder::Alpha {
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        let marked: Vec<Marked<Enum>> = sorted
+            .iter()
+            .map(|value| {
+                let names: &[&str] = if value.is_deprecated == Some(true) {
+                    &["deprecated"]
+                } else {
+                    &[]
+                };
+                Marked(value, icons.decorate(names, ""))
+            })
+            .collect();
+        s.push_str(&to_markdown_table(
+            vec![
+                "Name".to_string(),
+                "Description".to_string(),
+                "Deprecated".to_string(),
+            ],
+            &marked,
+            links,
+        ));
+    }
+
+    if let Some(interfaces) = typ.interfaces.as_ref().filter(|i| !i.is_empty()) {
+        s.push_str(&to_header(3, &links.section_title("Implements")));
+        let mut interfaces = interfaces.to_vec();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        let items: Vec<String> = interfaces
+            .iter()
+            .map(|interface| {
+                links.link(
+                    &to_inline_code(&interface.get_actual_name()),
+                    &get_link_for_type_ref(interface, links),
+                )
+            })
+            .collect();
+        s.push_str(&to_list(&items));
+    }
+
+    if let Some(possible_types) = &typ.possible_types {
+        s.push_str(&to_header(3, &links.section_title("Implemented by")));
+        let mut names: Vec<String> = possible_types
+            .iter()
+            .map(|typ| match &typ.name {
+                Some(name) => to_inline_code(name),
+                None => "".to_string(),
+            })
+            .collect();
+        names.sort();
+        s.push_str(&to_list(&names));
+    }
+
+    if let Some(referenced_by) = &typ.referenced_by {
+        s.push_str(&to_header(3, &links.section_title("Referenced By")));
+        let items: Vec<String> = referenced_by
+            .iter()
+            .map(|referrer| to_referrer(referrer, links))
+            .collect();
+        s.push_str(&to_list(&items));
+    }
+
+    if let Some(prose) = &typ.prose {
+        s.push_str(&to_prose(prose));
+    }
+
+    // Fields' prose is too long for their table rows, so it goes under a
+    // heading of its own after the tables
+    let parent = typ.name.as_deref().unwrap_or_default();
+    for field in typ.fields.iter().flatten() {
+        if let (Some(name), Some(prose)) = (&field.name, &field.prose) {
+            s.push_str(&to_header(4, &format!("{}.{}", parent, name)));
+            s.push_str(&to_prose(prose));
+        }
+    }
+
+    s
+}
+
+// Returns a link back to an operation, after its keyword, or to a type,
+// followed by the members that refer to the type
+fn to_referrer(referrer: &Referrer, links: &Links) -> String {
+    let page = match referrer.kind.as_str() {
+        "query" => Some("queries"),
+        "mutation" => Some("mutations"),
+        "subscription" => Some("subscriptions"),
+        _ => None,
+    };
+    let mut s = match page {
+        Some(page) => format!(
+            "{} {}",
+            referrer.kind,
+            links.link(
+                &to_inline_code(&referrer.name),
+                &format!(
+                    "{}#{}",
+                    links.page_link(page),
+                    links.fragment(&referrer.name)
+                )
+            )
+        ),
+        None => links.link(
+            &to_inline_code(&referrer.name),
+            &get_link_for_type(&referrer.kind, &referrer.name, links),
+        ),
+    };
+    if !referrer.members.is_empty() {
+        let members: Vec<String> = referrer
+            .members
+            .iter()
+            .map(|member| to_inline_code(member))
+            .collect();
+        s.push_str(&format!(": {}", members.join(", ")));
+    }
+    s
+}
+
+// Returns a one-row table that sums up the type before its details: its
+// kind, how many members it has, the interfaces it implements, how many
+// types use it, and how many of its members are deprecated
+fn to_quick_facts(typ: &Type, used_by: usize) -> String {
+    let kind = typ.kind.as_deref().unwrap_or_default();
+    let mut headers = vec!["Kind".to_string()];
+    let mut row = vec![to_kind_name(kind)];
+
+    let members = match kind {
+        "ENUM" => Some(("Values", typ.enums.as_ref().map_or(0, Vec::len))),
+        "UNION" => Some(("Types", typ.possible_types.as_ref().map_or(0, Vec::len))),
+        "INPUT_OBJECT" => Some(("Fields", typ.inputs.as_ref().map_or(0, Vec::len))),
+        "OBJECT" | "INTERFACE" => Some(("Fields", typ.fields.as_ref().map_or(0, Vec::len))),
+        _ => None,
+    };
+    if let Some((label, count)) = members {
+        headers.push(label.to_string());
+        row.push(count.to_string());
+    }
+
+    if kind == "OBJECT" || kind == "INTERFACE" {
+        let mut interfaces: Vec<String> = typ
+            .interfaces
+            .iter()
+            .flatten()
+            .map(|interface| to_inline_code(&interface.get_actual_name()))
+            .collect();
+        interfaces.sort();
+        headers.push("Implements".to_string());
+        row.push(if interfaces.is_empty() {
+            "none".to_string()
+        } else {
+            interfaces.join(", ")
+        });
+    }
+
+    headers.push("Used By".to_string());
+    row.push(match used_by {
+        1 => "1 type".to_string(),
+        count => format!("{} types", count),
+    });
+
+    if typ.fields.is_some() || typ.enums.is_some() {
+        let deprecated = typ
+            .fields
+            .iter()
+            .flatten()
+            .filter(|field| field.is_deprecated == Some(true))
+            .count()
+            + typ
+                .enums
+                .iter()
+                .flatten()
+                .filter(|value| value.is_deprecated == Some(true))
+                .count();
+        headers.push("Deprecated".to_string());
+        row.push(match deprecated {
+            0 => "none".to_string(),
+            count => count.to_string(),
+        });
+    }
+
+    let mut s = to_table_row(&headers);
+    s.push_str(&to_table_separator(headers.len()));
+    s.push_str(&to_table_row(&row));
+    s.push('\n');
+    s
+}
+
+// Returns the name of a kind as the docs refer to it
+fn to_kind_name(kind: &str) -> String {
+    match kind {
+        "OBJECT" => "Object",
+        "INPUT_OBJECT" => "Input object",
+        "INTERFACE" => "Interface",
+        "ENUM" => "Enum",
+        "UNION" => "Union",
+        "SCALAR" => "Scalar",
+        _ => kind,
+    }
+    .to_string()
+}
+
+// Returns long-form markdown from the docs directory as it was written
+fn to_prose(text: &str) -> String {
+    format!("{}\n\n", text)
+}
+
+fn directives_to_markdown(
+    schema: &Schema,
+    add_title: bool,
+    icons: &Icons,
+    links: &Links,
+    tables: &Tables,
+) -> String {
+    let mut s = String::new();
+
+    if let Some(directives) = &schema.directives {
+        if !directives.is_empty() {
+            if add_title {
+                s.push_str(&to_header(
+                    1,
+                    &icons.decorate(&["directives"], &links.page_title("directives")),
+                ));
+            }
+
+            let mut sorted: Vec<&Directive> = directives.iter().collect();
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for directive in sorted.iter() {
+                s.push_str(&directive_to_markdown(directive, links, tables));
+            }
+        }
+    }
+
+    s
+}
+
+fn directive_to_markdown(directive: &Directive, links: &Links, tables: &Tables) -> String {
+    let mut s = String::new();
+
+    if let Some(name) = &directive.name {
+        s.push_str(&to_header(2, &links.anchor(name)));
+    }
+
+    if let Some(description) = &directive.description {
+        s.push_str(&to_description(&links.description(description)));
+    }
+
+    if let Some(locations) = &directive.locations {
+        let locations: Vec<String> = locations.iter().map(|l| to_inline_code(l)).collect();
+        s.push_str(&to_label("Locations", &locations.join(", ")));
+    }
+
+    if let Some(is_repeatable) = &directive.is_repeatable {
+        s.push_str(&to_label(
+            "Repeatable",
+            if *is_repeatable { "yes" } else { "no" },
+        ));
+    }
+
+    if let Some(args) = &directive.args {
+        if !args.is_empty() {
+            s.push_str(&to_header(3, &links.section_title("Arguments")));
+            let mut sorted = args.to_vec();
+            if tables.sort.arguments == SortOrder::Alpha {
+                sorted.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+            s.push_str(&to_markdown_table(
+                vec![
+                    "Name".to_string(),
+                    "Type".to_string(),
+                    "Description".to_string(),
+                    "Default Value".to_string(),
+                ],
+                &sorted,
+                links,
+            ));
+        }
+    }
+
+    s
+}
+
+pub trait TableItem {
+    fn table_fields(&self, links: &Links) -> Vec<String>;
+}
+
+impl TableItem for Field {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let type_name = match self.field_type.as_ref() {
+            Some(typ) => typ.get_decorated_name(),
+            None => "".to_string(),
+        };
+        let link = match self.field_type.as_ref() {
+            Some(typ) => get_link_for_type_ref(typ, links),
+            None => "".to_string(),
+        };
+        vec![
+            to_inline_code(&to_safe_string(&self.name)),
+            links.link(&to_inline_code(&type_name), &link),
+            with_removal(
+                with_examples(
+                    with_since(
+                        with_tags(to_description_cell(&self.description, links), &self.tags),
+                        &self.since,
+                    ),
+                    &self.examples,
+                ),
+                &self.removal_date,
+            ),
+        ]
+    }
+}
+
+impl TableItem for Input {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let type_name = match self.input_type.as_ref() {
+            Some(typ) => typ.get_decorated_name(),
+            None => "".to_string(),
+        };
+        let link = match self.input_type.as_ref() {
+            Some(typ) => get_link_for_type_ref(typ, links),
+            None => "".to_string(),
+        };
+        vec![
+            to_inline_code(&to_safe_string(&self.name)),
+            links.link(&to_inline_code(&type_name), &link),
+            with_examples(
+                with_since(to_description_cell(&self.description, links), &self.since),
+                &self.examples,
+            ),
+            to_inline_code(&to_safe_string(&self.default_value)),
+        ]
+    }
+}
+
+/// An input field of a @oneOf input object, whose type is marked to show
+/// it's one of a set of mutually exclusive fields
+struct OneOfInput<'a>(&'a Input);
+
+impl TableItem for OneOfInput<'_> {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let mut fields = self.0.table_fields(links);
+        fields[1].push_str(" (one of)");
+        fields
+    }
+}
+
+/// A table row with icons prefixed to its first column
+struct Marked<'a, T: TableItem>(&'a T, String);
+
+impl<T: TableItem> TableItem for Marked<'_, T> {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let mut fields = self.0.table_fields(links);
+        if let Some(first) = fields.first_mut() {
+            first.insert_str(0, &self.1);
+        }
+        fields
+    }
+}
+
+impl TableItem for Enum {
+    fn table_fields(&self, links: &Links) -> Vec<String> {
+        let is_deprecated = match &self.is_deprecated {
+            Some(is_deprecated) => *is_deprecated,
+            None => false,
+        };
+        let deprecation_reason = to_description_cell(&self.deprecation_reason, links);
+        let dr = if is_deprecated {
+            with_removal(deprecation_reason, &self.removal_date)
+        } else {
+            "no".to_string()
+        };
+        vec![
+            to_inline_code(&to_safe_string(&self.name)),
+            with_since(to_description_cell(&self.description, links), &self.since),
+            dr,
+        ]
+    }
+}
+
+// Returns a description or deprecation reason for a table cell
+fn to_description_cell(text: &Option<String>, links: &Links) -> String {
+    to_safe_string(&text.as_deref().map(|text| links.description(text)))
+}
+
+fn to_since(since: &str) -> String {
+    format!("Available since {}", since)
+}
+
+// Adds when the element became available to its description in a table
+fn with_since(description: String, since: &Option<String>) -> String {
+    match since {
+        Some(since) if description.is_empty() => format!("_{}_", to_since(since)),
+        Some(since) => format!("{} _{}_", description, to_since(since)),
+        None => description,
+    }
+}
+
+fn to_tags(tags: &[String]) -> String {
+    let tags: Vec<String> = tags.iter().map(|tag| to_inline_code(tag)).collect();
+    tags.join(" ")
+}
+
+// Puts an element's tags before its description in a table
+fn with_tags(description: String, tags: &Option<Vec<String>>) -> String {
+    match tags {
+        Some(tags) if description.is_empty() => to_tags(tags),
+        Some(tags) => format!("{} {}", to_tags(tags), description),
+        None => description,
+    }
+}
+
+fn to_examples(examples: &[String]) -> String {
+    let examples: Vec<String> = examples.iter().map(|e| to_inline_code(e)).collect();
+    examples.join(", ")
+}
+
+fn get_examples_label(examples: &[String]) -> &'static str {
+    if examples.len() == 1 {
+        "Example"
+    } else {
+        "Examples"
+    }
+}
+
+fn to_examples_label(examples: &[String]) -> String {
+    to_label(get_examples_label(examples), &to_examples(examples))
+}
+
+// Adds example values to an element's description in a table
+fn with_examples(description: String, examples: &Option<Vec<String>>) -> String {
+    match examples {
+        Some(examples) => format!(
+            "{} _{}:_ {}",
+            description,
+            get_examples_label(examples),
+            to_examples(examples)
+        )
+        .trim_start()
+        .to_string(),
+        None => description,
+    }
+}
+
+fn to_removal(removal_date: &str) -> String {
+    format!("Removal: {}", removal_date)
+}
+
+// Adds a badge for when a deprecated element will be removed to a table cell
+fn with_removal(cell: String, removal_date: &Option<String>) -> String {
+    match removal_date {
+        Some(removal_date) if cell.is_empty() => format!("**{}**", to_removal(removal_date)),
+        Some(removal_date) => format!("{} **{}**", cell, to_removal(removal_date)),
+        None => cell,
+    }
+}
+
+// Flattens the text to one line for a table cell, joining its lines with
+// spaces
+fn to_safe_string(opt_s: &Option<String>) -> String {
+    match opt_s {
+        Some(s) => s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" "),
+        None => "".to_string(),
+    }
+}
+
+fn to_markdown_table(headers: Vec<String>, items: &[impl TableItem], links: &Links) -> String {
+    let mut s = String::new();
+    s.push_str(&to_table_row(&headers));
+    s.push_str(&to_table_separator(headers.len()));
+
+    for item in items.iter() {
+        let cells: Vec<String> = item
+            .table_fields(links)
+            .iter()
+            .map(|cell| escape_table_cell(cell))
+            .collect();
+        s.push_str(&to_table_row(&cells));
+    }
+    s.push('\n');
+    s
+}
+
+fn field_to_markdown(
+    field: &Field,
+    parent: &str,
+    level: u8,
+    icons: &Icons,
+    links: &Links,
+    tables: &Tables,
+) -> String {
+    let mut s = String::new();
+
+    if let Some(name) = &field.name {
+        let names = get_field_icon_names(field, parent, icons);
+        s.push_str(&to_header(
+            level,
+            &icons.decorate(&names, &links.heading(name)),
+        ));
+    }
+
+    if let Some(deprecated) = &field.is_deprecated {
+        if *deprecated {
+            s.push_str(&to_notice("Deprecated"));
+        }
+    }
+
+    if let Some(removal_date) = &field.removal_date {
+        s.push_str(&to_notice(&to_removal(removal_date)));
+    }
+
+    if let Some(tags) = &field.tags {
+        s.push_str(&to_label("Tags", &to_tags(tags)));
+    }
+
+    if let Some(cost) = field.cost {
+        s.push_str(&to_label("Estimated cost", &cost.to_string()));
+    }
+
+    if let Some(since) = &field.since {
+        s.push_str(&to_notice(&to_since(since)));
+    }
+
+    if let Some(description) = &field.description {
+        s.push_str(&to_description(&links.description(description)));
+    }
+
+    if let Some(typ) = &field.field_type {
+        s.push_str(&to_label(
+            "Type",
+            &links.link(
+                &to_inline_code(&typ.get_decorated_name()),
+                &get_link_for_type_ref(typ, links),
+            ),
+        ));
+    }
+
+    if let Some(examples) = &field.examples {
+        s.push_str(&to_examples_label(examples));
+    }
+
+    if let Some(args) = &field.args {
+        if !args.is_empty() {
+            s.push_str(&to_header(level + 1, &links.section_title("Arguments")));
+            let mut sorted = args.to_vec();
+            if tables.sort.arguments == SortOrder::Alpha {
+                sorted.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+            s.push_str(&to_markdown_table(
+                vec![
+                    "Name".to_string(),
+                    "Type".to_string(),
+                    "Description".to_string(),
+                    "Default Value".to_string(),
+                ],
+                &sorted,
+                links,
+            ));
+        }
+    }
+
+    if let Some(prose) = &field.prose {
+        s.push_str(&to_prose(prose));
+    }
+
+    s
+}
+
+// Mutations that follow the `FooInput`/`FooPayload` convention show both
+// types' fields on the mutation itself, so readers don't have to hop to the
+// Inputs and Objects pages to see what goes in and what comes back
+fn mutation_types_to_markdown(
+    schema: &Schema,
+    field: &Field,
+    level: u8,
+    links: &Links,
+    tables: &Tables,
+) -> String {
+    let mut s = String::new();
+
+    let payload_name = match &field.field_type {
+        Some(typ) => typ.get_actual_name(),
+        None => return s,
+    };
+    let input_name = match payload_name.strip_suffix("Payload") {
+        Some(base) if !base.is_empty() => format!("{}Input", base),
+        _ => return s,
+    };
+    let takes_input = field.args.iter().flatten().any(|arg| {
+        arg.input_type
+            .as_ref()
+            .is_some_and(|typ| typ.get_actual_name() == input_name)
+    });
+    if !takes_input {
+        return s;
+    }
+
+    let inputs = schema
+        .get_type(&input_name)
+        .and_then(|typ| typ.inputs.as_ref());
+    let fields = schema
+        .get_type(&payload_name)
+        .and_then(|typ| typ.fields.as_ref());
+    if let (Some(inputs), Some(fields)) = (inputs, fields) {
+        s.push_str(&to_header(
+            level + 1,
+            &format!("{}: {}", links.section_title("Input"), input_name),
+        ));
+        let mut sorted = inputs.to_vec();
+        if tables.sort.inputs == SortOrder::Alpha {
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        s.push_str(&to_markdown_table(
+            vec![
+                "Name".to_string(),
+                "Type".to_string(),
+                "Description".to_string(),
+                "Default Value".to_string(),
+            ],
+            &sorted,
+            links,
+        ));
+
+        s.push_str(&to_header(
+            level + 1,
+            &format!("{}: {}", links.section_title("Payload"), payload_name),
+        ));
+        let mut sorted = fields.to_vec();
+        if tables.sort.fields == SortOrder::Alpha {
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        s.push_str(&to_markdown_table(
+            vec![
+                "Name".to_string(),
+                "Type".to_string(),
+                "Description".to_string(),
+            ],
+            &sorted,
+            links,
+        ));
+    }
+
+    s
+}
+
+// Returns the names of the icons that mark a field: auth-required if the
+// field or its parent type requires authorization, and deprecated
+fn get_field_icon_names(field: &Field, parent: &str, icons: &Icons) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    let coordinate = format!("{}.{}", parent, field.name.as_deref().unwrap_or_default());
+    if icons.is_auth_required(parent) || icons.is_auth_required(&coordinate) {
+        names.push("auth");
+    }
+    if field.is_deprecated == Some(true) {
+        names.push("deprecated");
+    }
+    names
+}
+
+pub fn get_page_for_kind(kind: &str) -> &'static str {
+    GRAPHQL_TYPES.get(kind).copied().unwrap_or(GLOSSARY_PAGE)
+}
+
+// Links are built as strings rather than with Path so they always use
+// forward slashes, no matter which platform generates the output
+fn get_link_for_type_ref(type_ref: &TypeRef, links: &Links) -> String {
+    get_link_for_type(
+        &type_ref.get_actual_kind(),
+        &type_ref.get_actual_name(),
+        links,
+    )
+}
+
+fn get_link_for_type(kind: &str, name: &str, links: &Links) -> String {
+    format!(
+        "{}#{}",
+        links.page_link(get_page_for_kind(kind)),
+        links.fragment(name)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::TitleCase;
+    use crate::icons::IconSet;
+    use crate::links::Links;
+    use crate::schema::{ElementMut, TypeRef};
+    use crate::stats::{set_referenced_by, set_used_by};
+    use crate::tables::Sort;
+
+    #[test]
+    fn generate_from_schema_should_return_empty_when_empty_schema() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            description: None,
+        };
+        let map = generate_from_schema(
+            schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert_eq!(11, map.len());
+        assert_eq!("".to_string(), map["queries"]);
+        assert_eq!("".to_string(), map["mutations"]);
+        assert_eq!("".to_string(), map["subscriptions"]);
+        assert_eq!("".to_string(), map["inputs"]);
+        assert_eq!("".to_string(), map["objects"]);
+        assert_eq!("".to_string(), map["enums"]);
+        assert_eq!("".to_string(), map["interfaces"]);
+        assert_eq!("".to_string(), map["unions"]);
+        assert_eq!("".to_string(), map["scalars"]);
+        assert_eq!("".to_string(), map["directives"]);
+        assert_eq!("".to_string(), map["glossary"]);
+    }
+
+    #[test]
+    fn generate_from_schema_should_document_custom_root_types(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            schema { query: QueryRoot, mutation: MutationRoot }
+            type QueryRoot {
+              player: Player
+              teams: [String]
+            }
+            type MutationRoot { addPlayer(name: String!): Player }
+            type Player { name: String }
+            "#,
+        )?;
+        schema.for_each_element_mut(&mut |coordinate, element| {
+            if let (ElementMut::Field(field), "QueryRoot.teams") = (element, coordinate) {
+                field.group = Some("Teams".to_string());
+            }
+        });
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            "# QueryRoot\n\n## player\n\n**Type:** [`Player`](objects.md#player)\n\n## Teams\n\n### teams\n\n**Type:** [`[String]`](scalars.md#string)\n\n",
+            map["queries"]
+        );
+        assert!(map["mutations"].starts_with("# MutationRoot\n\n## addPlayer\n"));
+        let stats = get_page_stats(&schema);
+        assert_eq!(2, stats["queries"].field_count);
+        assert_eq!(1, stats["mutations"].field_count);
+        Ok(())
+    }
+
+    #[test]
+    fn generate_from_schema_should_add_prose_after_sections(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { players: [Player] }
+            type Player { name: String }
+            "#,
+        )?;
+        schema.for_each_element_mut(&mut |coordinate, mut element| {
+            if let Some(prose) = element.prose_mut() {
+                *prose = Some(format!("### About {}\n\nMore about it.", coordinate));
+            }
+        });
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert!(map["queries"].contains(
+            "(objects.md#player)\n\n### About Query.players\n\nMore about it.\n\n### About Query\n\nMore about it.\n\n"
+        ));
+        assert!(map["objects"].contains(
+            "| `name` | [`String`](scalars.md#string) |  |\n\n### About Player\n\nMore about it.\n\n#### Player.name\n\n### About Player.name\n\nMore about it.\n\n## "
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_kinds_should_link_to_the_glossary() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Schema::from_str(
+            r#"{
+                "types": [
+                    {
+                        "kind": "OBJECT",
+                        "name": "Query",
+                        "fields": [
+                            {"name": "gadget", "type": {"kind": "WIDGET", "name": "Gadget"}},
+                            {"name": "ok", "type": {"kind": "SCALAR", "name": "Int"}}
+                        ]
+                    },
+                    {"kind": "WIDGET", "name": "Gadget", "description": "A gadget"}
+                ]
+            }"#,
+        )?;
+        assert_eq!(
+            vec![
+                (
+                    "Query.gadget".to_string(),
+                    "Gadget".to_string(),
+                    "WIDGET".to_string()
+                ),
+                (
+                    "Gadget".to_string(),
+                    "Gadget".to_string(),
+                    "WIDGET".to_string()
+                ),
+            ],
+            get_unknown_kinds(&schema)
+        );
+        let links = Links::default();
+        assert_eq!(
+            "glossary.md#gadget",
+            get_link_for_type("WIDGET", "Gadget", &links)
+        );
+        assert_eq!(
+            "# Glossary\n\n## <a name=\"gadget\"></a>Gadget\n\n> A gadget\n\n**Kind:** `WIDGET`\n\n",
+            glossary_to_markdown(&schema, true, &links)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_query_should_return_empty_when_none() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            "".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_query_name(),
+                "queries",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_query_should_return_empty_when_some_and_no_members() {
+        let schema = &Schema {
+            query_type: Some(Type {
+                name: None,
+                kind: None,
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }),
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            "".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_query_name(),
+                "queries",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_query_should_return_markdown_when_some() {
+        let schema = &Schema {
+            query_type: Some(Type {
+                name: Some("Query".to_string()),
+                kind: None,
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }),
+            mutation_type: None,
+            subscription_type: None,
+            types: Some(vec![Type {
+                name: Some("Query".to_string()),
+                kind: None,
+                description: Some("The root query".to_string()),
+                fields: Some(vec![Field {
+                    name: Some("players".to_string()),
+                    description: Some("get the players".to_string()),
+                    args: None,
+                    field_type: None,
+                    is_deprecated: None,
+                    deprecation_reason: None,
+                    since: None,
+                    removal_date: None,
+                    cost: None,
+                    examples: None,
+                    group: None,
+                    prose: None,
+                    tags: None,
+                }]),
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }]),
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            r#"# Query
+
+> The root query
+
+## players
+
+> get the players
+
+"#
+            .to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_query_name(),
+                "queries",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_mutation_should_return_empty_when_none() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            "".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_mutation_name(),
+                "mutations",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_mutation_should_return_empty_when_some_and_no_members() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: Some(Type {
+                name: None,
                 kind: None,
-                description: Some("The root query".to_string()),
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }),
+            subscription_type: None,
+            types: None,
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            "".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_mutation_name(),
+                "mutations",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_mutation_should_return_markdown_when_some() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: Some(Type {
+                name: Some("Mutation".to_string()),
+                kind: None,
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }),
+            subscription_type: None,
+            types: Some(vec![Type {
+                name: Some("Mutation".to_string()),
+                kind: None,
+                description: Some("The root mutation".to_string()),
                 fields: Some(vec![Field {
-                    name: Some("players".to_string()),
-                    description: Some("get the players".to_string()),
+                    name: Some("addPlayer".to_string()),
+                    description: Some("add a player".to_string()),
+                    args: None,
+                    field_type: None,
+                    is_deprecated: None,
+                    deprecation_reason: None,
+                    since: None,
+                    removal_date: None,
+                    cost: None,
+                    examples: None,
+                    group: None,
+                    prose: None,
+                    tags: None,
+                }]),
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }]),
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            r#"# Mutation
+
+> The root mutation
+
+## addPlayer
+
+> add a player
+
+"#
+            .to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_mutation_name(),
+                "mutations",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_subscription_should_return_empty_when_none() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            "".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_subscription_name(),
+                "subscriptions",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_subscription_should_return_empty_when_some_and_no_members() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: Some(Type {
+                name: None,
+                kind: None,
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }),
+            types: None,
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            "".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_subscription_name(),
+                "subscriptions",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_subscription_should_return_markdown_when_some() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: Some(Type {
+                name: Some("Subscription".to_string()),
+                kind: None,
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }),
+            types: Some(vec![Type {
+                name: Some("Subscription".to_string()),
+                kind: None,
+                description: Some("The root subscription".to_string()),
+                fields: Some(vec![Field {
+                    name: Some("subscribePlayers".to_string()),
+                    description: Some("subscribe to players".to_string()),
                     args: None,
                     field_type: None,
                     is_deprecated: None,
                     deprecation_reason: None,
+                    since: None,
+                    removal_date: None,
+                    cost: None,
+                    examples: None,
+                    group: None,
+                    prose: None,
+                    tags: None,
                 }]),
                 inputs: None,
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }]),
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            r#"# Subscription
+
+> The root subscription
+
+## subscribePlayers
+
+> subscribe to players
+
+"#
+            .to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_subscription_name(),
+                "subscriptions",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn types_to_markdown_should_return_markdown() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: Some(vec![Type {
+                name: Some("Player".to_string()),
+                kind: Some("OBJECT".to_string()),
+                description: Some("A player".to_string()),
+                fields: Some(vec![
+                    Field {
+                        name: Some("firstName".to_string()),
+                        description: Some("The player's first name".to_string()),
+                        args: None,
+                        field_type: None,
+                        is_deprecated: None,
+                        deprecation_reason: None,
+                        since: None,
+                        removal_date: None,
+                        cost: None,
+                        examples: None,
+                        group: None,
+                        prose: None,
+                        tags: None,
+                    },
+                    Field {
+                        name: Some("lastName".to_string()),
+                        description: Some("The player's last name".to_string()),
+                        args: None,
+                        field_type: None,
+                        is_deprecated: None,
+                        deprecation_reason: None,
+                        since: None,
+                        removal_date: None,
+                        cost: None,
+                        examples: None,
+                        group: None,
+                        prose: None,
+                        tags: None,
+                    },
+                ]),
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }]),
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            r#"# Objects
+
+## <a name="player"></a>Player
+
+> A player
+
+### Fields
+
+| Name | Type | Description |
+| --- | --- | --- |
+| `firstName` |  | The player's first name |
+| `lastName` |  | The player's last name |
+
+"#
+            .to_string(),
+            types_to_markdown(
+                schema,
+                "OBJECT",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default(),
+                &mut RenderCache::default(),
+                &Budget::default()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn types_to_markdown_should_return_markdown_with_no_h1_when_add_titles_is_false() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: Some(vec![Type {
+                name: Some("Player".to_string()),
+                kind: Some("OBJECT".to_string()),
+                description: Some("A player".to_string()),
+                fields: Some(vec![
+                    Field {
+                        name: Some("firstName".to_string()),
+                        description: Some("The player's first name".to_string()),
+                        args: None,
+                        field_type: None,
+                        is_deprecated: None,
+                        deprecation_reason: None,
+                        since: None,
+                        removal_date: None,
+                        cost: None,
+                        examples: None,
+                        group: None,
+                        prose: None,
+                        tags: None,
+                    },
+                    Field {
+                        name: Some("lastName".to_string()),
+                        description: Some("The player's last name".to_string()),
+                        args: None,
+                        field_type: None,
+                        is_deprecated: None,
+                        deprecation_reason: None,
+                        since: None,
+                        removal_date: None,
+                        cost: None,
+                        examples: None,
+                        group: None,
+                        prose: None,
+                        tags: None,
+                    },
+                ]),
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                specified_by_url: None,
+                is_one_of: None,
+                since: None,
+                examples: None,
+                prose: None,
+                used_by: None,
+                duplicates: None,
+                referenced_by: None,
+                tags: None,
+            }]),
+            directives: None,
+            description: None,
+        };
+        assert_eq!(
+            r#"## <a name="player"></a>Player
+
+> A player
+
+### Fields
+
+| Name | Type | Description |
+| --- | --- | --- |
+| `firstName` |  | The player's first name |
+| `lastName` |  | The player's last name |
+
+"#
+            .to_string(),
+            types_to_markdown(
+                schema,
+                "OBJECT",
+                false,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default(),
+                &mut RenderCache::default(),
+                &Budget::default()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn type_to_markdown_should_return_markdown() {
+        let typ = &Type {
+            name: Some("Player".to_string()),
+            description: Some("This is a player".to_string()),
+            kind: None,
+            inputs: None,
+            interfaces: None,
+            enums: None,
+            possible_types: None,
+            specified_by_url: None,
+            is_one_of: None,
+            since: None,
+            examples: None,
+            prose: None,
+            used_by: None,
+            duplicates: None,
+            referenced_by: None,
+            tags: None,
+            fields: Some(vec![Field {
+                name: Some("id".to_string()),
+                description: Some("The ID".to_string()),
+                args: None,
+                field_type: None,
+                is_deprecated: None,
+                deprecation_reason: None,
+                since: None,
+                removal_date: None,
+                cost: None,
+                examples: None,
+                group: None,
+                prose: None,
+                tags: None,
             }]),
-            directives: None,
         };
         assert_eq!(
-            r#"# Query
+            r#"## <a name="player"></a>Player
 
-> The root query
+> This is a player
 
-## players
+### Fields
 
-> get the players
+| Name | Type | Description |
+| --- | --- | --- |
+| `id` |  | The ID |
 
 "#
             .to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name(), true)
+            type_to_markdown(
+                typ,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
         );
     }
 
     #[test]
-    fn schema_type_to_markdown_for_mutation_should_return_empty_when_none() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: None,
-            types: None,
-            directives: None,
+    fn about_to_markdown_should_return_markdown() {
+        let endpoint = Endpoint {
+            url: "https://example.com/graphql".to_string(),
+            headers: vec![("server".to_string(), "nginx".to_string())],
+            extensions: Some(serde_json::json!({ "version": "1.2" })),
         };
         assert_eq!(
-            "".to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name(), true)
+            format!(
+                r#"# About
+
+**Endpoint:** https://example.com/graphql
+
+**Generated:** 2020-08-01T00:00:00Z
+
+**Generated by:** gumwood {}
+
+## Server
+
+| Name | Value |
+| --- | --- |
+| `server` | nginx |
+
+## Extensions
+
+| Name | Value |
+| --- | --- |
+| `version` | `"1.2"` |
+
+"#,
+                env!("CARGO_PKG_VERSION")
+            ),
+            about_to_markdown(&[endpoint], "2020-08-01T00:00:00Z", true, &Links::default())
         );
     }
 
     #[test]
-    fn schema_type_to_markdown_for_mutation_should_return_empty_when_some_and_no_members() {
+    fn about_to_markdown_should_skip_empty_sections() {
+        let endpoint = Endpoint {
+            url: "https://example.com/graphql".to_string(),
+            ..Default::default()
+        };
+        let about = about_to_markdown(&[endpoint], "now", false, &Links::default());
+        assert!(about.starts_with("**Endpoint:**"));
+        assert!(!about.contains("## Server"));
+        assert!(!about.contains("## Extensions"));
+    }
+
+    #[test]
+    fn about_to_markdown_should_list_each_endpoint() {
+        let endpoints = [
+            Endpoint {
+                url: "https://players.example.com/graphql".to_string(),
+                headers: vec![("server".to_string(), "nginx".to_string())],
+                extensions: None,
+            },
+            Endpoint {
+                url: "https://teams.example.com/graphql".to_string(),
+                headers: Vec::new(),
+                extensions: None,
+            },
+        ];
+        let about = about_to_markdown(&endpoints, "now", false, &Links::default());
+        assert!(about.starts_with(
+            "**Endpoint:** https://players.example.com/graphql\n\n\
+             **Endpoint:** https://teams.example.com/graphql\n\n"
+        ));
+        assert!(about.contains("## Server: https://players.example.com/graphql\n"));
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_mutation_should_expand_input_and_payload() {
+        let schema = &Schema::from_sdl(
+            r#"
+            type Mutation {
+                addPlayer(input: AddPlayerInput!): AddPlayerPayload
+                removePlayer(id: ID!): RemovePlayerPayload
+            }
+            input AddPlayerInput { name: String! }
+            type AddPlayerPayload { id: ID! }
+            type RemovePlayerPayload { id: ID! }
+            "#,
+        )
+        .unwrap();
+        let markdown = schema_type_to_markdown(
+            schema,
+            schema.get_mutation_name(),
+            "mutations",
+            false,
+            &Icons::default(),
+            &Links::default(),
+            &Tables {
+                expand_mutations: true,
+                ..Tables::default()
+            },
+        );
+        assert!(markdown.contains(
+            r#"### Input: AddPlayerInput
+
+| Name | Type | Description | Default Value |
+| --- | --- | --- | --- |
+| `name` | [`String!`](scalars.md#string) |  |  |
+
+### Payload: AddPlayerPayload
+
+| Name | Type | Description |
+| --- | --- | --- |
+| `id` | [`ID!`](scalars.md#id) |  |
+
+"#
+        ));
+        // removePlayer doesn't take a RemovePlayerInput
+        assert!(!markdown.contains("Payload: RemovePlayerPayload"));
+
+        // Mutations are only expanded when asked
+        let markdown = schema_type_to_markdown(
+            schema,
+            schema.get_mutation_name(),
+            "mutations",
+            false,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+        );
+        assert!(!markdown.contains("Input: AddPlayerInput"));
+        assert!(!markdown.contains("Payload: AddPlayerPayload"));
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_mutation_should_apply_title_case_to_headings() {
+        let schema = &Schema::from_sdl(
+            r#"
+            type Mutation { addPlayer(input: AddPlayerInput!): AddPlayerPayload }
+            input AddPlayerInput { name: String! }
+            type AddPlayerPayload { id: ID! }
+            "#,
+        )
+        .unwrap();
+        let markdown = schema_type_to_markdown(
+            schema,
+            schema.get_mutation_name(),
+            "mutations",
+            false,
+            &Icons::default(),
+            &Links {
+                title_case: TitleCase::Upper,
+                ..Links::default()
+            },
+            &Tables {
+                expand_mutations: true,
+                ..Tables::default()
+            },
+        );
+        assert!(markdown.contains("### ARGUMENTS\n"));
+        assert!(markdown.contains("### INPUT: AddPlayerInput\n"));
+        assert!(markdown.contains("### PAYLOAD: AddPlayerPayload\n"));
+    }
+
+    #[test]
+    fn schema_type_to_markdown_for_query_should_include_schema_description() {
         let schema = &Schema {
-            query_type: None,
-            mutation_type: Some(Type {
-                name: None,
-                kind: None,
-                description: None,
-                fields: None,
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
+            description: Some("The league schema".to_string()),
+            query_type: Some(Type {
+                name: Some("Query".to_string()),
+                ..Default::default()
             }),
+            mutation_type: None,
             subscription_type: None,
-            types: None,
+            types: Some(vec![Type {
+                name: Some("Query".to_string()),
+                description: Some("The root query".to_string()),
+                ..Default::default()
+            }]),
             directives: None,
         };
         assert_eq!(
-            "".to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name(), true)
+            "# Query\n\n> The league schema\n\n> The root query\n\n".to_string(),
+            schema_type_to_markdown(
+                schema,
+                schema.get_query_name(),
+                "queries",
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
         );
     }
 
     #[test]
-    fn schema_type_to_markdown_for_mutation_should_return_markdown_when_some() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: Some(Type {
-                name: Some("Mutation".to_string()),
-                kind: None,
+    fn type_to_markdown_should_render_plain_names_without_links() {
+        let typ = &Type {
+            name: Some("Player".to_string()),
+            kind: Some("OBJECT".to_string()),
+            fields: Some(vec![Field {
+                name: Some("team".to_string()),
+                field_type: Some(TypeRef {
+                    name: Some("Team".to_string()),
+                    kind: Some("OBJECT".to_string()),
+                    of_type: None,
+                }),
                 description: None,
-                fields: None,
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
-            }),
-            subscription_type: None,
-            types: Some(vec![Type {
-                name: Some("Mutation".to_string()),
-                kind: None,
-                description: Some("The root mutation".to_string()),
-                fields: Some(vec![Field {
-                    name: Some("addPlayer".to_string()),
-                    description: Some("add a player".to_string()),
-                    args: None,
-                    field_type: None,
-                    is_deprecated: None,
-                    deprecation_reason: None,
-                }]),
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
+                args: None,
+                is_deprecated: None,
+                deprecation_reason: None,
+                since: None,
+                removal_date: None,
+                cost: None,
+                examples: None,
+                group: None,
+                prose: None,
+                tags: None,
             }]),
-            directives: None,
+            ..Default::default()
+        };
+        let links = Links {
+            plain: true,
+            ..Default::default()
+        };
+        let markdown = type_to_markdown(typ, &Icons::default(), &links, &Tables::default());
+        assert!(markdown.contains("| `team` | `Team` |  |\n"));
+    }
+
+    #[test]
+    fn schema_type_to_markdown_should_prefix_anchors() {
+        let schema =
+            &Schema::from_sdl("type Query { player: Player } type Player { id: ID }").unwrap();
+        let links = Links {
+            anchor_prefix: "gql-".to_string(),
+            ..Default::default()
+        };
+        let markdown = schema_type_to_markdown(
+            schema,
+            schema.get_query_name(),
+            "queries",
+            false,
+            &Icons::default(),
+            &links,
+            &Tables::default(),
+        );
+        assert!(markdown.starts_with(
+            "## <a name=\"gql-player\"></a>player\n\n**Type:** [`Player`](objects.md#gql-player)\n"
+        ));
+    }
+
+    #[test]
+    fn schema_type_to_markdown_should_group_operations() {
+        let mut schema = Schema::from_sdl(
+            "type Query { teams: [Team] players(first: Int): [Player] player: Player } type Player { id: ID } type Team { id: ID }",
+        )
+        .unwrap();
+        schema.for_each_element_mut(&mut |coordinate, element| {
+            if let ElementMut::Field(field) = element {
+                if coordinate.starts_with("Query.player") {
+                    field.group = Some("Players".to_string());
+                }
+            }
+        });
+        let markdown = schema_type_to_markdown(
+            &schema,
+            schema.get_query_name(),
+            "queries",
+            false,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+        );
+        let headings: Vec<&str> = markdown
+            .lines()
+            .filter(|line| line.starts_with('#'))
+            .collect();
+        assert_eq!(
+            vec![
+                "## teams",
+                "## Players",
+                "### players",
+                "#### Arguments",
+                "### player"
+            ],
+            headings
+        );
+    }
+
+    #[test]
+    fn type_to_markdown_should_include_examples() {
+        let typ = &Type {
+            name: Some("Player".to_string()),
+            kind: Some("OBJECT".to_string()),
+            fields: Some(vec![Field {
+                name: Some("name".to_string()),
+                description: Some("The name".to_string()),
+                args: None,
+                field_type: None,
+                is_deprecated: None,
+                deprecation_reason: None,
+                since: None,
+                removal_date: None,
+                cost: None,
+                examples: Some(vec!["\"Wayne\"".to_string(), "\"Mario\"".to_string()]),
+                group: None,
+                prose: None,
+                tags: None,
+            }]),
+            examples: Some(vec!["{}".to_string()]),
+            ..Default::default()
+        };
+        let markdown = type_to_markdown(
+            typ,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+        );
+        assert!(markdown.contains("**Example:** `{}`\n"));
+        assert!(markdown.contains("| The name _Examples:_ `\"Wayne\"`, `\"Mario\"` |\n"));
+    }
+
+    #[test]
+    fn type_to_markdown_should_include_specified_by_url() {
+        let typ = &Type {
+            name: Some("UUID".to_string()),
+            kind: Some("SCALAR".to_string()),
+            specified_by_url: Some("https://tools.ietf.org/html/rfc4122".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            r#"## <a name="uuid"></a>UUID
+
+**Specified by:** [https://tools.ietf.org/html/rfc4122](https://tools.ietf.org/html/rfc4122)
+
+"#
+            .to_string(),
+            type_to_markdown(
+                typ,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
+        );
+    }
+
+    #[test]
+    fn largest_types_to_markdown_should_rank_types() {
+        let schema = &Schema::from_sdl(
+            "type Query { player: Player players: [Player] } type Player { id: ID name: String age: Int }",
+        )
+        .unwrap();
+        assert_eq!(
+            r#"# Largest Types
+
+## Most Fields
+
+| Rank | Type | Kind | Fields | Inbound References |
+| --- | --- | --- | --- | --- |
+| 1 | [`Player`](objects.md#player) | `OBJECT` | 3 | 2 |
+
+## Most Referenced
+
+| Rank | Type | Kind | Fields | Inbound References |
+| --- | --- | --- | --- | --- |
+| 1 | [`Player`](objects.md#player) | `OBJECT` | 3 | 2 |
+
+"#
+            .to_string(),
+            largest_types_to_markdown(schema, 1, true, &Links::default())
+        );
+    }
+
+    #[test]
+    fn cost_to_markdown_should_list_operation_costs() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { players: [String] @cost(weight: "10") teams: [String] @cost(weight: "20") score: Int }
+            type Mutation { addPlayer: String @cost(weight: "10") }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            r#"# Cost
+
+## Rate Limits
+
+5,000 points per hour
+
+## Operations
+
+| Operation | Kind | Estimated Cost |
+| --- | --- | --- |
+| [`teams`](queries.md#teams) | query | 20 |
+| [`addPlayer`](mutations.md#addplayer) | mutation | 10 |
+| [`players`](queries.md#players) | query | 10 |
+
+"#,
+            cost_to_markdown(
+                &schema,
+                Some("5,000 points per hour"),
+                true,
+                &Links::default()
+            )
+        );
+    }
+
+    #[test]
+    fn interface_conformance_to_markdown_should_check_each_field() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { node: Node }
+            interface Node { id: ID friends: [Node] }
+            interface Named { name: String }
+            type Player implements Node { id: ID! friends: [Node] }
+            type Team implements Node { id: ID }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            r#"# Interface Conformance
+
+## Named
+
+Nothing implements this interface.
+
+## Node
+
+| [`Node`](interfaces.md#node) | [`Player`](objects.md#player) | [`Team`](objects.md#team) |
+| --- | --- | --- |
+| `id` | ✓ `ID!` | ✓ |
+| `friends` | ✓ | ✗ |
+
+"#
+            .to_string(),
+            interface_conformance_to_markdown(&schema, true, &Links::default())
+        );
+    }
+
+    #[test]
+    fn scalar_usage_to_markdown_should_audit_custom_scalars() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player(id: ID!, born: Date): Player }
+            type Player { born: Date joined: Date! url: URL }
+            input PlayerInput { born: Date }
+            "A date, as YYYY-MM-DD"
+            scalar Date
+            scalar URL @specifiedBy(url: "https://tools.ietf.org/html/rfc3986")
+            scalar Unused
+            directive @since(on: Date) on FIELD_DEFINITION
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            r#"# Scalar Usage
+
+| Scalar | Fields | Arguments | Description | Specified By |
+| --- | --- | --- | --- | --- |
+| [`Date`](scalars.md#date) | 3 | 2 | yes | no |
+| [`URL`](scalars.md#url) | 1 | 0 | no | yes |
+| [`Unused`](scalars.md#unused) | 0 | 0 | no | no |
+
+"#
+            .to_string(),
+            scalar_usage_to_markdown(&schema, true, &Links::default())
+        );
+        assert_eq!(
+            "",
+            scalar_usage_to_markdown(
+                &Schema::from_sdl("type Query { name: String }").unwrap(),
+                true,
+                &Links::default()
+            )
+        );
+    }
+
+    #[test]
+    fn generate_from_schema_should_keep_schema_order_when_asked(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { players(first: Int, after: String): [Player] }
+            type Player { number: Int, name: String }
+            "#,
+        )?;
+        let tables = Tables {
+            sort: Sort {
+                arguments: SortOrder::Schema,
+                ..Sort::default()
+            },
+            ..Tables::default()
+        };
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &tables,
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert!(map["queries"].contains("| `first` | [`Int`](scalars.md#int) |  |  |\n| `after` |"));
+        assert!(
+            map["objects"].contains("| `name` | [`String`](scalars.md#string) |  |\n| `number` |")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn generate_from_schema_should_add_quick_facts_when_counted(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player, players: [Player] }
+            interface Node { id: ID! }
+            type Player implements Node { id: ID!, number: Int @deprecated, team: Team }
+            type Team { players: [Player], captain: Player }
+            enum Position { CENTER, WING }
+            "#,
+        )?;
+        set_used_by(&mut schema);
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert!(map["objects"].contains(
+            "Player\n\n| Kind | Fields | Implements | Used By | Deprecated |\n| --- | --- | --- | --- | --- |\n| Object | 3 | `Node` | 2 types | 1 |\n\n### Fields"
+        ));
+        assert!(map["enums"].contains(
+            "Position\n\n| Kind | Values | Used By | Deprecated |\n| --- | --- | --- | --- |\n| Enum | 2 | 0 types | none |\n\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn generate_from_schema_should_stop_once_over_budget() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Player { id: ID!, name: String }
+            type Team { id: ID!, players: [Player!]! }
+            "#,
+        )
+        .unwrap();
+        let generate = |budget: &Budget| {
+            generate_from_schema(
+                &schema,
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default(),
+                &mut RenderCache::default(),
+                budget,
+            )
+        };
+        let size: usize = generate(&Budget::default())
+            .unwrap()
+            .values()
+            .map(String::len)
+            .sum();
+        assert!(generate(&Budget::new(Some(size as u64))).is_ok());
+        match generate(&Budget::new(Some(size as u64 - 1))) {
+            Ok(_) => panic!("docs over budget should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .contains(&format!("more than --max-output-size {} bytes", size - 1))),
+        }
+    }
+
+    #[test]
+    fn generate_from_schema_should_link_implemented_interfaces() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            interface Node { id: ID! }
+            interface Named { name: String }
+            interface Person implements Named { name: String }
+            type Player implements Node & Person & Named { id: ID!, name: String }
+            "#,
+        )
+        .unwrap();
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert!(map["objects"].contains(
+            "### Implements\n\n* [`Named`](interfaces.md#named)\n* [`Node`](interfaces.md#node)\n* [`Person`](interfaces.md#person)\n\n"
+        ));
+        assert!(map["interfaces"]
+            .contains("### Implements\n\n* [`Named`](interfaces.md#named)\n\n### Implemented by"));
+        assert_eq!(1, map["objects"].matches("### Implements").count());
+    }
+
+    #[test]
+    fn generate_from_schema_should_escape_pipes_in_table_cells() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Player {
+              "Wins | losses, as `w | l`"
+              record: String
+            }
+            "#,
+        )
+        .unwrap();
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert!(map["objects"].contains(" | Wins \\| losses, as `w \\| l` |\n"));
+    }
+
+    #[test]
+    fn generate_from_schema_should_escape_descriptions_when_asked() {
+        let schema = Schema::from_sdl(
+            r#"
+            "Wins for *this* <season>"
+            type Query { player: Player }
+            type Player {
+              "Set by `rank_player`"
+              rank: Int
+            }
+            enum Status {
+              "Out for [injury]"
+              INJURED @deprecated(reason: "Use <b>OUT</b>")
+            }
+            "#,
+        )
+        .unwrap();
+        let generate = |mdx| {
+            let links = Links {
+                escape_descriptions: true,
+                mdx,
+                ..Links::default()
+            };
+            generate_from_schema(
+                &schema,
+                true,
+                &Icons::default(),
+                &links,
+                &Tables::default(),
+                &mut RenderCache::default(),
+                &Budget::default(),
+            )
+            .unwrap()
         };
+        let map = generate(false);
+        assert!(map["queries"].contains("> Wins for \\*this\\* \\<season\\>\n"));
+        assert!(map["objects"].contains(" | Set by \\`rank\\_player\\` |\n"));
+        assert!(map["enums"].contains(" | Out for \\[injury\\] | Use \\<b\\>OUT\\</b\\> |\n"));
+        // MDX conversion escapes < on its own
+        let map = generate(true);
+        assert!(map["queries"].contains("> Wins for \\*this\\* <season\\>\n"));
+        // The schema itself is left alone for the other outputs
+        assert_eq!(
+            Some("Wins for *this* <season>"),
+            schema
+                .get_type("Query")
+                .and_then(|typ| typ.description.as_deref())
+        );
+    }
+
+    #[test]
+    fn generate_from_schema_should_add_referenced_by_when_indexed(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query { player(team: TeamFilter): Player }
+            type Mutation { addPlayer(name: String): Player }
+            type Team { captain: Player, roster(captain: Player): [Player], rival: Team }
+            input TeamFilter { name: String }
+            type Player { name: String }
+            "#,
+        )?;
+        set_referenced_by(&mut schema);
+        let map = generate_from_schema(
+            &schema,
+            true,
+            &Icons::default(),
+            &Links::default(),
+            &Tables::default(),
+            &mut RenderCache::default(),
+            &Budget::default(),
+        )
+        .unwrap();
+        assert!(map["objects"].contains(
+            "### Referenced By\n\n* query [`player`](queries.md#player)\n* mutation [`addPlayer`](mutations.md#addplayer)\n* [`Team`](objects.md#team): `captain`, `roster`, `roster(captain:)`\n\n"
+        ));
+        assert!(map["inputs"].contains(
+            "### Referenced By\n\n* query [`player`](queries.md#player): `player(team:)`\n\n"
+        ));
+        assert!(!map["objects"].contains("[`Team`](objects.md#team): `rival`"));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_conflicts_to_markdown_should_list_resolutions() {
+        let schema = Schema::from_sdl(
+            "type Query { player: Player } type Player { id: ID! } type Player_2 { id: Int }",
+        )
+        .unwrap();
+        let conflicts = vec![
+            MergeConflict {
+                coordinate: "Query.player".to_string(),
+                source: "teams.graphql".to_string(),
+                renamed: None,
+            },
+            MergeConflict {
+                coordinate: "Player.id".to_string(),
+                source: "teams.graphql".to_string(),
+                renamed: Some("Player_2".to_string()),
+            },
+        ];
+        assert_eq!(
+            "# Merge Conflicts\n\n| Coordinate | Source | Resolution |\n| --- | --- | --- |\n| [`Query.player`](objects.md#query) | `teams.graphql` | Kept the earlier definition |\n| [`Player.id`](objects.md#player) | `teams.graphql` | Kept both, as [`Player_2`](objects.md#player_2) |\n\n",
+            merge_conflicts_to_markdown(&conflicts, &schema, true, &Links::default())
+        );
+        assert_eq!(
+            "",
+            merge_conflicts_to_markdown(&[], &schema, true, &Links::default())
+        );
+    }
+
+    #[test]
+    fn defaults_to_markdown_should_group_defaults_by_type() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+              players(first: Int = 10, after: String, filter: PlayerFilter): [String]
+            }
+            input PlayerFilter { active: Boolean = true, name: String, order: Order = ASC }
+            enum Order { ASC DESC }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            r#"# Defaults
+
+## PlayerFilter
+
+| Name | Type | Default Value |
+| --- | --- | --- |
+| `active` | [`Boolean`](scalars.md#boolean) | `true` |
+| `order` | [`Order`](enums.md#order) | `ASC` |
+
+## Query
+
+| Name | Type | Default Value |
+| --- | --- | --- |
+| `players(first:)` | [`Int`](scalars.md#int) | `10` |
+
+"#,
+            defaults_to_markdown(&schema, true, &Links::default())
+        );
         assert_eq!(
-            r#"# Mutation
+            "",
+            defaults_to_markdown(
+                &Schema::from_sdl("type Query { name(first: Int): String }").unwrap(),
+                true,
+                &Links::default()
+            )
+        );
+    }
 
-> The root mutation
+    #[test]
+    fn cycles_to_markdown_should_list_inputs_first() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Player { team: Team }
+            type Team { players: [Player!]! }
+            input Filter { and: [Filter!] }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            r#"# Cycles
 
-## addPlayer
+## Input Cycles
 
-> add a player
+Clients can nest these inputs as deeply as they like, so code that reads them must handle any depth.
+
+* [`Filter`](inputs.md#filter) references itself
+
+## Output Cycles
+
+Queries can follow these types' fields back around as far as they like, so generated types must be recursive.
+
+* [`Player`](objects.md#player), [`Team`](objects.md#team)
 
 "#
             .to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name(), true)
+            cycles_to_markdown(&schema, true, &Links::default())
+        );
+        assert_eq!(
+            "",
+            cycles_to_markdown(
+                &Schema::from_sdl("type Query { name: String }").unwrap(),
+                true,
+                &Links::default()
+            )
         );
     }
 
     #[test]
-    fn schema_type_to_markdown_for_subscription_should_return_empty_when_none() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: None,
-            types: None,
-            directives: None,
-        };
+    fn pagination_to_markdown_should_group_fields_by_pattern() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { players(first: Int, after: String): PlayerConnection games(limit: Int, offset: Int): [String] }
+            type Team { players(first: Int, after: String): PlayerConnection }
+            type PlayerConnection { nodes: [String] }
+            "#,
+        )
+        .unwrap();
         assert_eq!(
-            "".to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name(), true)
+            r#"# Pagination
+
+## Connections
+
+Pass `first` and `after` to page forward, or `last` and `before` to page back. Each connection's `pageInfo` has the cursors to pass next and says whether there are more pages.
+
+* [`Query.players`](queries.md#players)
+* [`Team.players`](objects.md#team)
+
+## Offsets
+
+Pass `limit` for the page size and `offset` for how many items to skip.
+
+* [`Query.games`](queries.md#games)
+
+"#,
+            pagination_to_markdown(&schema, true, &Links::default())
         );
     }
 
     #[test]
-    fn schema_type_to_markdown_for_subscription_should_return_empty_when_some_and_no_members() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: Some(Type {
-                name: None,
-                kind: None,
-                description: None,
-                fields: None,
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
-            }),
-            types: None,
-            directives: None,
-        };
+    fn errors_to_markdown_should_catalog_error_types() {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Mutation { addPlayer(name: String): AddPlayerResult trade(id: ID): TradePayload }
+            type Player { name: String }
+            type UserError { message: String }
+            union AddPlayerResult = Player | UserError
+            type TradePayload { player: Player, userErrors: [UserError!]! }
+            "#,
+        )
+        .unwrap();
         assert_eq!(
-            "".to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name(), true)
+            r#"# Errors
+
+## Union Payloads
+
+Some mutations return a union of their result and the errors they can report. Select each error type with an inline fragment, as in `... on UserError { message }`.
+
+## Error Fields
+
+Some mutations return a payload with an `errors` or `userErrors` field that lists what went wrong. An empty list means the mutation succeeded.
+
+## Error Types
+
+| Error | Returned By |
+| --- | --- |
+| [`UserError`](objects.md#usererror) | [`addPlayer`](mutations.md#addplayer), [`trade`](mutations.md#trade) |
+
+"#,
+            errors_to_markdown(&schema, true, &Links::default())
+        );
+        assert_eq!(
+            "",
+            errors_to_markdown(
+                &Schema::from_sdl("type Query { score: Int }").unwrap(),
+                true,
+                &Links::default()
+            )
         );
     }
 
     #[test]
-    fn schema_type_to_markdown_for_subscription_should_return_markdown_when_some() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: Some(Type {
-                name: Some("Subscription".to_string()),
-                kind: None,
-                description: None,
-                fields: None,
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
-            }),
-            types: Some(vec![Type {
-                name: Some("Subscription".to_string()),
-                kind: None,
-                description: Some("The root subscription".to_string()),
-                fields: Some(vec![Field {
-                    name: Some("subscribePlayers".to_string()),
-                    description: Some("subscribe to players".to_string()),
-                    args: None,
-                    field_type: None,
-                    is_deprecated: None,
-                    deprecation_reason: None,
-                }]),
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
+    fn pagination_to_markdown_should_return_empty_without_pagination() {
+        let schema = Schema::from_sdl("type Query { score: Int }").unwrap();
+        assert_eq!("", pagination_to_markdown(&schema, true, &Links::default()));
+    }
+
+    #[test]
+    fn cost_to_markdown_should_return_empty_without_costs() {
+        let schema = Schema::from_sdl("type Query { score: Int }").unwrap();
+        assert_eq!("", cost_to_markdown(&schema, None, true, &Links::default()));
+    }
+
+    #[test]
+    fn type_to_markdown_should_show_versions() {
+        let typ = &Type {
+            name: Some("Player".to_string()),
+            kind: Some("OBJECT".to_string()),
+            since: Some("v2.0".to_string()),
+            fields: Some(vec![Field {
+                name: Some("position".to_string()),
+                description: Some("Where they play".to_string()),
+                args: None,
+                field_type: None,
+                is_deprecated: None,
+                deprecation_reason: None,
+                since: Some("v2.3".to_string()),
+                removal_date: None,
+                cost: None,
+                examples: None,
+                group: None,
+                prose: None,
+                tags: None,
             }]),
-            directives: None,
+            ..Default::default()
         };
         assert_eq!(
-            r#"# Subscription
+            r#"## <a name="player"></a>Player
 
-> The root subscription
+_Available since v2.0_
 
-## subscribePlayers
+### Fields
 
-> subscribe to players
+| Name | Type | Description |
+| --- | --- | --- |
+| `position` |  | Where they play _Available since v2.3_ |
 
 "#
             .to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name(), true)
+            type_to_markdown(
+                typ,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
         );
     }
 
     #[test]
-    fn types_to_markdown_should_return_markdown() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: None,
-            types: Some(vec![Type {
-                name: Some("Player".to_string()),
-                kind: Some("OBJECT".to_string()),
-                description: Some("A player".to_string()),
-                fields: Some(vec![
-                    Field {
-                        name: Some("firstName".to_string()),
-                        description: Some("The player's first name".to_string()),
-                        args: None,
-                        field_type: None,
-                        is_deprecated: None,
-                        deprecation_reason: None,
-                    },
-                    Field {
-                        name: Some("lastName".to_string()),
-                        description: Some("The player's last name".to_string()),
-                        args: None,
-                        field_type: None,
-                        is_deprecated: None,
-                        deprecation_reason: None,
-                    },
-                ]),
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
+    fn type_to_markdown_should_add_icons() {
+        let typ = &Type {
+            name: Some("Player".to_string()),
+            kind: Some("OBJECT".to_string()),
+            fields: Some(vec![Field {
+                name: Some("rank".to_string()),
+                description: None,
+                args: None,
+                field_type: None,
+                is_deprecated: Some(true),
+                deprecation_reason: None,
+                since: None,
+                removal_date: None,
+                cost: None,
+                examples: None,
+                group: None,
+                prose: None,
+                tags: None,
             }]),
-            directives: None,
+            ..Default::default()
         };
+        let icons = Icons::new(
+            IconSet::Ascii,
+            &HashMap::new(),
+            &["Player.rank".to_string()],
+        )
+        .unwrap();
         assert_eq!(
-            r#"# Objects
-
-## <a name="player"></a>Player
-
-> A player
+            r#"## [O] <a name="player"></a>Player
 
 ### Fields
 
 | Name | Type | Description |
 | --- | --- | --- |
-| `firstName` |  | The player's first name |
-| `lastName` |  | The player's last name |
+| [auth] [!] `rank` |  |  |
 
 "#
             .to_string(),
-            types_to_markdown(schema, "Objects", "OBJECT", true)
+            type_to_markdown(typ, &icons, &Links::default(), &Tables::default())
         );
     }
 
     #[test]
-    fn types_to_markdown_should_return_markdown_with_no_h1_when_add_titles_is_false() {
-        let schema = &Schema {
-            query_type: None,
-            mutation_type: None,
-            subscription_type: None,
-            types: Some(vec![Type {
-                name: Some("Player".to_string()),
-                kind: Some("OBJECT".to_string()),
-                description: Some("A player".to_string()),
-                fields: Some(vec![
-                    Field {
-                        name: Some("firstName".to_string()),
-                        description: Some("The player's first name".to_string()),
-                        args: None,
-                        field_type: None,
-                        is_deprecated: None,
-                        deprecation_reason: None,
-                    },
-                    Field {
-                        name: Some("lastName".to_string()),
-                        description: Some("The player's last name".to_string()),
-                        args: None,
-                        field_type: None,
-                        is_deprecated: None,
-                        deprecation_reason: None,
-                    },
-                ]),
-                inputs: None,
-                interfaces: None,
-                enums: None,
-                possible_types: None,
+    fn type_to_markdown_should_mark_one_of_inputs() {
+        let typ = &Type {
+            name: Some("PlayerBy".to_string()),
+            kind: Some("INPUT_OBJECT".to_string()),
+            is_one_of: Some(true),
+            inputs: Some(vec![Input {
+                name: Some("id".to_string()),
+                description: None,
+                input_type: Some(TypeRef {
+                    name: Some("ID".to_string()),
+                    kind: Some("SCALAR".to_string()),
+                    of_type: None,
+                }),
+                default_value: None,
+                since: None,
+                examples: None,
             }]),
-            directives: None,
+            ..Default::default()
         };
         assert_eq!(
-            r#"## <a name="player"></a>Player
+            r#"## <a name="playerby"></a>PlayerBy
 
-> A player
+### Inputs
 
-### Fields
+**One of:** exactly one of the following fields must be provided
 
-| Name | Type | Description |
-| --- | --- | --- |
-| `firstName` |  | The player's first name |
-| `lastName` |  | The player's last name |
+| Name | Type | Description | Default Value |
+| --- | --- | --- | --- |
+| `id` | [`ID`](scalars.md#id) (one of) |  |  |
 
 "#
             .to_string(),
-            types_to_markdown(schema, "Objects", "OBJECT", false)
+            type_to_markdown(
+                typ,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
         );
     }
 
     #[test]
-    fn type_to_markdown_should_return_markdown() {
-        let typ = &Type {
-            name: Some("Player".to_string()),
-            description: Some("This is a player".to_string()),
-            kind: None,
-            inputs: None,
-            interfaces: None,
-            enums: None,
-            possible_types: None,
-            fields: Some(vec![Field {
-                name: Some("id".to_string()),
-                description: Some("The ID".to_string()),
-                args: None,
-                field_type: None,
-                is_deprecated: None,
-                deprecation_reason: None,
+    fn directives_to_markdown_should_return_markdown() {
+        let schema = &Schema {
+            description: None,
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: Some(vec![Directive {
+                name: Some("tag".to_string()),
+                description: Some("Tags an element".to_string()),
+                locations: Some(vec!["OBJECT".to_string(), "FIELD_DEFINITION".to_string()]),
+                args: Some(vec![Input {
+                    name: Some("name".to_string()),
+                    description: None,
+                    input_type: None,
+                    default_value: None,
+                    since: None,
+                    examples: None,
+                }]),
+                is_repeatable: Some(true),
             }]),
         };
         assert_eq!(
-            r#"## <a name="player"></a>Player
+            r#"# Directives
 
-> This is a player
+## <a name="tag"></a>tag
 
-### Fields
+> Tags an element
 
-| Name | Type | Description |
-| --- | --- | --- |
-| `id` |  | The ID |
+**Locations:** `OBJECT`, `FIELD_DEFINITION`
+
+**Repeatable:** yes
+
+### Arguments
+
+| Name | Type | Description | Default Value |
+| --- | --- | --- | --- |
+| `name` |  |  |  |
 
 "#
             .to_string(),
-            type_to_markdown(typ)
+            directives_to_markdown(
+                schema,
+                true,
+                &Icons::default(),
+                &Links::default(),
+                &Tables::default()
+            )
         );
     }
 
@@ -772,6 +3837,14 @@ mod tests {
         assert_eq!("".to_string(), to_safe_string(&None));
     }
 
+    #[test]
+    fn to_safe_string_should_join_lines_with_spaces() {
+        assert_eq!(
+            "Finds a player. By  name.".to_string(),
+            to_safe_string(&Some(" Finds a player.\n\n  By  name.\n".to_string()))
+        );
+    }
+
     #[test]
     fn input_table_fields_should_return_table_fields_when_some() {
         let input = Input {
@@ -787,8 +3860,10 @@ mod tests {
                 })),
             }),
             default_value: Some("default".to_string()),
+            since: None,
+            examples: None,
         };
-        let fields = input.table_fields();
+        let fields = input.table_fields(&Links::default());
         assert_eq!(4, fields.len());
         assert_eq!("`name`".to_string(), fields[0]);
         assert_eq!("[`ID!`](scalars.md#id)".to_string(), fields[1]);
@@ -803,8 +3878,10 @@ mod tests {
             description: None,
             input_type: None,
             default_value: None,
+            since: None,
+            examples: None,
         };
-        let fields = input.table_fields();
+        let fields = input.table_fields(&Links::default());
         assert_eq!(4, fields.len());
         assert_eq!("".to_string(), fields[0]);
         assert_eq!("".to_string(), fields[1]);
@@ -819,14 +3896,32 @@ mod tests {
             description: Some("description".to_string()),
             is_deprecated: Some(true),
             deprecation_reason: Some("meh".to_string()),
+            since: None,
+            removal_date: None,
         };
-        let fields = enm.table_fields();
+        let fields = enm.table_fields(&Links::default());
         assert_eq!(3, fields.len());
         assert_eq!("`name`".to_string(), fields[0]);
         assert_eq!("description".to_string(), fields[1]);
         assert_eq!("meh".to_string(), fields[2]);
     }
 
+    #[test]
+    fn enum_table_fields_should_show_removal_date() {
+        let enm = Enum {
+            name: Some("name".to_string()),
+            description: None,
+            is_deprecated: Some(true),
+            deprecation_reason: Some("meh".to_string()),
+            since: None,
+            removal_date: Some("2025-01-01".to_string()),
+        };
+        assert_eq!(
+            "meh **Removal: 2025-01-01**",
+            enm.table_fields(&Links::default())[2]
+        );
+    }
+
     #[test]
     fn enum_table_fields_should_return_table_fields_when_some_and_is_deprecated_is_false() {
         let enm = Enum {
@@ -834,8 +3929,10 @@ mod tests {
             description: Some("description".to_string()),
             is_deprecated: Some(false),
             deprecation_reason: Some("meh".to_string()),
+            since: None,
+            removal_date: None,
         };
-        let fields = enm.table_fields();
+        let fields = enm.table_fields(&Links::default());
         assert_eq!(3, fields.len());
         assert_eq!("`name`".to_string(), fields[0]);
         assert_eq!("description".to_string(), fields[1]);
@@ -849,8 +3946,10 @@ mod tests {
             description: None,
             is_deprecated: None,
             deprecation_reason: None,
+            since: None,
+            removal_date: None,
         };
-        let fields = enm.table_fields();
+        let fields = enm.table_fields(&Links::default());
         assert_eq!(3, fields.len());
         assert_eq!("".to_string(), fields[0]);
         assert_eq!("".to_string(), fields[1]);
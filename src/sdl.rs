@@ -0,0 +1,659 @@
+use super::schema::{Directive, Enum, Field, Input, Schema, Type, TypeRef};
+use graphql_parser::schema::{self as ast, Definition, TypeDefinition, TypeExtension};
+use std::{collections::HashMap, convert::TryFrom, error::Error};
+
+/// The scalars every GraphQL schema has
+pub const BUILT_IN_SCALARS: &[&str] = &["Boolean", "Float", "ID", "Int", "String"];
+
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+type Document<'a> = ast::Document<'a, String>;
+
+/// Parses GraphQL SDL into a Schema, as if the schema it describes had been
+/// introspected
+///
+/// # Arguments
+///
+/// * `text` - the SDL to parse
+pub fn parse(text: &str) -> Result<Schema, Box<dyn Error>> {
+    let mut schema = Schema {
+        description: None,
+        query_type: None,
+        mutation_type: None,
+        subscription_type: None,
+        types: Some(Vec::new()),
+        directives: Some(Vec::new()),
+    };
+    apply(&mut schema, text)?;
+    Ok(schema)
+}
+
+/// Applies the definitions and extensions in GraphQL SDL on top of an
+/// existing Schema. New types and directives are added, `extend` blocks add
+/// to types that already exist, and root operation types are set when the
+/// SDL has a `schema` block or uses the default root type names.
+///
+/// # Arguments
+///
+/// * `schema` - the schema to update
+/// * `text` - the SDL to apply
+pub fn apply(schema: &mut Schema, text: &str) -> Result<(), Box<dyn Error>> {
+    let document: Document = graphql_parser::parse_schema(text)?;
+
+    let mut types = schema.types.take().unwrap_or_default();
+    let mut directives = schema.directives.take().unwrap_or_default();
+    let mut extensions = Vec::new();
+
+    for definition in document.definitions.iter() {
+        match definition {
+            Definition::SchemaDefinition(def) => {
+                if let Some(name) = &def.query {
+                    schema.query_type = Some(root_type(name));
+                }
+                if let Some(name) = &def.mutation {
+                    schema.mutation_type = Some(root_type(name));
+                }
+                if let Some(name) = &def.subscription {
+                    schema.subscription_type = Some(root_type(name));
+                }
+            }
+            Definition::TypeDefinition(def) => {
+                let typ = type_from_definition(def);
+                types.retain(|t| t.name != typ.name);
+                types.push(typ);
+            }
+            Definition::TypeExtension(ext) => extensions.push(ext),
+            Definition::DirectiveDefinition(def) => {
+                directives.retain(|d| d.name.as_ref() != Some(&def.name));
+                directives.push(directive_from_definition(def));
+            }
+        }
+    }
+
+    for ext in extensions {
+        extend_type(&mut types, ext)?;
+    }
+
+    add_built_in_scalars(&mut types);
+    resolve_kinds(&mut types);
+    resolve_possible_types(&mut types);
+
+    for (root, name) in [
+        (&mut schema.query_type, "Query"),
+        (&mut schema.mutation_type, "Mutation"),
+        (&mut schema.subscription_type, "Subscription"),
+    ] {
+        if root.is_none() && types.iter().any(|t| t.name.as_deref() == Some(name)) {
+            *root = Some(root_type(name));
+        }
+    }
+
+    schema.types = Some(types);
+    schema.directives = Some(directives);
+
+    Ok(())
+}
+
+fn root_type(name: &str) -> Type {
+    Type {
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+fn type_from_definition(def: &TypeDefinition<String>) -> Type {
+    match def {
+        TypeDefinition::Scalar(scalar) => Type {
+            name: Some(scalar.name.clone()),
+            kind: Some("SCALAR".to_string()),
+            description: scalar.description.clone(),
+            specified_by_url: specified_by_url(&scalar.directives),
+            ..Default::default()
+        },
+        TypeDefinition::Object(object) => Type {
+            name: Some(object.name.clone()),
+            kind: Some("OBJECT".to_string()),
+            description: object.description.clone(),
+            fields: Some(object.fields.iter().map(field_from_definition).collect()),
+            interfaces: Some(named_type_refs(&object.implements_interfaces)),
+            ..Default::default()
+        },
+        TypeDefinition::Interface(interface) => Type {
+            name: Some(interface.name.clone()),
+            kind: Some("INTERFACE".to_string()),
+            description: interface.description.clone(),
+            fields: Some(interface.fields.iter().map(field_from_definition).collect()),
+            interfaces: Some(named_type_refs(&interface.implements_interfaces)),
+            possible_types: Some(Vec::new()),
+            ..Default::default()
+        },
+        TypeDefinition::Union(union) => Type {
+            name: Some(union.name.clone()),
+            kind: Some("UNION".to_string()),
+            description: union.description.clone(),
+            possible_types: Some(named_type_refs(&union.types)),
+            ..Default::default()
+        },
+        TypeDefinition::Enum(enm) => Type {
+            name: Some(enm.name.clone()),
+            kind: Some("ENUM".to_string()),
+            description: enm.description.clone(),
+            enums: Some(enm.values.iter().map(enum_from_definition).collect()),
+            ..Default::default()
+        },
+        TypeDefinition::InputObject(input) => Type {
+            name: Some(input.name.clone()),
+            kind: Some("INPUT_OBJECT".to_string()),
+            description: input.description.clone(),
+            inputs: Some(input.fields.iter().map(input_from_definition).collect()),
+            is_one_of: Some(is_one_of(&input.directives)),
+            ..Default::default()
+        },
+    }
+}
+
+fn extend_type(types: &mut [Type], ext: &TypeExtension<String>) -> Result<(), Box<dyn Error>> {
+    let name = match ext {
+        TypeExtension::Scalar(e) => &e.name,
+        TypeExtension::Object(e) => &e.name,
+        TypeExtension::Interface(e) => &e.name,
+        TypeExtension::Union(e) => &e.name,
+        TypeExtension::Enum(e) => &e.name,
+        TypeExtension::InputObject(e) => &e.name,
+    };
+
+    let typ = match types.iter_mut().find(|t| t.name.as_ref() == Some(name)) {
+        Some(typ) => typ,
+        None => return Err(format!("cannot extend unknown type {}", name).into()),
+    };
+
+    match ext {
+        TypeExtension::Scalar(e) => {
+            if let Some(url) = specified_by_url(&e.directives) {
+                typ.specified_by_url = Some(url);
+            }
+        }
+        TypeExtension::Object(e) => {
+            extend_fields(typ, &e.fields);
+            extend_interfaces(typ, &e.implements_interfaces);
+        }
+        TypeExtension::Interface(e) => {
+            extend_fields(typ, &e.fields);
+            extend_interfaces(typ, &e.implements_interfaces);
+        }
+        TypeExtension::Union(e) => {
+            let possible_types = typ.possible_types.get_or_insert_with(Vec::new);
+            possible_types.extend(named_type_refs(&e.types));
+        }
+        TypeExtension::Enum(e) => {
+            let enums = typ.enums.get_or_insert_with(Vec::new);
+            enums.retain(|v| !e.values.iter().any(|ev| v.name.as_ref() == Some(&ev.name)));
+            enums.extend(e.values.iter().map(enum_from_definition));
+        }
+        TypeExtension::InputObject(e) => {
+            if is_one_of(&e.directives) {
+                typ.is_one_of = Some(true);
+            }
+            let inputs = typ.inputs.get_or_insert_with(Vec::new);
+            inputs.retain(|i| !e.fields.iter().any(|ef| i.name.as_ref() == Some(&ef.name)));
+            inputs.extend(e.fields.iter().map(input_from_definition));
+        }
+    }
+
+    Ok(())
+}
+
+fn extend_fields(typ: &mut Type, new_fields: &[ast::Field<String>]) {
+    let fields = typ.fields.get_or_insert_with(Vec::new);
+    fields.retain(|f| {
+        !new_fields
+            .iter()
+            .any(|nf| f.name.as_ref() == Some(&nf.name))
+    });
+    fields.extend(new_fields.iter().map(field_from_definition));
+}
+
+fn extend_interfaces(typ: &mut Type, names: &[String]) {
+    let interfaces = typ.interfaces.get_or_insert_with(Vec::new);
+    for name in names {
+        if !interfaces.iter().any(|i| i.name.as_ref() == Some(name)) {
+            interfaces.push(named_type_ref(name));
+        }
+    }
+}
+
+fn field_from_definition(def: &ast::Field<String>) -> Field {
+    let (is_deprecated, deprecation_reason) = deprecation(&def.directives);
+    Field {
+        name: Some(def.name.clone()),
+        description: def.description.clone(),
+        args: Some(def.arguments.iter().map(input_from_definition).collect()),
+        field_type: Some(type_ref_from_type(&def.field_type)),
+        is_deprecated: Some(is_deprecated),
+        deprecation_reason,
+        since: None,
+        removal_date: None,
+        cost: cost(&def.directives),
+        examples: None,
+        group: None,
+        prose: None,
+        tags: None,
+    }
+}
+
+fn input_from_definition(def: &ast::InputValue<String>) -> Input {
+    Input {
+        name: Some(def.name.clone()),
+        description: def.description.clone(),
+        input_type: Some(type_ref_from_type(&def.value_type)),
+        default_value: def.default_value.as_ref().map(|value| value.to_string()),
+        since: None,
+        examples: None,
+    }
+}
+
+fn enum_from_definition(def: &ast::EnumValue<String>) -> Enum {
+    let (is_deprecated, deprecation_reason) = deprecation(&def.directives);
+    Enum {
+        name: Some(def.name.clone()),
+        description: def.description.clone(),
+        is_deprecated: Some(is_deprecated),
+        deprecation_reason,
+        since: None,
+        removal_date: None,
+    }
+}
+
+fn directive_from_definition(def: &ast::DirectiveDefinition<String>) -> Directive {
+    Directive {
+        name: Some(def.name.clone()),
+        description: def.description.clone(),
+        locations: Some(
+            def.locations
+                .iter()
+                .map(|location| location.as_str().to_string())
+                .collect(),
+        ),
+        args: Some(def.arguments.iter().map(input_from_definition).collect()),
+        is_repeatable: Some(def.repeatable),
+    }
+}
+
+fn specified_by_url(directives: &[ast::Directive<String>]) -> Option<String> {
+    string_argument(directives, "specifiedBy", "url")
+}
+
+fn is_one_of(directives: &[ast::Directive<String>]) -> bool {
+    directives.iter().any(|d| d.name == "oneOf")
+}
+
+fn string_argument(
+    directives: &[ast::Directive<String>],
+    directive: &str,
+    argument: &str,
+) -> Option<String> {
+    directives
+        .iter()
+        .find(|d| d.name == directive)?
+        .arguments
+        .iter()
+        .find(|(name, _)| name == argument)
+        .and_then(|(_, value)| match value {
+            ast::Value::String(s) => Some(s.clone()),
+            _ => None,
+        })
+}
+
+// Reads the weight from a @cost directive, which the IBM cost specification
+// passes as a string and other servers pass as an int
+fn cost(directives: &[ast::Directive<String>]) -> Option<u32> {
+    directives
+        .iter()
+        .find(|d| d.name == "cost")?
+        .arguments
+        .iter()
+        .find(|(name, _)| name == "weight" || name == "complexity")
+        .and_then(|(_, value)| match value {
+            ast::Value::Int(n) => n.as_i64().and_then(|n| u32::try_from(n).ok()),
+            ast::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        })
+}
+
+fn deprecation(directives: &[ast::Directive<String>]) -> (bool, Option<String>) {
+    if directives.iter().any(|d| d.name == "deprecated") {
+        let reason = string_argument(directives, "deprecated", "reason")
+            .unwrap_or_else(|| DEFAULT_DEPRECATION_REASON.to_string());
+        (true, Some(reason))
+    } else {
+        (false, None)
+    }
+}
+
+fn type_ref_from_type(typ: &ast::Type<String>) -> TypeRef {
+    match typ {
+        ast::Type::NamedType(name) => named_type_ref(name),
+        ast::Type::ListType(inner) => TypeRef {
+            name: None,
+            kind: Some("LIST".to_string()),
+            of_type: Some(Box::new(type_ref_from_type(inner))),
+        },
+        ast::Type::NonNullType(inner) => TypeRef {
+            name: None,
+            kind: Some("NON_NULL".to_string()),
+            of_type: Some(Box::new(type_ref_from_type(inner))),
+        },
+    }
+}
+
+fn named_type_ref(name: &str) -> TypeRef {
+    TypeRef {
+        name: Some(name.to_string()),
+        kind: None,
+        of_type: None,
+    }
+}
+
+fn named_type_refs(names: &[String]) -> Vec<TypeRef> {
+    names.iter().map(|name| named_type_ref(name)).collect()
+}
+
+// Introspection only lists the built-in scalars a schema uses, so do the same
+fn add_built_in_scalars(types: &mut Vec<Type>) {
+    let mut referenced = Vec::new();
+    for typ in types.iter() {
+        for type_ref in type_refs(typ) {
+            let name = type_ref.get_actual_name();
+            if BUILT_IN_SCALARS.contains(&name.as_str()) && !referenced.contains(&name) {
+                referenced.push(name);
+            }
+        }
+    }
+
+    for name in referenced {
+        if !types.iter().any(|t| t.name.as_ref() == Some(&name)) {
+            types.push(Type {
+                name: Some(name),
+                kind: Some("SCALAR".to_string()),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+fn type_refs(typ: &Type) -> Vec<&TypeRef> {
+    let mut refs = Vec::new();
+    for field in typ.fields.iter().flatten() {
+        refs.extend(field.field_type.iter());
+        for arg in field.args.iter().flatten() {
+            refs.extend(arg.input_type.iter());
+        }
+    }
+    for input in typ.inputs.iter().flatten() {
+        refs.extend(input.input_type.iter());
+    }
+    refs
+}
+
+// SDL refers to types by name only, so fill in the kinds introspection
+// would have returned once every type is known
+fn resolve_kinds(types: &mut [Type]) {
+    let kinds: HashMap<String, String> = types
+        .iter()
+        .filter_map(|t| match (&t.name, &t.kind) {
+            (Some(name), Some(kind)) => Some((name.clone(), kind.clone())),
+            _ => None,
+        })
+        .collect();
+
+    for typ in types.iter_mut() {
+        typ.for_each_type_ref_mut(&mut |type_ref| resolve_kind_of(type_ref, &kinds));
+    }
+}
+
+fn resolve_kind_of(type_ref: &mut TypeRef, kinds: &HashMap<String, String>) {
+    match &mut type_ref.of_type {
+        Some(of_type) => resolve_kind_of(of_type, kinds),
+        None => {
+            if let Some(name) = &type_ref.name {
+                type_ref.kind = kinds.get(name).cloned();
+            }
+        }
+    }
+}
+
+// Interfaces list the objects that implement them as possible types
+fn resolve_possible_types(types: &mut [Type]) {
+    let implementations: Vec<(String, TypeRef)> = types
+        .iter()
+        .filter(|t| t.kind.as_deref() == Some("OBJECT"))
+        .flat_map(|t| {
+            t.interfaces.iter().flatten().filter_map(move |i| {
+                i.name.clone().map(|interface| {
+                    (
+                        interface,
+                        TypeRef {
+                            name: t.name.clone(),
+                            kind: t.kind.clone(),
+                            of_type: None,
+                        },
+                    )
+                })
+            })
+        })
+        .collect();
+
+    for typ in types.iter_mut() {
+        if typ.kind.as_deref() != Some("INTERFACE") {
+            continue;
+        }
+        let possible_types = typ.possible_types.get_or_insert_with(Vec::new);
+        for (interface, implementation) in implementations.iter() {
+            if typ.name.as_ref() == Some(interface)
+                && !possible_types.iter().any(|p| p.name == implementation.name)
+            {
+                possible_types.push(implementation.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+        "The root query"
+        type Query {
+          "Get the players"
+          players(first: Int = 10, team: String): [Player!]!
+          oldPlayers: [Player] @deprecated(reason: "Use players")
+        }
+
+        interface Named {
+          name: String
+        }
+
+        "A player"
+        type Player implements Named {
+          name: String
+          position: Position
+        }
+
+        enum Position {
+          GOALIE
+          FORWARD @deprecated
+        }
+
+        input PlayerFilter {
+          team: String = "Bruins"
+        }
+
+        union Result = Player
+
+        scalar DateTime @specifiedBy(url: "https://tools.ietf.org/html/rfc3339")
+
+        directive @auth(role: String) repeatable on FIELD_DEFINITION
+    "#;
+
+    #[test]
+    fn parse_should_set_default_root_types() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        assert_eq!(Some("Query".to_string()), schema.get_query_name());
+        assert!(schema.get_mutation_name().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_use_schema_definition_for_root_types() -> Result<(), Box<dyn Error>> {
+        let schema = parse("schema { query: QueryRoot } type QueryRoot { a: Int }")?;
+        assert_eq!(Some("QueryRoot".to_string()), schema.get_query_name());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_types_with_kinds() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        for (name, kind) in [
+            ("Query", "OBJECT"),
+            ("Named", "INTERFACE"),
+            ("Player", "OBJECT"),
+            ("Position", "ENUM"),
+            ("PlayerFilter", "INPUT_OBJECT"),
+            ("Result", "UNION"),
+            ("DateTime", "SCALAR"),
+            ("String", "SCALAR"),
+            ("Int", "SCALAR"),
+        ] {
+            let typ = schema.get_type(name).expect(name);
+            assert_eq!(Some(kind.to_string()), typ.kind, "{}", name);
+        }
+        assert!(schema.get_type("Float").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_resolve_field_types() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        let query = schema.get_type("Query").unwrap();
+        let players = &query.fields.as_ref().unwrap()[0];
+        let field_type = players.field_type.as_ref().unwrap();
+        assert_eq!("[Player!]!", field_type.get_decorated_name());
+        assert_eq!("OBJECT", field_type.get_actual_kind());
+        assert_eq!(Some("Get the players".to_string()), players.description);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_default_values() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        let query = schema.get_type("Query").unwrap();
+        let args = query.fields.as_ref().unwrap()[0].args.as_ref().unwrap();
+        assert_eq!(Some("10".to_string()), args[0].default_value);
+        assert_eq!(None, args[1].default_value);
+        let filter = schema.get_type("PlayerFilter").unwrap();
+        assert_eq!(
+            Some("\"Bruins\"".to_string()),
+            filter.inputs.as_ref().unwrap()[0].default_value
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_deprecations() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        let query = schema.get_type("Query").unwrap();
+        let old = &query.fields.as_ref().unwrap()[1];
+        assert_eq!(Some(true), old.is_deprecated);
+        assert_eq!(Some("Use players".to_string()), old.deprecation_reason);
+        let position = schema.get_type("Position").unwrap();
+        let forward = &position.enums.as_ref().unwrap()[1];
+        assert_eq!(Some(true), forward.is_deprecated);
+        assert_eq!(
+            Some(DEFAULT_DEPRECATION_REASON.to_string()),
+            forward.deprecation_reason
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_list_implementations_as_possible_types() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        let named = schema.get_type("Named").unwrap();
+        let possible_types = named.possible_types.as_ref().unwrap();
+        assert_eq!(1, possible_types.len());
+        assert_eq!(Some("Player".to_string()), possible_types[0].name);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_directives() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        let directives = schema.directives.unwrap();
+        assert_eq!(1, directives.len());
+        assert_eq!(Some("auth".to_string()), directives[0].name);
+        assert_eq!(
+            Some(vec!["FIELD_DEFINITION".to_string()]),
+            directives[0].locations
+        );
+        assert_eq!(Some(true), directives[0].is_repeatable);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_costs() -> Result<(), Box<dyn Error>> {
+        let schema = parse(
+            r#"type Query {
+                players: [String] @cost(weight: "10")
+                teams: [String] @cost(complexity: 5)
+                score: Int
+            }"#,
+        )?;
+        let fields = schema.get_type("Query").unwrap().fields.as_ref().unwrap();
+        assert_eq!(Some(10), fields[0].cost);
+        assert_eq!(Some(5), fields[1].cost);
+        assert_eq!(None, fields[2].cost);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_specified_by() -> Result<(), Box<dyn Error>> {
+        let schema = parse(SDL)?;
+        assert_eq!(
+            Some("https://tools.ietf.org/html/rfc3339".to_string()),
+            schema.get_type("DateTime").unwrap().specified_by_url
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_convert_one_of() -> Result<(), Box<dyn Error>> {
+        let schema = parse(
+            "type Query { a(by: PlayerBy): Int } input PlayerBy @oneOf { id: ID name: String } input Plain { id: ID }",
+        )?;
+        assert_eq!(Some(true), schema.get_type("PlayerBy").unwrap().is_one_of);
+        assert_eq!(Some(false), schema.get_type("Plain").unwrap().is_one_of);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_apply_extensions() -> Result<(), Box<dyn Error>> {
+        let schema = parse("type Query { a: Int } extend type Query { b: String }")?;
+        let query = schema.get_type("Query").unwrap();
+        assert_eq!(2, query.fields.as_ref().unwrap().len());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_should_fail_when_extending_unknown_type() {
+        match parse("extend type Query { b: String }") {
+            Ok(_) => panic!("extending an unknown type should fail"),
+            Err(err) => assert_eq!("cannot extend unknown type Query", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn parse_should_fail_when_not_sdl() {
+        assert!(parse("{ \"data\": {} }").is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use super::budget::Budget;
+use super::links::Links;
+use super::model::{DocField, DocInput, Link, Model};
+use serde::Serialize;
+use std::{error::Error, fs, path::Path};
+
+/// Something a reader might search for, in the shape client-side search
+/// libraries such as lunr and elasticlunr index
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SearchEntry {
+    /// The name, qualified by its type for fields, arguments, and values,
+    /// such as `Player.name`
+    pub name: String,
+    pub kind: String,
+    pub description: Option<String>,
+    /// The page and anchor that document it, relative to the pages
+    pub url: String,
+}
+
+/// A record for Algolia DocSearch, placed in the hierarchy of page, type,
+/// and member that DocSearch shows results in
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoliaRecord {
+    #[serde(rename = "objectID")]
+    pub object_id: String,
+    pub hierarchy: Hierarchy,
+    /// The level the record is at, such as `lvl2` for a field
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub url: String,
+    pub anchor: String,
+    pub content: Option<String>,
+}
+
+/// The page, type, and member a record is under: Objects, Player, name
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Hierarchy {
+    pub lvl0: String,
+    pub lvl1: String,
+    pub lvl2: Option<String>,
+}
+
+/// Writes a search entry for every operation, type, field, argument, input
+/// field, enum value, and directive as a JSON array
+///
+/// # Arguments
+///
+/// * `model` - the documentation model
+/// * `links` - how pages are linked
+/// * `docs_url` - the URL the pages are published at, if URLs should be
+///   absolute
+/// * `file` - the file to write
+/// * `budget` - the output budget to count the file against
+pub fn write_index(
+    model: &Model,
+    links: &Links,
+    docs_url: Option<&str>,
+    file: &Path,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    write_json(
+        &get_entries(model, links, docs_url),
+        file,
+        "search index",
+        budget,
+    )
+}
+
+/// Writes an Algolia record for every operation, type, field, input field,
+/// and enum value as a JSON array, ready to push to an index
+///
+/// # Arguments
+///
+/// * `model` - the documentation model
+/// * `links` - how pages are linked
+/// * `docs_url` - the URL the pages are published at, if URLs should be
+///   absolute
+/// * `file` - the file to write
+/// * `budget` - the output budget to count the file against
+pub fn write_algolia_records(
+    model: &Model,
+    links: &Links,
+    docs_url: Option<&str>,
+    file: &Path,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    write_json(
+        &get_algolia_records(model, links, docs_url),
+        file,
+        "Algolia records",
+        budget,
+    )
+}
+
+fn write_json<T: Serialize>(
+    value: &T,
+    file: &Path,
+    what: &str,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    let mut json = serde_json::to_string_pretty(value)?;
+    json.push('\n');
+    budget.spend(json.len())?;
+    fs::write(file, json)
+        .map_err(|err| format!("can't write {} {}: {}", what, file.display(), err).into())
+}
+
+/// Returns the search entries, operations first, then types and
+/// directives by name, each followed by its members
+pub fn get_entries(model: &Model, links: &Links, docs_url: Option<&str>) -> Vec<SearchEntry> {
+    let mut entries = Vec::new();
+    for (kind, operations) in &[
+        ("query", &model.queries),
+        ("mutation", &model.mutations),
+        ("subscription", &model.subscriptions),
+    ] {
+        for operation in operations.iter() {
+            if let Some(link) = &operation.link {
+                let url = to_url(link, links, docs_url);
+                entries.push(to_entry(
+                    &operation.name,
+                    kind,
+                    &operation.description,
+                    &url,
+                ));
+                add_arguments(&mut entries, &operation.name, &operation.arguments, &url);
+            }
+        }
+    }
+
+    for typ in model.types.iter().filter(|typ| !typ.name.starts_with("__")) {
+        let url = to_url(&typ.link, links, docs_url);
+        let kind = typ.kind.to_lowercase().replace('_', " ");
+        entries.push(to_entry(&typ.name, &kind, &typ.description, &url));
+        for field in typ.fields.iter().flatten() {
+            add_field(&mut entries, &typ.name, field, &url);
+        }
+        for input in typ.inputs.iter().flatten() {
+            let name = format!("{}.{}", typ.name, input.name);
+            entries.push(to_entry(&name, "input field", &input.description, &url));
+        }
+        for value in typ.values.iter().flatten() {
+            let name = format!("{}.{}", typ.name, value.name);
+            entries.push(to_entry(&name, "enum value", &value.description, &url));
+        }
+    }
+
+    for directive in &model.directives {
+        let name = format!("@{}", directive.name);
+        let url = to_url(&directive.link, links, docs_url);
+        entries.push(to_entry(&name, "directive", &directive.description, &url));
+        add_arguments(&mut entries, &name, &directive.arguments, &url);
+    }
+    entries
+}
+
+// Adds a field and its arguments, which are documented with the type
+fn add_field(entries: &mut Vec<SearchEntry>, parent: &str, field: &DocField, url: &str) {
+    let name = format!("{}.{}", parent, field.name);
+    entries.push(to_entry(&name, "field", &field.description, url));
+    add_arguments(entries, &name, &field.arguments, url);
+}
+
+fn add_arguments(entries: &mut Vec<SearchEntry>, parent: &str, arguments: &[DocInput], url: &str) {
+    for argument in arguments {
+        let name = format!("{}.{}", parent, argument.name);
+        entries.push(to_entry(&name, "argument", &argument.description, url));
+    }
+}
+
+fn to_entry(name: &str, kind: &str, description: &Option<String>, url: &str) -> SearchEntry {
+    SearchEntry {
+        name: name.to_string(),
+        kind: kind.to_string(),
+        description: description.clone(),
+        url: url.to_string(),
+    }
+}
+
+/// Returns the Algolia records: a record at `lvl1` for each operation and
+/// type, and at `lvl2` for each of a type's fields, input fields, and enum
+/// values
+pub fn get_algolia_records(
+    model: &Model,
+    links: &Links,
+    docs_url: Option<&str>,
+) -> Vec<AlgoliaRecord> {
+    let mut records = Vec::new();
+    for operations in &[&model.queries, &model.mutations, &model.subscriptions] {
+        for operation in operations.iter() {
+            if let Some(link) = &operation.link {
+                records.push(to_record(
+                    link,
+                    &operation.name,
+                    None,
+                    &operation.description,
+                    links,
+                    docs_url,
+                ));
+            }
+        }
+    }
+
+    for typ in model.types.iter().filter(|typ| !typ.name.starts_with("__")) {
+        records.push(to_record(
+            &typ.link,
+            &typ.name,
+            None,
+            &typ.description,
+            links,
+            docs_url,
+        ));
+        let members = typ
+            .fields
+            .iter()
+            .flatten()
+            .map(|field| (&field.name, &field.description))
+            .chain(
+                typ.inputs
+                    .iter()
+                    .flatten()
+                    .map(|input| (&input.name, &input.description)),
+            )
+            .chain(
+                typ.values
+                    .iter()
+                    .flatten()
+                    .map(|value| (&value.name, &value.description)),
+            );
+        for (name, description) in members {
+            records.push(to_record(
+                &typ.link,
+                &typ.name,
+                Some(name),
+                description,
+                links,
+                docs_url,
+            ));
+        }
+    }
+    records
+}
+
+// Returns the record for an operation or type, or for a member of the type
+fn to_record(
+    link: &Link,
+    name: &str,
+    member: Option<&str>,
+    description: &Option<String>,
+    links: &Links,
+    docs_url: Option<&str>,
+) -> AlgoliaRecord {
+    let object_id = match member {
+        Some(member) => format!("{}:{}.{}", link.page, name, member),
+        None => format!("{}:{}", link.page, name),
+    };
+    AlgoliaRecord {
+        object_id,
+        hierarchy: Hierarchy {
+            lvl0: links.page_title(&link.page),
+            lvl1: name.to_string(),
+            lvl2: member.map(str::to_string),
+        },
+        record_type: if member.is_some() { "lvl2" } else { "lvl1" }.to_string(),
+        url: to_url(link, links, docs_url),
+        anchor: link.anchor.clone(),
+        content: description.clone(),
+    }
+}
+
+// Returns the page and anchor, after the docs' URL if there is one
+fn to_url(link: &Link, links: &Links, docs_url: Option<&str>) -> String {
+    let url = format!("{}#{}", links.page_link(&link.page), link.anchor);
+    match docs_url {
+        Some(docs_url) => format!("{}/{}", docs_url.trim_end_matches('/'), url),
+        None => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icons::Icons;
+    use crate::schema::Schema;
+
+    #[test]
+    fn get_entries_should_index_members_with_their_type() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+              "Get a player"
+              player(id: ID!): Player
+            }
+            "A hockey player"
+            type Player {
+              "The player's name"
+              name: String
+            }
+            enum Position { CENTER }
+            "#,
+        )?;
+        let links = Links::default();
+        let model = Model::from_schema(&schema, &Icons::default(), &links);
+        let entries = get_entries(&model, &links, None);
+        let find = |name: &str| entries.iter().find(|entry| entry.name == name);
+        assert_eq!(
+            Some(&SearchEntry {
+                name: "player".to_string(),
+                kind: "query".to_string(),
+                description: Some("Get a player".to_string()),
+                url: "queries.md#player".to_string(),
+            }),
+            find("player")
+        );
+        assert_eq!(
+            Some("argument"),
+            find("player.id").map(|entry| entry.kind.as_str())
+        );
+        assert_eq!(
+            Some(&SearchEntry {
+                name: "Player.name".to_string(),
+                kind: "field".to_string(),
+                description: Some("The player's name".to_string()),
+                url: "objects.md#player".to_string(),
+            }),
+            find("Player.name")
+        );
+        assert_eq!(
+            Some("enum value"),
+            find("Position.CENTER").map(|entry| entry.kind.as_str())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_algolia_records_should_nest_members_under_types() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+              player: Player
+            }
+            "A hockey player"
+            type Player {
+              "The player's name"
+              name: String
+            }
+            "#,
+        )?;
+        let links = Links::default();
+        let model = Model::from_schema(&schema, &Icons::default(), &links);
+        let records = get_algolia_records(&model, &links, Some("https://example.com/docs/"));
+        let find = |id: &str| records.iter().find(|record| record.object_id == id);
+        assert_eq!(
+            Some(&AlgoliaRecord {
+                object_id: "objects:Player.name".to_string(),
+                hierarchy: Hierarchy {
+                    lvl0: "Objects".to_string(),
+                    lvl1: "Player".to_string(),
+                    lvl2: Some("name".to_string()),
+                },
+                record_type: "lvl2".to_string(),
+                url: "https://example.com/docs/objects.md#player".to_string(),
+                anchor: "player".to_string(),
+                content: Some("The player's name".to_string()),
+            }),
+            find("objects:Player.name")
+        );
+        assert_eq!(
+            Some("lvl1"),
+            find("objects:Player").map(|record| record.record_type.as_str())
+        );
+        assert_eq!(
+            Some("Queries"),
+            find("queries:player").map(|record| record.hierarchy.lvl0.as_str())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+use super::format::get_nav_pages;
+use super::links::Links;
+use super::markdown::{split_code, split_heading_id, to_header, to_link, to_named_anchor};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref ANCHOR: Regex = Regex::new(r#"<a name="([^"]*)"></a>"#).unwrap();
+    static ref LINK: Regex = Regex::new(r"\]\(([^)\s]*)\)").unwrap();
+}
+
+/// Combines the pages into one markdown document: a table of contents,
+/// then each page in navigation order. Each page's anchors get the page's
+/// name, so the Player type and the player query don't collide, and links
+/// between pages become links within the document.
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name
+/// * `links` - how pages and anchors are named
+pub fn to_single_file(contents: &HashMap<String, String>, links: &Links) -> String {
+    let (toc, body) = combine(contents, links);
+    format!(
+        "{}{}\n\n{}",
+        to_header(1, &links.section_title("Contents")),
+        toc.join("\n"),
+        body
+    )
+}
+
+/// Returns the page with a linked list of its sections, such as its types or
+/// operations, after its title
+///
+/// # Arguments
+///
+/// * `markdown` - the page
+/// * `links` - how anchors are named
+pub fn add_contents(markdown: &str, links: &Links) -> String {
+    let sections = get_sections(markdown, links);
+    if sections.is_empty() {
+        return markdown.to_string();
+    }
+    let toc: Vec<String> = sections
+        .iter()
+        .map(|(text, fragment)| format!("* {}\n", to_link(text, &format!("#{}", fragment))))
+        .collect();
+    let title_end = match markdown.strip_prefix("# ") {
+        Some(rest) => rest.find("\n\n").map_or(markdown.len(), |end| end + 4),
+        None => 0,
+    };
+    format!(
+        "{}{}\n{}",
+        &markdown[..title_end],
+        toc.concat(),
+        &markdown[title_end..]
+    )
+}
+
+/// Combines the pages into one markdown document as `to_single_file`
+/// does, without the table of contents, for site generators that build
+/// their own
+pub fn join_pages(contents: &HashMap<String, String>, links: &Links) -> String {
+    combine(contents, links).1
+}
+
+// Returns the table of contents' lines and the combined pages
+fn combine(contents: &HashMap<String, String>, links: &Links) -> (Vec<String>, String) {
+    let pages = get_nav_pages(contents);
+    let page_ids: HashMap<String, String> = pages
+        .iter()
+        .map(|page| (links.page_link(page), links.page_id(page)))
+        .collect();
+
+    let mut toc = Vec::new();
+    let mut body = String::new();
+    for page in pages {
+        let page_id = links.page_id(page);
+        let markdown = rewrite_page(&contents[page], &page_id, &page_ids, links);
+        let markdown = match markdown.strip_prefix("# ") {
+            Some(rest) => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                format!(
+                    "# {}{}",
+                    links.named_anchor(&to_page_fragment(&page_id, links), &rest[..end]),
+                    &rest[end..]
+                )
+            }
+            None => format!(
+                "{}{}",
+                to_header(
+                    1,
+                    &links
+                        .named_anchor(&to_page_fragment(&page_id, links), &links.page_title(page))
+                ),
+                markdown
+            ),
+        };
+        toc.push(format!(
+            "* {}",
+            to_link(
+                &links.page_title(page),
+                &format!("#{}", to_page_fragment(&page_id, links))
+            )
+        ));
+        for (text, fragment) in get_sections(&markdown, links) {
+            toc.push(format!("  * {}", to_link(&text, &format!("#{}", fragment))));
+        }
+        body.push_str(&markdown);
+    }
+    (toc, body)
+}
+
+// Prefixes the page's anchors with its ID, gives operation headings an
+// anchor of their own, and points links to other pages within the document
+fn rewrite_page(
+    markdown: &str,
+    page_id: &str,
+    page_ids: &HashMap<String, String>,
+    links: &Links,
+) -> String {
+    let mut s = String::new();
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        let fence = line.trim_start().starts_with("```");
+        if fence {
+            in_fence = !in_fence;
+        }
+        if fence || in_fence {
+            s.push_str(line);
+            continue;
+        }
+
+        // Operations' headings are bare, since markdown renderers give
+        // them the anchor links point at
+        let line = match line.strip_prefix("## ") {
+            Some(text) if !ANCHOR.is_match(text) && split_heading_id(text).is_none() => {
+                let text = text.trim_end();
+                let name = text.rsplit(' ').next().unwrap_or_default();
+                format!(
+                    "## {}{}{}",
+                    &text[..text.len() - name.len()],
+                    links.named_anchor(&links.fragment(name), name),
+                    &line[3 + text.len()..]
+                )
+            }
+            _ => line.to_string(),
+        };
+        let line = match split_heading_id(&line) {
+            Some((text, id)) if line.starts_with('#') => format!(
+                "{} {{#{}}}{}",
+                text,
+                to_fragment(page_id, id, links),
+                &line[line.trim_end().len()..]
+            ),
+            _ => line,
+        };
+
+        for (piece, code) in split_code(&line) {
+            if code {
+                s.push_str(piece);
+                continue;
+            }
+            let piece = ANCHOR.replace_all(piece, |captures: &Captures| {
+                to_named_anchor(&to_fragment(page_id, &captures[1], links), "")
+            });
+            let piece = LINK.replace_all(&piece, |captures: &Captures| {
+                let destination = &captures[1];
+                let (page, fragment) = match destination.find('#') {
+                    Some(hash) => (&destination[..hash], Some(&destination[hash + 1..])),
+                    None => (destination, None),
+                };
+                match (page_ids.get(page), fragment) {
+                    (Some(id), Some(fragment)) => {
+                        format!("](#{})", to_fragment(id, fragment, links))
+                    }
+                    (Some(id), None) => format!("](#{})", to_page_fragment(id, links)),
+                    (None, _) => captures[0].to_string(),
+                }
+            });
+            s.push_str(&piece);
+        }
+    }
+    s
+}
+
+// Returns the page's anchor
+fn to_page_fragment(page_id: &str, links: &Links) -> String {
+    format!("{}{}", links.anchor_prefix, page_id)
+}
+
+// Returns the anchor on the page, with the page's ID after any prefix
+fn to_fragment(page_id: &str, fragment: &str, links: &Links) -> String {
+    let name = fragment
+        .strip_prefix(&links.anchor_prefix)
+        .unwrap_or(fragment);
+    format!("{}{}-{}", links.anchor_prefix, page_id, name)
+}
+
+// Returns the text and anchor of each second-level heading. Bare headings,
+// as operations have, are linked to by the anchor for their last word.
+fn get_sections(markdown: &str, links: &Links) -> Vec<(String, String)> {
+    let mut sections = Vec::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some(text) = line.strip_prefix("## ") {
+                if let Some((text, id)) = split_heading_id(text) {
+                    sections.push((text.to_string(), id.to_string()));
+                } else if let Some(captures) = ANCHOR.captures(text) {
+                    sections.push((
+                        ANCHOR.replace_all(text, "").trim().to_string(),
+                        captures[1].to_string(),
+                    ));
+                } else {
+                    let text = text.trim();
+                    let name = text.rsplit(' ').next().unwrap_or_default();
+                    sections.push((text.to_string(), links.fragment(name)));
+                }
+            }
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::links::AnchorStyle;
+
+    fn contents() -> HashMap<String, String> {
+        let mut contents = HashMap::new();
+        contents.insert(
+            "objects".to_string(),
+            "# Objects\n\n## <a name=\"player\"></a>Player\n\n> See [`player`](queries.md#player), not `[x](queries.md#player)`\n\n".to_string(),
+        );
+        contents.insert(
+            "queries".to_string(),
+            "# Queries\n\n## player\n\n**Type:** [`Player`](objects.md#player)\n\n```graphql\n## player\n```\n\n".to_string(),
+        );
+        contents.insert("mutations".to_string(), "".to_string());
+        contents
+    }
+
+    #[test]
+    fn to_single_file_should_link_within_the_document() {
+        assert_eq!(
+            r#"# Contents
+
+* [Queries](#queries)
+  * [player](#queries-player)
+* [Objects](#objects)
+  * [Player](#objects-player)
+
+# <a name="queries"></a>Queries
+
+## <a name="queries-player"></a>player
+
+**Type:** [`Player`](#objects-player)
+
+```graphql
+## player
+```
+
+# <a name="objects"></a>Objects
+
+## <a name="objects-player"></a>Player
+
+> See [`player`](#queries-player), not `[x](queries.md#player)`
+
+"#,
+            to_single_file(&contents(), &Links::default())
+        );
+    }
+
+    #[test]
+    fn add_contents_should_list_sections_after_the_title() {
+        let contents = contents();
+        assert_eq!(
+            "# Objects\n\n* [Player](#player)\n\n## <a name=\"player\"></a>Player\n\n",
+            &add_contents(&contents["objects"], &Links::default())[..64]
+        );
+        let links = Links {
+            anchor_prefix: "gql-".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            "* [player](#gql-player)\n\n## player\n\n**Type:** [`Player`](objects.md#player)\n\n",
+            add_contents(
+                "## player\n\n**Type:** [`Player`](objects.md#player)\n\n",
+                &links
+            )
+        );
+        assert_eq!("", add_contents(&contents["mutations"], &links));
+    }
+
+    #[test]
+    fn to_single_file_should_keep_anchor_prefix_and_add_missing_titles() {
+        let mut contents = HashMap::new();
+        contents.insert(
+            "objects".to_string(),
+            "## <a name=\"gql-player\"></a>Player\n\n".to_string(),
+        );
+        let links = Links {
+            anchor_prefix: "gql-".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            "# Contents\n\n* [Objects](#gql-objects)\n  * [Player](#gql-objects-player)\n\n# <a name=\"gql-objects\"></a>Objects\n\n## <a name=\"gql-objects-player\"></a>Player\n\n",
+            to_single_file(&contents, &links)
+        );
+    }
+
+    #[test]
+    fn to_single_file_should_use_heading_ids() {
+        let mut contents = HashMap::new();
+        contents.insert(
+            "objects".to_string(),
+            "# Objects\n\n## Player {#player}\n\n".to_string(),
+        );
+        contents.insert(
+            "queries".to_string(),
+            "# Queries\n\n## player\n\n**Type:** [`Player`](objects.md#player)\n\n".to_string(),
+        );
+        let links = Links {
+            anchor_style: AnchorStyle::HeadingId,
+            ..Default::default()
+        };
+        assert_eq!(
+            "# Contents\n\n* [Queries](#queries)\n  * [player](#queries-player)\n* [Objects](#objects)\n  * [Player](#objects-player)\n\n# Queries {#queries}\n\n## player {#queries-player}\n\n**Type:** [`Player`](#objects-player)\n\n# Objects {#objects}\n\n## Player {#objects-player}\n\n",
+            to_single_file(&contents, &links)
+        );
+    }
+}
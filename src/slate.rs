@@ -0,0 +1,186 @@
+use super::examples::{to_document, to_variables};
+use super::format::to_yaml_string;
+use super::links::Links;
+use super::markdown::split_heading_id;
+use super::model::{DocField, DocInput, Model};
+use super::single_file::join_pages;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// Where the example requests go when gumwood wasn't given a URL
+const EXAMPLE_URL: &str = "https://example.com/graphql";
+
+/// Returns Slate's `source/index.html.md`: front matter with a tab for
+/// each language the examples are in, then the pages in navigation order,
+/// queries first, with links between them pointing within the document
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name, with their examples added
+/// * `site_name` - the name of the documentation site
+/// * `links` - how pages and anchors are named
+pub fn to_slate(contents: &HashMap<String, String>, site_name: &str, links: &Links) -> String {
+    format!(
+        "---\ntitle: {}\nlanguage_tabs:\n  - graphql: GraphQL\n  - shell: cURL\nsearch: true\n---\n\n{}",
+        to_yaml_string(site_name),
+        join_pages(contents, links)
+    )
+}
+
+/// Adds an example request below each operation's heading, as a GraphQL
+/// document and as a curl command, which Slate shows in tabs beside the
+/// operation
+///
+/// # Arguments
+///
+/// * `contents` - the pages, keyed by name
+/// * `model` - the documentation model the pages were rendered from
+/// * `url` - the URL the API is served at, if gumwood introspected one
+pub fn add_examples(contents: &mut HashMap<String, String>, model: &Model, url: Option<&str>) {
+    for (page, keyword, operations) in [
+        ("queries", "query", &model.queries),
+        ("mutations", "mutation", &model.mutations),
+        ("subscriptions", "subscription", &model.subscriptions),
+    ] {
+        if let Some(markdown) = contents.get_mut(page) {
+            *markdown = insert_examples(
+                markdown,
+                keyword,
+                operations,
+                model,
+                url.unwrap_or(EXAMPLE_URL),
+            );
+        }
+    }
+}
+
+fn insert_examples(
+    markdown: &str,
+    keyword: &str,
+    operations: &[DocField],
+    model: &Model,
+    url: &str,
+) -> String {
+    let mut s = String::new();
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        s.push_str(line);
+        let heading = match line.strip_prefix("## ") {
+            Some(text) if !in_fence => text.trim_end(),
+            _ => continue,
+        };
+        // The name follows any icons and anchor
+        let heading = split_heading_id(heading).map_or(heading, |(text, _)| text);
+        let name = heading.rsplit([' ', '>']).next().unwrap_or_default();
+        if let Some(operation) = operations.iter().find(|operation| operation.name == name) {
+            let document = to_document(keyword, operation, model, 1);
+            s.push_str(&format!(
+                "\n```graphql\n{}\n```\n\n```shell\n{}\n```\n",
+                document,
+                to_curl(url, &document, &operation.arguments, model)
+            ));
+        }
+    }
+    s
+}
+
+// Returns a curl command that posts the document, on one line, with a
+// placeholder value for each variable
+fn to_curl(url: &str, document: &str, arguments: &[DocInput], model: &Model) -> String {
+    let query = document.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let mut body = json!({ "query": query });
+    if !arguments.is_empty() {
+        body["variables"] = Value::Object(to_variables(arguments, model));
+    }
+    format!(
+        "curl {} \\\n  -H 'Content-Type: application/json' \\\n  -d {}",
+        to_shell_string(url),
+        to_shell_string(&body.to_string())
+    )
+}
+
+// Quotes the string for a POSIX shell
+fn to_shell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icons::Icons;
+    use crate::schema::Schema;
+    use std::error::Error;
+
+    #[test]
+    fn add_examples_should_add_graphql_and_curl_tabs() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query {
+              player(id: ID!, active: Boolean): Player
+            }
+            type Player {
+              name: String
+              team: Team
+              stats(season: Int!): String
+              position: Position
+            }
+            type Team { name: String }
+            enum Position { CENTER }
+            "#,
+        )?;
+        let links = Links::default();
+        let model = Model::from_schema(&schema, &Icons::default(), &links);
+        let mut contents = HashMap::new();
+        contents.insert(
+            "queries".to_string(),
+            "# Queries\n\n## player\n\n**Type:** [`Player`](objects.md#player)\n".to_string(),
+        );
+        add_examples(&mut contents, &model, None);
+        assert_eq!(
+            r#"# Queries
+
+## player
+
+```graphql
+query player($active: Boolean, $id: ID!) {
+  player(active: $active, id: $id) {
+    name
+    position
+  }
+}
+```
+
+```shell
+curl 'https://example.com/graphql' \
+  -H 'Content-Type: application/json' \
+  -d '{"query":"query player($active: Boolean, $id: ID!) { player(active: $active, id: $id) { name position } }","variables":{"active":false,"id":""}}'
+```
+
+**Type:** [`Player`](objects.md#player)
+"#,
+            contents["queries"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_slate_should_add_language_tabs() {
+        let mut contents = HashMap::new();
+        contents.insert(
+            "objects".to_string(),
+            "# Objects\n\n## <a name=\"player\"></a>Player\n".to_string(),
+        );
+        assert_eq!(
+            "---\ntitle: \"League: API\"\nlanguage_tabs:\n  - graphql: GraphQL\n  - shell: cURL\nsearch: true\n---\n\n# <a name=\"objects\"></a>Objects\n\n## <a name=\"objects-player\"></a>Player\n",
+            to_slate(&contents, "League: API", &Links::default())
+        );
+    }
+
+    #[test]
+    fn to_shell_string_should_escape_quotes() {
+        assert_eq!("'it'\\''s'", to_shell_string("it's"));
+    }
+}
@@ -0,0 +1,120 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// Turns names into the slugs used for anchors, links, and file names, so
+/// all three always agree
+pub trait Slugifier: fmt::Debug {
+    fn slugify(&self, name: &str) -> String;
+}
+
+/// The built-in slugifiers, chosen with `slugifier` in the config file
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlugStyle {
+    /// `addPlayer` becomes `addplayer`
+    #[default]
+    Lowercase,
+    /// `addPlayer` becomes `add-player`
+    Kebab,
+    /// What GitHub generates for headings: lowercase, spaces become hyphens,
+    /// and punctuation other than hyphens and underscores is dropped
+    Github,
+}
+
+impl SlugStyle {
+    pub fn slugifier(self) -> Box<dyn Slugifier> {
+        match self {
+            SlugStyle::Lowercase => Box::new(Lowercase),
+            SlugStyle::Kebab => Box::new(Kebab),
+            SlugStyle::Github => Box::new(Github),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Lowercase;
+
+impl Slugifier for Lowercase {
+    fn slugify(&self, name: &str) -> String {
+        name.to_lowercase()
+    }
+}
+
+#[derive(Debug)]
+pub struct Kebab;
+
+impl Slugifier for Kebab {
+    fn slugify(&self, name: &str) -> String {
+        let chars: Vec<char> = name.chars().collect();
+        let mut slug = String::new();
+        for (i, c) in chars.iter().enumerate() {
+            if c.is_alphanumeric() || *c == '_' {
+                // Start a word at a lowercase-to-uppercase change, or at the
+                // last capital of an acronym ("HTTPServer" is "http-server")
+                let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+                let next = chars.get(i + 1);
+                let starts_word = c.is_uppercase()
+                    && prev.is_some_and(|p| {
+                        p.is_lowercase()
+                            || p.is_ascii_digit()
+                            || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+                    });
+                if starts_word && !slug.is_empty() && !slug.ends_with('-') {
+                    slug.push('-');
+                }
+                slug.extend(c.to_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        slug.trim_end_matches('-').to_string()
+    }
+}
+
+#[derive(Debug)]
+pub struct Github;
+
+impl Slugifier for Github {
+    fn slugify(&self, name: &str) -> String {
+        name.trim()
+            .to_lowercase()
+            .chars()
+            .filter_map(|c| match c {
+                ' ' => Some('-'),
+                c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowercase_should_lowercase() {
+        assert_eq!("addplayer", Lowercase.slugify("addPlayer"));
+    }
+
+    #[test]
+    fn kebab_should_split_words() {
+        assert_eq!("add-player", Kebab.slugify("addPlayer"));
+        assert_eq!("player-v2", Kebab.slugify("PlayerV2"));
+        assert_eq!("http-server", Kebab.slugify("HTTPServer"));
+        assert_eq!("largest-types", Kebab.slugify("largest-types"));
+        assert_eq!("__type", Kebab.slugify("__Type"));
+        assert_eq!("a-b", Kebab.slugify("a :: b"));
+    }
+
+    #[test]
+    fn github_should_match_heading_anchors() {
+        assert_eq!("addplayer", Github.slugify("addPlayer"));
+        assert_eq!("largest-types", Github.slugify("Largest Types"));
+        assert_eq!("player-v2", Github.slugify("Player (v2)"));
+        assert_eq!(
+            "input-addplayerinput",
+            Github.slugify("Input: AddPlayerInput")
+        );
+    }
+}
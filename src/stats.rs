@@ -0,0 +1,619 @@
+use super::schema::{Referrer, Schema, Type, TypeRef};
+use super::sdl::BUILT_IN_SCALARS;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// How big and how connected a type is
+#[derive(Debug, PartialEq)]
+pub struct TypeStats {
+    pub name: String,
+    pub kind: String,
+    /// Fields, input fields, or enum values, depending on the kind
+    pub field_count: usize,
+    /// References to the type from other types' fields, arguments, input
+    /// fields, interfaces, and unions
+    pub inbound_refs: usize,
+}
+
+/// Types that reference each other, directly or through other types, so
+/// following their fields never ends
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Cycle {
+    /// The types in the cycle, sorted by name
+    pub types: Vec<String>,
+    /// Whether the types are input types, which clients can nest as deeply
+    /// as they like
+    pub input: bool,
+}
+
+/// How a custom scalar is used and documented
+#[derive(Debug, PartialEq)]
+pub struct ScalarUsage {
+    pub name: String,
+    /// Fields and input fields of the scalar's type
+    pub field_count: usize,
+    /// Arguments of fields and directives of the scalar's type
+    pub argument_count: usize,
+    pub described: bool,
+    pub specified_by: bool,
+}
+
+/// Counts of what a page documents, for templates that show summaries
+#[derive(Debug, Default, PartialEq)]
+pub struct PageStats {
+    pub type_count: usize,
+    /// Fields, input fields, and enum values
+    pub field_count: usize,
+    /// Deprecated fields and enum values
+    pub deprecated_count: usize,
+}
+
+impl PageStats {
+    /// Adds a type and its fields to the counts
+    pub fn add(&mut self, typ: &Type) {
+        self.type_count += 1;
+        self.field_count += count_fields(typ);
+        self.deprecated_count += typ
+            .fields
+            .iter()
+            .flatten()
+            .filter(|field| field.is_deprecated == Some(true))
+            .count()
+            + typ
+                .enums
+                .iter()
+                .flatten()
+                .filter(|value| value.is_deprecated == Some(true))
+                .count();
+    }
+}
+
+/// How many of the schema's elements have descriptions
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Coverage {
+    pub types: CoverageCount,
+    /// Fields and input fields
+    pub fields: CoverageCount,
+    pub arguments: CoverageCount,
+    pub enum_values: CoverageCount,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct CoverageCount {
+    pub described: usize,
+    pub total: usize,
+}
+
+impl CoverageCount {
+    fn add(&mut self, description: &Option<String>) {
+        self.total += 1;
+        if description.as_deref().is_some_and(|d| !d.trim().is_empty()) {
+            self.described += 1;
+        }
+    }
+}
+
+/// Returns how many types, fields, arguments, and enum values have
+/// descriptions, not counting the introspection types
+pub fn get_coverage(schema: &Schema) -> Coverage {
+    let mut coverage = Coverage::default();
+    for typ in schema.types.iter().flatten() {
+        if is_introspection_type(typ) {
+            continue;
+        }
+        coverage.types.add(&typ.description);
+        for field in typ.fields.iter().flatten() {
+            coverage.fields.add(&field.description);
+            for arg in field.args.iter().flatten() {
+                coverage.arguments.add(&arg.description);
+            }
+        }
+        for input in typ.inputs.iter().flatten() {
+            coverage.fields.add(&input.description);
+        }
+        for value in typ.enums.iter().flatten() {
+            coverage.enum_values.add(&value.description);
+        }
+    }
+    coverage
+}
+
+/// Returns the stats for every type in the schema except the introspection
+/// types, sorted by name
+pub fn get_type_stats(schema: &Schema) -> Vec<TypeStats> {
+    let types: Vec<&Type> = schema
+        .types
+        .iter()
+        .flatten()
+        .filter(|typ| !is_introspection_type(typ))
+        .collect();
+
+    let mut inbound: HashMap<String, usize> = HashMap::new();
+    for typ in types.iter() {
+        for type_ref in get_type_refs(typ) {
+            let name = type_ref.get_actual_name();
+            if typ.name.as_ref() != Some(&name) {
+                *inbound.entry(name).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut stats: Vec<TypeStats> = types
+        .iter()
+        .filter_map(|typ| {
+            let name = typ.name.clone()?;
+            Some(TypeStats {
+                kind: typ.kind.clone().unwrap_or_default(),
+                field_count: count_fields(typ),
+                inbound_refs: inbound.get(&name).copied().unwrap_or(0),
+                name,
+            })
+        })
+        .collect();
+    stats.sort_by(|a, b| a.name.cmp(&b.name));
+    stats
+}
+
+/// Records on every type how many other types refer to it, for the quick
+/// facts at the top of each type
+pub fn set_used_by(schema: &mut Schema) {
+    let mut referrers: HashMap<String, HashSet<String>> = HashMap::new();
+    for typ in schema.types.iter().flatten() {
+        if is_introspection_type(typ) {
+            continue;
+        }
+        let name = typ.name.clone().unwrap_or_default();
+        for type_ref in get_type_refs(typ) {
+            let referenced = type_ref.get_actual_name();
+            if referenced != name {
+                referrers
+                    .entry(referenced)
+                    .or_default()
+                    .insert(name.clone());
+            }
+        }
+    }
+    for typ in schema.types.iter_mut().flatten() {
+        if let Some(name) = &typ.name {
+            typ.used_by = Some(referrers.get(name).map_or(0, HashSet::len));
+        }
+    }
+}
+
+/// Records on every type the operations and other types whose fields,
+/// arguments, and input fields refer to it, for its backlinks: queries,
+/// mutations, then subscriptions, in schema order, then types by name
+pub fn set_referenced_by(schema: &mut Schema) {
+    let roots = [
+        ("query", schema.get_query_name()),
+        ("mutation", schema.get_mutation_name()),
+        ("subscription", schema.get_subscription_name()),
+    ];
+    let mut referrers: HashMap<String, Vec<Referrer>> = HashMap::new();
+    let mut add = |type_ref: &TypeRef, name: &str, kind: &str, member: Option<String>| {
+        let referenced = type_ref.get_actual_name();
+        let list = referrers.entry(referenced).or_default();
+        match list
+            .iter_mut()
+            .find(|referrer| referrer.name == name && referrer.kind == kind)
+        {
+            Some(referrer) => referrer.members.extend(member),
+            None => list.push(Referrer {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                members: member.into_iter().collect(),
+            }),
+        }
+    };
+    for typ in schema.types.iter().flatten() {
+        if is_introspection_type(typ) {
+            continue;
+        }
+        let name = typ.name.as_deref().unwrap_or_default();
+        let keyword = roots
+            .iter()
+            .find(|(_, root)| root.as_deref() == Some(name))
+            .map(|(keyword, _)| *keyword);
+        // A type's references to itself aren't worth linking back to
+        let is_other =
+            |type_ref: &&TypeRef| keyword.is_some() || type_ref.get_actual_name() != name;
+        for field in typ.fields.iter().flatten() {
+            let field_name = field.name.as_deref().unwrap_or_default();
+            // An operation refers to what it returns as a whole, and to
+            // what its arguments take by name
+            let (referrer, kind, member) = match keyword {
+                Some(keyword) => (field_name, keyword, None),
+                None => (
+                    name,
+                    typ.kind.as_deref().unwrap_or_default(),
+                    Some(field_name),
+                ),
+            };
+            if let Some(field_type) = field.field_type.as_ref().filter(is_other) {
+                add(field_type, referrer, kind, member.map(String::from));
+            }
+            for arg in field.args.iter().flatten() {
+                if let Some(input_type) = arg.input_type.as_ref().filter(is_other) {
+                    let arg_name = arg.name.as_deref().unwrap_or_default();
+                    add(
+                        input_type,
+                        referrer,
+                        kind,
+                        Some(format!("{}({}:)", field_name, arg_name)),
+                    );
+                }
+            }
+        }
+        for input in typ.inputs.iter().flatten() {
+            if let Some(input_type) = input.input_type.as_ref().filter(is_other) {
+                add(
+                    input_type,
+                    name,
+                    typ.kind.as_deref().unwrap_or_default(),
+                    input.name.clone(),
+                );
+            }
+        }
+    }
+    for list in referrers.values_mut() {
+        list.sort_by_key(|referrer| {
+            match roots
+                .iter()
+                .position(|(keyword, _)| *keyword == referrer.kind)
+            {
+                Some(position) => (position, String::new()),
+                None => (roots.len(), referrer.name.clone()),
+            }
+        });
+    }
+    for typ in schema.types.iter_mut().flatten() {
+        if let Some(name) = &typ.name {
+            typ.referenced_by = referrers.remove(name);
+        }
+    }
+}
+
+/// Returns how every scalar other than the built-in ones is used and
+/// documented, sorted by name
+pub fn get_scalar_usage(schema: &Schema) -> Vec<ScalarUsage> {
+    let mut usage: Vec<ScalarUsage> = schema
+        .get_types_of_kind("SCALAR")
+        .into_iter()
+        .filter_map(|typ| typ.name.as_ref().map(|name| (name, typ)))
+        .filter(|(name, _)| !BUILT_IN_SCALARS.contains(&name.as_str()))
+        .map(|(name, typ)| ScalarUsage {
+            name: name.clone(),
+            field_count: 0,
+            argument_count: 0,
+            described: typ
+                .description
+                .as_deref()
+                .is_some_and(|d| !d.trim().is_empty()),
+            specified_by: typ.specified_by_url.is_some(),
+        })
+        .collect();
+    usage.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut count = |type_ref: &Option<TypeRef>, argument: bool| {
+        let name = match type_ref {
+            Some(type_ref) => type_ref.get_actual_name(),
+            None => return,
+        };
+        if let Some(scalar) = usage.iter_mut().find(|scalar| scalar.name == name) {
+            if argument {
+                scalar.argument_count += 1;
+            } else {
+                scalar.field_count += 1;
+            }
+        }
+    };
+    for typ in schema.types.iter().flatten() {
+        if is_introspection_type(typ) {
+            continue;
+        }
+        for field in typ.fields.iter().flatten() {
+            count(&field.field_type, false);
+            for arg in field.args.iter().flatten() {
+                count(&arg.input_type, true);
+            }
+        }
+        for input in typ.inputs.iter().flatten() {
+            count(&input.input_type, false);
+        }
+    }
+    for directive in schema.directives.iter().flatten() {
+        for arg in directive.args.iter().flatten() {
+            count(&arg.input_type, true);
+        }
+    }
+    usage
+}
+
+/// Returns the reference cycles in the schema, including types that
+/// reference themselves, not counting the introspection types. Each cycle
+/// is a set of types that can all reach each other, sorted by its first
+/// type's name.
+pub fn get_cycles(schema: &Schema) -> Vec<Cycle> {
+    let types: HashMap<&str, &Type> = schema
+        .types
+        .iter()
+        .flatten()
+        .filter(|typ| !is_introspection_type(typ))
+        .filter_map(|typ| Some((typ.name.as_deref()?, typ)))
+        .collect();
+    let mut edges: HashMap<&str, Vec<String>> = HashMap::new();
+    for (name, typ) in types.iter() {
+        let mut targets: Vec<String> = get_type_refs(typ)
+            .into_iter()
+            .map(TypeRef::get_actual_name)
+            .filter(|target| types.contains_key(target.as_str()))
+            .collect();
+        targets.sort();
+        targets.dedup();
+        edges.insert(name, targets);
+    }
+
+    let mut names: Vec<&str> = types.keys().copied().collect();
+    names.sort_unstable();
+    let mut search = CycleSearch {
+        edges: &edges,
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for name in names {
+        if !search.index.contains_key(name) {
+            search.visit(name);
+        }
+    }
+
+    let mut cycles: Vec<Cycle> = search
+        .components
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1 || edges[component[0].as_str()].contains(&component[0])
+        })
+        .map(|mut component| {
+            component.sort();
+            Cycle {
+                input: component
+                    .iter()
+                    .all(|name| types[name.as_str()].kind.as_deref() == Some("INPUT_OBJECT")),
+                types: component,
+            }
+        })
+        .collect();
+    cycles.sort_by(|a, b| a.types.cmp(&b.types));
+    cycles
+}
+
+// Tarjan's search for strongly connected components: the sets of types
+// that can all reach each other
+struct CycleSearch<'a> {
+    edges: &'a HashMap<&'a str, Vec<String>>,
+    index: HashMap<&'a str, usize>,
+    low_link: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> CycleSearch<'a> {
+    fn visit(&mut self, name: &'a str) {
+        let index = self.index.len();
+        self.index.insert(name, index);
+        self.low_link.insert(name, index);
+        self.stack.push(name);
+        self.on_stack.insert(name);
+
+        let edges = self.edges;
+        for target in edges[name].iter() {
+            let target = target.as_str();
+            if !self.index.contains_key(target) {
+                self.visit(target);
+                let low_link = self.low_link[name].min(self.low_link[target]);
+                self.low_link.insert(name, low_link);
+            } else if self.on_stack.contains(target) {
+                let low_link = self.low_link[name].min(self.index[target]);
+                self.low_link.insert(name, low_link);
+            }
+        }
+
+        if self.low_link[name] == index {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member.to_string());
+                if member == name {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+fn count_fields(typ: &Type) -> usize {
+    typ.fields.as_ref().map_or(0, Vec::len)
+        + typ.inputs.as_ref().map_or(0, Vec::len)
+        + typ.enums.as_ref().map_or(0, Vec::len)
+}
+
+/// Returns whether the type is one of introspection's own, such as `__Type`
+pub fn is_introspection_type(typ: &Type) -> bool {
+    typ.name
+        .as_deref()
+        .is_some_and(|name| name.starts_with("__"))
+}
+
+fn get_type_refs(typ: &Type) -> Vec<&TypeRef> {
+    let mut refs = Vec::new();
+    for field in typ.fields.iter().flatten() {
+        refs.extend(field.field_type.iter());
+        for arg in field.args.iter().flatten() {
+            refs.extend(arg.input_type.iter());
+        }
+    }
+    for input in typ.inputs.iter().flatten() {
+        refs.extend(input.input_type.iter());
+    }
+    refs.extend(typ.interfaces.iter().flatten());
+    // Interfaces list their implementations as possible types too, but those
+    // point away from the interface, so only unions count as references
+    if typ.kind.as_deref() == Some("UNION") {
+        refs.extend(typ.possible_types.iter().flatten());
+    }
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    const SDL: &str = r#"
+        type Query { player(id: ID!): Player players: [Player!]! team: Team }
+        type Player implements Node { id: ID! name: String team: Team }
+        type Team implements Node { id: ID! players: [Player] }
+        interface Node { id: ID! }
+        union Anyone = Player | Team
+    "#;
+
+    fn get<'a>(stats: &'a [TypeStats], name: &str) -> &'a TypeStats {
+        stats.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn get_type_stats_should_count_fields() -> Result<(), Box<dyn Error>> {
+        let stats = get_type_stats(&Schema::from_sdl(SDL)?);
+        assert_eq!(3, get(&stats, "Query").field_count);
+        assert_eq!(3, get(&stats, "Player").field_count);
+        assert_eq!(0, get(&stats, "Anyone").field_count);
+        Ok(())
+    }
+
+    #[test]
+    fn get_type_stats_should_count_inbound_references() -> Result<(), Box<dyn Error>> {
+        let stats = get_type_stats(&Schema::from_sdl(SDL)?);
+        // Query.player, Query.players, Team.players, and Anyone
+        assert_eq!(4, get(&stats, "Player").inbound_refs);
+        // Player and Team implement it
+        assert_eq!(2, get(&stats, "Node").inbound_refs);
+        assert_eq!(0, get(&stats, "Query").inbound_refs);
+        Ok(())
+    }
+
+    #[test]
+    fn page_stats_should_count_types_fields_and_deprecations() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Player { id: ID! number: Int @deprecated }
+            enum Position { GOALIE LEFT_WING @deprecated WING }
+            "#,
+        )?;
+        let mut stats = PageStats::default();
+        stats.add(schema.get_type("Player").unwrap());
+        stats.add(schema.get_type("Position").unwrap());
+        assert_eq!(
+            PageStats {
+                type_count: 2,
+                field_count: 5,
+                deprecated_count: 2,
+            },
+            stats
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_coverage_should_count_descriptions() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            "A player"
+            type Player { "The ID" id: ID! team(
+                "Which season" season: Int
+                league: String
+            ): String }
+            input PlayerInput { "The name" name: String }
+            enum Position { "Stops pucks" GOALIE WING }
+            "#,
+        )?;
+        let coverage = get_coverage(&schema);
+        // The built-in ID, Int, and String scalars count too
+        assert_eq!(
+            CoverageCount {
+                described: 1,
+                total: 6
+            },
+            coverage.types
+        );
+        assert_eq!(
+            CoverageCount {
+                described: 2,
+                total: 3
+            },
+            coverage.fields
+        );
+        assert_eq!(
+            CoverageCount {
+                described: 1,
+                total: 2
+            },
+            coverage.arguments
+        );
+        assert_eq!(
+            CoverageCount {
+                described: 1,
+                total: 2
+            },
+            coverage.enum_values
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_type_stats_should_skip_introspection_types() {
+        let schema = Schema {
+            types: Some(vec![Type {
+                name: Some("__Schema".to_string()),
+                ..Default::default()
+            }]),
+            description: None,
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            directives: None,
+        };
+        assert!(get_type_stats(&schema).is_empty());
+    }
+
+    #[test]
+    fn get_cycles_should_find_mutual_and_self_references() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_sdl(
+            r#"
+            type Query { player: Player }
+            type Player { team: Team name: String }
+            type Team { players: [Player!]! league: League }
+            type League { name: String }
+            input Filter { and: [Filter!] or: [Filter!] name: String }
+            input Sort { field: String }
+            "#,
+        )?;
+        assert_eq!(
+            vec![
+                Cycle {
+                    types: vec!["Filter".to_string()],
+                    input: true,
+                },
+                Cycle {
+                    types: vec!["Player".to_string(), "Team".to_string()],
+                    input: false,
+                },
+            ],
+            get_cycles(&schema)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use super::schema::{ElementMut, Schema};
+use chrono::NaiveDate;
+use std::{collections::HashMap, error::Error};
+
+// Words that introduce a removal date in a deprecation reason, as in
+// "Removed after 2025-01-01" or "Sunset: 2025-01-01"
+const REMOVAL_MARKERS: &[&str] = &["removed after", "removal", "sunset"];
+
+/// Records when each deprecated field and enum value will be removed, taken
+/// from the removal dates in the config or, failing that, from a marker in
+/// its deprecation reason
+///
+/// # Arguments
+///
+/// * `schema` - the schema to update
+/// * `removals` - removal dates (YYYY-MM-DD), keyed by schema coordinate
+pub fn apply(
+    schema: &mut Schema,
+    removals: &HashMap<String, String>,
+) -> Result<(), Box<dyn Error>> {
+    for (coordinate, date) in removals {
+        if parse_date(date).is_none() {
+            return Err(format!(
+                "invalid removal date for {}: {} (expected YYYY-MM-DD)",
+                coordinate, date
+            )
+            .into());
+        }
+    }
+
+    schema.for_each_element_mut(&mut |coordinate, element| {
+        let (is_deprecated, reason, removal_date) = match element {
+            ElementMut::Field(field) => (
+                field.is_deprecated,
+                &field.deprecation_reason,
+                &mut field.removal_date,
+            ),
+            ElementMut::Enum(value) => (
+                value.is_deprecated,
+                &value.deprecation_reason,
+                &mut value.removal_date,
+            ),
+            _ => return,
+        };
+        if is_deprecated == Some(true) {
+            *removal_date = removals.get(coordinate).cloned().or_else(|| {
+                reason
+                    .as_deref()
+                    .and_then(parse_removal_date)
+                    .map(|date| date.to_string())
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Returns the date that follows a removal marker in a deprecation reason
+pub fn parse_removal_date(reason: &str) -> Option<NaiveDate> {
+    let lower = reason.to_lowercase();
+    REMOVAL_MARKERS.iter().find_map(|marker| {
+        let start = lower.find(marker)? + marker.len();
+        lower[start..]
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == ')')
+            .map(|word| word.trim_matches(|c: char| !c.is_ascii_digit()))
+            .find_map(parse_date)
+    })
+}
+
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDL: &str = r#"
+        type Player {
+            number: Int @deprecated(reason: "Use jersey. Removed after 2025-01-01.")
+            jersey: Int @deprecated(reason: "Use uniform")
+            name: String
+        }
+        enum Position {
+            LEFT_WING @deprecated(reason: "Use WING (sunset: 2024-06-30)")
+            WING
+        }
+    "#;
+
+    #[test]
+    fn parse_removal_date_should_find_marked_dates() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 1);
+        assert_eq!(date, parse_removal_date("Removed after 2025-01-01"));
+        assert_eq!(date, parse_removal_date("Use jersey; removal: 2025-01-01."));
+        assert_eq!(date, parse_removal_date("Sunset 2025-01-01"));
+        assert_eq!(None, parse_removal_date("Deprecated on 2025-01-01"));
+        assert_eq!(None, parse_removal_date("Removed after launch"));
+    }
+
+    #[test]
+    fn apply_should_record_removal_dates() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(SDL)?;
+        let mut removals = HashMap::new();
+        removals.insert("Player.jersey".to_string(), "2026-03-01".to_string());
+        removals.insert("Player.name".to_string(), "2026-03-01".to_string());
+        apply(&mut schema, &removals)?;
+
+        let fields = schema.get_type("Player").unwrap().fields.as_ref().unwrap();
+        assert_eq!(Some("2025-01-01".to_string()), fields[0].removal_date);
+        assert_eq!(Some("2026-03-01".to_string()), fields[1].removal_date);
+        // Only deprecated fields can be removed
+        assert_eq!(None, fields[2].removal_date);
+
+        let values = schema.get_type("Position").unwrap().enums.as_ref().unwrap();
+        assert_eq!(Some("2024-06-30".to_string()), values[0].removal_date);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_should_fail_on_invalid_dates() {
+        let mut schema = Schema::from_sdl(SDL).unwrap();
+        let mut removals = HashMap::new();
+        removals.insert("Player.jersey".to_string(), "next year".to_string());
+        assert!(apply(&mut schema, &removals).is_err());
+    }
+}
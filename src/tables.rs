@@ -0,0 +1,35 @@
+use serde::Deserialize;
+
+/// The order of a table's rows
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// By name
+    #[default]
+    Alpha,
+    /// As the schema declares them
+    Schema,
+}
+
+/// The order of each kind of table's rows, chosen under `[sort]` in the
+/// config file
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sort {
+    pub arguments: SortOrder,
+    pub fields: SortOrder,
+    pub inputs: SortOrder,
+    pub values: SortOrder,
+}
+
+/// How the argument, field, input, and value tables on the pages are
+/// rendered, passed alongside the `Links`
+#[derive(Debug, Default)]
+pub struct Tables {
+    /// The order of the tables' rows
+    pub sort: Sort,
+
+    /// Show the fields of a mutation's `FooInput` and `FooPayload` types
+    /// under the mutation
+    pub expand_mutations: bool,
+}
@@ -0,0 +1,122 @@
+use super::schema::{ElementMut, Schema};
+
+/// Moves the tags at the start of each type's and field's description, as
+/// in `[beta] [admin] Deletes a player`, out of the description and onto the
+/// element, and lists each tagged operation without a group under its
+/// first tag
+///
+/// # Arguments
+///
+/// * `schema` - the schema to update
+pub fn apply(schema: &mut Schema) {
+    schema.for_each_element_mut(&mut |_, element| {
+        let (description, tags) = match element {
+            ElementMut::Type(typ) => (&mut typ.description, &mut typ.tags),
+            ElementMut::Field(field) => (&mut field.description, &mut field.tags),
+            _ => return,
+        };
+        if let Some((found, rest)) = description.as_deref().and_then(split_tags) {
+            *description = Some(rest.to_string()).filter(|rest| !rest.is_empty());
+            *tags = Some(found);
+        }
+    });
+
+    let roots = [
+        schema.get_query_name(),
+        schema.get_mutation_name(),
+        schema.get_subscription_name(),
+    ];
+    for typ in schema.types.iter_mut().flatten() {
+        if typ.name.is_none() || !roots.contains(&typ.name) {
+            continue;
+        }
+        for field in typ.fields.iter_mut().flatten() {
+            if field.group.is_none() {
+                field.group = field.tags.iter().flatten().next().cloned();
+            }
+        }
+    }
+}
+
+// Returns the tags at the start of a description and the rest of it, or
+// None if it doesn't start with a tag. A tag is a word in brackets followed
+// by a space, so a link such as `[docs](https://example.com)` isn't one.
+fn split_tags(description: &str) -> Option<(Vec<String>, &str)> {
+    let mut tags = Vec::new();
+    let mut rest = description.trim_start();
+    while let Some((tag, after)) = rest
+        .strip_prefix('[')
+        .and_then(|after| after.split_once(']'))
+    {
+        let is_tag = tag.starts_with(|c: char| c.is_ascii_alphabetic())
+            && tag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            && (after.is_empty() || after.starts_with(char::is_whitespace));
+        if !is_tag {
+            break;
+        }
+        tags.push(tag.to_string());
+        rest = after.trim_start();
+    }
+    if tags.is_empty() {
+        None
+    } else {
+        Some((tags, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn split_tags_should_take_leading_tags_only() {
+        assert_eq!(
+            Some((
+                vec!["beta".to_string(), "admin".to_string()],
+                "Deletes a [player] for good"
+            )),
+            split_tags("[beta] [admin] Deletes a [player] for good")
+        );
+        assert_eq!(Some((vec!["beta".to_string()], "")), split_tags("[beta]"));
+        assert_eq!(None, split_tags("[Docs](https://example.com) say so"));
+        assert_eq!(None, split_tags("[not a tag] Deletes a player"));
+        assert_eq!(None, split_tags("Deletes a player"));
+    }
+
+    #[test]
+    fn apply_should_tag_elements_and_group_operations() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_sdl(
+            r#"
+            type Query {
+              "[beta] Finds a player"
+              player: Player
+              "[internal]"
+              stats: Int
+            }
+            "[admin] Someone on a team"
+            type Player {
+              "[beta] The player's name"
+              name: String
+            }
+            "#,
+        )?;
+        apply(&mut schema);
+
+        let query = schema.get_type("Query").unwrap().fields.as_ref().unwrap();
+        assert_eq!(Some("Finds a player".to_string()), query[0].description);
+        assert_eq!(Some(vec!["beta".to_string()]), query[0].tags);
+        assert_eq!(Some("beta".to_string()), query[0].group);
+        assert_eq!(None, query[1].description);
+        assert_eq!(Some("internal".to_string()), query[1].group);
+        let player = schema.get_type("Player").unwrap();
+        assert_eq!(Some(vec!["admin".to_string()]), player.tags);
+        assert_eq!(Some("Someone on a team".to_string()), player.description);
+        let name = &player.fields.as_ref().unwrap()[0];
+        assert_eq!(Some(vec!["beta".to_string()]), name.tags);
+        assert_eq!(None, name.group);
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::Path};
+
+/// The introspection requests made to each URL and what came back, so a
+/// run can be replayed later without the endpoints
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Tape {
+    pub exchanges: Vec<Exchange>,
+}
+
+/// One introspection request and its response. The request's headers are
+/// left out, since they often carry credentials.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Exchange {
+    pub url: String,
+    pub request: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Tape {
+    /// Reads a tape written by --record
+    pub fn from_file(file: &Path) -> Result<Tape, Box<dyn Error>> {
+        let text = fs::read_to_string(file)
+            .map_err(|err| format!("can't read tape {}: {}", file.display(), err))?;
+        serde_json::from_str(&text)
+            .map_err(|err| format!("invalid tape {}: {}", file.display(), err).into())
+    }
+
+    /// Writes the tape as pretty-printed JSON
+    pub fn write(&self, file: &Path) -> Result<(), Box<dyn Error>> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(file, json)
+            .map_err(|err| format!("can't write tape {}: {}", file.display(), err).into())
+    }
+
+    /// Returns the exchange recorded for the URL
+    pub fn find(&self, url: &str) -> Result<&Exchange, Box<dyn Error>> {
+        self.exchanges
+            .iter()
+            .find(|exchange| exchange.url == url)
+            .ok_or_else(|| format!("the tape has no response from {}", url).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn tape_should_round_trip_through_a_file() -> Result<(), Box<dyn Error>> {
+        let tape = Tape {
+            exchanges: vec![Exchange {
+                url: "https://example.com/graphql".to_string(),
+                request: "{\"query\": \"{ __schema { types { name } } }\"}".to_string(),
+                status: 200,
+                headers: vec![("server".to_string(), "example".to_string())],
+                body: "{\"data\": {\"__schema\": {}}}".to_string(),
+            }],
+        };
+        let file = env::temp_dir().join("gumwood-tape-test.json");
+        tape.write(&file)?;
+        let read = Tape::from_file(&file);
+        fs::remove_file(&file)?;
+        let read = read?;
+        assert_eq!(tape.exchanges, read.exchanges);
+        assert!(read.find("https://example.com/graphql").is_ok());
+        assert_eq!(
+            "the tape has no response from https://example.org/graphql",
+            read.find("https://example.org/graphql")
+                .unwrap_err()
+                .to_string()
+        );
+        Ok(())
+    }
+}
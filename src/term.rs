@@ -0,0 +1,257 @@
+use super::markdown::{self, Block, Inline};
+
+// ANSI select graphic rendition codes
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const ITALIC: &str = "3";
+const UNDERLINE: &str = "4";
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+const CYAN: &str = "36";
+
+// The gap between table columns
+const COLUMN_GAP: &str = "  ";
+
+/// Converts a page of gumwood's markdown to text for a terminal: headings
+/// in bold, types and other links in color, code in yellow, descriptions
+/// set off by a bar, and tables with their columns aligned
+///
+/// # Arguments
+///
+/// * `markdown` - the page's markdown
+pub fn to_ansi(markdown: &str) -> String {
+    format!("{}\n", to_blocks(&markdown::parse_blocks(markdown)))
+}
+
+// The styles that apply to a piece of text, from the blocks and inlines
+// around it
+#[derive(Clone, Copy, Default)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    color: Option<&'static str>,
+}
+
+impl Style {
+    // Wraps the text in the escape codes for the style
+    fn paint(self, text: &str) -> String {
+        let codes: Vec<&str> = [
+            (self.bold, BOLD),
+            (self.italic, ITALIC),
+            (self.underline, UNDERLINE),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, code)| *code)
+        .chain(self.color)
+        .collect();
+        if codes.is_empty() || text.is_empty() {
+            text.to_string()
+        } else {
+            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+        }
+    }
+}
+
+// Writes the blocks with a blank line between them
+fn to_blocks(blocks: &[Block]) -> String {
+    let mut texts = Vec::new();
+    for block in blocks {
+        let text = match block {
+            Block::Heading(level, text) => to_inline(
+                text,
+                Style {
+                    bold: true,
+                    underline: *level == 1,
+                    ..Style::default()
+                },
+            ),
+            Block::Paragraph(text) => to_inline(text, Style::default()),
+            Block::Notice(text) => to_inline(
+                text,
+                Style {
+                    italic: true,
+                    ..Style::default()
+                },
+            ),
+            Block::List(items) => items
+                .iter()
+                .map(|item| format!("• {}", to_inline(item, Style::default())))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            Block::Table(rows) => to_table(rows),
+            Block::Quote(blocks) => {
+                let bar = Style {
+                    color: Some(DIM),
+                    ..Style::default()
+                }
+                .paint("│");
+                to_blocks(blocks)
+                    .lines()
+                    .map(|line| format!("{} {}", bar, line).trim_end().to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }
+            Block::Code { code, .. } => code
+                .lines()
+                .map(|line| {
+                    let style = Style {
+                        color: Some(GREEN),
+                        ..Style::default()
+                    };
+                    format!("    {}", style.paint(line))
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+        };
+        texts.push(text);
+    }
+    texts.join("\n\n")
+}
+
+// Pads the cells so the columns line up, with the header row in bold
+fn to_table(rows: &[Vec<String>]) -> String {
+    let cells: Vec<Vec<(String, usize)>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let style = Style {
+                bold: i == 0,
+                ..Style::default()
+            };
+            row.iter()
+                .map(|cell| {
+                    let inlines = markdown::parse_inline(cell);
+                    (to_inlines(&inlines, style), get_width(&inlines))
+                })
+                .collect()
+        })
+        .collect();
+
+    let columns = cells.iter().map(Vec::len).max().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|(_, width)| *width)
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    for (i, row) in cells.iter().enumerate() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|((text, width), column_width)| {
+                format!("{}{}", text, " ".repeat(column_width - width))
+            })
+            .collect();
+        lines.push(line.join(COLUMN_GAP).trim_end().to_string());
+        if i == 0 {
+            let rules: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+            lines.push(rules.join(COLUMN_GAP));
+        }
+    }
+    lines.join("\n")
+}
+
+fn to_inline(text: &str, style: Style) -> String {
+    to_inlines(&markdown::parse_inline(text), style)
+}
+
+fn to_inlines(inlines: &[Inline], style: Style) -> String {
+    let mut s = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) => s.push_str(&style.paint(&markdown::unescape(text))),
+            Inline::Code(code) => {
+                // Links keep their color, so types stand out from other code
+                let color = style.color.or(Some(YELLOW));
+                s.push_str(&Style { color, ..style }.paint(code))
+            }
+            Inline::Strong(inlines) => s.push_str(&to_inlines(
+                inlines,
+                Style {
+                    bold: true,
+                    ..style
+                },
+            )),
+            Inline::Link { text, .. } => s.push_str(&to_inlines(
+                text,
+                Style {
+                    color: Some(CYAN),
+                    ..style
+                },
+            )),
+            // Terminals can't jump to anchors
+            Inline::Anchor(_) => {}
+        }
+    }
+    s
+}
+
+// Returns how many columns the inlines take up on the screen
+fn get_width(inlines: &[Inline]) -> usize {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => markdown::unescape(text).chars().count(),
+            Inline::Code(code) => code.chars().count(),
+            Inline::Strong(inlines) | Inline::Link { text: inlines, .. } => get_width(inlines),
+            Inline::Anchor(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Removes the escape codes, leaving what the reader sees
+    fn strip(text: &str) -> String {
+        let mut s = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("\x1b[") {
+            s.push_str(&rest[..start]);
+            rest = &rest[start..];
+            rest = &rest[rest.find('m').map_or(rest.len(), |end| end + 1)..];
+        }
+        s.push_str(rest);
+        s
+    }
+
+    #[test]
+    fn to_ansi_should_style_headings_links_and_code() {
+        let ansi = to_ansi(
+            "# Objects\n\n## <a name=\"player\"></a>Player\n\n**Type:** [`Player`](objects.md#player) or `String`\n",
+        );
+        assert_eq!(
+            "\x1b[1;4mObjects\x1b[0m\n\n\x1b[1mPlayer\x1b[0m\n\n\x1b[1mType:\x1b[0m \x1b[36mPlayer\x1b[0m or \x1b[33mString\x1b[0m\n",
+            ansi
+        );
+    }
+
+    #[test]
+    fn to_ansi_should_align_tables() {
+        let ansi = to_ansi(
+            "| Field | Type |\n| --- | --- |\n| name | [`String`](scalars.md#string) |\n| position | Position &amp; more |\n",
+        );
+        assert_eq!(
+            "Field     Type\n────────  ───────────────\nname      String\nposition  Position & more\n",
+            strip(&ansi)
+        );
+    }
+
+    #[test]
+    fn to_ansi_should_set_off_quotes_and_code() {
+        let ansi = to_ansi("> A player\n>\n> ```graphql\n> { player }\n> ```\n\n* one\n* two\n");
+        assert_eq!(
+            "│ A player\n│\n│     { player }\n\n• one\n• two\n",
+            strip(&ansi)
+        );
+    }
+}
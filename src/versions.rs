@@ -0,0 +1,96 @@
+use super::descriptions::get_unmatched;
+use super::schema::Schema;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+};
+
+/// The versions that schema elements were added in, keyed by schema
+/// coordinate, as produced by a changelog tool or written by hand
+#[derive(Debug, Default)]
+pub struct Versions {
+    since: HashMap<String, String>,
+}
+
+impl Versions {
+    /// Reads the version map from a JSON file (if it ends in .json) or a
+    /// TOML file
+    pub fn from_file(file: &Path) -> Result<Versions, Box<dyn Error>> {
+        let contents = fs::read_to_string(file)
+            .map_err(|err| format!("can't read versions {}: {}", file.display(), err))?;
+        let is_json = file
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let since = if is_json {
+            serde_json::from_str(&contents).map_err(|err| err.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|err| err.to_string())
+        };
+        match since {
+            Ok(since) => Ok(Versions { since }),
+            Err(err) => Err(format!("invalid versions {}: {}", file.display(), err).into()),
+        }
+    }
+
+    /// Records the version each element was added in and returns the
+    /// coordinates that didn't match anything in the schema, sorted
+    pub fn apply(&self, schema: &mut Schema) -> Vec<String> {
+        let mut applied = HashSet::new();
+
+        schema.for_each_element_mut(&mut |coordinate, mut element| {
+            if let (Some(version), Some(since)) = (self.since.get(coordinate), element.since_mut())
+            {
+                *since = Some(version.clone());
+                applied.insert(coordinate.to_string());
+            }
+        });
+
+        get_unmatched(self.since.keys(), &applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_file_should_read_toml() -> Result<(), Box<dyn Error>> {
+        let versions = Versions::from_file(Path::new("testdata/versions.toml"))?;
+        assert_eq!("v2.3", versions.since["Player.position"]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_file_should_fail_when_missing() {
+        match Versions::from_file(Path::new("testdata/missing.json")) {
+            Ok(_) => panic!("missing versions should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .starts_with("can't read versions testdata/missing.json")),
+        }
+    }
+
+    #[test]
+    fn apply_should_record_versions() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_schema(Path::new("testdata/schema.graphql"))?;
+        let versions = Versions::from_file(Path::new("testdata/versions.toml"))?;
+        let unmatched = versions.apply(&mut schema);
+
+        let player = schema.get_type("Player").unwrap();
+        let position = player
+            .fields
+            .iter()
+            .flatten()
+            .find(|field| field.name.as_deref() == Some("position"))
+            .unwrap();
+        assert_eq!(Some("v2.3".to_string()), position.since);
+        assert_eq!(
+            Some("v2.0".to_string()),
+            schema.get_type("Position").unwrap().since
+        );
+        assert_eq!(vec!["Player.rookieYear".to_string()], unmatched);
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use super::budget::Budget;
+use super::schema::{Enum, Field, Input, Schema, Type, TypeRef};
+use serde_json::{json, Value};
+use std::{error::Error, fs, path::Path};
+
+// Pinned so the page renders the same no matter when it's opened
+const VOYAGER_URL: &str = "https://cdn.jsdelivr.net/npm/graphql-voyager@2.1.0/dist";
+
+/// Writes the schema for GraphQL Voyager's interactive graph view: an HTML
+/// page that loads Voyager and embeds the schema, or, if the file ends in
+/// .json, just the introspection result Voyager reads
+///
+/// # Arguments
+///
+/// * `schema` - the schema to view
+/// * `file` - the file to write
+/// * `site_name` - the name of the documentation site, for the page title
+/// * `budget` - the output budget to count the file against
+pub fn write(
+    schema: &Schema,
+    file: &Path,
+    site_name: &str,
+    budget: &Budget,
+) -> Result<(), Box<dyn Error>> {
+    let introspection = to_introspection(schema);
+    let contents = if file.extension().is_some_and(|ext| ext == "json") {
+        format!("{}\n", serde_json::to_string_pretty(&introspection)?)
+    } else {
+        to_html(&introspection, site_name)?
+    };
+    budget.spend(contents.len())?;
+    fs::write(file, contents)
+        .map_err(|err| format!("can't write Voyager file {}: {}", file.display(), err).into())
+}
+
+/// Returns the schema as an introspection result, in the shape GraphQL
+/// tools expect: `{"data": {"__schema": ...}}` with camelCase keys and
+/// empty lists where the spec requires them
+pub fn to_introspection(schema: &Schema) -> Value {
+    let root = |typ: &Option<Type>| match typ.as_ref().and_then(|typ| typ.name.as_ref()) {
+        Some(name) => json!({ "name": name }),
+        None => Value::Null,
+    };
+    json!({
+        "data": {
+            "__schema": {
+                "description": schema.description,
+                "queryType": root(&schema.query_type),
+                "mutationType": root(&schema.mutation_type),
+                "subscriptionType": root(&schema.subscription_type),
+                "types": schema.types.iter().flatten().map(to_type).collect::<Vec<Value>>(),
+                "directives": schema.directives.iter().flatten().map(|directive| json!({
+                    "name": directive.name,
+                    "description": directive.description,
+                    "locations": directive.locations.clone().unwrap_or_default(),
+                    "args": to_inputs(&directive.args),
+                    "isRepeatable": directive.is_repeatable.unwrap_or(false),
+                })).collect::<Vec<Value>>(),
+            }
+        }
+    })
+}
+
+fn to_type(typ: &Type) -> Value {
+    let kind = typ.kind.as_deref().unwrap_or_default();
+    // Lists the kind has are required, and the others must be null
+    let list_if = |kinds: &[&str], list: Value| {
+        if kinds.contains(&kind) {
+            list
+        } else {
+            Value::Null
+        }
+    };
+    json!({
+        "kind": kind,
+        "name": typ.name,
+        "description": typ.description,
+        "specifiedByURL": typ.specified_by_url,
+        "isOneOf": typ.is_one_of,
+        "fields": list_if(
+            &["OBJECT", "INTERFACE"],
+            typ.fields.iter().flatten().map(to_field).collect::<Vec<Value>>().into(),
+        ),
+        "inputFields": list_if(&["INPUT_OBJECT"], to_inputs(&typ.inputs)),
+        "interfaces": list_if(&["OBJECT", "INTERFACE"], to_type_refs(&typ.interfaces)),
+        "enumValues": list_if(
+            &["ENUM"],
+            typ.enums.iter().flatten().map(to_enum).collect::<Vec<Value>>().into(),
+        ),
+        "possibleTypes": list_if(&["INTERFACE", "UNION"], to_type_refs(&typ.possible_types)),
+    })
+}
+
+fn to_field(field: &Field) -> Value {
+    json!({
+        "name": field.name,
+        "description": field.description,
+        "args": to_inputs(&field.args),
+        "type": field.field_type.as_ref().map(to_type_ref),
+        "isDeprecated": field.is_deprecated.unwrap_or(false),
+        "deprecationReason": field.deprecation_reason,
+    })
+}
+
+fn to_inputs(inputs: &Option<Vec<Input>>) -> Value {
+    inputs
+        .iter()
+        .flatten()
+        .map(|input| {
+            json!({
+                "name": input.name,
+                "description": input.description,
+                "type": input.input_type.as_ref().map(to_type_ref),
+                "defaultValue": input.default_value,
+            })
+        })
+        .collect::<Vec<Value>>()
+        .into()
+}
+
+fn to_enum(value: &Enum) -> Value {
+    json!({
+        "name": value.name,
+        "description": value.description,
+        "isDeprecated": value.is_deprecated.unwrap_or(false),
+        "deprecationReason": value.deprecation_reason,
+    })
+}
+
+fn to_type_refs(type_refs: &Option<Vec<TypeRef>>) -> Value {
+    type_refs
+        .iter()
+        .flatten()
+        .map(to_type_ref)
+        .collect::<Vec<Value>>()
+        .into()
+}
+
+fn to_type_ref(type_ref: &TypeRef) -> Value {
+    json!({
+        "kind": type_ref.kind,
+        "name": type_ref.name,
+        "ofType": type_ref.of_type.as_deref().map(to_type_ref),
+    })
+}
+
+fn to_html(introspection: &Value, site_name: &str) -> Result<String, Box<dyn Error>> {
+    // "</" would end the script early, and JSON allows escaping the slash
+    let data = serde_json::to_string(introspection)?.replace("</", "<\\/");
+    let title = site_name
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8" />
+    <title>{title}</title>
+    <style>
+      body {{ height: 100vh; margin: 0; overflow: hidden; }}
+      #voyager {{ height: 100vh; }}
+    </style>
+    <link rel="stylesheet" href="{url}/voyager.css" />
+    <script src="{url}/voyager.standalone.js"></script>
+  </head>
+  <body>
+    <div id="voyager">Loading...</div>
+    <script>
+      const introspection = {data};
+      GraphQLVoyager.renderVoyager(document.getElementById("voyager"), {{
+        introspection,
+      }});
+    </script>
+  </body>
+</html>
+"#,
+        title = title,
+        url = VOYAGER_URL,
+        data = data
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Result<Schema, Box<dyn Error>> {
+        Schema::from_sdl(
+            r#"
+            type Query { player(id: ID!): Player teams: [Team!]! }
+            type Player implements Node { id: ID! name: String @deprecated(reason: "</script>") }
+            interface Node { id: ID! }
+            type Team { name: String }
+            union Entity = Player | Team
+            enum Position { CENTER }
+            input Filter { name: String = "x" }
+            "#,
+        )
+    }
+
+    fn get_type<'a>(introspection: &'a Value, name: &str) -> &'a Value {
+        introspection["data"]["__schema"]["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|typ| typ["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn to_introspection_should_use_introspection_shape() -> Result<(), Box<dyn Error>> {
+        let introspection = to_introspection(&schema()?);
+        assert_eq!(
+            json!({ "name": "Query" }),
+            introspection["data"]["__schema"]["queryType"]
+        );
+        assert_eq!(
+            Value::Null,
+            introspection["data"]["__schema"]["mutationType"]
+        );
+
+        let query = get_type(&introspection, "Query");
+        assert_eq!(json!([]), query["interfaces"]);
+        assert_eq!(Value::Null, query["inputFields"]);
+        assert_eq!(
+            json!({
+                "kind": "NON_NULL",
+                "name": null,
+                "ofType": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                        "kind": "NON_NULL",
+                        "name": null,
+                        "ofType": { "kind": "OBJECT", "name": "Team", "ofType": null }
+                    }
+                }
+            }),
+            query["fields"][1]["type"]
+        );
+        assert_eq!(json!([]), query["fields"][1]["args"]);
+
+        let player = get_type(&introspection, "Player");
+        assert_eq!("Node", player["interfaces"][0]["name"]);
+        assert_eq!(true, player["fields"][1]["isDeprecated"]);
+        assert_eq!(
+            2,
+            get_type(&introspection, "Entity")["possibleTypes"]
+                .as_array()
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            "CENTER",
+            get_type(&introspection, "Position")["enumValues"][0]["name"]
+        );
+        assert_eq!(
+            "\"x\"",
+            get_type(&introspection, "Filter")["inputFields"][0]["defaultValue"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn to_html_should_embed_introspection_safely() -> Result<(), Box<dyn Error>> {
+        let introspection = to_introspection(&schema()?);
+        let html = to_html(&introspection, "League <API>")?;
+        assert!(html.contains("<title>League &lt;API&gt;</title>"));
+        assert!(html.contains("<\\/script>"));
+        assert_eq!(2, html.matches("</script>").count());
+        let start = html.find("const introspection = ").unwrap() + 22;
+        let end = html[start..].find(";\n").unwrap() + start;
+        assert_eq!(
+            introspection,
+            serde_json::from_str::<Value>(&html[start..end])?
+        );
+        Ok(())
+    }
+}